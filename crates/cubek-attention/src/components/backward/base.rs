@@ -0,0 +1,112 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::CubeOption;
+
+/// Offset of a row of a `[batch, heads, seq, ..]` tensor. For `[batch, heads, seq]` tensors of
+/// row statistics such as the log-sum-exp, this is the offset of the statistic itself.
+#[cube]
+pub(crate) fn row_offset<E: CubePrimitive>(
+    tensor: &Tensor<E>,
+    batch: u32,
+    head: u32,
+    row: u32,
+) -> u32 {
+    batch * tensor.stride(0) + head * tensor.stride(1) + row * tensor.stride(2)
+}
+
+/// Cooperatively copies `num_rows` rows of a `[batch, heads, seq, dim]` tensor starting at
+/// `row_start` to shared memory. Rows past the end of the sequence are filled with zeros.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn stage_rows<E: Float, A: Float>(
+    tensor: &Tensor<E>,
+    stage: &mut SharedMemory<A>,
+    batch: u32,
+    head: u32,
+    row_start: u32,
+    #[comptime] num_rows: u32,
+    #[comptime] row_size: u32,
+    #[comptime] num_units: u32,
+) {
+    let seq = tensor.shape(2);
+    let stage_size = comptime!(num_rows * row_size);
+
+    for k in 0..comptime!(stage_size.div_ceil(num_units)) {
+        let index = k * num_units + UNIT_POS;
+
+        if index < stage_size {
+            let row = row_start + index / row_size;
+            let col = index % row_size;
+
+            let mut value = A::from_int(0);
+            if row < seq {
+                value = A::cast_from(
+                    tensor[row_offset(tensor, batch, head, row) + col * tensor.stride(3)],
+                );
+            }
+            stage[index] = value;
+        }
+    }
+}
+
+/// Cooperatively copies the statistics of `num_rows` rows starting at `row_start` to shared
+/// memory. Rows past the end of the sequence are filled with zeros.
+#[cube]
+pub(crate) fn stage_stats<A: Float>(
+    tensor: &Tensor<A>,
+    stage: &mut SharedMemory<A>,
+    batch: u32,
+    head: u32,
+    row_start: u32,
+    #[comptime] num_rows: u32,
+    #[comptime] num_units: u32,
+) {
+    let seq = tensor.shape(2);
+
+    for k in 0..comptime!(num_rows.div_ceil(num_units)) {
+        let index = k * num_units + UNIT_POS;
+
+        if index < num_rows {
+            let row = row_start + index;
+
+            let mut value = A::from_int(0);
+            if row < seq {
+                value = tensor[row_offset(tensor, batch, head, row)];
+            }
+            stage[index] = value;
+        }
+    }
+}
+
+/// Recomputes the softmax probability of a scaled score from the log-sum-exp of its query row.
+///
+/// Masked scores have a probability of zero. This includes all scores of fully masked rows,
+/// whose log-sum-exp is infinite.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn recompute_probability<A: Float, M: Numeric>(
+    score: A,
+    lse: A,
+    mask: &CubeOption<Tensor<M>>,
+    batch: u32,
+    head: u32,
+    row_q: u32,
+    row_kv: u32,
+    #[comptime] causal: bool,
+) -> A {
+    let mut masked = false;
+
+    if comptime!(causal) {
+        masked = row_kv > row_q;
+    }
+
+    match mask {
+        CubeOption::Some(mask) => {
+            let offset = row_offset(mask, batch, head, row_q) + row_kv * mask.stride(3);
+            masked = masked || mask[offset] != M::from_int(0);
+        }
+        CubeOption::None => {}
+    }
+
+    select(masked, A::from_int(0), A::exp(score - lse))
+}
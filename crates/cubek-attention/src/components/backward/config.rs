@@ -0,0 +1,28 @@
+use crate::definition::{AttentionBackwardBlueprint, AttentionDims};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration of the attention backward kernels
+pub struct AttentionBackwardConfig {
+    pub block_size_q: u32,
+    pub block_size_kv: u32,
+    pub head_dim: u32,
+    pub val_dim: u32,
+    pub causal: bool,
+}
+
+impl AttentionBackwardConfig {
+    pub fn new(blueprint: &AttentionBackwardBlueprint, dims: &AttentionDims) -> Self {
+        Self {
+            block_size_q: blueprint.block_size_q,
+            block_size_kv: blueprint.block_size_kv,
+            head_dim: dims.head_dim as u32,
+            val_dim: dims.val_dim as u32,
+            causal: blueprint.causal,
+        }
+    }
+
+    /// Scale applied to scores before the softmax
+    pub fn score_scale(&self) -> f32 {
+        1.0 / (self.head_dim as f32).sqrt()
+    }
+}
@@ -0,0 +1,191 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::CubeOption;
+
+use crate::components::backward::{
+    AttentionBackwardConfig,
+    base::{recompute_probability, row_offset, stage_rows, stage_stats},
+};
+
+#[cube(launch_unchecked)]
+/// Computes the key and value gradients.
+///
+/// Each unit accumulates the gradients of one key/value row in registers, with `block_size_kv`
+/// rows per cube. The query rows of every query head sharing the key/value head are staged
+/// `block_size_q` at a time, along with their output gradient, log-sum-exp and delta, from which
+/// each unit recomputes the probabilities of its row.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn attention_backward_key_value<E: Float, A: Float, M: Numeric>(
+    query: &Tensor<E>,
+    key: &Tensor<E>,
+    value: &Tensor<E>,
+    out_grad: &Tensor<E>,
+    lse: &Tensor<A>,
+    delta: &Tensor<A>,
+    mask: &CubeOption<Tensor<M>>,
+    key_grad: &mut Tensor<E>,
+    value_grad: &mut Tensor<E>,
+    #[comptime] config: AttentionBackwardConfig,
+    #[define(E, A, M)] _elem_types: [StorageType; 3],
+) {
+    let block_size_q = config.block_size_q;
+    let block_size_kv = config.block_size_kv;
+    let head_dim = config.head_dim;
+    let val_dim = config.val_dim;
+
+    let num_heads = query.shape(1);
+    let num_kv_heads = key.shape(1);
+    let num_heads_per_kv_head = num_heads / num_kv_heads;
+    let seq_q = query.shape(2);
+    let seq_kv = key.shape(2);
+
+    let batch = CUBE_POS_Y / num_kv_heads;
+    let kv_head = CUBE_POS_Y % num_kv_heads;
+    let kv_start = CUBE_POS_X * block_size_kv;
+    let row_kv = kv_start + UNIT_POS;
+    let in_bounds = row_kv < seq_kv;
+
+    let scale = A::new(comptime!(config.score_scale()));
+
+    let mut key_row = Array::<A>::new(head_dim);
+    let mut value_row = Array::<A>::new(val_dim);
+    let mut key_grad_row = Array::<A>::new(head_dim);
+    let mut value_grad_row = Array::<A>::new(val_dim);
+
+    let key_offset = row_offset(key, batch, kv_head, row_kv);
+    let value_offset = row_offset(value, batch, kv_head, row_kv);
+    for d in 0..head_dim {
+        let mut val = A::from_int(0);
+        if in_bounds {
+            val = A::cast_from(key[key_offset + d * key.stride(3)]);
+        }
+        key_row[d] = val;
+        key_grad_row[d] = A::from_int(0);
+    }
+    for d in 0..val_dim {
+        let mut val = A::from_int(0);
+        if in_bounds {
+            val = A::cast_from(value[value_offset + d * value.stride(3)]);
+        }
+        value_row[d] = val;
+        value_grad_row[d] = A::from_int(0);
+    }
+
+    let mut query_stage = SharedMemory::<A>::new(comptime!(block_size_q * head_dim));
+    let mut out_grad_stage = SharedMemory::<A>::new(comptime!(block_size_q * val_dim));
+    let mut lse_stage = SharedMemory::<A>::new(block_size_q);
+    let mut delta_stage = SharedMemory::<A>::new(block_size_q);
+
+    // With a causal mask, query rows before the first key/value row of the cube have no
+    // unmasked score in the cube
+    let mut first_q_block = 0u32;
+    if comptime!(config.causal) {
+        first_q_block = kv_start / block_size_q;
+    }
+    let num_q_blocks = seq_q.div_ceil(block_size_q);
+
+    for head_in_group in 0..num_heads_per_kv_head {
+        let head = kv_head * num_heads_per_kv_head + head_in_group;
+
+        for q_block in first_q_block..num_q_blocks {
+            let q_start = q_block * block_size_q;
+
+            stage_rows::<E, A>(
+                query,
+                &mut query_stage,
+                batch,
+                head,
+                q_start,
+                block_size_q,
+                head_dim,
+                block_size_kv,
+            );
+            stage_rows::<E, A>(
+                out_grad,
+                &mut out_grad_stage,
+                batch,
+                head,
+                q_start,
+                block_size_q,
+                val_dim,
+                block_size_kv,
+            );
+            stage_stats::<A>(
+                lse,
+                &mut lse_stage,
+                batch,
+                head,
+                q_start,
+                block_size_q,
+                block_size_kv,
+            );
+            stage_stats::<A>(
+                delta,
+                &mut delta_stage,
+                batch,
+                head,
+                q_start,
+                block_size_q,
+                block_size_kv,
+            );
+
+            sync_cube();
+
+            if in_bounds {
+                let num_rows = select(
+                    seq_q - q_start < block_size_q,
+                    seq_q - q_start,
+                    block_size_q,
+                );
+
+                for row in 0..num_rows {
+                    let mut score = A::from_int(0);
+                    for d in 0..head_dim {
+                        score += query_stage[row * head_dim + d] * key_row[d];
+                    }
+
+                    let probability = recompute_probability::<A, M>(
+                        score * scale,
+                        lse_stage[row],
+                        mask,
+                        batch,
+                        head,
+                        q_start + row,
+                        row_kv,
+                        config.causal,
+                    );
+
+                    // dV += P^T dO, and the gradient of the probability is dP = dO V^T
+                    let mut probability_grad = A::from_int(0);
+                    for d in 0..val_dim {
+                        let out_grad_val = out_grad_stage[row * val_dim + d];
+                        value_grad_row[d] += probability * out_grad_val;
+                        probability_grad += out_grad_val * value_row[d];
+                    }
+
+                    // dK += dS^T Q, with the score gradient dS = P * (dP - delta)
+                    let score_grad = probability * (probability_grad - delta_stage[row]);
+                    for d in 0..head_dim {
+                        key_grad_row[d] += score_grad * query_stage[row * head_dim + d];
+                    }
+                }
+            }
+
+            sync_cube();
+        }
+    }
+
+    if in_bounds {
+        let key_grad_offset = row_offset(key_grad, batch, kv_head, row_kv);
+        let value_grad_offset = row_offset(value_grad, batch, kv_head, row_kv);
+
+        for d in 0..head_dim {
+            key_grad[key_grad_offset + d * key_grad.stride(3)] =
+                E::cast_from(key_grad_row[d] * scale);
+        }
+        for d in 0..val_dim {
+            value_grad[value_grad_offset + d * value_grad.stride(3)] =
+                E::cast_from(value_grad_row[d]);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Backward pass of attention, computing the gradients of the query, key and value from the
+//! gradient of the output.
+//!
+//! Like the forward pass, probabilities are never materialized: they are recomputed block by
+//! block from the log-sum-exp of each query row saved by the forward pass.
+//!
+//! The kernels work on scalars rather than tiles, and serve as a reference for plain softmax
+//! attention. Options they don't implement are rejected when the routine is prepared.
+
+mod base;
+mod config;
+mod key_value;
+mod preprocess;
+mod query;
+
+pub use config::*;
+
+pub(crate) use key_value::*;
+pub(crate) use preprocess::*;
+pub(crate) use query::*;
@@ -0,0 +1,39 @@
+use cubecl;
+use cubecl::prelude::*;
+
+use crate::components::backward::{AttentionBackwardConfig, base::row_offset};
+
+#[cube(launch_unchecked)]
+/// Computes `delta = rowsum(dO * O)` for each query row, which is also the sum of the
+/// probabilities of the row weighted by the gradient of their score.
+///
+/// Each unit handles one query row, with `block_size_q` rows per cube.
+pub(crate) fn attention_backward_preprocess<E: Float, A: Float>(
+    out: &Tensor<E>,
+    out_grad: &Tensor<E>,
+    delta: &mut Tensor<A>,
+    #[comptime] config: AttentionBackwardConfig,
+    #[define(E, A)] _elem_types: [StorageType; 2],
+) {
+    let num_heads = out.shape(1);
+    let seq_q = out.shape(2);
+
+    let batch = CUBE_POS_Y / num_heads;
+    let head = CUBE_POS_Y % num_heads;
+    let row = CUBE_POS_X * config.block_size_q + UNIT_POS;
+
+    if row >= seq_q {
+        terminate!();
+    }
+
+    let out_offset = row_offset(out, batch, head, row);
+    let out_grad_offset = row_offset(out_grad, batch, head, row);
+
+    let mut sum = A::from_int(0);
+    for d in 0..config.val_dim {
+        sum += A::cast_from(out[out_offset + d * out.stride(3)])
+            * A::cast_from(out_grad[out_grad_offset + d * out_grad.stride(3)]);
+    }
+
+    delta[row_offset(delta, batch, head, row)] = sum;
+}
@@ -0,0 +1,161 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::CubeOption;
+
+use crate::components::backward::{
+    AttentionBackwardConfig,
+    base::{recompute_probability, row_offset, stage_rows},
+};
+
+#[cube(launch_unchecked)]
+/// Computes the query gradient.
+///
+/// Each unit accumulates the gradient of one query row in registers, with `block_size_q` rows
+/// per cube. Key and value rows are staged `block_size_kv` at a time, from which each unit
+/// recomputes the probabilities of its row.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn attention_backward_query<E: Float, A: Float, M: Numeric>(
+    query: &Tensor<E>,
+    key: &Tensor<E>,
+    value: &Tensor<E>,
+    out_grad: &Tensor<E>,
+    lse: &Tensor<A>,
+    delta: &Tensor<A>,
+    mask: &CubeOption<Tensor<M>>,
+    query_grad: &mut Tensor<E>,
+    #[comptime] config: AttentionBackwardConfig,
+    #[define(E, A, M)] _elem_types: [StorageType; 3],
+) {
+    let block_size_q = config.block_size_q;
+    let block_size_kv = config.block_size_kv;
+    let head_dim = config.head_dim;
+    let val_dim = config.val_dim;
+
+    let num_heads = query.shape(1);
+    let num_kv_heads = key.shape(1);
+    let seq_q = query.shape(2);
+    let seq_kv = key.shape(2);
+
+    let batch = CUBE_POS_Y / num_heads;
+    let head = CUBE_POS_Y % num_heads;
+    let kv_head = head / (num_heads / num_kv_heads);
+    let q_start = CUBE_POS_X * block_size_q;
+    let row_q = q_start + UNIT_POS;
+    let in_bounds = row_q < seq_q;
+
+    let scale = A::new(comptime!(config.score_scale()));
+
+    let mut query_row = Array::<A>::new(head_dim);
+    let mut out_grad_row = Array::<A>::new(val_dim);
+    let mut query_grad_row = Array::<A>::new(head_dim);
+    let mut row_lse = A::from_int(0);
+    let mut row_delta = A::from_int(0);
+
+    let query_offset = row_offset(query, batch, head, row_q);
+    let out_grad_offset = row_offset(out_grad, batch, head, row_q);
+    for d in 0..head_dim {
+        let mut val = A::from_int(0);
+        if in_bounds {
+            val = A::cast_from(query[query_offset + d * query.stride(3)]);
+        }
+        query_row[d] = val;
+        query_grad_row[d] = A::from_int(0);
+    }
+    for d in 0..val_dim {
+        let mut val = A::from_int(0);
+        if in_bounds {
+            val = A::cast_from(out_grad[out_grad_offset + d * out_grad.stride(3)]);
+        }
+        out_grad_row[d] = val;
+    }
+    if in_bounds {
+        row_lse = lse[row_offset(lse, batch, head, row_q)];
+        row_delta = delta[row_offset(delta, batch, head, row_q)];
+    }
+
+    let mut key_stage = SharedMemory::<A>::new(comptime!(block_size_kv * head_dim));
+    let mut value_stage = SharedMemory::<A>::new(comptime!(block_size_kv * val_dim));
+
+    // With a causal mask, key/value rows after the last query row of the cube have no unmasked
+    // score in the cube
+    let mut kv_end = seq_kv;
+    if comptime!(config.causal) {
+        let last_q = q_start + block_size_q;
+        kv_end = select(last_q < seq_kv, last_q, seq_kv);
+    }
+    let num_kv_blocks = kv_end.div_ceil(block_size_kv);
+
+    for kv_block in 0..num_kv_blocks {
+        let kv_start = kv_block * block_size_kv;
+
+        stage_rows::<E, A>(
+            key,
+            &mut key_stage,
+            batch,
+            kv_head,
+            kv_start,
+            block_size_kv,
+            head_dim,
+            block_size_q,
+        );
+        stage_rows::<E, A>(
+            value,
+            &mut value_stage,
+            batch,
+            kv_head,
+            kv_start,
+            block_size_kv,
+            val_dim,
+            block_size_q,
+        );
+
+        sync_cube();
+
+        if in_bounds {
+            let num_rows = select(
+                seq_kv - kv_start < block_size_kv,
+                seq_kv - kv_start,
+                block_size_kv,
+            );
+
+            for row in 0..num_rows {
+                let mut score = A::from_int(0);
+                for d in 0..head_dim {
+                    score += query_row[d] * key_stage[row * head_dim + d];
+                }
+
+                let probability = recompute_probability::<A, M>(
+                    score * scale,
+                    row_lse,
+                    mask,
+                    batch,
+                    head,
+                    row_q,
+                    kv_start + row,
+                    config.causal,
+                );
+
+                let mut probability_grad = A::from_int(0);
+                for d in 0..val_dim {
+                    probability_grad += out_grad_row[d] * value_stage[row * val_dim + d];
+                }
+
+                // dQ += dS K, with the score gradient dS = P * (dP - delta)
+                let score_grad = probability * (probability_grad - row_delta);
+                for d in 0..head_dim {
+                    query_grad_row[d] += score_grad * key_stage[row * head_dim + d];
+                }
+            }
+        }
+
+        sync_cube();
+    }
+
+    if in_bounds {
+        let query_grad_offset = row_offset(query_grad, batch, head, row_q);
+        for d in 0..head_dim {
+            query_grad[query_grad_offset + d * query_grad.stride(3)] =
+                E::cast_from(query_grad_row[d] * scale);
+        }
+    }
+}
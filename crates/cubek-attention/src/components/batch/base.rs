@@ -1,14 +1,16 @@
 use cubecl;
 use cubecl::prelude::*;
-use cubecl::std::{CubeOption, tensor::r#virtual::VirtualTensor};
+use cubecl::std::{CubeOption, CubeOptionExpand, tensor::r#virtual::VirtualTensor};
 
-use crate::components::global::GlobalAttentionConfig;
+use crate::components::global::{GlobalAttentionConfig, SequenceBounds};
+use crate::components::stage::SoftmaxParams;
 use crate::definition::{
     AttentionBlueprint, AttentionElems, AttentionPrecision, AttentionSetupError, CubeCountInput,
-    InputRuntimeArg, OutputRuntimeArg,
+    InputRuntimeArg, LseRuntimeArg, OutputRuntimeArg, ScoreArgsRuntimeArg,
+    SequenceLengthsRuntimeArg,
 };
 use crate::definition::{CubeCountInputArgs, attention_types::*};
-use crate::launch::AttentionArgs;
+use crate::launch::{AttentionArgs, KvPageTable, ScoreArgs, SequenceLengths};
 use std::{fmt::Debug, hash::Hash};
 
 /// A family of [BatchAttention] implementations that operate with any [precision](AttentionPrecision).
@@ -32,6 +34,9 @@ pub trait BatchAttentionFamily: Send + Sync + 'static {
         cube_count: CubeCount,
         input: InputRuntimeArg<'a, AA, R>,
         output: OutputRuntimeArg<'a, AA, R>,
+        lse: LseRuntimeArg<'a, R>,
+        sequence_lengths: SequenceLengthsRuntimeArg<'a, R>,
+        score_args: ScoreArgsRuntimeArg<'a, R>,
         cube_count_input: CubeCountInputArgs<'a, R>,
         dtypes: &AttentionElems,
         attention_blueprint: Self::Blueprint,
@@ -53,8 +58,12 @@ pub trait BatchAttention<AP: AttentionPrecision>: 'static + Send + Sync {
         query: VirtualTensor<QG<AP>>,
         key: VirtualTensor<KG<AP>>,
         value: VirtualTensor<VG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         mask: CubeOption<VirtualTensor<MSK<AP>>>,
         out: VirtualTensor<OG<AP>, ReadWrite>,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+        sequence: CubeSequence,
+        score_args: &ScoreArgs,
         cube_count_args: CubeCountInput,
         #[comptime] config: Self::Config,
     );
@@ -70,3 +79,85 @@ pub trait BatchAttentionConfig:
 
     fn cube_dim(&self) -> CubeDim;
 }
+
+/// Sequence attended to by a cube
+#[derive(CubeType, Clone, Copy)]
+pub struct CubeSequence {
+    /// Index of the batch holding the sequence in the tensors
+    pub batch: u32,
+    /// Rows of the batch that belong to the sequence
+    pub bounds: SequenceBounds,
+}
+
+/// Locates the sequence at `sequence_index`, which is its own batch unless sequences are packed
+/// in a single batch.
+#[cube]
+pub(crate) fn cube_sequence<QG: Numeric, KG: Numeric>(
+    query: &VirtualTensor<QG>,
+    key: &VirtualTensor<KG>,
+    sequence_lengths: &SequenceLengths,
+    sequence_index: u32,
+) -> CubeSequence {
+    let mut batch = sequence_index;
+    let mut q_start = 0u32;
+    let mut seq_q = query.shape(2);
+    let mut kv_start = 0u32;
+    let mut seq_kv = key.shape(2);
+
+    match &sequence_lengths.cu_seqlens_q {
+        CubeOption::Some(cu_seqlens_q) => {
+            batch = 0u32;
+            q_start = cu_seqlens_q[sequence_index];
+            seq_q = cu_seqlens_q[sequence_index + 1] - q_start;
+        }
+        CubeOption::None => {}
+    }
+
+    match &sequence_lengths.cu_seqlens_kv {
+        CubeOption::Some(cu_seqlens_kv) => {
+            kv_start = cu_seqlens_kv[sequence_index];
+            seq_kv = cu_seqlens_kv[sequence_index + 1] - kv_start;
+        }
+        CubeOption::None => match &sequence_lengths.kv_lengths {
+            CubeOption::Some(kv_lengths) => {
+                seq_kv = kv_lengths[sequence_index];
+            }
+            CubeOption::None => {}
+        },
+    }
+
+    CubeSequence {
+        batch,
+        bounds: SequenceBounds {
+            q_start,
+            seq_q,
+            kv_start,
+            seq_kv,
+        },
+    }
+}
+
+/// Softmax parameters of the head at `head_index`, whose sink is only included if `with_sink`.
+#[cube]
+pub(crate) fn softmax_params(
+    score_args: &ScoreArgs,
+    head_index: u32,
+    with_sink: bool,
+) -> SoftmaxParams {
+    let mut sink = f32::min_value();
+
+    match &score_args.sinks {
+        CubeOption::Some(sinks) => {
+            if with_sink {
+                sink = sinks[head_index];
+            }
+        }
+        CubeOption::None => {}
+    }
+
+    SoftmaxParams {
+        scale: score_args.scale,
+        softcap: score_args.softcap,
+        sink,
+    }
+}
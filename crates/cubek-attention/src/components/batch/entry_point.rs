@@ -1,8 +1,10 @@
 use crate::components::batch::BatchAttentionFamily;
-use crate::components::batch::base::BatchAttention;
+use crate::components::batch::base::{BatchAttention, cube_sequence};
 use crate::definition::AttentionBlueprint;
 use crate::definition::CubeCountInput;
 use crate::launch::AttentionArgs;
+use crate::launch::ScoreArgs;
+use crate::launch::SequenceLengths;
 use crate::launch::TensorKey;
 use crate::launch::TensorMask;
 use crate::launch::TensorOutput;
@@ -36,6 +38,9 @@ pub(crate) fn attention<
 >(
     inputs: &Input<Args, QG, KG, VG, MSK>,
     output: &mut Output<Args, OG>,
+    lse: &mut CubeOption<Tensor<Line<ACC>>>,
+    sequence_lengths: &SequenceLengths,
+    score_args: &ScoreArgs,
     cube_count_args: CubeCountInput,
     #[comptime] blueprint: AttentionBlueprint,
     #[define(QG, QT, KG, KS, VG, VS, KVT, SM, ACC, MSK, OG, OS)] _elem_types: [StorageType; 12],
@@ -58,6 +63,8 @@ pub(crate) fn attention<
     let value = TensorValue::<QG, KG, VG, MSK, OG, Args>::new(&state);
     let value = VirtualTensor::<VG>::new::<TensorValue<QG, KG, VG, MSK, OG, Args>>(&value);
 
+    let page_table = Args::kv_page_table(&state);
+
     let has_mask = Args::has_mask(&state);
     let mask: CubeOption<VirtualTensor<MSK>> = match has_mask {
         CubeOption::Some(_) => {
@@ -72,12 +79,27 @@ pub(crate) fn attention<
     let out =
         VirtualTensor::<OG, ReadWrite>::new::<TensorOutput<QG, KG, VG, MSK, OG, Args>>(&mut out);
 
+    let lse: CubeOption<VirtualTensor<ACC, ReadWrite>> = match lse {
+        CubeOption::Some(lse) => {
+            let lse = VirtualTensor::<ACC, ReadWrite>::new::<Tensor<Line<ACC>>>(lse);
+            CubeOption::new_Some(lse)
+        }
+        CubeOption::None => CubeOption::new_None(),
+    };
+
+    // Cubes along y each handle one head of one sequence
+    let sequence = cube_sequence(&query, &key, sequence_lengths, CUBE_POS_Y / query.shape(1));
+
     BMMF::Attention::<(QG, QT, KG, KS, VG, VS, KVT, SM, ACC, MSK, OG, OS)>::execute(
         query,
         key,
         value,
+        page_table,
         mask,
         out,
+        lse,
+        sequence,
+        score_args,
         cube_count_args,
         config,
     );
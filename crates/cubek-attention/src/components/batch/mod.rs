@@ -1,4 +1,5 @@
 pub mod simple;
+pub mod split_kv;
 
 mod base;
 mod entry_point;
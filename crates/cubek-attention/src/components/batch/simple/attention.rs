@@ -4,12 +4,17 @@ use cubecl::std::{CubeOption, tensor::r#virtual::VirtualTensor};
 use std::marker::PhantomData;
 
 use crate::components::{
-    batch::{BatchAttention, BatchAttentionConfig, simple::config::SimpleBatchConfig},
+    batch::{
+        BatchAttention, BatchAttentionConfig, CubeSequence, simple::config::SimpleBatchConfig,
+        softmax_params,
+    },
     global::{GlobalAttention, GlobalAttentionConfig as _},
-    stage::StageAttentionConfig as _,
+    stage::{ScorePositions, StageAttentionConfig as _, window_kv_range},
+    tile::TileAttentionConfig as _,
 };
 use crate::definition::attention_types::*;
 use crate::definition::{AttentionPrecision, CubeCountInput};
+use crate::launch::{KvPageTable, ScoreArgs};
 
 pub struct SimpleBatchAttention<AP: AttentionPrecision, GA: GlobalAttention<AP>> {
     _phantom: PhantomData<(AP, GA)>,
@@ -25,30 +30,97 @@ impl<GA: GlobalAttention<AP>, AP: AttentionPrecision> BatchAttention<AP>
         query: VirtualTensor<QG<AP>>,
         key: VirtualTensor<KG<AP>>,
         value: VirtualTensor<VG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         mask: CubeOption<VirtualTensor<MSK<AP>>>,
         out: VirtualTensor<OG<AP>, ReadWrite>,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+        sequence: CubeSequence,
+        score_args: &ScoreArgs,
         _cube_count_args: CubeCountInput,
         #[comptime] config: Self::Config,
     ) {
         let global_config = config.global_config();
         let q_index = CUBE_POS_X;
-        let batch_index = CUBE_POS_Y;
 
         let stage_q_offset = q_index * global_config.stage_config().elements_in_stage_seq_q();
 
         // Assume [batch, num_heads, seq_*, head_dim] layout
-        let seq_q = query.shape(2);
         let seq_kv = key.shape(2);
 
-        GA::execute(
-            GA::init_query_reader(batch_index, stage_q_offset, query, global_config),
-            GA::init_key_reader(batch_index, key, global_config),
-            GA::init_value_reader(batch_index, value, global_config),
-            GA::init_mask_reader(batch_index, stage_q_offset, mask, seq_kv, global_config),
-            GA::init_writer(batch_index, stage_q_offset, out, global_config),
-            seq_q,
-            seq_kv,
-            config.global_config(),
-        )
+        // Key and value may have fewer heads than query, with each group of consecutive query
+        // heads sharing the same key/value head
+        let num_heads = query.shape(1);
+        let num_kv_heads = key.shape(1);
+        let head_index = CUBE_POS_Y % num_heads;
+        let kv_head_index = head_index / (num_heads / num_kv_heads);
+        let batch_index = sequence.batch * num_heads + head_index;
+        let kv_batch_index = sequence.batch * num_kv_heads + kv_head_index;
+
+        let bounds = sequence.bounds;
+
+        // Key/value rows outside the window of every query row of the stage are skipped,
+        // starting on a partition so that the first one is aligned as without window
+        let tile_config = global_config.stage_config().tile_config();
+        let window = tile_config.window();
+        let positions = ScorePositions::new(
+            bounds.seq_q,
+            bounds.seq_kv,
+            head_index,
+            num_heads,
+            window,
+            tile_config.alibi(),
+        );
+        let stage_q_end = Min::min(
+            stage_q_offset + global_config.stage_config().elements_in_stage_seq_q(),
+            bounds.seq_q,
+        );
+        let kv_range = window_kv_range(
+            stage_q_offset,
+            stage_q_end,
+            bounds.seq_kv,
+            positions.diagonal,
+            window,
+        );
+        let step = global_config.stage_config().elements_in_partition_seq_kv();
+        let kv_start = kv_range.0 / step * step;
+
+        // Sequences shorter than the others have stages with no query row
+        if stage_q_offset < bounds.seq_q {
+            GA::execute(
+                GA::init_query_reader(batch_index, bounds, stage_q_offset, query, global_config),
+                GA::init_key_reader(
+                    kv_batch_index,
+                    bounds,
+                    kv_start,
+                    key,
+                    page_table,
+                    global_config,
+                ),
+                GA::init_value_reader(
+                    kv_batch_index,
+                    bounds,
+                    kv_start,
+                    value,
+                    page_table,
+                    global_config,
+                ),
+                GA::init_mask_reader(
+                    batch_index,
+                    stage_q_offset,
+                    kv_start,
+                    mask,
+                    seq_kv,
+                    global_config,
+                ),
+                GA::init_writer(batch_index, bounds, stage_q_offset, out, global_config),
+                GA::init_lse_writer(batch_index, bounds, stage_q_offset, lse, global_config),
+                bounds.seq_q,
+                kv_start,
+                kv_range.1,
+                positions,
+                softmax_params(score_args, head_index, true),
+                config.global_config(),
+            )
+        }
     }
 }
@@ -3,4 +3,5 @@ mod config;
 mod setup;
 
 pub use attention::*;
+pub use config::SimpleBatchConfig;
 pub use setup::SimpleBatchAttentionFamily;
@@ -13,7 +13,8 @@ use crate::{
     },
     definition::{
         AttentionBlueprint, AttentionElems, AttentionPrecision, AttentionSetupError,
-        CubeCountInputArgs, InputRuntimeArg, OutputRuntimeArg,
+        CubeCountInputArgs, InputRuntimeArg, LseRuntimeArg, OutputRuntimeArg, ScoreArgsRuntimeArg,
+        SequenceLengthsRuntimeArg,
     },
     launch::AttentionArgs,
 };
@@ -33,6 +34,9 @@ impl<GA: GlobalAttentionFamily> BatchAttentionFamily for SimpleBatchAttentionFam
         cube_count: cubecl::CubeCount,
         input: InputRuntimeArg<'a, AA, R>,
         output: OutputRuntimeArg<'a, AA, R>,
+        lse: LseRuntimeArg<'a, R>,
+        sequence_lengths: SequenceLengthsRuntimeArg<'a, R>,
+        score_args: ScoreArgsRuntimeArg<'a, R>,
         cube_count_input: CubeCountInputArgs<'a, R>,
         dtypes: &AttentionElems,
         blueprint: AttentionBlueprint,
@@ -44,6 +48,9 @@ impl<GA: GlobalAttentionFamily> BatchAttentionFamily for SimpleBatchAttentionFam
                 cube_dim,
                 input,
                 output,
+                lse,
+                sequence_lengths,
+                score_args,
                 cube_count_input,
                 blueprint,
                 dtypes.into(),
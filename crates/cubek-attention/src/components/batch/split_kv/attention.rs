@@ -0,0 +1,162 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::{CubeOption, tensor::r#virtual::VirtualTensor};
+use std::marker::PhantomData;
+
+use crate::components::{
+    batch::{
+        BatchAttention, BatchAttentionConfig, CubeSequence, simple::SimpleBatchConfig,
+        softmax_params,
+    },
+    global::{GlobalAttention, GlobalAttentionConfig as _},
+    stage::{ScorePositions, StageAttentionConfig as _, window_kv_range},
+    tile::TileAttentionConfig as _,
+};
+use crate::definition::attention_types::*;
+use crate::definition::{AttentionPrecision, CubeCountInput};
+use crate::launch::{KvPageTable, ScoreArgs};
+
+/// Batch attention where each cube only attends to a contiguous range of `seq_kv`.
+///
+/// The number of splits is the cube count along `z`. The output and log-sum-exp are partial
+/// results of shape `[num_splits * batch, num_heads, seq_q, *]`, the ones of split `s` starting
+/// at batch `s * batch`, which are then combined by weighting each split with its log-sum-exp.
+pub struct SplitKvBatchAttention<AP: AttentionPrecision, GA: GlobalAttention<AP>> {
+    _phantom: PhantomData<(AP, GA)>,
+}
+
+#[cube]
+impl<GA: GlobalAttention<AP>, AP: AttentionPrecision> BatchAttention<AP>
+    for SplitKvBatchAttention<AP, GA>
+{
+    type Config = SimpleBatchConfig<GA::Config>;
+
+    fn execute(
+        query: VirtualTensor<QG<AP>>,
+        key: VirtualTensor<KG<AP>>,
+        value: VirtualTensor<VG<AP>>,
+        page_table: CubeOption<KvPageTable>,
+        mask: CubeOption<VirtualTensor<MSK<AP>>>,
+        out: VirtualTensor<OG<AP>, ReadWrite>,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+        sequence: CubeSequence,
+        score_args: &ScoreArgs,
+        _cube_count_args: CubeCountInput,
+        #[comptime] config: Self::Config,
+    ) {
+        let global_config = config.global_config();
+        let q_index = CUBE_POS_X;
+        let split_index = CUBE_POS_Z;
+        let num_splits = CUBE_COUNT_Z;
+
+        let stage_q_offset = q_index * global_config.stage_config().elements_in_stage_seq_q();
+
+        // Assume [batch, num_heads, seq_*, head_dim] layout
+        let seq_kv = key.shape(2);
+
+        let num_heads = query.shape(1);
+        let num_kv_heads = key.shape(1);
+        let head_index = CUBE_POS_Y % num_heads;
+        let kv_head_index = head_index / (num_heads / num_kv_heads);
+        let batch_index = sequence.batch * num_heads + head_index;
+        let kv_batch_index = sequence.batch * num_kv_heads + kv_head_index;
+
+        let bounds = sequence.bounds;
+
+        // Only key/value rows within the window of some query row of the stage are split
+        let tile_config = global_config.stage_config().tile_config();
+        let window = tile_config.window();
+        let positions = ScorePositions::new(
+            bounds.seq_q,
+            bounds.seq_kv,
+            head_index,
+            num_heads,
+            window,
+            tile_config.alibi(),
+        );
+        let stage_q_end = Min::min(
+            stage_q_offset + global_config.stage_config().elements_in_stage_seq_q(),
+            bounds.seq_q,
+        );
+        let kv_range = window_kv_range(
+            stage_q_offset,
+            stage_q_end,
+            bounds.seq_kv,
+            positions.diagonal,
+            window,
+        );
+
+        // Splits are whole numbers of partitions, so that only the last split can end in the
+        // middle of one
+        let step = global_config.stage_config().elements_in_partition_seq_kv();
+        let kv_first = kv_range.0 / step * step;
+        let kv_last = kv_range.1;
+        let kv_len = kv_last - kv_first;
+        let kv_per_split = kv_len.div_ceil(num_splits).div_ceil(step) * step;
+        let kv_start = select(
+            split_index * kv_per_split < kv_len,
+            kv_first + split_index * kv_per_split,
+            kv_last,
+        );
+        let kv_end = select(
+            kv_start + kv_per_split < kv_last,
+            kv_start + kv_per_split,
+            kv_last,
+        );
+
+        // Partial results of each split are stacked along the batch dimension
+        let partial_batch_index = split_index * CUBE_COUNT_Y + batch_index;
+
+        // The sink takes part in the softmax of the first split only, so that it is counted once
+        // when combining splits
+        let softmax = softmax_params(score_args, head_index, split_index == 0);
+
+        GA::execute(
+            GA::init_query_reader(batch_index, bounds, stage_q_offset, query, global_config),
+            GA::init_key_reader(
+                kv_batch_index,
+                bounds,
+                kv_start,
+                key,
+                page_table,
+                global_config,
+            ),
+            GA::init_value_reader(
+                kv_batch_index,
+                bounds,
+                kv_start,
+                value,
+                page_table,
+                global_config,
+            ),
+            GA::init_mask_reader(
+                batch_index,
+                stage_q_offset,
+                kv_start,
+                mask,
+                seq_kv,
+                global_config,
+            ),
+            GA::init_writer(
+                partial_batch_index,
+                bounds,
+                stage_q_offset,
+                out,
+                global_config,
+            ),
+            GA::init_lse_writer(
+                partial_batch_index,
+                bounds,
+                stage_q_offset,
+                lse,
+                global_config,
+            ),
+            bounds.seq_q,
+            kv_start,
+            kv_end,
+            positions,
+            softmax,
+            config.global_config(),
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::{CubeOption, CubeOptionExpand};
+
+#[cube(launch_unchecked)]
+/// Combines the partial results of the key/value splits into the final output.
+///
+/// Each split `s` attended to its range of keys with log-sum-exp `lse_s`, so its partial output
+/// is weighted by `exp(lse_s - max_s lse_s)`. Splits without any unmasked key, with a log-sum-exp
+/// of negative infinity, are ignored. Rows where all splits are ignored are written as zeros.
+///
+/// Each unit writes one element of the output, with cubes along `y` for each batch and head.
+pub(crate) fn attention_split_kv_combine<E: Float, A: Float>(
+    partial_out: &Tensor<A>,
+    partial_lse: &Tensor<A>,
+    out: &mut Tensor<E>,
+    lse: &mut CubeOption<Tensor<A>>,
+    #[define(E, A)] _elem_types: [StorageType; 2],
+) {
+    let batch = out.shape(0);
+    let num_heads = out.shape(1);
+    let seq_q = out.shape(2);
+    let val_dim = out.shape(3);
+    let num_splits = partial_lse.shape(0) / batch;
+
+    let pos = CUBE_POS_X * CUBE_DIM_X + UNIT_POS_X;
+    if pos >= seq_q * val_dim {
+        terminate!();
+    }
+
+    let b = CUBE_POS_Y / num_heads;
+    let h = CUBE_POS_Y % num_heads;
+    let row = pos / val_dim;
+    let d = pos % val_dim;
+
+    let mut max = A::min_value();
+    for split in 0..num_splits {
+        let split_lse = partial_lse[lse_offset(partial_lse, split * batch + b, h, row)];
+        max = select(split_lse > max, split_lse, max);
+    }
+
+    let mut sum = A::from_int(0);
+    let mut acc = A::from_int(0);
+    for split in 0..num_splits {
+        let split_batch = split * batch + b;
+        let split_lse = partial_lse[lse_offset(partial_lse, split_batch, h, row)];
+
+        // Partial outputs of fully masked splits are not finite and must not be multiplied
+        if split_lse > A::min_value() {
+            let weight = Exp::exp(split_lse - max);
+            let offset = split_batch * partial_out.stride(0)
+                + h * partial_out.stride(1)
+                + row * partial_out.stride(2)
+                + d * partial_out.stride(3);
+
+            sum += weight;
+            acc += weight * partial_out[offset];
+        }
+    }
+
+    let out_offset =
+        b * out.stride(0) + h * out.stride(1) + row * out.stride(2) + d * out.stride(3);
+    out[out_offset] = E::cast_from(select(sum > A::from_int(0), acc / sum, A::from_int(0)));
+
+    if d == 0 {
+        match lse {
+            CubeOption::Some(lse) => {
+                // Negative infinity when all splits are ignored, since the sum is then zero
+                lse[lse_offset(lse, b, h, row)] = max + Log::log(sum);
+            }
+            CubeOption::None => {}
+        }
+    }
+}
+
+#[cube]
+fn lse_offset<A: Float>(lse: &Tensor<A>, batch: u32, head: u32, row: u32) -> u32 {
+    batch * lse.stride(0) + head * lse.stride(1) + row * lse.stride(2)
+}
@@ -0,0 +1,7 @@
+mod attention;
+mod combine;
+mod setup;
+
+pub use attention::*;
+pub(crate) use combine::attention_split_kv_combine;
+pub use setup::SplitKvBatchAttentionFamily;
@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+
+use cubecl::server::LaunchError;
+
+use crate::{
+    components::{
+        batch::{
+            BatchAttentionFamily, entry_point::attention, simple::SimpleBatchConfig,
+            split_kv::SplitKvBatchAttention,
+        },
+        global::GlobalAttentionFamily,
+    },
+    definition::{
+        AttentionBlueprint, AttentionElems, AttentionPrecision, AttentionSetupError,
+        CubeCountInputArgs, InputRuntimeArg, LseRuntimeArg, OutputRuntimeArg, ScoreArgsRuntimeArg,
+        SequenceLengthsRuntimeArg, SplitKvBlueprint,
+    },
+    launch::AttentionArgs,
+};
+
+pub struct SplitKvBatchAttentionFamily<GA: GlobalAttentionFamily> {
+    _phantom: PhantomData<GA>,
+}
+
+impl<GA: GlobalAttentionFamily> BatchAttentionFamily for SplitKvBatchAttentionFamily<GA> {
+    type Attention<AP: AttentionPrecision> = SplitKvBatchAttention<AP, GA::Attention<AP>>;
+    type Config = SimpleBatchConfig<GA::Config>;
+    type Blueprint = SplitKvBlueprint;
+
+    unsafe fn launch_unchecked<'a, AA: AttentionArgs, R: cubecl::Runtime>(
+        client: &cubecl::prelude::ComputeClient<R>,
+        cube_dim: cubecl::CubeDim,
+        cube_count: cubecl::CubeCount,
+        input: InputRuntimeArg<'a, AA, R>,
+        output: OutputRuntimeArg<'a, AA, R>,
+        lse: LseRuntimeArg<'a, R>,
+        sequence_lengths: SequenceLengthsRuntimeArg<'a, R>,
+        score_args: ScoreArgsRuntimeArg<'a, R>,
+        cube_count_input: CubeCountInputArgs<'a, R>,
+        dtypes: &AttentionElems,
+        blueprint: SplitKvBlueprint,
+    ) -> Result<(), LaunchError> {
+        unsafe {
+            attention::launch_unchecked::<AA, Self, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input,
+                output,
+                lse,
+                sequence_lengths,
+                score_args,
+                cube_count_input,
+                blueprint.attention,
+                dtypes.into(),
+            )
+        }
+    }
+
+    fn expand_blueprint(
+        blueprint: AttentionBlueprint,
+    ) -> Result<Self::Config, AttentionSetupError> {
+        let global_config = GA::expand_blueprint(&blueprint)?;
+
+        Ok(SimpleBatchConfig::new(global_config))
+    }
+}
@@ -2,12 +2,16 @@ use cubecl;
 use cubecl::prelude::*;
 
 use crate::{
-    components::global::simple::AttentionWriter,
+    components::global::simple::{AttentionWriter, LseWriter},
     definition::{AttentionBlueprint, AttentionPrecision, AttentionSetupError, attention_types::*},
 };
 use cubecl::std::{CubeOption, tensor::r#virtual::VirtualTensor};
 
-use crate::components::{global::simple::QueryReader, stage::StageAttentionConfig};
+use crate::components::{
+    global::simple::QueryReader,
+    stage::{ScorePositions, SoftmaxParams, StageAttentionConfig},
+};
+use crate::launch::KvPageTable;
 use std::{fmt::Debug, hash::Hash};
 
 /// A family of [GlobalAttention] implementations that operate with any [precision](AttentionPrecision).
@@ -41,39 +45,55 @@ pub trait GlobalAttention<AP: AttentionPrecision>: 'static + Send + Sync {
     /// The configuration type associated with this Attention.
     type Config: GlobalAttentionConfig;
 
+    /// Computes attention of the query rows of the readers over the key/value rows in
+    /// `kv_offset..seq_kv`, where `positions` places the scores for the window mask and ALiBi
     fn execute(
         query_reader: QueryReader<AP>,
         key_reader: Self::KeyReader,
         value_reader: Self::ValueReader,
         mask_reader: Self::MaskReader,
         writer: Self::Writer,
+        lse_writer: LseWriter<AP>,
         seq_q: u32,
+        kv_offset: u32,
         seq_kv: u32,
+        positions: ScorePositions,
+        softmax: SoftmaxParams,
         #[comptime] config: Self::Config,
     );
 
     fn init_query_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
         stage_q_offset: u32,
         query: VirtualTensor<QG<AP>>,
         #[comptime] config: Self::Config,
     ) -> QueryReader<AP>;
 
+    /// `page_table` is given when the key is stored in a paged cache
     fn init_key_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
+        kv_offset: u32,
         key: VirtualTensor<KG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         #[comptime] config: Self::Config,
     ) -> Self::KeyReader;
 
+    /// `page_table` is given when the value is stored in a paged cache
     fn init_value_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
+        kv_offset: u32,
         value: VirtualTensor<VG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         #[comptime] config: Self::Config,
     ) -> Self::ValueReader;
 
     fn init_mask_reader(
         batch_index: u32,
         stage_q_offset: u32,
+        kv_offset: u32,
         mask: CubeOption<VirtualTensor<MSK<AP>>>,
         seq_kv_shape: u32,
         #[comptime] config: Self::Config,
@@ -81,10 +101,35 @@ pub trait GlobalAttention<AP: AttentionPrecision>: 'static + Send + Sync {
 
     fn init_writer(
         batch_index: u32,
+        sequence: SequenceBounds,
         stage_q_offset: u32,
         out: VirtualTensor<OG<AP>, ReadWrite>,
         #[comptime] config: Self::Config,
     ) -> Self::Writer;
+
+    fn init_lse_writer(
+        batch_index: u32,
+        sequence: SequenceBounds,
+        stage_q_offset: u32,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+        #[comptime] config: Self::Config,
+    ) -> LseWriter<AP>;
+}
+
+/// Rows of the query and key/value that belong to the sequence of a cube.
+///
+/// Rows past the end of the sequence are treated as out of bounds, so that sequences packed
+/// along the `seq_*` axes never read or write each other.
+#[derive(CubeType, Clone, Copy)]
+pub struct SequenceBounds {
+    /// First query row of the sequence
+    pub q_start: u32,
+    /// Number of query rows of the sequence
+    pub seq_q: u32,
+    /// First key/value row of the sequence
+    pub kv_start: u32,
+    /// Number of key/value rows of the sequence
+    pub seq_kv: u32,
 }
 
 /// Configuration for the Global Attention level
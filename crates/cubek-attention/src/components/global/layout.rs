@@ -6,6 +6,8 @@ use cubecl::std::tensor::{
 use cubecl::{self as cubecl};
 use cubek_matmul::components::global::memory::GlobalMemoryConfig;
 
+use crate::launch::KvPageTable;
+
 /// Global layout that uses the last two dimensions and ignores all others.
 #[derive(CubeType, Clone, Copy)]
 pub struct AttentionGlobalLayout {
@@ -20,22 +22,38 @@ pub struct AttentionGlobalLayout {
 
 #[cube]
 impl AttentionGlobalLayout {
-    /// Creates a new 2D layout starting at `batch_index`.
+    /// Creates a new 2D layout starting at `batch_index`, the flattened index over the first two
+    /// dimensions. These are strided separately so that either can be broadcast.
     pub fn new<T: Numeric, IO: Clone>(
         tensor: &VirtualTensor<T, IO>,
         batch_index: u32,
         #[comptime] config: GlobalMemoryConfig,
     ) -> Self {
-        let stride_batch = tensor.stride(1);
+        let num_heads = tensor.shape(1);
+        let batch_offset = (batch_index / num_heads) * tensor.stride(0)
+            + (batch_index % num_heads) * tensor.stride(1);
+
         AttentionGlobalLayout {
             rows: tensor.shape(2),
             stride_row: tensor.stride(2),
             columns: tensor.shape(3),
             stride_col: tensor.stride(3),
-            batch_offset: batch_index * stride_batch,
+            batch_offset,
             config,
         }
     }
+
+    /// Restricts the layout to the `rows` rows starting at `start`.
+    pub fn with_rows(self, start: u32, rows: u32) -> Self {
+        AttentionGlobalLayout {
+            rows,
+            stride_row: self.stride_row,
+            columns: self.columns,
+            stride_col: self.stride_col,
+            batch_offset: self.batch_offset + start * self.stride_row,
+            config: self.config,
+        }
+    }
 }
 
 #[cube]
@@ -70,3 +88,134 @@ impl Layout for AttentionGlobalLayout {
         }
     }
 }
+
+/// Global layout of one head of a sequence whose key or value is stored in a paged cache of
+/// shape `[num_pages, num_kv_heads, page_size, dim]`.
+///
+/// The offset of the page holding the rows of the current stage is resolved once per stage with
+/// [resolve_stage](PagedGlobalLayout::resolve_stage) and shared by every clone of the layout.
+/// Only rows of another page, when a stage spans several pages, look up the block table.
+#[derive(CubeType, Clone)]
+pub struct PagedGlobalLayout {
+    page_table: KvPageTable,
+    rows: u32,
+    columns: u32,
+    page_size: u32,
+    stride_page: u32,
+    stride_row: u32,
+    stride_col: u32,
+    /// Offset of the head within a page
+    head_offset: u32,
+    /// Offset of the sequence in the block table
+    table_offset: u32,
+    table_stride: u32,
+    context_length: u32,
+    stage_page: RuntimeCell<u32>,
+    stage_page_offset: RuntimeCell<u32>,
+    #[cube(comptime)]
+    config: GlobalMemoryConfig,
+}
+
+#[cube]
+impl PagedGlobalLayout {
+    /// Creates a new 2D layout over the first `rows` rows of `batch_index`, the flattened index
+    /// over the sequences and heads.
+    pub fn new<T: Numeric>(
+        tensor: &VirtualTensor<T>,
+        page_table: KvPageTable,
+        batch_index: u32,
+        rows: u32,
+        #[comptime] config: GlobalMemoryConfig,
+    ) -> Self {
+        let num_heads = tensor.shape(1);
+        let batch = batch_index / num_heads;
+        let max_pages = unsafe { (*page_table.block_table).shape(1) };
+        let table_offset = unsafe { batch * (*page_table.block_table).stride(0) };
+        let table_stride = unsafe { (*page_table.block_table).stride(1) };
+        let context_length = unsafe {
+            (*page_table.context_lengths)[batch * (*page_table.context_lengths).stride(0)]
+        };
+
+        PagedGlobalLayout {
+            page_table,
+            rows,
+            columns: tensor.shape(3),
+            page_size: tensor.shape(2) / max_pages,
+            stride_page: tensor.stride(0),
+            stride_row: tensor.stride(2),
+            stride_col: tensor.stride(3),
+            head_offset: (batch_index % num_heads) * tensor.stride(1),
+            table_offset,
+            table_stride,
+            context_length,
+            stage_page: RuntimeCell::new(u32::MAX),
+            stage_page_offset: RuntimeCell::new(0u32),
+            config,
+        }
+    }
+
+    /// Resolves the page holding `row`, which following reads of its rows use without looking up
+    /// the block table.
+    pub fn resolve_stage(&self, row: u32) {
+        let page_index = row / self.page_size;
+        self.stage_page.store(page_index);
+        self.stage_page_offset.store(self.page_offset(page_index));
+    }
+
+    /// Offset in the cache of the head within the page at `page_index` of the sequence.
+    ///
+    /// Pages past the context length are read from the first page instead, since their block
+    /// table entry may not be a valid page. Their rows are masked by the attention anyway.
+    fn page_offset(&self, page_index: u32) -> u32 {
+        let in_context = page_index * self.page_size < self.context_length;
+        let table_index = select(
+            in_context,
+            self.table_offset + page_index * self.table_stride,
+            self.table_offset,
+        );
+        let page = unsafe { (*self.page_table.block_table)[table_index] };
+        let page = select(in_context, page, 0u32);
+
+        page * self.stride_page + self.head_offset
+    }
+}
+
+#[cube]
+impl Layout for PagedGlobalLayout {
+    type Coordinates = Coords2d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, coords: Self::Coordinates) -> u32 {
+        let line_size = comptime![self.config.line_size];
+        let (row, col) = coords;
+        let page_index = row / self.page_size;
+
+        let mut page_offset = self.stage_page_offset.read();
+        if page_index != self.stage_page.read() {
+            page_offset = self.page_offset(page_index);
+        }
+
+        let idx = page_offset + (row % self.page_size) * self.stride_row + col * self.stride_col;
+
+        idx / line_size
+    }
+
+    fn to_source_pos_checked(&self, coords: Self::Coordinates) -> (u32, bool) {
+        (self.to_source_pos(coords), self.is_in_bounds(coords))
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        (self.rows, self.columns)
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        let (row, col) = pos;
+
+        match comptime!((self.config.check_row_bounds, self.config.check_col_bounds)) {
+            (true, true) => row < self.rows && col < self.columns,
+            (true, false) => row < self.rows,
+            (false, true) => col < self.columns,
+            (false, false) => true,
+        }
+    }
+}
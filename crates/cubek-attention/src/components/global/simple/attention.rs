@@ -3,20 +3,21 @@ use cubecl::prelude::*;
 use cubecl::std::tensor::r#virtual::VirtualTensor;
 use cubecl::std::{CubeOption, CubeOptionExpand};
 use cubek_matmul::components::global::PartitionedStage;
-use cubek_matmul::components::global::read::FullStageGlobalReader;
 use cubek_matmul::components::stage::StridedStageMemory;
 use std::marker::PhantomData;
 
-use crate::components::global::AttentionGlobalLayout;
-use crate::components::global::simple::QueryReader;
-use crate::components::global::simple::{AttentionWriter, AttentionWriterExpand, MaskReader};
+use crate::components::global::simple::{
+    AttentionWriter, AttentionWriterExpand, KeyValueReader, LseWriter, MaskReader, QueryReader,
+};
+use crate::components::global::{AttentionGlobalLayout, SequenceBounds};
 use crate::components::global::{GlobalAttention, simple::config::SimpleGlobalAttentionConfig};
 use crate::components::stage::{
-    AttentionLoadingStrategy, AttentionPartitioner, AttentionTilingLayout, StageAttention,
+    AttentionPartitioner, AttentionTilingLayout, ScorePositions, SoftmaxParams, StageAttention,
     StageAttentionConfig as _,
 };
 use crate::definition::AttentionPrecision;
 use crate::definition::attention_types::*;
+use crate::launch::KvPageTable;
 
 pub struct SimpleGlobalAttention<AP: AttentionPrecision, SA: StageAttention<AP>> {
     _phantom: PhantomData<(AP, SA)>,
@@ -33,8 +34,8 @@ impl<
     AP: AttentionPrecision,
 > GlobalAttention<AP> for SimpleGlobalAttention<AP, SA>
 {
-    type KeyReader = FullStageGlobalReader<KG<AP>, KS<AP>, AttentionLoadingStrategy>;
-    type ValueReader = FullStageGlobalReader<VG<AP>, VS<AP>, AttentionLoadingStrategy>;
+    type KeyReader = KeyValueReader<KG<AP>, KS<AP>>;
+    type ValueReader = KeyValueReader<VG<AP>, VS<AP>>;
     type MaskReader = MaskReader<AP>;
 
     type Writer = <SA::Partitioner as AttentionPartitioner>::Writer<OS<AP>, OG<AP>>;
@@ -47,8 +48,12 @@ impl<
         mut value_reader: Self::ValueReader,
         mut mask_reader: Self::MaskReader,
         mut writer: Self::Writer,
+        mut lse_writer: LseWriter<AP>,
         seq_q: u32,
+        kv_offset: u32,
         seq_kv: u32,
+        positions: ScorePositions,
+        softmax: SoftmaxParams,
         #[comptime] config: Self::Config,
     ) {
         // Load queries which stay alive in registers for all the kernel
@@ -57,17 +62,20 @@ impl<
 
         // Init registers that will change inside global loop
         let mut key_value_registers = SA::init_key_value(config.stage_config);
-        let mut mask_registers =
-            SA::init_mask(CubeOption::new_Some((seq_q, seq_kv)), config.stage_config);
+        let mut mask_registers = SA::init_mask(
+            CubeOption::new_Some((seq_q, seq_kv)),
+            positions,
+            config.stage_config,
+        );
         let mut softmax_registers = SA::init_softmax(config.stage_config);
         let mut accumulator_registers = SA::init_accumulator(config.stage_config);
 
-        // Init running state
-        let mut stage_state = SA::init_state(config.stage_config);
+        // Init running state, which already accounts for the sink
+        let mut stage_state = SA::init_state(softmax.sink, config.stage_config);
 
         // Define number of global iterations
         let num_stage_iterations =
-            seq_kv.div_ceil(config.stage_config.elements_in_partition_seq_kv());
+            (seq_kv - kv_offset).div_ceil(config.stage_config.elements_in_partition_seq_kv());
 
         let mut barrier = ();
 
@@ -90,6 +98,7 @@ impl<
                 &mut softmax_registers,
                 &mut accumulator_registers,
                 &mut stage_state,
+                &softmax,
                 config.stage_config,
             );
 
@@ -101,6 +110,9 @@ impl<
             mask_reader.advance_view();
         }
 
+        // Running state holds the statistics of the full rows
+        SA::write_lse(&stage_state, &mut lse_writer, config.stage_config);
+
         // Accumulators must be rescaled using running state
         SA::rescale(&mut accumulator_registers, stage_state, config.stage_config);
 
@@ -116,40 +128,61 @@ impl<
 
     fn init_query_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
         stage_q_offset: u32,
         query: VirtualTensor<QG<AP>>,
         #[comptime] config: Self::Config,
     ) -> QueryReader<AP> {
-        let layout = AttentionGlobalLayout::new(&query, batch_index, config.query_gmem_config);
+        let layout = AttentionGlobalLayout::new(&query, batch_index, config.query_gmem_config)
+            .with_rows(sequence.q_start, sequence.seq_q);
 
         QueryReader::<AP>::new(stage_q_offset, query.view(layout), config.query_gmem_config)
     }
 
     fn init_key_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
+        kv_offset: u32,
         key: VirtualTensor<KG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         #[comptime] config: Self::Config,
     ) -> Self::KeyReader {
         let step = config.stage_config.elements_in_partition_seq_kv().runtime();
-        let layout =
-            AttentionGlobalLayout::new(&key, batch_index, config.key_reader_config.gmem_config);
-        FullStageGlobalReader::new(key.view(layout), step, config.key_reader_config)
+        KeyValueReader::new(
+            key,
+            batch_index,
+            sequence,
+            kv_offset,
+            page_table,
+            step,
+            config.key_reader_config,
+        )
     }
 
     fn init_value_reader(
         batch_index: u32,
+        sequence: SequenceBounds,
+        kv_offset: u32,
         value: VirtualTensor<VG<AP>>,
+        page_table: CubeOption<KvPageTable>,
         #[comptime] config: Self::Config,
     ) -> Self::ValueReader {
         let step = config.stage_config.elements_in_partition_seq_kv().runtime();
-        let layout =
-            AttentionGlobalLayout::new(&value, batch_index, config.value_reader_config.gmem_config);
-        FullStageGlobalReader::new(value.view(layout), step, config.value_reader_config)
+        KeyValueReader::new(
+            value,
+            batch_index,
+            sequence,
+            kv_offset,
+            page_table,
+            step,
+            config.value_reader_config,
+        )
     }
 
     fn init_mask_reader(
         batch_index: u32,
         stage_q_offset: u32,
+        kv_offset: u32,
         mask: CubeOption<VirtualTensor<MSK<AP>>>,
         seq_kv_shape: u32,
         #[comptime] config: Self::Config,
@@ -166,24 +199,29 @@ impl<
                 MaskReader::new_materialized(
                     stage_q_offset,
                     partition_q_offset,
+                    kv_offset,
                     mask.view(layout),
                     step,
                     seq_kv_shape,
                     config.mask_gmem_config,
                 )
             }
-            CubeOption::None => MaskReader::new_logical(stage_q_offset + partition_q_offset, step),
+            CubeOption::None => {
+                MaskReader::new_logical(stage_q_offset + partition_q_offset, kv_offset, step)
+            }
         }
     }
 
     fn init_writer(
         batch_index: u32,
+        sequence: SequenceBounds,
         stage_q_offset: u32,
         out: VirtualTensor<OG<AP>, ReadWrite>,
         #[comptime] config: Self::Config,
     ) -> Self::Writer {
         let layout =
-            AttentionGlobalLayout::new(&out, batch_index, config.writer_config.gmem_config);
+            AttentionGlobalLayout::new(&out, batch_index, config.writer_config.gmem_config)
+                .with_rows(sequence.q_start, sequence.seq_q);
         let out = out.view_mut(layout);
 
         Self::Writer::init::<SA::Config>(
@@ -191,4 +229,14 @@ impl<
             config.writer_config,
         )
     }
+
+    fn init_lse_writer(
+        batch_index: u32,
+        sequence: SequenceBounds,
+        stage_q_offset: u32,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+        #[comptime] _config: Self::Config,
+    ) -> LseWriter<AP> {
+        LseWriter::<AP>::new(batch_index, sequence, stage_q_offset, lse)
+    }
 }
@@ -0,0 +1,112 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::tensor::r#virtual::VirtualTensor;
+use cubecl::std::{CubeOption, CubeOptionExpand};
+use cubek_matmul::components::global::GlobalReaderConfig;
+use cubek_matmul::components::global::read::{
+    FullLoadingStrategy, FullStageGlobalReader, SyncBarrier,
+};
+use cubek_matmul::components::stage::StridedStageMemory;
+
+use crate::components::global::{AttentionGlobalLayout, PagedGlobalLayout, SequenceBounds};
+use crate::components::stage::{AttentionLoadingStrategy, AttentionTilingLayout};
+use crate::launch::KvPageTable;
+
+type KeyValueBarrier = SyncBarrier<<AttentionLoadingStrategy as FullLoadingStrategy>::SyncStrategy>;
+
+/// Loads the key or the value into stage memory, one stage of `seq_kv` rows at a time.
+///
+/// When they are stored in a paged cache, the page of each stage is resolved once before loading
+/// it, instead of for every line.
+#[derive(CubeType)]
+pub struct KeyValueReader<EG: Numeric, ES: Numeric> {
+    reader: FullStageGlobalReader<EG, ES, AttentionLoadingStrategy>,
+    pages: CubeOption<PageCursor>,
+}
+
+/// Row of the paged cache the next stage starts at.
+#[derive(CubeType)]
+pub struct PageCursor {
+    layout: PagedGlobalLayout,
+    row: RuntimeCell<u32>,
+    step: u32,
+}
+
+#[cube]
+impl<EG: Numeric, ES: Numeric> KeyValueReader<EG, ES> {
+    /// Creates a reader over the rows of `sequence` starting at `kv_offset`, for the head at
+    /// `batch_index`, the flattened index over the first two dimensions.
+    pub fn new(
+        tensor: VirtualTensor<EG>,
+        batch_index: u32,
+        sequence: SequenceBounds,
+        kv_offset: u32,
+        page_table: CubeOption<KvPageTable>,
+        step: u32,
+        #[comptime] config: GlobalReaderConfig,
+    ) -> Self {
+        match page_table {
+            CubeOption::Some(page_table) => {
+                // Paged sequences are never packed, so they start at the first row
+                let layout = PagedGlobalLayout::new(
+                    &tensor,
+                    page_table,
+                    batch_index,
+                    sequence.seq_kv,
+                    config.gmem_config,
+                );
+                let view = tensor.view(layout.clone());
+                let view = view.slice((kv_offset, 0), view.shape());
+
+                KeyValueReader::<EG, ES> {
+                    reader: FullStageGlobalReader::new(view, step, config),
+                    pages: CubeOption::new_Some(PageCursor {
+                        layout,
+                        row: RuntimeCell::new(kv_offset),
+                        step,
+                    }),
+                }
+            }
+            CubeOption::None => {
+                let layout = AttentionGlobalLayout::new(&tensor, batch_index, config.gmem_config)
+                    .with_rows(sequence.kv_start, sequence.seq_kv);
+                let view = tensor.view(layout);
+                let view = view.slice((kv_offset, 0), view.shape());
+
+                KeyValueReader::<EG, ES> {
+                    reader: FullStageGlobalReader::new(view, step, config),
+                    pages: CubeOption::new_None(),
+                }
+            }
+        }
+    }
+
+    /// Give a reader to the loaded stage memory.
+    pub fn stage(&self) -> StridedStageMemory<ES, AttentionTilingLayout> {
+        self.reader.stage()
+    }
+
+    /// Load the current stage into stage memory.
+    pub fn load_stage(
+        &mut self,
+        barrier: &mut KeyValueBarrier,
+        #[comptime] config: GlobalReaderConfig,
+    ) {
+        match &self.pages {
+            CubeOption::Some(cursor) => cursor.layout.resolve_stage(cursor.row.read()),
+            CubeOption::None => {}
+        }
+
+        self.reader.load_stage(barrier, config);
+    }
+
+    /// Advance to the next stage along `seq_kv`.
+    pub fn advance_view(&mut self) {
+        self.reader.advance_view();
+
+        match &self.pages {
+            CubeOption::Some(cursor) => cursor.row.store(cursor.row.read() + cursor.step),
+            CubeOption::None => {}
+        }
+    }
+}
@@ -20,10 +20,10 @@ pub struct LogicalIterator {
 
 #[cube]
 impl LogicalIterator {
-    fn init(stage_q_offset: u32, step_col: u32) -> LogicalIterator {
+    fn init(stage_q_offset: u32, kv_offset: u32, step_col: u32) -> LogicalIterator {
         LogicalIterator {
             row: stage_q_offset,
-            col: RuntimeCell::new(0),
+            col: RuntimeCell::new(kv_offset),
             step_col,
         }
     }
@@ -55,24 +55,25 @@ pub enum MaskReader<AP: AttentionPrecision> {
 
 #[cube]
 impl<AP: AttentionPrecision> MaskReader<AP> {
-    pub fn new_logical(partition_q_offset: u32, step: u32) -> Self {
-        MaskReader::<AP>::new_Logical(LogicalIterator::init(partition_q_offset, step))
+    pub fn new_logical(partition_q_offset: u32, kv_offset: u32, step: u32) -> Self {
+        MaskReader::<AP>::new_Logical(LogicalIterator::init(partition_q_offset, kv_offset, step))
     }
 
     pub fn new_materialized(
         stage_q_offset: u32,
         partition_q_offset: u32,
+        kv_offset: u32,
         mask: View<Line<MSK<AP>>, Coords2d>,
         step: u32,
         seq_kv_shape: u32,
         #[comptime] gmem_config: GlobalMemoryConfig,
     ) -> Self {
-        let mask = mask.slice((stage_q_offset, 0), mask.shape());
+        let mask = mask.slice((stage_q_offset, kv_offset), mask.shape());
         let global_iter = GlobalIterator::new(mask, step, gmem_config.view_direction, false);
 
         MaskReader::<AP>::new_Materialized(MaterializedMaskReader::new(
             global_iter,
-            LogicalIterator::init(partition_q_offset, step),
+            LogicalIterator::init(partition_q_offset, kv_offset, step),
             seq_kv_shape,
             gmem_config,
         ))
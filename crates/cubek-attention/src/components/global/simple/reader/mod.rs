@@ -1,5 +1,7 @@
+mod key_value;
 mod mask;
 mod query;
 
+pub use key_value::*;
 pub use mask::*;
 pub use query::*;
@@ -122,6 +122,7 @@ impl<
             smem_config: stage_config.out_smem_config(),
             role_rule_config: RoleRuleConfig::MainFlowOnly,
             plane_dim,
+            epilogue: Default::default(),
         };
 
         Ok(SimpleGlobalAttentionConfig {
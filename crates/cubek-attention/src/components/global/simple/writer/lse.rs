@@ -0,0 +1,53 @@
+use cubecl;
+use cubecl::prelude::*;
+use cubecl::std::{CubeOption, CubeOptionExpand, tensor::r#virtual::VirtualTensor};
+
+use crate::components::global::SequenceBounds;
+use crate::definition::AttentionPrecision;
+use crate::definition::attention_types::ACC;
+
+#[derive(CubeType)]
+/// Writes the log-sum-exp of each query row to the optional `[batch, heads, seq_q]` output,
+/// or does nothing if no such output was given.
+pub struct LseWriter<AP: AttentionPrecision> {
+    lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+    batch_index: u32,
+    sequence: SequenceBounds,
+    stage_q_offset: u32,
+}
+
+#[cube]
+impl<AP: AttentionPrecision> LseWriter<AP> {
+    pub fn new(
+        batch_index: u32,
+        sequence: SequenceBounds,
+        stage_q_offset: u32,
+        lse: CubeOption<VirtualTensor<ACC<AP>, ReadWrite>>,
+    ) -> Self {
+        LseWriter::<AP> {
+            lse,
+            batch_index,
+            sequence,
+            stage_q_offset,
+        }
+    }
+
+    /// Writes the log-sum-exp of the row at `row_in_stage`, ignoring rows out of bounds
+    pub fn write(&mut self, row_in_stage: u32, value: ACC<AP>) {
+        match &mut self.lse {
+            CubeOption::Some(lse) => {
+                let row = self.stage_q_offset + row_in_stage;
+
+                if row < self.sequence.seq_q {
+                    let num_heads = lse.shape(1);
+                    let offset = (self.batch_index / num_heads) * lse.stride(0)
+                        + (self.batch_index % num_heads) * lse.stride(1)
+                        + (self.sequence.q_start + row) * lse.stride(2);
+
+                    lse.write(offset, Line::cast_from(value));
+                }
+            }
+            CubeOption::None => {}
+        }
+    }
+}
@@ -3,10 +3,12 @@ use cubecl::{self as cubecl};
 
 use cubek_matmul::components::global::{GlobalWriterConfig, PartitionedStage, WriteEventListener};
 
+mod lse;
 mod plane;
 mod unit;
 
 use cubecl::std::tensor::{View, layout::Coords2d};
+pub use lse::*;
 pub use plane::*;
 pub use unit::*;
 
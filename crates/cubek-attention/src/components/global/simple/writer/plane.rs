@@ -2,8 +2,8 @@ use cubecl::prelude::*;
 use cubecl::std::tensor::{View, layout::Coords2d};
 use cubecl::{self as cubecl};
 use cubek_matmul::components::global::{
-    GlobalWriterConfig, PartitionedStage, WriteEvent, WriteEventExpand, WriteEventListener,
-    plane_write,
+    EpilogueReader, GlobalWriterConfig, PartitionedStage, WriteEvent, WriteEventExpand,
+    WriteEventListener, plane_write,
     read::tiled::{TiledCoords, TiledLayout},
 };
 use cubek_matmul::definition::StageIdent;
@@ -33,6 +33,7 @@ impl<ES: Numeric, EG: Numeric> WriteEventListener for PlaneAttentionWriter<ES, E
             WriteEvent::TileStored { tile } => plane_write::<ES, EG>(
                 &mut this.global,
                 &this.stage.unit_tile,
+                &EpilogueReader::none(),
                 tile,
                 this.config.plane_dim,
                 comptime!(this.config.smem_config.elements_per_tile()),
@@ -2,7 +2,8 @@ use cubecl::prelude::*;
 use cubecl::std::tensor::{View, layout::Coords2d};
 use cubecl::{self as cubecl};
 use cubek_matmul::components::global::{
-    GlobalWriterConfig, PartitionedStage, WriteEvent, WriteEventExpand, WriteEventListener,
+    EpilogueReader, GlobalWriterConfig, PartitionedStage, WriteEvent, WriteEventExpand,
+    WriteEventListener,
     read::tiled::{TiledCoords, TiledLayout},
     unit_write,
 };
@@ -30,6 +31,7 @@ impl<ES: Numeric, EG: Numeric> WriteEventListener for UnitAttentionWriter<ES, EG
             WriteEvent::TileStored { tile } => unit_write::<ES, EG>(
                 &mut this.global,
                 &this.stage.unit_tile,
+                &EpilogueReader::none(),
                 tile,
                 comptime!(this.config.smem_config.elements_per_tile()),
             ),
@@ -1,3 +1,4 @@
+pub mod backward;
 pub mod batch;
 pub mod global;
 pub mod stage;
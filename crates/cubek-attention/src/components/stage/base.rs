@@ -14,17 +14,23 @@ use crate::{
     components::{global::GlobalAttentionConfig, stage::RunningState},
     definition::attention_types::*,
 };
-use crate::{
-    components::{global::simple::MaskReader, stage::AttentionPartitioner},
-    definition::AttentionSetupError,
-};
 use crate::{
     components::{
         global::simple::QueryReader,
-        stage::{plane::PlanePartitionStageConfig, unit::UnitPartitionStageConfig},
+        stage::{
+            ScorePositions, SoftmaxParams, plane::PlanePartitionStageConfig,
+            unit::UnitPartitionStageConfig,
+        },
     },
     definition::AttentionBlueprint,
 };
+use crate::{
+    components::{
+        global::simple::{LseWriter, MaskReader},
+        stage::AttentionPartitioner,
+    },
+    definition::AttentionSetupError,
+};
 use cubecl::std::CubeOption;
 use cubecl::std::tensor::layout::Coords2d;
 
@@ -73,7 +79,8 @@ pub trait StageAttention<AP: AttentionPrecision>: 'static + Send + Sync {
     type AccumulatorRegisters: CubeType;
     type MaskRegisters: CubeType;
 
-    fn init_state(#[comptime] config: Self::Config) -> Sequence<RunningState<SM<AP>>>;
+    /// Init the running state of every row, including the given sink logit
+    fn init_state(sink: f32, #[comptime] config: Self::Config) -> Sequence<RunningState<SM<AP>>>;
 
     fn execute(
         query: &Self::QueryRegisters,
@@ -85,6 +92,14 @@ pub trait StageAttention<AP: AttentionPrecision>: 'static + Send + Sync {
         score: &mut Self::SoftmaxRegisters,
         accumulator: &mut Self::AccumulatorRegisters,
         prev_state: &mut Sequence<RunningState<SM<AP>>>,
+        softmax: &SoftmaxParams,
+        #[comptime] config: Self::Config,
+    );
+
+    /// Writes the log-sum-exp of each row from the final running state
+    fn write_lse(
+        state: &Sequence<RunningState<SM<AP>>>,
+        writer: &mut LseWriter<AP>,
         #[comptime] config: Self::Config,
     );
 
@@ -105,6 +120,7 @@ pub trait StageAttention<AP: AttentionPrecision>: 'static + Send + Sync {
     fn init_key_value(#[comptime] config: Self::Config) -> Self::KeyValueRegisters;
     fn init_mask(
         out_of_bounds: CubeOption<Coords2d>,
+        positions: ScorePositions,
         #[comptime] config: Self::Config,
     ) -> Self::MaskRegisters;
     fn init_softmax(#[comptime] config: Self::Config) -> Self::SoftmaxRegisters;
@@ -2,7 +2,7 @@ use cubecl;
 use cubecl::prelude::*;
 
 use crate::components::stage::StageAttentionConfig;
-use crate::components::stage::{MaskTile, PartitionAttentionConfig};
+use crate::components::stage::{MaskTile, PartitionAttentionConfig, ScorePositions};
 use crate::components::tile::TileAttention;
 use crate::definition::AttentionPrecision;
 use cubecl::std::CubeOption;
@@ -18,11 +18,16 @@ pub struct MaskPartition<AP: AttentionPrecision, TA: TileAttention<AP>> {
 impl<AP: AttentionPrecision, TA: TileAttention<AP>> MaskPartition<AP, TA> {
     pub fn new(
         out_of_bounds: CubeOption<Coords2d>,
+        positions: ScorePositions,
         #[comptime] config: PartitionAttentionConfig<TA::Config>,
     ) -> MaskPartition<AP, TA> {
         let mut sequence = Sequence::new();
 
-        sequence.push(MaskTile::new(out_of_bounds, config.tile_config()));
+        sequence.push(MaskTile::new(
+            out_of_bounds,
+            positions,
+            config.tile_config(),
+        ));
 
         MaskPartition::<AP, TA> { sequence }
     }
@@ -8,14 +8,20 @@ use cubek_matmul::components::{
 use std::marker::PhantomData;
 
 use crate::components::{
-    global::simple::{MaskReader, QueryReader},
-    stage::{AccumulatorPartition, MaskPartition, partitioner::AttentionPartitioner},
+    global::simple::{LseWriter, MaskReader, QueryReader},
+    stage::{
+        AccumulatorPartition, MaskPartition, ScorePositions, SoftmaxParams,
+        partitioner::AttentionPartitioner,
+    },
 };
 use crate::components::{
     stage::{
         KeyValuePartition, QueryPartition, RunningState, SoftmaxPartition, StageAttentionConfig,
     },
-    tile::{FragmentSoftmax, FragmentSoftmaxExpand, RowWise, TileAttention},
+    tile::{
+        FragmentLayout, FragmentLayoutExpand, FragmentSoftmax, FragmentSoftmaxExpand, RowWise,
+        TileAttention,
+    },
 };
 use crate::{components::stage::StageAttention, definition::AttentionPrecision};
 use crate::{
@@ -82,6 +88,7 @@ impl<
         softmax_partition: &mut SoftmaxPartition<AP, TA>,
         accumulator_partition: &mut AccumulatorPartition<AP, TA>,
         state: &mut Sequence<RunningState<SM<AP>>>,
+        softmax: &SoftmaxParams,
         #[comptime] config: Self::Config,
     ) {
         let p = config.shared().partition_size;
@@ -145,7 +152,7 @@ impl<
                     state_q,
                     &mut max_placeholder,
                     &mut sum_placeholder,
-                    p.head_dim * config.tile_config().attention_tile_size().head_dim,
+                    softmax,
                     config.tile_config(),
                 );
 
@@ -180,6 +187,33 @@ impl<
         }
     }
 
+    fn write_lse(
+        state: &Sequence<RunningState<SM<AP>>>,
+        writer: &mut LseWriter<AP>,
+        #[comptime] config: Self::Config,
+    ) {
+        let p = config.shared().partition_size;
+        let tile_seq_q = config.tile_config().attention_tile_size().seq_q;
+        let layout = TA::softmax_layout(config.tile_config());
+
+        #[unroll]
+        for q in 0..p.seq_q {
+            let lse = state.index(q).log_sum_exp();
+            let tile_row_offset = (P::seq_q_index() * p.seq_q + q) * tile_seq_q;
+
+            #[unroll]
+            for r in 0..config.shared().tile_config.num_rows_per_unit() {
+                // Units sharing a row hold the same statistics, only the one holding
+                // the first column writes it
+                let (row, col) = layout.absolute_pos((r, 0).runtime());
+
+                if col == 0 {
+                    writer.write(tile_row_offset + row, ACC::<AP>::cast_from(lse.index(r)));
+                }
+            }
+        }
+    }
+
     fn rescale(
         acc: &mut AccumulatorPartition<AP, TA>,
         state: Sequence<RunningState<SM<AP>>>,
@@ -198,13 +232,14 @@ impl<
         }
     }
 
-    fn init_state(#[comptime] config: Self::Config) -> Sequence<RunningState<SM<AP>>> {
+    fn init_state(sink: f32, #[comptime] config: Self::Config) -> Sequence<RunningState<SM<AP>>> {
         let partition_seq_q = config.shared().partition_size.seq_q;
         let mut sequence = Sequence::new();
 
         #[unroll]
         for _ in 0..partition_seq_q {
-            sequence.push(RunningState::<SM<AP>>::init(
+            sequence.push(RunningState::<SM<AP>>::init_with_sink(
+                sink,
                 config.shared().tile_config.num_rows_per_unit(),
             ));
         }
@@ -260,9 +295,10 @@ impl<
 
     fn init_mask(
         out_of_bounds: CubeOption<Coords2d>,
+        positions: ScorePositions,
         #[comptime] config: Self::Config,
     ) -> MaskPartition<AP, TA> {
-        MaskPartition::<AP, TA>::new(out_of_bounds, config)
+        MaskPartition::<AP, TA>::new(out_of_bounds, positions, config)
     }
 
     fn read_query(
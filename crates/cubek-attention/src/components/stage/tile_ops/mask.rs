@@ -7,8 +7,8 @@ use crate::components::tile::{
     FragmentLayout, FragmentLayoutExpand, FragmentMask, FragmentMaskExpand,
 };
 use crate::components::tile::{TileAttention, TileAttentionConfig};
-use crate::definition::AttentionPrecision;
 use crate::definition::attention_types::MSK;
+use crate::definition::{AttentionPrecision, AttentionWindow, DiagonalAlignment};
 use cubek_matmul::components::tile::StridedTile;
 
 use cubecl::std::tensor::layout::Coordinates;
@@ -19,7 +19,7 @@ use cubecl::std::tensor::layout::Coordinates;
 pub enum MaskTile<AP: AttentionPrecision, TA: TileAttention<AP>> {
     /// When a mask tensor is supplied. Also contains a logical part
     Materialized(MaterializedTileMask<AP, TA>),
-    /// When no mask tensor is supplied. Used for out of bounds and causal mask
+    /// When no mask tensor is supplied. Used for out of bounds, window mask and ALiBi
     Logical(LogicalTileMask<TA::FragmentLayout>),
 }
 
@@ -27,11 +27,14 @@ pub enum MaskTile<AP: AttentionPrecision, TA: TileAttention<AP>> {
 impl<AP: AttentionPrecision, TA: TileAttention<AP>> MaskTile<AP, TA> {
     pub fn new(
         out_of_bounds: CubeOption<Coords2d>,
+        positions: ScorePositions,
         #[comptime] config: TA::Config,
     ) -> MaskTile<AP, TA> {
         let logical_mask = LogicalTileMask::<TA::FragmentLayout> {
             logical_iter_origin: LogicalIterOrigin::init(),
-            causal: config.causal_mask(),
+            window: config.window(),
+            alibi: config.alibi(),
+            positions,
             out_of_bounds,
             fragment_layout: TA::softmax_layout(config),
         };
@@ -94,8 +97,13 @@ pub struct LogicalTileMask<F: FragmentLayout> {
     // Indicates where the logical mask currently starts
     logical_iter_origin: LogicalIterOrigin,
     #[cube(comptime)]
-    // Whether to apply causal mask
-    causal: bool,
+    // Band around the diagonal outside of which positions are masked, including causal mask
+    window: AttentionWindow,
+    #[cube(comptime)]
+    // Whether to add the ALiBi bias
+    alibi: bool,
+    // Alignment on the diagonal and ALiBi slope of the scores
+    positions: ScorePositions,
     // Coordinates over which softmax is out of bounds, corresponds to seq_q, seq_kv of the problem
     out_of_bounds: CubeOption<Coords2d>,
     // Allows mapping local position of a unit to its absolute position
@@ -109,14 +117,50 @@ impl<F: FragmentLayout> LogicalTileMask<F> {
 
         let pos = Coords2d::add(self.logical_iter_origin.read(), pos_in_tile);
 
-        let causal_masked = self.causal && pos.0 < pos.1;
+        let q_pos = pos.0 + self.positions.diagonal.0;
+        let kv_pos = pos.1 + self.positions.diagonal.1;
+        let window = comptime![self.window];
+
+        let mut window_masked = false;
+        if comptime![window.right.is_some()] {
+            window_masked = kv_pos > q_pos + comptime![window.right.unwrap()];
+        }
+        if comptime![window.left.is_some()] {
+            window_masked = window_masked || kv_pos + comptime![window.left.unwrap()] < q_pos;
+        }
 
         let oob_masked = match self.out_of_bounds {
             CubeOption::Some(bounds) => !Coords2d::is_in_bounds(&pos, &bounds),
             CubeOption::None => false,
         };
 
-        causal_masked || oob_masked
+        window_masked || oob_masked
+    }
+
+    /// ALiBi bias of the score at `local_pos`, zero without ALiBi
+    pub fn alibi_bias<B: Float>(&self, local_pos: Coords2d) -> B {
+        let mut bias = B::from_int(0);
+
+        if comptime![self.alibi] {
+            let pos_in_tile = self.fragment_layout.absolute_pos(local_pos);
+            let pos = Coords2d::add(self.logical_iter_origin.read(), pos_in_tile);
+
+            let q_pos = pos.0 + self.positions.diagonal.0;
+            let kv_pos = pos.1 + self.positions.diagonal.1;
+            let distance = Max::max(q_pos, kv_pos) - Min::min(q_pos, kv_pos);
+
+            bias = -B::cast_from(self.positions.alibi_slope) * B::cast_from(distance);
+        }
+
+        bias
+    }
+
+    pub fn score_bias<B: Float>(&self, local_pos: Coords2d) -> B {
+        select(
+            self.should_mask(local_pos),
+            B::min_value(),
+            self.alibi_bias::<B>(local_pos),
+        )
     }
 
     pub fn update_origin(&mut self, new_origin: Coords2d) {
@@ -124,6 +168,98 @@ impl<F: FragmentLayout> LogicalTileMask<F> {
     }
 }
 
+#[derive(CubeType, Clone, Copy)]
+/// Where the scores of a sequence and head lie relative to the diagonal, which the window mask
+/// and ALiBi bias depend on
+pub struct ScorePositions {
+    /// Shifts of the query and key/value positions that align them on the diagonal
+    pub diagonal: Coords2d,
+    /// ALiBi slope of the head, unused without ALiBi
+    pub alibi_slope: f32,
+}
+
+#[cube]
+impl ScorePositions {
+    pub fn new(
+        seq_q: u32,
+        seq_kv: u32,
+        head_index: u32,
+        num_heads: u32,
+        #[comptime] window: AttentionWindow,
+        #[comptime] alibi: bool,
+    ) -> ScorePositions {
+        let mut alibi_slope = 0f32;
+        if alibi {
+            alibi_slope = alibi_slope_of_head(head_index, num_heads);
+        }
+
+        ScorePositions {
+            diagonal: window_diagonal(seq_q, seq_kv, window),
+            alibi_slope,
+        }
+    }
+}
+
+/// ALiBi slope of a head: `2^(-8 (h + 1) / n)` for a power of two number of heads `n`,
+/// otherwise those of the largest power of two below, followed by every other slope of twice as
+/// many heads
+#[cube]
+pub fn alibi_slope_of_head(head_index: u32, num_heads: u32) -> f32 {
+    let mut pow2 = 1u32;
+    while pow2 * 2 <= num_heads {
+        pow2 *= 2;
+    }
+
+    let mut exponent = f32::cast_from((head_index + 1) * 8);
+    if head_index >= pow2 {
+        exponent = f32::cast_from((2 * (head_index - pow2) + 1) * 4);
+    }
+
+    Powf::powf(f32::new(2.0), -exponent / f32::cast_from(pow2))
+}
+
+/// Shifts added to the query and key/value positions of a sequence so that the positions of
+/// the rows aligned on the diagonal of the window are equal
+#[cube]
+pub fn window_diagonal(seq_q: u32, seq_kv: u32, #[comptime] window: AttentionWindow) -> Coords2d {
+    let mut q_shift = comptime![window.alignment.fixed_offset()].runtime();
+    let mut kv_shift = 0u32;
+
+    if comptime![window.alignment == DiagonalAlignment::BottomRight] {
+        q_shift = Max::max(seq_kv, seq_q) - seq_q;
+        kv_shift = Max::max(seq_q, seq_kv) - seq_kv;
+    }
+
+    (q_shift, kv_shift)
+}
+
+/// Range of key/value rows within the window of at least one of the query rows in
+/// `[q_start, q_end)`. Rows outside of it are masked for all these query rows, so they need not
+/// be computed at all.
+#[cube]
+pub fn window_kv_range(
+    q_start: u32,
+    q_end: u32,
+    seq_kv: u32,
+    diagonal: Coords2d,
+    #[comptime] window: AttentionWindow,
+) -> Coords2d {
+    let mut kv_start = 0u32;
+    let mut kv_end = seq_kv;
+
+    if comptime![window.left.is_some()] {
+        let first = q_start + diagonal.0;
+        let before = diagonal.1 + comptime![window.left.unwrap()];
+        kv_start = Max::max(first, before) - before;
+    }
+    if comptime![window.right.is_some()] {
+        let last = q_end + diagonal.0 + comptime![window.right.unwrap()];
+        kv_end = Min::min(Max::max(last, diagonal.1) - diagonal.1, seq_kv);
+    }
+
+    (Min::min(kv_start, kv_end), kv_end)
+}
+
 #[derive(CubeType)]
 pub struct MaterializedTileMask<AP: AttentionPrecision, TA: TileAttention<AP>> {
     fragment: TA::Mask,
@@ -136,9 +272,22 @@ pub struct MaterializedTileMask<AP: AttentionPrecision, TA: TileAttention<AP>> {
 impl<AP: AttentionPrecision, TA: TileAttention<AP>> MaterializedTileMask<AP, TA> {
     pub fn should_mask(&self, local_pos: Coords2d) -> bool {
         let logical_masked = self.logical_mask.should_mask(local_pos);
-        let materialized_masked = self.fragment.should_mask(local_pos);
 
-        logical_masked || materialized_masked
+        if comptime![self.config.additive_mask()] {
+            // Additive masks only mask through very negative biases
+            logical_masked
+        } else {
+            logical_masked || self.fragment.should_mask(local_pos)
+        }
+    }
+
+    pub fn score_bias<B: Float>(&self, local_pos: Coords2d) -> B {
+        let mut bias = self.logical_mask.alibi_bias::<B>(local_pos);
+        if comptime![self.config.additive_mask()] {
+            bias += self.fragment.score_bias::<B>(local_pos);
+        }
+
+        select(self.should_mask(local_pos), B::min_value(), bias)
     }
 
     pub fn update_tile(&mut self, tile: StridedTile<MSK<AP>>) {
@@ -158,4 +307,13 @@ impl<AP: AttentionPrecision, TA: TileAttention<AP>> FragmentMask for MaskTile<AP
             MaskTile::Logical(logical_tile_mask) => logical_tile_mask.should_mask(local_pos),
         }
     }
+
+    fn score_bias<B: Float>(&self, local_pos: (u32, u32)) -> B {
+        match self {
+            MaskTile::Materialized(materialized_tile_mask) => {
+                materialized_tile_mask.score_bias::<B>(local_pos)
+            }
+            MaskTile::Logical(logical_tile_mask) => logical_tile_mask.score_bias::<B>(local_pos),
+        }
+    }
 }
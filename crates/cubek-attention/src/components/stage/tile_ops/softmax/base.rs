@@ -14,11 +14,23 @@ use crate::components::tile::TileAttention;
 use crate::definition::AttentionPrecision;
 use crate::definition::attention_types::SM;
 
+#[derive(CubeType, Clone, Copy)]
+/// Parameters of the softmax of the scores of one head, only known at runtime
+pub struct SoftmaxParams {
+    /// Factor the scores are multiplied by
+    pub scale: f32,
+    /// Bound of the soft-capped scores, only read when the tile config soft-caps them
+    pub softcap: f32,
+    /// Logit taking part in the denominator of the softmax without contributing any value.
+    /// The lowest value stands for no sink.
+    pub sink: f32,
+}
+
 #[cube]
 /// Applies softmax to a tile with masking and updates the running state.
 ///
-/// Scales by `1 / sqrt(head_dim)`, applies the mask, computes row-wise max and sum,
-/// exponentiates, and updates the softmax state.
+/// Scales the scores, soft-capping them to `softcap * tanh(score / softcap)` if enabled,
+/// applies the mask, computes row-wise max and sum, exponentiates, and updates the softmax state.
 ///
 /// Returns the exponential difference used for normalization.
 pub fn tile_softmax<AP: AttentionPrecision, TA: TileAttention<AP>, R: Reducer>(
@@ -27,14 +39,18 @@ pub fn tile_softmax<AP: AttentionPrecision, TA: TileAttention<AP>, R: Reducer>(
     state: &mut RunningState<SM<AP>>,
     max_placeholder: &mut RowWise<SM<AP>>,
     sum_placeholder: &mut RowWise<SM<AP>>,
-    #[comptime] head_dim: u32,
+    params: &SoftmaxParams,
     #[comptime] config: TA::Config,
 ) -> RowWise<SM<AP>> {
-    TA::SoftmaxRow::scale_and_mask::<MaskTile<AP, TA>>(
-        rowwise_softmax,
-        SM::<AP>::new(comptime!(1.0 / (head_dim as f32).sqrt())),
-        mask,
-    );
+    let mut scale = SM::<AP>::cast_from(params.scale);
+
+    // Soft-capping comes before biases and masking, so it also applies the scale
+    if comptime!(config.softcap()) {
+        TA::SoftmaxRow::softcap(rowwise_softmax, scale, SM::<AP>::cast_from(params.softcap));
+        scale = SM::<AP>::from_int(1);
+    }
+
+    TA::SoftmaxRow::scale_and_mask::<MaskTile<AP, TA>>(rowwise_softmax, scale, mask);
 
     row_max::<SM<AP>, <TA as TileAttention<AP>>::SoftmaxRow, R, TA::Config>(
         max_placeholder,
@@ -1,7 +1,7 @@
 use cubecl;
 use cubecl::prelude::*;
 
-use crate::components::tile::RowWise;
+use crate::components::tile::{RowVal, RowWise};
 
 #[derive(CubeType)]
 /// Flash Attention's running state, per row
@@ -20,6 +20,20 @@ impl<E: Float> RunningState<E> {
         }
     }
 
+    /// Init the state as if a sink logit had already been seen, so that it takes part in the
+    /// denominator of the softmax. A sink of the lowest value is the same as no sink.
+    pub fn init_with_sink(sink: f32, #[comptime] num_rows: u32) -> RunningState<E> {
+        let has_sink = sink > f32::min_value();
+
+        RunningState::<E> {
+            m: RowWise::new_filled(
+                num_rows,
+                select(has_sink, E::cast_from(sink), E::min_value()),
+            ),
+            l: RowWise::new_filled(num_rows, E::cast_from(has_sink)),
+        }
+    }
+
     /// Update the state for next iteration
     pub fn update(&mut self, new_m: &RowWise<E>, new_l: &RowWise<E>) {
         RowWise::copy_from(&mut self.m, new_m);
@@ -35,4 +49,22 @@ impl<E: Float> RunningState<E> {
     pub fn l(&self) -> &RowWise<E> {
         &self.l
     }
+
+    /// Log-sum-exp of the scores of each row, i.e. `m + ln(l)`
+    ///
+    /// Fully masked rows have `l = 0` and therefore a log-sum-exp of negative infinity.
+    pub fn log_sum_exp(&self) -> RowWise<E> {
+        let mut vals = Sequence::new();
+
+        #[unroll]
+        for i in 0..self.m.num_rows {
+            let val = self.m.index(i) + Log::log(self.l.index(i));
+            vals.push(RowVal::<E> { val });
+        }
+
+        RowWise::<E> {
+            num_rows: self.m.num_rows,
+            vals,
+        }
+    }
 }
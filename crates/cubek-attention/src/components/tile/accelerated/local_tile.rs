@@ -213,6 +213,20 @@ impl<E: Float> RowwiseFormat<E> for LocalTile<E> {
         }
     }
 
+    fn softcap(this: &mut Self, scale: E, cap: E) {
+        let factor = scale / cap;
+
+        #[unroll]
+        for r in 0..this.layout.unit_size.0 {
+            let row_offset = r * this.layout.unit_size.1;
+            #[unroll]
+            for c in 0..this.layout.unit_size.1 {
+                let index = row_offset + c;
+                this.array[index] = cap * Tanh::tanh(this.array[index] * factor);
+            }
+        }
+    }
+
     fn scale_and_mask<M: FragmentMask>(this: &mut Self, scale: E, mask: &M) {
         #[unroll]
         for r in 0..this.layout.unit_size.0 {
@@ -220,8 +234,8 @@ impl<E: Float> RowwiseFormat<E> for LocalTile<E> {
             #[unroll]
             for c in 0..this.layout.unit_size.1 {
                 let index = row_offset + c;
-                this.array[index] = this.array[index] * scale
-                    + E::cast_from(mask.should_mask((r, c).runtime())) * E::min_value();
+                this.array[index] =
+                    this.array[index] * scale + mask.score_bias::<E>((r, c).runtime());
             }
         }
     }
@@ -277,4 +291,8 @@ impl<E: Numeric> FragmentMask for LocalTile<E> {
     fn should_mask(&self, local_pos: Coords2d) -> bool {
         bool::cast_from(self.array[local_pos.0 * self.layout.unit_size.1 + local_pos.1])
     }
+
+    fn score_bias<B: Float>(&self, local_pos: Coords2d) -> B {
+        B::cast_from(self.array[local_pos.0 * self.layout.unit_size.1 + local_pos.1])
+    }
 }
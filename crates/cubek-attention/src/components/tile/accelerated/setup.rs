@@ -9,6 +9,7 @@ use crate::definition::AttentionBlueprint;
 use crate::definition::AttentionPrecision;
 use crate::definition::AttentionSetupError;
 use crate::definition::AttentionTileSize;
+use crate::definition::AttentionWindow;
 use crate::definition::InvalidConfigError;
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -37,13 +38,25 @@ impl TileAttentionConfig for BlackboxAcceleratedAttentionMatmulConfig {
         }
     }
 
-    fn causal_mask(&self) -> bool {
-        self.shared.causal_mask
+    fn window(&self) -> AttentionWindow {
+        self.shared.window
     }
 
     fn materialized_mask(&self) -> bool {
         self.shared.materialized_mask
     }
+
+    fn additive_mask(&self) -> bool {
+        self.shared.additive_mask
+    }
+
+    fn alibi(&self) -> bool {
+        self.shared.alibi
+    }
+
+    fn softcap(&self) -> bool {
+        self.shared.softcap
+    }
 }
 
 impl TileAttentionFamily for BlackboxAcceleratedTileAttention {
@@ -68,8 +81,11 @@ impl TileAttentionFamily for BlackboxAcceleratedTileAttention {
                     plane_dim: blueprint.plane_dim,
                     num_planes: blueprint.tiling_scheme.stage_size.seq_q,
                     attention_tile_size: blueprint.tiling_scheme.tile_size,
-                    causal_mask: blueprint.causal,
+                    window: blueprint.window.with_causal(blueprint.causal),
                     materialized_mask: blueprint.masked,
+                    additive_mask: blueprint.additive_mask,
+                    alibi: blueprint.alibi,
+                    softcap: blueprint.softcap,
                 },
                 inner_layout: if blueprint.two_rows_in_array_tile {
                     InnerLayout::SplitRows
@@ -9,7 +9,7 @@ use crate::components::tile::{
 use crate::definition::attention_types::{ACC, SM};
 use crate::definition::{
     AttentionBlueprint, AttentionPrecision, AttentionSetupError, AttentionTileSize,
-    InvalidConfigError,
+    AttentionWindow, InvalidConfigError,
 };
 
 use std::fmt::Debug;
@@ -96,8 +96,15 @@ pub trait TileAttentionConfig:
     fn num_planes(&self) -> u32;
     fn attention_tile_size(&self) -> AttentionTileSize;
     fn num_rows_per_unit(&self) -> u32;
-    fn causal_mask(&self) -> bool;
+    /// Band outside of which scores are masked, including causal masking
+    fn window(&self) -> AttentionWindow;
     fn materialized_mask(&self) -> bool;
+    /// Whether the materialized mask is a float bias added to the scores
+    fn additive_mask(&self) -> bool;
+    /// Whether to add the ALiBi bias to the scores
+    fn alibi(&self) -> bool;
+    /// Whether to soft-cap the scores with `tanh`
+    fn softcap(&self) -> bool;
 }
 
 pub trait TileAttentionFamily: Send + Sync + 'static {
@@ -126,6 +133,9 @@ pub struct SharedTileAttentionConfig {
     pub plane_dim: u32,
     pub num_planes: u32,
     pub attention_tile_size: AttentionTileSize,
-    pub causal_mask: bool,
+    pub window: AttentionWindow,
     pub materialized_mask: bool,
+    pub additive_mask: bool,
+    pub alibi: bool,
+    pub softcap: bool,
 }
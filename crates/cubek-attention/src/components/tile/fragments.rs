@@ -72,7 +72,11 @@ pub trait RowwiseFormat<E: Float> {
     /// Units only output values for rows they participate in
     fn rowwise_sum(&self) -> RowWise<E>;
 
-    /// Scale every element by a constant factor, and masks values identified by the mask
+    /// Replaces every element `x` by `cap * tanh(x * scale / cap)`
+    fn softcap(this: &mut Self, scale: E, cap: E);
+
+    /// Scale every element by a constant factor, then adds the bias of the mask, which masks
+    /// the values it identifies
     fn scale_and_mask<M: FragmentMask>(this: &mut Self, scale: E, mask: &M);
 
     /// Changes each value x_ij for e^(x_ij - m_i) for every row
@@ -96,4 +100,8 @@ pub trait FragmentMask: CubeType {
 
     /// Returns `true` if the element at `local_pos` should be masked
     fn should_mask(&self, local_pos: Coords2d) -> bool;
+
+    /// Returns the value added to the scaled score at `local_pos`, which is
+    /// the lowest value of `B` if it should be masked
+    fn score_bias<B: Float>(&self, local_pos: Coords2d) -> B;
 }
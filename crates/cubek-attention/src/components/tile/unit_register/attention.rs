@@ -125,6 +125,20 @@ impl<E: Float> RowwiseFormat<E> for UnitTile<E> {
         }
     }
 
+    fn softcap(this: &mut Self, scale: E, cap: E) {
+        let factor = scale / cap;
+
+        #[unroll]
+        for r in 0..this.layout.num_rows {
+            let row_offset = r * this.layout.num_cols;
+            #[unroll]
+            for c in 0..this.layout.num_cols {
+                let index = row_offset + c;
+                this.data[index] = cap * Tanh::tanh(this.data[index] * factor);
+            }
+        }
+    }
+
     fn scale_and_mask<M: FragmentMask>(this: &mut Self, scale: E, mask: &M) {
         #[unroll]
         for r in 0..this.layout.num_rows {
@@ -132,8 +146,8 @@ impl<E: Float> RowwiseFormat<E> for UnitTile<E> {
             #[unroll]
             for c in 0..this.layout.num_cols {
                 let index = row_offset + c;
-                this.data[index] = this.data[index] * scale
-                    + E::cast_from(mask.should_mask((r, c).runtime())) * E::min_value();
+                this.data[index] =
+                    this.data[index] * scale + mask.score_bias::<E>((r, c).runtime());
             }
         }
     }
@@ -209,6 +223,10 @@ impl<E: Numeric> FragmentMask for UnitTile<E> {
     fn should_mask(&self, local_pos: Coords2d) -> bool {
         bool::cast_from(self.data[local_pos.0 * self.layout.num_cols + local_pos.1])
     }
+
+    fn score_bias<B: Float>(&self, local_pos: Coords2d) -> B {
+        B::cast_from(self.data[local_pos.0 * self.layout.num_cols + local_pos.1])
+    }
 }
 
 #[cube]
@@ -5,7 +5,7 @@ use crate::components::tile::unit_register::UnitRegisterTileAttention;
 use crate::components::tile::{SharedTileAttentionConfig, TileAttentionConfig};
 use crate::definition::{
     AttentionBlueprint, AttentionPrecision, AttentionSetupError, AttentionTileSize,
-    InvalidConfigError,
+    AttentionWindow, InvalidConfigError,
 };
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -30,13 +30,25 @@ impl TileAttentionConfig for UnitTileAttentionConfig {
         self.shared.attention_tile_size.seq_q
     }
 
-    fn causal_mask(&self) -> bool {
-        self.shared.causal_mask
+    fn window(&self) -> AttentionWindow {
+        self.shared.window
     }
 
     fn materialized_mask(&self) -> bool {
         self.shared.materialized_mask
     }
+
+    fn additive_mask(&self) -> bool {
+        self.shared.additive_mask
+    }
+
+    fn alibi(&self) -> bool {
+        self.shared.alibi
+    }
+
+    fn softcap(&self) -> bool {
+        self.shared.softcap
+    }
 }
 
 impl TileAttentionFamily for UnitRegisterTileAttention {
@@ -60,8 +72,11 @@ impl TileAttentionFamily for UnitRegisterTileAttention {
                 plane_dim: blueprint.plane_dim,
                 attention_tile_size: blueprint.tiling_scheme.tile_size,
                 num_planes: blueprint.tiling_scheme.stage_size.seq_q,
-                causal_mask: blueprint.causal,
+                window: blueprint.window.with_causal(blueprint.causal),
                 materialized_mask: blueprint.masked,
+                additive_mask: blueprint.additive_mask,
+                alibi: blueprint.alibi,
+                softcap: blueprint.softcap,
             },
         })
     }
@@ -0,0 +1,13 @@
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+/// Blueprint of the attention backward pass
+pub struct AttentionBackwardBlueprint {
+    /// Number of query rows per cube when computing query gradients, which is also the number of
+    /// query rows staged at once when computing key/value gradients.
+    pub block_size_q: u32,
+    /// Number of key/value rows per cube when computing key/value gradients, which is also the
+    /// number of key/value rows staged at once when computing query gradients.
+    pub block_size_kv: u32,
+
+    pub masked: bool,
+    pub causal: bool,
+}
@@ -1,11 +1,13 @@
 use cubecl::ir::{ElemType, FloatKind, StorageType};
+use cubek_quant::scheme::QuantScheme;
 
 #[derive(Clone, Debug)]
 /// Description of an attention problem to solve, regardless of actual data
 pub struct AttentionProblem {
     pub dims: AttentionDims,
 
-    /// Whether a mask is supplied (shape is always [batch, seq_q, heads, seq_kv])
+    /// Whether a mask is supplied (shape is always [batch, seq_q, heads, seq_kv], where
+    /// dimensions of size 1 other than seq_kv are broadcast). A float mask is added to the scores.
     pub masked: bool,
 
     pub global_dtypes: AttentionGlobalTypes,
@@ -21,12 +23,97 @@ pub enum AttentionIdent {
     Value,
     Mask,
     Out,
+    /// Log-sum-exp of the scores of each query row
+    Lse,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct AttentionOptions {
     pub causal: bool,
     pub accumulator_precision: AccumulatorPrecision,
+    /// Band around the diagonal outside of which scores are masked, on top of causal masking
+    pub window: AttentionWindow,
+    /// Whether to add the ALiBi bias `-slope * |q_pos - kv_pos|` to the scores, with the
+    /// standard geometric slope of each head and positions aligned on the window diagonal
+    pub alibi: bool,
+    /// Factor the scores are multiplied by, `1 / sqrt(head_dim)` if `None`
+    pub scale: Option<f32>,
+    /// Soft-caps the scaled scores to `softcap * tanh(score / softcap)`, before biases and masking
+    pub softcap: Option<f32>,
+}
+
+impl AttentionOptions {
+    /// Factor the scores are multiplied by
+    pub fn score_scale(&self, head_dim: usize) -> f32 {
+        self.scale.unwrap_or(1.0 / (head_dim as f32).sqrt())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// Band of key/value rows around the diagonal that each query row attends to.
+///
+/// Evaluated from positions alone, without a mask tensor. Key/value row `j` is attended to by
+/// query row `i` when `i - left <= j <= i + right` once both are aligned on the diagonal.
+pub struct AttentionWindow {
+    /// Number of key/value rows before the diagonal, unbounded if `None`
+    pub left: Option<u32>,
+    /// Number of key/value rows after the diagonal, unbounded if `None`
+    pub right: Option<u32>,
+    /// Where the diagonal lies when `seq_q` and `seq_kv` differ.
+    /// Also applies to causal masking.
+    pub alignment: DiagonalAlignment,
+}
+
+impl AttentionWindow {
+    /// Sliding window where each query row attends to itself and the `size - 1` rows before it
+    pub fn sliding(size: u32) -> Self {
+        Self {
+            left: Some(size.saturating_sub(1)),
+            right: Some(0),
+            alignment: DiagonalAlignment::default(),
+        }
+    }
+
+    /// Whether every key/value row is within the window
+    pub fn is_unbounded(&self) -> bool {
+        self.left.is_none() && self.right.is_none()
+    }
+
+    /// The window additionally restricted to rows before the diagonal when `causal`
+    pub fn with_causal(self, causal: bool) -> Self {
+        if causal {
+            Self {
+                right: Some(0),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// Key/value row aligned with each query row
+pub enum DiagonalAlignment {
+    /// Query row `i` is aligned with key/value row `i`
+    #[default]
+    TopLeft,
+    /// The last query row is aligned with the last key/value row, as when the queries are the
+    /// latest rows of a key/value cache
+    BottomRight,
+    /// Query row `i` is aligned with key/value row `i + offset`, as when prefilling the chunk of
+    /// a prompt starting at `offset`
+    Offset(u32),
+}
+
+impl DiagonalAlignment {
+    /// Offset of the aligned key/value row that is known without the sequence lengths
+    pub fn fixed_offset(&self) -> u32 {
+        match self {
+            DiagonalAlignment::Offset(offset) => *offset,
+            _ => 0,
+        }
+    }
 }
 
 impl AttentionProblem {
@@ -40,8 +127,12 @@ impl AttentionProblem {
 pub struct AttentionDims {
     /// Batch size
     pub batch: usize,
-    /// Number of attention heads
+    /// Number of query attention heads
     pub num_heads: usize,
+    /// Number of key/value attention heads, which must divide `num_heads`.
+    /// Smaller than `num_heads` for grouped-query and multi-query attention, where each group of
+    /// `num_heads / num_kv_heads` consecutive query heads shares a single key/value head.
+    pub num_kv_heads: usize,
 
     /// Query sequence length
     pub seq_q: usize,
@@ -58,22 +149,35 @@ impl AttentionDims {
     pub fn shape(&self, ident: AttentionIdent) -> [usize; 4] {
         match ident {
             AttentionIdent::Query => [self.batch, self.num_heads, self.seq_q, self.head_dim],
-            AttentionIdent::Key => [self.batch, self.num_heads, self.seq_kv, self.head_dim],
-            AttentionIdent::Value => [self.batch, self.num_heads, self.seq_kv, self.val_dim],
+            AttentionIdent::Key => [self.batch, self.num_kv_heads, self.seq_kv, self.head_dim],
+            AttentionIdent::Value => [self.batch, self.num_kv_heads, self.seq_kv, self.val_dim],
             AttentionIdent::Mask => [self.batch, self.num_heads, self.seq_q, self.seq_kv],
             AttentionIdent::Out => [self.batch, self.num_heads, self.seq_q, self.val_dim],
+            // One value per query row, the trailing unit dimension can be omitted
+            AttentionIdent::Lse => [self.batch, self.num_heads, self.seq_q, 1],
             AttentionIdent::Softmax => unreachable!("Not a materialized tensor"),
         }
     }
+
+    /// Number of query heads sharing each key/value head
+    pub fn num_heads_per_kv_head(&self) -> usize {
+        self.num_heads / self.num_kv_heads
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct AttentionGlobalTypes {
     pub query: StorageType,
+    /// Type the key is read as, the type it is dequantized to if quantized
     pub key: StorageType,
+    /// Type the value is read as, the type it is dequantized to if quantized
     pub value: StorageType,
     pub mask: StorageType,
     pub out: StorageType,
+    /// Quantization of the stored key, if any
+    pub key_quant: Option<QuantScheme>,
+    /// Quantization of the stored value, if any
+    pub value_quant: Option<QuantScheme>,
 }
 
 impl AttentionGlobalTypes {
@@ -84,8 +188,21 @@ impl AttentionGlobalTypes {
             value: dtype,
             mask: StorageType::Scalar(ElemType::UInt(cubecl::ir::UIntKind::U8)),
             out: dtype,
+            key_quant: None,
+            value_quant: None,
         }
     }
+
+    /// Whether the key or the value is stored quantized
+    pub fn quantized_key_value(&self) -> bool {
+        self.key_quant.is_some() || self.value_quant.is_some()
+    }
+
+    /// Whether the mask holds float biases added to the scores, rather than booleans where
+    /// non-zero values mask the score
+    pub fn additive_mask(&self) -> bool {
+        matches!(self.mask.elem_type(), ElemType::Float(_))
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -99,6 +216,14 @@ impl AccumulatorPrecision {
     pub fn default_accumulator_type() -> StorageType {
         StorageType::Scalar(ElemType::Float(FloatKind::F32))
     }
+
+    /// The accumulator type, which is also the type the log-sum-exp is written in.
+    pub fn storage_type(&self) -> StorageType {
+        match self {
+            AccumulatorPrecision::Strict(storage_type) => *storage_type,
+            AccumulatorPrecision::Loose => Self::default_accumulator_type(),
+        }
+    }
 }
 
 impl Default for AccumulatorPrecision {
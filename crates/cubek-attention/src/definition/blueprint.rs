@@ -1,6 +1,6 @@
 use cubek_matmul::definition::TileSize;
 
-use crate::definition::{AttentionDims, AttentionLineSizes, HypercubeBlueprint};
+use crate::definition::{AttentionDims, AttentionLineSizes, AttentionWindow, HypercubeBlueprint};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct AttentionBlueprint {
@@ -15,11 +15,26 @@ pub struct AttentionBlueprint {
     pub line_sizes: AttentionLineSizes,
 
     pub masked: bool,
+    /// Whether the mask is a float bias added to the scores
+    pub additive_mask: bool,
     pub causal: bool,
+    pub window: AttentionWindow,
+    pub alibi: bool,
+    /// Whether the scores are soft-capped, with the cap given at runtime
+    pub softcap: bool,
 
     pub check_bounds: AttentionCheckBounds,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+/// Blueprint of attention where `seq_kv` is split across cubes
+pub struct SplitKvBlueprint {
+    /// Blueprint used by each cube on its range of keys and values
+    pub attention: AttentionBlueprint,
+    /// Number of ranges `seq_kv` is split into, whose partial results are combined afterwards
+    pub num_kv_splits: u32,
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct AttentionTilingScheme {
     pub tile_size: AttentionTileSize,
@@ -19,6 +19,7 @@ impl HypercubeBlueprint {
                     * blueprint.tiling_scheme.stage_size.seq_q,
             ),
             outer: (dims.batch * dims.num_heads) as u32,
+            kv_splits: 1,
         }
     }
 }
@@ -26,11 +27,17 @@ impl HypercubeBlueprint {
 pub struct CubeCountPlan {
     inner: u32,
     outer: u32,
+    kv_splits: u32,
 }
 
 impl CubeCountPlan {
+    /// Launches one cube per range of `seq_kv` for each of the cubes of the plan
+    pub fn with_kv_splits(self, kv_splits: u32) -> Self {
+        Self { kv_splits, ..self }
+    }
+
     pub fn resolve(&self) -> CubeCount {
-        CubeCount::Static(self.inner, self.outer, 1)
+        CubeCount::Static(self.inner, self.outer, self.kv_splits)
     }
 
     /// Make a CubeCountInput from CubeCountPlan
@@ -42,14 +42,21 @@ impl AttentionLineSizes {
                 &problem.dims.shape(AttentionIdent::Query),
                 problem.global_dtypes.query.size(),
             ),
-            key: find_line_size(
-                &problem.dims.shape(AttentionIdent::Key),
-                problem.global_dtypes.key.size(),
-            ),
-            value: find_line_size(
-                &problem.dims.shape(AttentionIdent::Value),
-                problem.global_dtypes.value.size(),
-            ),
+            // A quantized line holds the values of a single stored element
+            key: match &problem.global_dtypes.key_quant {
+                Some(scheme) => scheme.num_quants() as u8,
+                None => find_line_size(
+                    &problem.dims.shape(AttentionIdent::Key),
+                    problem.global_dtypes.key.size(),
+                ),
+            },
+            value: match &problem.global_dtypes.value_quant {
+                Some(scheme) => scheme.num_quants() as u8,
+                None => find_line_size(
+                    &problem.dims.shape(AttentionIdent::Value),
+                    problem.global_dtypes.value.size(),
+                ),
+            },
             // lined mask not always supported at the moment
             mask: 1,
             out: find_line_size(
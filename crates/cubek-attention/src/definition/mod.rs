@@ -1,3 +1,4 @@
+mod backward;
 mod base;
 mod blueprint;
 mod error;
@@ -5,6 +6,7 @@ mod hypercube;
 mod line_size;
 mod spec;
 
+pub use backward::*;
 pub use base::*;
 pub use blueprint::*;
 pub use error::*;
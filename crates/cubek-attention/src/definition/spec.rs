@@ -1,9 +1,10 @@
 use cubecl::prelude::*;
+use cubecl::std::CubeOption;
 use half::{bf16, f16};
 
 use crate::{
     definition::{AccumulatorPrecision, AttentionGlobalTypes},
-    launch::{AttentionArgs, TensorArgs},
+    launch::{AttentionArgs, ScoreArgs, SequenceLengths, TensorArgs},
 };
 
 /// Attention spec defining each element types used in the computation as well as
@@ -217,12 +218,24 @@ pub type InputArg<AA> = <AA as AttentionArgs>::Input<
 /// Output argument
 pub type OutputArg<AA> = <AA as AttentionArgs>::Output<NumericExpand<10>>;
 
+/// Optional log-sum-exp output argument, in the accumulator precision
+pub type LseArg = CubeOption<Tensor<Line<NumericExpand<8>>>>;
+
 /// Input runtime argument
 pub type InputRuntimeArg<'a, AA, R> = <InputArg<AA> as LaunchArg>::RuntimeArg<'a, R>;
 
 /// Output runtime argument
 pub type OutputRuntimeArg<'a, AA, R> = <OutputArg<AA> as LaunchArg>::RuntimeArg<'a, R>;
 
+/// Optional log-sum-exp output runtime argument
+pub type LseRuntimeArg<'a, R> = <LseArg as LaunchArg>::RuntimeArg<'a, R>;
+
+/// Sequence lengths runtime argument
+pub type SequenceLengthsRuntimeArg<'a, R> = <SequenceLengths as LaunchArg>::RuntimeArg<'a, R>;
+
+/// Score parameters runtime argument
+pub type ScoreArgsRuntimeArg<'a, R> = <ScoreArgs as LaunchArg>::RuntimeArg<'a, R>;
+
 pub mod attention_types {
     use crate::definition::{
         AttentionPrecision, AttentionSpec, QueryPrecision, StagedMatrixPrecision,
@@ -273,10 +286,7 @@ impl AttentionElems {
         global_dtypes: &AttentionGlobalTypes,
         accumulator_precision: &AccumulatorPrecision,
     ) -> AttentionElems {
-        let accumulator = match accumulator_precision {
-            AccumulatorPrecision::Strict(storage_type) => *storage_type,
-            AccumulatorPrecision::Loose => AccumulatorPrecision::default_accumulator_type(),
-        };
+        let accumulator = accumulator_precision.storage_type();
 
         Self {
             query_global: global_dtypes.query,
@@ -1,7 +1,11 @@
 use cubecl::prelude::*;
 use cubecl::std::{
     CubeOption, CubeOptionArgs, CubeOptionExpand,
-    tensor::r#virtual::{VirtualTensorOperations, VirtualTensorOperationsExpand},
+    tensor::{
+        View,
+        layout::{Coords1d, Layout, LayoutExpand},
+        r#virtual::{VirtualTensorOperations, VirtualTensorOperationsExpand},
+    },
 };
 use cubecl::{self as cubecl};
 
@@ -55,6 +59,12 @@ pub trait AttentionArgs: Send + Sync + 'static + Clone {
         state: &Self::State<Q, K, V, M, O>,
     ) -> CubeOption<()>;
 
+    /// The block table key and value are read through, when they are stored in a paged cache.
+    /// Their reads then index the cache directly, see [PagedTensorArgs].
+    fn kv_page_table<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<KvPageTable>;
+
     /// Read the line of the query tensor using the state at the given coordinate.
     fn read_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
         state: &Self::State<Q, K, V, M, O>,
@@ -1092,6 +1102,12 @@ impl AttentionArgs for TensorArgs {
         }
     }
 
+    fn kv_page_table<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<KvPageTable> {
+        CubeOption::new_None()
+    }
+
     fn read_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
         state: &Self::State<Q, K, V, M, O>,
         coordinate: u32,
@@ -1375,6 +1391,894 @@ impl AttentionArgs for TensorArgs {
     }
 }
 
+#[derive(CubeLaunch, CubeType)]
+/// Lengths of the sequences of a batch, for batches whose sequences don't all span the full
+/// `seq_q` and `seq_kv`.
+pub struct SequenceLengths {
+    /// Number of key/value rows of each batch, of shape `[batch]`
+    pub kv_lengths: CubeOption<Tensor<u32>>,
+    /// Offsets of the query rows of each sequence, of shape `[num_sequences + 1]`, for
+    /// sequences packed along `seq_q` in a single batch
+    pub cu_seqlens_q: CubeOption<Tensor<u32>>,
+    /// Offsets of the key/value rows of each sequence, of shape `[num_sequences + 1]`, for
+    /// sequences packed along `seq_kv` in a single batch
+    pub cu_seqlens_kv: CubeOption<Tensor<u32>>,
+}
+
+impl<'a, R: Runtime> SequenceLengthsLaunch<'a, R> {
+    /// Lengths of sequences that each span the full `seq_q` and `seq_kv` of their batch
+    pub fn full() -> Self {
+        Self::new(None.into(), None.into(), None.into())
+    }
+
+    /// Lengths of sequences that each attend to the first `kv_lengths` key/value rows of their
+    /// batch
+    pub fn kv_lengths(kv_lengths: &'a TensorHandleRef<'a, R>) -> Self {
+        Self::new(
+            Some(kv_lengths.as_tensor_arg(1)).into(),
+            None.into(),
+            None.into(),
+        )
+    }
+
+    /// Lengths of sequences packed in a single batch, given by their query and key/value offsets
+    pub fn packed(
+        cu_seqlens_q: &'a TensorHandleRef<'a, R>,
+        cu_seqlens_kv: &'a TensorHandleRef<'a, R>,
+    ) -> Self {
+        Self::new(
+            None.into(),
+            Some(cu_seqlens_q.as_tensor_arg(1)).into(),
+            Some(cu_seqlens_kv.as_tensor_arg(1)).into(),
+        )
+    }
+}
+
+#[derive(CubeLaunch, CubeType)]
+/// Parameters of the scores that are only known at runtime
+pub struct ScoreArgs {
+    /// Factor the scores are multiplied by
+    pub scale: f32,
+    /// Bound of the soft-capped scores, only read when the blueprint soft-caps them
+    pub softcap: f32,
+    /// Learned sink logit of each head, of shape `[num_heads]`. Sinks take part in the
+    /// denominator of the softmax without contributing any value.
+    pub sinks: CubeOption<Tensor<f32>>,
+}
+
+impl<'a, R: Runtime> ScoreArgsLaunch<'a, R> {
+    /// Score parameters of the problem, with its validated sinks
+    pub fn from_problem(
+        problem: &AttentionProblem,
+        sinks: &'a Option<TensorHandleRef<'a, R>>,
+    ) -> Self {
+        let options = &problem.options;
+
+        Self::new(
+            ScalarArg::new(options.score_scale(problem.dims.head_dim)),
+            ScalarArg::new(options.softcap.unwrap_or(1.0)),
+            sinks.as_ref().map(|it| it.as_tensor_arg(1)).into(),
+        )
+    }
+}
+
+#[derive(Clone)]
+/// Type implementing [AttentionArgs] where key and value are read from a paged cache.
+///
+/// The key and value caches have shape `[num_pages, num_kv_heads, page_size, dim]`. The block
+/// table of shape `[batch, max_pages]` gives the page holding each range of `page_size` rows of
+/// every sequence, so that key and value have the shape of contiguous tensors
+/// `[batch, num_kv_heads, max_pages * page_size, dim]`. Their strides and reads stay those of the
+/// caches, which are indexed through the [KvPageTable].
+pub struct PagedTensorArgs;
+
+#[derive(CubeLaunch, CubeType)]
+/// Input representation for [PagedTensorArgs] implementing [AttentionArgs].
+pub struct PagedTensorInputs<Q: Float, K: Float, V: Float, M: Numeric> {
+    pub query: Tensor<Line<Q>>,
+    pub key: Tensor<Line<K>>,
+    pub value: Tensor<Line<V>>,
+    pub mask: CubeOption<Tensor<Line<M>>>,
+    pub block_table: Tensor<u32>,
+    /// Number of key/value rows of each sequence, past which the block table is not read
+    pub context_lengths: Tensor<u32>,
+}
+
+#[derive(CubeType, Clone, Copy)]
+/// Block table of the paged key/value cache of [PagedTensorArgs].
+///
+/// The key and value are read one stage at a time, so the page holding the rows of a stage is
+/// resolved once for the whole stage rather than for every line, see
+/// [PagedGlobalLayout](crate::components::global::PagedGlobalLayout).
+pub struct KvPageTable {
+    pub block_table: *const Tensor<u32>,
+    pub context_lengths: *const Tensor<u32>,
+}
+
+#[derive(CubeType)]
+pub struct PagedAttentionState<Q: Float, K: Float, V: Float, M: Numeric, O: Float> {
+    pub query: *const Tensor<Line<Q>>,
+    pub key: *const Tensor<Line<K>>,
+    pub value: *const Tensor<Line<V>>,
+    pub mask: CubeOption<*const Tensor<Line<M>>>,
+    pub block_table: *const Tensor<u32>,
+    pub context_lengths: *const Tensor<u32>,
+    pub output: *mut Tensor<Line<O>>,
+}
+
+#[cube]
+impl AttentionArgs for PagedTensorArgs {
+    type Input<Q: Float, K: Float, V: Float, M: Numeric> = PagedTensorInputs<Q, K, V, M>;
+    type Output<O: Float> = Tensor<Line<O>>;
+    type State<Q: Float, K: Float, V: Float, M: Numeric, O: Float> =
+        PagedAttentionState<Q, K, V, M, O>;
+
+    fn init_state<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        input: &Self::Input<Q, K, V, M>,
+        output: &mut Self::Output<O>,
+    ) -> Self::State<Q, K, V, M, O> {
+        let mask = match &input.mask {
+            CubeOption::None => CubeOption::new_None(),
+            CubeOption::Some(mask) => {
+                let ptr: *const Tensor<Line<M>> = mask;
+                CubeOption::new_Some(ptr)
+            }
+        };
+
+        PagedAttentionState::<Q, K, V, M, O> {
+            query: &input.query,
+            key: &input.key,
+            value: &input.value,
+            mask,
+            block_table: &input.block_table,
+            context_lengths: &input.context_lengths,
+            output,
+        }
+    }
+
+    fn has_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<()> {
+        match state.mask {
+            CubeOption::None => CubeOption::new_None(),
+            CubeOption::Some(_) => CubeOption::new_Some(()),
+        }
+    }
+
+    fn kv_page_table<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<KvPageTable> {
+        CubeOption::new_Some(KvPageTable {
+            block_table: state.block_table,
+            context_lengths: state.context_lengths,
+        })
+    }
+
+    fn read_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<Q> {
+        unsafe { (*state.query)[coordinate] }
+    }
+
+    fn read_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<K> {
+        unsafe { (*state.key)[coordinate] }
+    }
+
+    fn read_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<V> {
+        unsafe { (*state.value)[coordinate] }
+    }
+
+    fn read_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<M> {
+        unsafe { (*state.mask.unwrap())[coordinate] }
+    }
+
+    fn read_window_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        start: u32,
+        end: u32,
+    ) -> Slice<Line<Q>> {
+        unsafe { (*state.query).slice(start, end) }
+    }
+
+    fn read_window_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+        _start: u32,
+        _end: u32,
+    ) -> Slice<Line<K>> {
+        panic!("Paged key can't be read as a contiguous window")
+    }
+
+    fn read_window_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+        _start: u32,
+        _end: u32,
+    ) -> Slice<Line<V>> {
+        panic!("Paged value can't be read as a contiguous window")
+    }
+
+    fn read_window_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        start: u32,
+        end: u32,
+    ) -> Slice<Line<M>> {
+        unsafe { (*state.mask.unwrap()).slice(start, end) }
+    }
+
+    fn as_tensor_map_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<Q, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<K, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<V, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<M, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn shape_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.query).shape(dim) }
+    }
+
+    fn shape_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { paged_shape(&(*state.key), &(*state.block_table), dim) }
+    }
+
+    fn shape_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { paged_shape(&(*state.value), &(*state.block_table), dim) }
+    }
+
+    fn shape_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).shape(dim) }
+    }
+
+    fn shape_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.output).shape(dim) }
+    }
+
+    fn stride_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.query).stride(dim) }
+    }
+
+    fn stride_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.key).stride(dim) }
+    }
+
+    fn stride_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.value).stride(dim) }
+    }
+
+    fn stride_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).stride(dim) }
+    }
+
+    fn stride_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.output).stride(dim) }
+    }
+
+    fn write_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &mut Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+        val: Line<O>,
+    ) {
+        unsafe { (*state.output)[coordinate] = val }
+    }
+
+    fn rank_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).rank() }
+    }
+
+    fn rank_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.key).rank() }
+    }
+
+    fn rank_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.value).rank() }
+    }
+
+    fn rank_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).rank() }
+    }
+
+    fn rank_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).rank() }
+    }
+
+    fn len_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).len() }
+    }
+
+    fn len_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.key).len() }
+    }
+
+    fn len_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.value).len() }
+    }
+
+    fn len_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).len() }
+    }
+
+    fn len_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).len() }
+    }
+
+    fn buffer_len_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).buffer_len() }
+    }
+
+    fn buffer_len_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.key).buffer_len() }
+    }
+
+    fn buffer_len_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.value).buffer_len() }
+    }
+
+    fn buffer_len_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).buffer_len() }
+    }
+
+    fn buffer_len_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).buffer_len() }
+    }
+
+    fn line_size_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.query).line_size() }
+    }
+
+    fn line_size_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.key).line_size() }
+    }
+
+    fn line_size_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.value).line_size() }
+    }
+
+    fn line_size_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.mask.unwrap()).line_size() }
+    }
+
+    fn line_size_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.output).line_size() }
+    }
+}
+
+#[cube]
+/// Shape of a paged cache seen as a `[batch, num_kv_heads, seq_kv, dim]` tensor
+fn paged_shape<E: Numeric>(cache: &Tensor<Line<E>>, block_table: &Tensor<u32>, axis: u32) -> u32 {
+    let mut shape = cache.shape(axis);
+    if axis == 0 {
+        shape = block_table.shape(0);
+    } else if axis == 2 {
+        shape = block_table.shape(1) * cache.shape(2);
+    }
+    shape
+}
+
+#[derive(Clone)]
+/// Type implementing [AttentionArgs] where key and value may be stored quantized.
+///
+/// A quantized key or value is dequantized to its global type on read, using the scales of its
+/// [QuantScheme](cubek_quant::scheme::QuantScheme), so that it is staged into shared memory as
+/// floats.
+pub struct QuantizedTensorArgs;
+
+#[derive(CubeLaunch, CubeType)]
+/// Input representation for [QuantizedTensorArgs] implementing [AttentionArgs].
+pub struct QuantizedTensorInputs<Q: Float, K: Float, V: Float, M: Numeric> {
+    pub query: Tensor<Line<Q>>,
+    pub key: QuantizedTensor<K>,
+    pub value: QuantizedTensor<V>,
+    pub mask: CubeOption<Tensor<Line<M>>>,
+}
+
+#[derive(CubeLaunch, CubeType)]
+/// Contiguous tensor of shape `[batch, num_kv_heads, seq_kv, dim]` that is dequantized when read
+pub struct QuantizedTensor<F: Float> {
+    /// Values indexed by element, dequantized with their scale if the tensor is quantized
+    pub values: View<Line<F>, Coords1d>,
+    pub batch: u32,
+    pub num_heads: u32,
+    pub seq: u32,
+    pub dim: u32,
+}
+
+#[cube]
+impl<F: Float> QuantizedTensor<F> {
+    pub fn shape(&self, axis: u32) -> u32 {
+        let mut shape = self.dim;
+        if axis == 0 {
+            shape = self.batch;
+        } else if axis == 1 {
+            shape = self.num_heads;
+        } else if axis == 2 {
+            shape = self.seq;
+        }
+        shape
+    }
+
+    pub fn stride(&self, axis: u32) -> u32 {
+        let mut stride = 1u32;
+        for inner in axis + 1..4 {
+            stride *= self.shape(inner);
+        }
+        stride
+    }
+
+    /// Number of lines of the dequantized tensor
+    pub fn num_lines(&self) -> u32 {
+        self.stride(0) * self.batch / self.values.line_size()
+    }
+
+    /// Reads the line at `coordinate` of the dequantized tensor
+    pub fn read(&self, coordinate: u32) -> Line<F> {
+        self.values[coordinate * self.values.line_size()]
+    }
+}
+
+#[derive(CubeType, CubeLaunch)]
+/// Layout of the stored values of a contiguous, possibly quantized tensor, from the index of a
+/// dequantized element to the index of the stored line holding it
+pub struct QuantizedValuesLayout {
+    len: u32,
+    #[cube(comptime)]
+    line_size: u32,
+    /// Number of values packed in a stored element
+    #[cube(comptime)]
+    packing: u32,
+}
+
+#[cube]
+impl Layout for QuantizedValuesLayout {
+    type Coordinates = Coords1d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, pos: Self::Coordinates) -> u32 {
+        pos / comptime![self.packing * self.line_size]
+    }
+
+    fn to_source_pos_checked(&self, pos: Self::Coordinates) -> (u32, bool) {
+        (self.to_source_pos(pos), self.is_in_bounds(pos))
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        self.len
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        pos < self.len
+    }
+}
+
+#[derive(CubeType)]
+pub struct QuantizedAttentionState<Q: Float, K: Float, V: Float, M: Numeric, O: Float> {
+    pub query: *const Tensor<Line<Q>>,
+    pub key: *const QuantizedTensor<K>,
+    pub value: *const QuantizedTensor<V>,
+    pub mask: CubeOption<*const Tensor<Line<M>>>,
+    pub output: *mut Tensor<Line<O>>,
+}
+
+#[cube]
+impl AttentionArgs for QuantizedTensorArgs {
+    type Input<Q: Float, K: Float, V: Float, M: Numeric> = QuantizedTensorInputs<Q, K, V, M>;
+    type Output<O: Float> = Tensor<Line<O>>;
+    type State<Q: Float, K: Float, V: Float, M: Numeric, O: Float> =
+        QuantizedAttentionState<Q, K, V, M, O>;
+
+    fn init_state<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        input: &Self::Input<Q, K, V, M>,
+        output: &mut Self::Output<O>,
+    ) -> Self::State<Q, K, V, M, O> {
+        let mask = match &input.mask {
+            CubeOption::None => CubeOption::new_None(),
+            CubeOption::Some(mask) => {
+                let ptr: *const Tensor<Line<M>> = mask;
+                CubeOption::new_Some(ptr)
+            }
+        };
+
+        QuantizedAttentionState::<Q, K, V, M, O> {
+            query: &input.query,
+            key: &input.key,
+            value: &input.value,
+            mask,
+            output,
+        }
+    }
+
+    fn has_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<()> {
+        match state.mask {
+            CubeOption::None => CubeOption::new_None(),
+            CubeOption::Some(_) => CubeOption::new_Some(()),
+        }
+    }
+
+    fn kv_page_table<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<KvPageTable> {
+        CubeOption::new_None()
+    }
+
+    fn read_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<Q> {
+        unsafe { (*state.query)[coordinate] }
+    }
+
+    fn read_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<K> {
+        unsafe { (*state.key).read(coordinate) }
+    }
+
+    fn read_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<V> {
+        unsafe { (*state.value).read(coordinate) }
+    }
+
+    fn read_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+    ) -> Line<M> {
+        unsafe { (*state.mask.unwrap())[coordinate] }
+    }
+
+    fn read_window_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        start: u32,
+        end: u32,
+    ) -> Slice<Line<Q>> {
+        unsafe { (*state.query).slice(start, end) }
+    }
+
+    fn read_window_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+        _start: u32,
+        _end: u32,
+    ) -> Slice<Line<K>> {
+        panic!("Quantized key can't be read as a window of dequantized values")
+    }
+
+    fn read_window_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+        _start: u32,
+        _end: u32,
+    ) -> Slice<Line<V>> {
+        panic!("Quantized value can't be read as a window of dequantized values")
+    }
+
+    fn read_window_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        start: u32,
+        end: u32,
+    ) -> Slice<Line<M>> {
+        unsafe { (*state.mask.unwrap()).slice(start, end) }
+    }
+
+    fn as_tensor_map_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<Q, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<K, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<V, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn as_tensor_map_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> CubeOption<TensorMap<M, Tiled>> {
+        CubeOption::new_None()
+    }
+
+    fn shape_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.query).shape(dim) }
+    }
+
+    fn shape_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.key).shape(dim) }
+    }
+
+    fn shape_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.value).shape(dim) }
+    }
+
+    fn shape_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).shape(dim) }
+    }
+
+    fn shape_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.output).shape(dim) }
+    }
+
+    fn stride_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.query).stride(dim) }
+    }
+
+    fn stride_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.key).stride(dim) }
+    }
+
+    fn stride_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.value).stride(dim) }
+    }
+
+    fn stride_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).stride(dim) }
+    }
+
+    fn stride_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+        dim: u32,
+    ) -> u32 {
+        unsafe { (*state.output).stride(dim) }
+    }
+
+    fn write_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &mut Self::State<Q, K, V, M, O>,
+        coordinate: u32,
+        val: Line<O>,
+    ) {
+        unsafe { (*state.output)[coordinate] = val }
+    }
+
+    fn rank_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).rank() }
+    }
+
+    fn rank_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        4u32.runtime()
+    }
+
+    fn rank_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        _state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        4u32.runtime()
+    }
+
+    fn rank_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).rank() }
+    }
+
+    fn rank_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).rank() }
+    }
+
+    fn len_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).len() }
+    }
+
+    fn len_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.key).num_lines() }
+    }
+
+    fn len_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.value).num_lines() }
+    }
+
+    fn len_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).len() }
+    }
+
+    fn len_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).len() }
+    }
+
+    fn buffer_len_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.query).buffer_len() }
+    }
+
+    fn buffer_len_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.key).num_lines() }
+    }
+
+    fn buffer_len_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.value).num_lines() }
+    }
+
+    fn buffer_len_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.mask.unwrap()).buffer_len() }
+    }
+
+    fn buffer_len_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> u32 {
+        unsafe { (*state.output).buffer_len() }
+    }
+
+    fn line_size_query<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.query).line_size() }
+    }
+
+    fn line_size_key<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.key).values.line_size() }
+    }
+
+    fn line_size_value<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.value).values.line_size() }
+    }
+
+    fn line_size_mask<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.mask.unwrap()).line_size() }
+    }
+
+    fn line_size_out<Q: Float, K: Float, V: Float, M: Numeric, O: Float>(
+        state: &Self::State<Q, K, V, M, O>,
+    ) -> comptime_type!(u32) {
+        unsafe { (*state.output).line_size() }
+    }
+}
+
 mod __query {
     use super::*;
 
@@ -0,0 +1,193 @@
+use cubecl::std::tensor::TensorHandle;
+use cubecl::{CubeCount, CubeDim, Runtime, client::ComputeClient, prelude::TensorHandleRef};
+
+use crate::components::backward::{
+    attention_backward_key_value, attention_backward_preprocess, attention_backward_query,
+};
+use crate::definition::{
+    AttentionGlobalTypes, AttentionIdent, AttentionOptions, AttentionProblem, AttentionSetupError,
+};
+use crate::launch::{BlueprintStrategy, attention_problem, validate_lse_dtype, validate_lse_shape};
+use crate::routines::{BackwardRoutine, DeviceSettings, unit_backward::UnitBackwardRoutine};
+
+#[derive(Debug, Clone)]
+pub enum BackwardStrategy {
+    /// Scalar reference path, limited to plain softmax attention, see [UnitBackwardRoutine]
+    Unit(BlueprintStrategy<UnitBackwardRoutine>),
+}
+
+/// Tensors saved from, or given to, the forward pass that are needed to compute gradients
+pub struct AttentionBackwardInputs<'a, R: Runtime> {
+    pub query: &'a TensorHandleRef<'a, R>,
+    pub key: &'a TensorHandleRef<'a, R>,
+    pub value: &'a TensorHandleRef<'a, R>,
+    /// Output of the forward pass
+    pub out: &'a TensorHandleRef<'a, R>,
+    /// Gradient of the loss with respect to the output
+    pub out_grad: &'a TensorHandleRef<'a, R>,
+    /// Log-sum-exp of the scaled scores of each query row, as written by the forward pass
+    pub lse: &'a TensorHandleRef<'a, R>,
+    pub mask: &'a Option<TensorHandleRef<'a, R>>,
+}
+
+/// Tensors the gradients are written to, with the same shapes and type as their inputs
+pub struct AttentionGradients<'a, R: Runtime> {
+    pub query_grad: &'a TensorHandleRef<'a, R>,
+    pub key_grad: &'a TensorHandleRef<'a, R>,
+    pub value_grad: &'a TensorHandleRef<'a, R>,
+}
+
+#[allow(clippy::result_large_err)]
+pub fn launch_backward_ref<R: Runtime>(
+    strategy: BackwardStrategy,
+    client: &ComputeClient<R>,
+    inputs: &AttentionBackwardInputs<'_, R>,
+    gradients: &AttentionGradients<'_, R>,
+    attention_global_types: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    match strategy {
+        BackwardStrategy::Unit(strategy) => launch_attention_backward::<R, UnitBackwardRoutine>(
+            client,
+            inputs,
+            gradients,
+            attention_global_types,
+            strategy,
+            attention_options,
+        ),
+    }
+}
+
+pub fn launch_attention_backward<R: Runtime, A: BackwardRoutine>(
+    client: &ComputeClient<R>,
+    inputs: &AttentionBackwardInputs<'_, R>,
+    gradients: &AttentionGradients<'_, R>,
+    global_dtypes: &AttentionGlobalTypes,
+    strategy: BlueprintStrategy<A>,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    let definition = attention_problem(
+        inputs.query,
+        inputs.key,
+        inputs.value,
+        inputs.mask.is_some(),
+        global_dtypes,
+        attention_options,
+    )?;
+    validate(&definition, inputs, gradients)?;
+
+    let device_settings = DeviceSettings::new(client, &definition);
+    let launch_info = A::prepare(&definition, &device_settings, strategy)?;
+    let config = launch_info.config;
+    let dtypes = launch_info.dtypes;
+    validate_lse_dtype(inputs.lse, dtypes.accumulator)?;
+
+    let dims = &definition.dims;
+    let num_q_blocks = (dims.seq_q as u32).div_ceil(config.block_size_q);
+    let num_kv_blocks = (dims.seq_kv as u32).div_ceil(config.block_size_kv);
+    let query_cube_count = CubeCount::Static(num_q_blocks, (dims.batch * dims.num_heads) as u32, 1);
+    let key_value_cube_count =
+        CubeCount::Static(num_kv_blocks, (dims.batch * dims.num_kv_heads) as u32, 1);
+    let query_cube_dim = CubeDim::new_1d(config.block_size_q);
+    let key_value_cube_dim = CubeDim::new_1d(config.block_size_kv);
+
+    let delta = TensorHandle::<R>::empty(
+        client,
+        vec![dims.batch, dims.num_heads, dims.seq_q],
+        dtypes.accumulator,
+    );
+
+    let result = unsafe {
+        attention_backward_preprocess::launch_unchecked::<R>(
+            client,
+            query_cube_count.clone(),
+            query_cube_dim,
+            inputs.out.as_tensor_arg(1),
+            inputs.out_grad.as_tensor_arg(1),
+            delta.as_ref().as_tensor_arg(1),
+            config,
+            [dtypes.out_global, dtypes.accumulator],
+        )
+        .and_then(|_| {
+            attention_backward_key_value::launch_unchecked::<R>(
+                client,
+                key_value_cube_count,
+                key_value_cube_dim,
+                inputs.query.as_tensor_arg(1),
+                inputs.key.as_tensor_arg(1),
+                inputs.value.as_tensor_arg(1),
+                inputs.out_grad.as_tensor_arg(1),
+                inputs.lse.as_tensor_arg(1),
+                delta.as_ref().as_tensor_arg(1),
+                inputs.mask.as_ref().map(|it| it.as_tensor_arg(1)).into(),
+                gradients.key_grad.as_tensor_arg(1),
+                gradients.value_grad.as_tensor_arg(1),
+                config,
+                [dtypes.query_global, dtypes.accumulator, dtypes.mask],
+            )
+        })
+        .and_then(|_| {
+            attention_backward_query::launch_unchecked::<R>(
+                client,
+                query_cube_count,
+                query_cube_dim,
+                inputs.query.as_tensor_arg(1),
+                inputs.key.as_tensor_arg(1),
+                inputs.value.as_tensor_arg(1),
+                inputs.out_grad.as_tensor_arg(1),
+                inputs.lse.as_tensor_arg(1),
+                delta.as_ref().as_tensor_arg(1),
+                inputs.mask.as_ref().map(|it| it.as_tensor_arg(1)).into(),
+                gradients.query_grad.as_tensor_arg(1),
+                config,
+                [dtypes.query_global, dtypes.accumulator, dtypes.mask],
+            )
+        })
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(AttentionSetupError::Execution(err)),
+    }
+}
+
+fn validate<R: Runtime>(
+    problem: &AttentionProblem,
+    inputs: &AttentionBackwardInputs<'_, R>,
+    gradients: &AttentionGradients<'_, R>,
+) -> Result<(), AttentionSetupError> {
+    let dtypes = &problem.global_dtypes;
+    if dtypes.key != dtypes.query || dtypes.value != dtypes.query || dtypes.out != dtypes.query {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Backward pass requires query, key, value and out to share the same type",
+        )));
+    }
+
+    let dims = &problem.dims;
+    let shapes = [
+        (inputs.out, AttentionIdent::Out, "Output"),
+        (inputs.out_grad, AttentionIdent::Out, "Output gradient"),
+        (
+            gradients.query_grad,
+            AttentionIdent::Query,
+            "Query gradient",
+        ),
+        (gradients.key_grad, AttentionIdent::Key, "Key gradient"),
+        (
+            gradients.value_grad,
+            AttentionIdent::Value,
+            "Value gradient",
+        ),
+    ];
+    for (tensor, ident, name) in shapes {
+        if tensor.shape != dims.shape(ident) {
+            return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+                "{name} has shape {:?}, expected {:?}",
+                tensor.shape,
+                dims.shape(ident)
+            ))));
+        }
+    }
+
+    validate_lse_shape(&inputs.lse.shape, dims)
+}
@@ -1,29 +1,44 @@
-use cubecl::{Runtime, client::ComputeClient, prelude::TensorHandleRef};
+use cubecl::{Runtime, client::ComputeClient, ir::StorageType, prelude::TensorHandleRef};
 
 use cubecl::std::tensor::TensorHandle;
 
 use crate::definition::AttentionSetupError;
-use crate::definition::{AttentionDims, AttentionGlobalTypes, AttentionOptions, AttentionProblem};
-use crate::launch::args::{TensorArgs, TensorInputsLaunch};
-use crate::routines::DeviceSettings;
+use crate::definition::{
+    AttentionDims, AttentionGlobalTypes, AttentionIdent, AttentionLineSizes, AttentionOptions,
+    AttentionProblem, InputRuntimeArg, OutputRuntimeArg,
+};
+use crate::launch::args::{
+    AttentionArgs, ScoreArgsLaunch, SequenceLengthsLaunch, TensorArgs, TensorInputsLaunch,
+};
+use crate::launch::launch_split_kv_attention;
+use crate::routines::{DeviceSettings, RoutineBlueprint};
 use crate::routines::{
-    Routine, blackbox_accelerated::BlackboxAcceleratedRoutine, unit::UnitRoutine,
+    Routine, blackbox_accelerated::BlackboxAcceleratedRoutine, split_kv::SplitKvRoutine,
+    unit::UnitRoutine,
 };
 
 use crate::components::batch::BatchAttentionFamily;
 
 #[derive(Debug, Clone)]
-pub enum BlueprintStrategy<R: Routine> {
+pub enum BlueprintStrategy<R: RoutineBlueprint> {
     /// Use a predefined blueprint
     Forced(R::Blueprint),
     /// Allows to give limited settings information, and the rest is inferred from it
     Inferred(R::Strategy),
 }
 
+/// Largest `seq_q` for which [Strategy::Auto] splits `seq_kv` across cubes
+pub(crate) const SPLIT_KV_MAX_SEQ_Q: usize = 16;
+
 #[derive(Debug, Clone)]
 pub enum Strategy {
     BlackboxAccelerated(BlueprintStrategy<BlackboxAcceleratedRoutine>),
     Unit(BlueprintStrategy<UnitRoutine>),
+    /// Splits `seq_kv` across cubes and combines their partial results, see [SplitKvRoutine]
+    SplitKv(BlueprintStrategy<SplitKvRoutine>),
+    /// Uses [Strategy::SplitKv] when there are few query rows, such as when decoding, and
+    /// [Strategy::Unit] otherwise
+    Auto,
 }
 
 #[allow(clippy::result_large_err, clippy::too_many_arguments)]
@@ -34,7 +49,9 @@ pub fn launch<R: Runtime>(
     key: TensorHandle<R>,
     value: TensorHandle<R>,
     mask: Option<TensorHandle<R>>,
+    sinks: Option<TensorHandle<R>>,
     out: TensorHandle<R>,
+    lse: Option<TensorHandle<R>>,
     attention_global_types: &AttentionGlobalTypes,
     attention_options: AttentionOptions,
 ) -> Result<(), AttentionSetupError> {
@@ -45,7 +62,9 @@ pub fn launch<R: Runtime>(
         &key.as_ref(),
         &value.as_ref(),
         &mask.as_ref().map(|m| m.as_ref()),
+        &sinks.as_ref().map(|s| s.as_ref()),
         &out.as_ref(),
+        &lse.as_ref().map(|l| l.as_ref()),
         attention_global_types,
         attention_options,
     )
@@ -59,10 +78,21 @@ pub fn launch_ref<R: Runtime>(
     key: &TensorHandleRef<R>,
     value: &TensorHandleRef<R>,
     mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
     out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
     attention_global_types: &AttentionGlobalTypes,
     attention_options: AttentionOptions,
 ) -> Result<(), AttentionSetupError> {
+    let mask_layout = match mask {
+        Some(mask) => Some(broadcast_mask_layout(
+            mask,
+            [query.shape[0], query.shape[1], query.shape[2], key.shape[2]],
+        )?),
+        None => None,
+    };
+    let mask = &broadcast_mask(mask, &mask_layout);
+
     match strategy {
         Strategy::BlackboxAccelerated(strategy) => {
             launch_attention::<R, BlackboxAcceleratedRoutine>(
@@ -71,7 +101,9 @@ pub fn launch_ref<R: Runtime>(
                 key,
                 value,
                 mask,
+                sinks,
                 out,
+                lse,
                 attention_global_types,
                 strategy,
                 attention_options,
@@ -83,11 +115,47 @@ pub fn launch_ref<R: Runtime>(
             key,
             value,
             mask,
+            sinks,
+            out,
+            lse,
+            attention_global_types,
+            strategy,
+            attention_options,
+        ),
+        Strategy::SplitKv(strategy) => launch_split_kv_attention::<R>(
+            client,
+            query,
+            key,
+            value,
+            mask,
+            sinks,
             out,
+            lse,
             attention_global_types,
             strategy,
             attention_options,
         ),
+        Strategy::Auto => {
+            let strategy = if query.shape[2] <= SPLIT_KV_MAX_SEQ_Q {
+                Strategy::SplitKv(BlueprintStrategy::Inferred(()))
+            } else {
+                Strategy::Unit(BlueprintStrategy::Inferred(()))
+            };
+
+            launch_ref(
+                strategy,
+                client,
+                query,
+                key,
+                value,
+                mask,
+                sinks,
+                out,
+                lse,
+                attention_global_types,
+                attention_options,
+            )
+        }
     }
 }
 
@@ -98,42 +166,82 @@ pub fn launch_attention<R: Runtime, A: Routine>(
     key: &TensorHandleRef<R>,
     value: &TensorHandleRef<R>,
     mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
     out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
     global_dtypes: &AttentionGlobalTypes,
     strategy: BlueprintStrategy<A>,
     attention_options: AttentionOptions,
 ) -> Result<(), AttentionSetupError> {
-    let definition = AttentionProblem {
-        dims: AttentionDims {
-            batch: query.shape[0],
-            num_heads: query.shape[1],
-            seq_q: query.shape[2],
-            head_dim: query.shape[3],
-            seq_kv: key.shape[2],
-            val_dim: value.shape[3],
+    let definition = attention_problem(
+        query,
+        key,
+        value,
+        mask.is_some(),
+        global_dtypes,
+        attention_options,
+    )?;
+
+    if let Some(lse) = lse {
+        validate_lse_shape(&lse.shape, &definition.dims)?;
+    }
+    validate_sinks(sinks, &definition.dims)?;
+
+    launch_routine::<R, A, TensorArgs>(
+        client,
+        &definition,
+        strategy,
+        |line_sizes| {
+            (
+                TensorInputsLaunch::new(
+                    query.as_tensor_arg(line_sizes.query),
+                    key.as_tensor_arg(line_sizes.key),
+                    value.as_tensor_arg(line_sizes.value),
+                    mask.as_ref()
+                        .map(|it| it.as_tensor_arg(line_sizes.mask))
+                        .into(),
+                ),
+                out.as_tensor_arg(line_sizes.out),
+            )
         },
-        masked: mask.is_some(),
-        global_dtypes: global_dtypes.clone(),
-        options: attention_options,
-    };
+        lse,
+        SequenceLengthsLaunch::full(),
+        ScoreArgsLaunch::from_problem(&definition, sinks),
+    )
+}
 
-    let device_settings = DeviceSettings::new(client, &definition);
-    let launch_info = A::prepare(&definition, &device_settings, strategy)?;
+/// Launches the routine on the given problem, with inputs and output created from the line
+/// sizes of the device.
+///
+/// The lengths of the sequences of the batch bound the rows each cube reads and writes.
+/// The sinks of the score arguments must have been validated against the problem.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn launch_routine<'a, R: Runtime, A: Routine, AA: AttentionArgs>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    strategy: BlueprintStrategy<A>,
+    args: impl FnOnce(&AttentionLineSizes) -> (InputRuntimeArg<'a, AA, R>, OutputRuntimeArg<'a, AA, R>),
+    lse: &'a Option<TensorHandleRef<'a, R>>,
+    sequence_lengths: SequenceLengthsLaunch<'a, R>,
+    score_args: ScoreArgsLaunch<'a, R>,
+) -> Result<(), AttentionSetupError> {
+    let device_settings = DeviceSettings::new(client, problem);
+    let launch_info = A::prepare(problem, &device_settings, strategy)?;
+    if let Some(lse) = lse {
+        validate_lse_dtype(lse, launch_info.dtypes.accumulator)?;
+    }
+    let (inputs, out) = args(&device_settings.line_sizes);
 
     let result = unsafe {
-        <A as Routine>::BatchAttention::launch_unchecked::<TensorArgs, R>(
+        <A as Routine>::BatchAttention::launch_unchecked::<AA, R>(
             client,
             launch_info.cube_dim,
             launch_info.cube_count_plan.resolve(),
-            TensorInputsLaunch::new(
-                query.as_tensor_arg(device_settings.line_sizes.query),
-                key.as_tensor_arg(device_settings.line_sizes.key),
-                value.as_tensor_arg(device_settings.line_sizes.value),
-                mask.as_ref()
-                    .map(|it| it.as_tensor_arg(device_settings.line_sizes.mask))
-                    .into(),
-            ),
-            out.as_tensor_arg(device_settings.line_sizes.out),
+            inputs,
+            out,
+            lse.as_ref().map(|it| it.as_tensor_arg(1)).into(),
+            sequence_lengths,
+            score_args,
             launch_info.cube_count_plan.as_args(),
             &launch_info.dtypes,
             launch_info.blueprint,
@@ -145,3 +253,159 @@ pub fn launch_attention<R: Runtime, A: Routine>(
         Err(err) => Err(AttentionSetupError::Execution(err)),
     }
 }
+
+/// Describes the attention problem of the given tensors, validating that the key/value heads
+/// can be shared evenly between query heads and that key and value aren't quantized.
+pub(crate) fn attention_problem<R: Runtime>(
+    query: &TensorHandleRef<R>,
+    key: &TensorHandleRef<R>,
+    value: &TensorHandleRef<R>,
+    masked: bool,
+    global_dtypes: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<AttentionProblem, AttentionSetupError> {
+    if global_dtypes.quantized_key_value() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Quantized key/value are only supported by launch_quantized_ref",
+        )));
+    }
+
+    let definition = AttentionProblem {
+        dims: AttentionDims {
+            batch: query.shape[0],
+            num_heads: query.shape[1],
+            num_kv_heads: key.shape[1],
+            seq_q: query.shape[2],
+            head_dim: query.shape[3],
+            seq_kv: key.shape[2],
+            val_dim: value.shape[3],
+        },
+        masked,
+        global_dtypes: global_dtypes.clone(),
+        options: attention_options,
+    };
+
+    if definition.dims.num_kv_heads == 0
+        || definition.dims.num_heads % definition.dims.num_kv_heads != 0
+    {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Number of key/value heads ({}) must divide the number of query heads ({})",
+            definition.dims.num_kv_heads, definition.dims.num_heads
+        ))));
+    }
+    if value.shape[1] != definition.dims.num_kv_heads {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Key and value must have the same number of heads",
+        )));
+    }
+
+    Ok(definition)
+}
+
+/// Validates that the sink logits are contiguous `f32`, with one per query head
+pub(crate) fn validate_sinks<R: Runtime>(
+    sinks: &Option<TensorHandleRef<R>>,
+    dims: &AttentionDims,
+) -> Result<(), AttentionSetupError> {
+    let Some(sinks) = sinks else {
+        return Ok(());
+    };
+
+    if sinks.shape != [dims.num_heads] {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Expected sinks of shape [{}], one per head, got {:?}",
+            dims.num_heads, sinks.shape
+        ))));
+    }
+    if sinks.strides != [1] {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Sinks must be contiguous",
+        )));
+    }
+    if sinks.elem_size != size_of::<f32>() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Sinks must be f32, got elements of {} bytes",
+            sinks.elem_size
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Shape and strides of the mask seen as `[batch, num_heads, seq_q, seq_kv]`, where its
+/// dimensions of size 1 other than `seq_kv` are broadcast.
+pub(crate) fn broadcast_mask_layout<R: Runtime>(
+    mask: &TensorHandleRef<R>,
+    shape: [usize; 4],
+) -> Result<([usize; 4], [usize; 4]), AttentionSetupError> {
+    let invalid = || {
+        AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Mask has shape {:?}, which can't be broadcast to {shape:?}",
+            mask.shape
+        )))
+    };
+
+    if mask.shape.len() != 4 || mask.shape[3] != shape[3] {
+        return Err(invalid());
+    }
+
+    let mut strides = [0; 4];
+    for (axis, stride) in strides.iter_mut().enumerate() {
+        *stride = match mask.shape[axis] {
+            size if size == shape[axis] => mask.strides[axis],
+            1 => 0,
+            _ => return Err(invalid()),
+        };
+    }
+
+    Ok((shape, strides))
+}
+
+/// The mask with the given broadcast shape and strides
+pub(crate) fn broadcast_mask<'a, R: Runtime>(
+    mask: &Option<TensorHandleRef<'a, R>>,
+    layout: &'a Option<([usize; 4], [usize; 4])>,
+) -> Option<TensorHandleRef<'a, R>> {
+    mask.as_ref()
+        .zip(layout.as_ref())
+        .map(|(mask, (shape, strides))| unsafe {
+            TensorHandleRef::from_raw_parts(mask.handle, strides, shape, mask.elem_size)
+        })
+}
+
+/// Validates that the log-sum-exp has one value per query row, either as `[batch, heads, seq_q]`
+/// or with a trailing unit dimension.
+pub(crate) fn validate_lse_shape(
+    shape: &[usize],
+    dims: &AttentionDims,
+) -> Result<(), AttentionSetupError> {
+    let expected = dims.shape(AttentionIdent::Lse);
+
+    if shape != &expected[..3] && shape != expected {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Log-sum-exp has shape {:?}, expected {:?}",
+            shape,
+            &expected[..3]
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Validates that the log-sum-exp holds elements of the accumulator type selected by the
+/// routine, in which it is written.
+pub(crate) fn validate_lse_dtype<R: Runtime>(
+    lse: &TensorHandleRef<R>,
+    accumulator: StorageType,
+) -> Result<(), AttentionSetupError> {
+    if lse.elem_size != accumulator.size() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Log-sum-exp has elements of {} bytes, expected {:?} of {} bytes",
+            lse.elem_size,
+            accumulator,
+            accumulator.size()
+        ))));
+    }
+
+    Ok(())
+}
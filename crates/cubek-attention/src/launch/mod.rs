@@ -1,5 +1,15 @@
 mod args;
+mod backward;
 mod base;
+mod paged;
+mod quantized;
+mod split_kv;
+mod varlen;
 
 pub use args::*;
+pub use backward::*;
 pub use base::*;
+pub use paged::*;
+pub use quantized::*;
+pub use split_kv::*;
+pub use varlen::*;
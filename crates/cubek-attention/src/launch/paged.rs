@@ -0,0 +1,226 @@
+use cubecl::{Runtime, client::ComputeClient, prelude::TensorHandleRef};
+
+use crate::definition::{
+    AttentionGlobalTypes, AttentionIdent, AttentionOptions, AttentionProblem, AttentionSetupError,
+};
+use crate::launch::args::{
+    PagedTensorArgs, PagedTensorInputsLaunch, ScoreArgsLaunch, SequenceLengthsLaunch,
+};
+use crate::launch::split_kv::SplitKvLaunch;
+use crate::launch::{
+    BlueprintStrategy, SPLIT_KV_MAX_SEQ_Q, Strategy, attention_problem, broadcast_mask,
+    broadcast_mask_layout, launch_routine, validate_lse_shape, validate_sinks,
+};
+use crate::routines::{
+    Routine, blackbox_accelerated::BlackboxAcceleratedRoutine, split_kv::SplitKvRoutine,
+    unit::UnitRoutine,
+};
+
+/// Key and value stored in fixed-size pages shared by all sequences
+pub struct PagedKeyValue<'a, R: Runtime> {
+    /// Key pages of shape `[num_pages, num_kv_heads, page_size, head_dim]`
+    pub key: &'a TensorHandleRef<'a, R>,
+    /// Value pages of shape `[num_pages, num_kv_heads, page_size, val_dim]`
+    pub value: &'a TensorHandleRef<'a, R>,
+    /// Pages of each sequence, as `u32` of shape `[batch, max_pages]`, where page `i` holds the
+    /// key/value rows `i * page_size..(i + 1) * page_size` of the sequence.
+    ///
+    /// Entries past the context length of a sequence are never followed.
+    pub block_table: &'a TensorHandleRef<'a, R>,
+    /// Number of key/value rows of each sequence, as `u32` of shape `[batch]`, at most
+    /// `max_pages * page_size`
+    pub context_lengths: &'a TensorHandleRef<'a, R>,
+}
+
+/// Launches attention with key and value read from pages.
+///
+/// Each sequence attends to its first `context_lengths` key/value rows. A mask, if given, has
+/// shape `[batch, num_heads, seq_q, max_pages * page_size]`, where dimensions other than the
+/// last can be of size 1 to be broadcast.
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
+pub fn launch_paged_ref<R: Runtime>(
+    strategy: Strategy,
+    client: &ComputeClient<R>,
+    query: &TensorHandleRef<R>,
+    paged: &PagedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    attention_global_types: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    let problem = paged_attention_problem(
+        query,
+        paged,
+        mask.is_some(),
+        attention_global_types,
+        attention_options,
+    )?;
+
+    let mask_layout = match mask {
+        Some(mask) => Some(broadcast_mask_layout(
+            mask,
+            problem.shape(AttentionIdent::Mask),
+        )?),
+        None => None,
+    };
+    let mask = &broadcast_mask(mask, &mask_layout);
+
+    match strategy {
+        Strategy::BlackboxAccelerated(strategy) => {
+            launch_paged_attention::<R, BlackboxAcceleratedRoutine>(
+                client, &problem, query, paged, mask, sinks, out, lse, strategy,
+            )
+        }
+        Strategy::Unit(strategy) => launch_paged_attention::<R, UnitRoutine>(
+            client, &problem, query, paged, mask, sinks, out, lse, strategy,
+        ),
+        Strategy::SplitKv(strategy) => launch_paged_split_kv_attention(
+            client, &problem, query, paged, mask, sinks, out, lse, strategy,
+        ),
+        Strategy::Auto => {
+            let strategy = if problem.dims.seq_q <= SPLIT_KV_MAX_SEQ_Q {
+                Strategy::SplitKv(BlueprintStrategy::Inferred(()))
+            } else {
+                Strategy::Unit(BlueprintStrategy::Inferred(()))
+            };
+
+            launch_paged_ref(
+                strategy,
+                client,
+                query,
+                paged,
+                mask,
+                sinks,
+                out,
+                lse,
+                attention_global_types,
+                problem.options,
+            )
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn launch_paged_attention<R: Runtime, A: Routine>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    query: &TensorHandleRef<R>,
+    paged: &PagedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    strategy: BlueprintStrategy<A>,
+) -> Result<(), AttentionSetupError> {
+    if let Some(lse) = lse {
+        validate_lse_shape(&lse.shape, &problem.dims)?;
+    }
+
+    validate_sinks(sinks, &problem.dims)?;
+
+    launch_routine::<R, A, PagedTensorArgs>(
+        client,
+        problem,
+        strategy,
+        |line_sizes| {
+            (
+                PagedTensorInputsLaunch::new(
+                    query.as_tensor_arg(line_sizes.query),
+                    paged.key.as_tensor_arg(line_sizes.key),
+                    paged.value.as_tensor_arg(line_sizes.value),
+                    mask.as_ref()
+                        .map(|it| it.as_tensor_arg(line_sizes.mask))
+                        .into(),
+                    paged.block_table.as_tensor_arg(1),
+                    paged.context_lengths.as_tensor_arg(1),
+                ),
+                out.as_tensor_arg(line_sizes.out),
+            )
+        },
+        lse,
+        SequenceLengthsLaunch::kv_lengths(paged.context_lengths),
+        ScoreArgsLaunch::from_problem(problem, sinks),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn launch_paged_split_kv_attention<R: Runtime>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    query: &TensorHandleRef<R>,
+    paged: &PagedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    strategy: BlueprintStrategy<SplitKvRoutine>,
+) -> Result<(), AttentionSetupError> {
+    validate_sinks(sinks, &problem.dims)?;
+
+    let split_kv = SplitKvLaunch::new(client, problem, strategy, lse)?;
+    let line_sizes = &split_kv.line_sizes;
+
+    split_kv.launch::<PagedTensorArgs>(
+        client,
+        problem,
+        PagedTensorInputsLaunch::new(
+            query.as_tensor_arg(line_sizes.query),
+            paged.key.as_tensor_arg(line_sizes.key),
+            paged.value.as_tensor_arg(line_sizes.value),
+            mask.as_ref()
+                .map(|it| it.as_tensor_arg(line_sizes.mask))
+                .into(),
+            paged.block_table.as_tensor_arg(1),
+            paged.context_lengths.as_tensor_arg(1),
+        ),
+        out,
+        lse,
+        SequenceLengthsLaunch::kv_lengths(paged.context_lengths),
+        ScoreArgsLaunch::from_problem(problem, sinks),
+    )
+}
+
+/// Describes the attention problem of a paged key and value, which are seen as tensors of
+/// `max_pages * page_size` rows.
+fn paged_attention_problem<R: Runtime>(
+    query: &TensorHandleRef<R>,
+    paged: &PagedKeyValue<'_, R>,
+    masked: bool,
+    global_dtypes: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<AttentionProblem, AttentionSetupError> {
+    let mut problem = attention_problem(
+        query,
+        paged.key,
+        paged.value,
+        masked,
+        global_dtypes,
+        attention_options,
+    )?;
+
+    let batch = problem.dims.batch;
+    let page_size = paged.key.shape[2];
+    if paged.value.shape[..3] != paged.key.shape[..3] {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Key and value pages must have the same number of pages, heads and page size",
+        )));
+    }
+    if paged.block_table.shape.len() != 2 || paged.block_table.shape[0] != batch {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Block table has shape {:?}, expected [{batch}, max_pages]",
+            paged.block_table.shape
+        ))));
+    }
+    if paged.context_lengths.shape != [batch] {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Context lengths have shape {:?}, expected [{batch}]",
+            paged.context_lengths.shape
+        ))));
+    }
+
+    problem.dims.seq_kv = paged.block_table.shape[1] * page_size;
+
+    Ok(problem)
+}
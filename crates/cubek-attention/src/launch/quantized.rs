@@ -0,0 +1,429 @@
+use cubecl::std::tensor::launch::ViewArg;
+use cubecl::{
+    Runtime,
+    client::ComputeClient,
+    prelude::{ScalarArg, TensorHandleRef},
+};
+use cubek_quant::layout::{ScalesLayout, scales_layout};
+use cubek_quant::scheme::{QuantLevel, QuantScheme};
+
+use crate::definition::{
+    AttentionGlobalTypes, AttentionIdent, AttentionOptions, AttentionProblem, AttentionSetupError,
+};
+use crate::launch::args::{
+    QuantizedTensorArgs, QuantizedTensorInputsLaunch, QuantizedTensorLaunch, QuantizedValuesLayout,
+    QuantizedValuesLayoutLaunch, ScoreArgsLaunch, SequenceLengthsLaunch,
+};
+use crate::launch::split_kv::SplitKvLaunch;
+use crate::launch::{
+    BlueprintStrategy, SPLIT_KV_MAX_SEQ_Q, Strategy, attention_problem, broadcast_mask,
+    broadcast_mask_layout, launch_routine, validate_lse_shape, validate_sinks,
+};
+use crate::routines::{
+    Routine, blackbox_accelerated::BlackboxAcceleratedRoutine, split_kv::SplitKvRoutine,
+    unit::UnitRoutine,
+};
+
+/// Key and value of which either can be stored quantized, following the schemes of the
+/// [AttentionGlobalTypes]
+pub struct QuantizedKeyValue<'a, R: Runtime> {
+    /// Contiguous key of shape `[batch, num_kv_heads, seq_kv, head_dim / num_quants]`, where
+    /// `num_quants` values are packed in each stored element
+    pub key: &'a TensorHandleRef<'a, R>,
+    /// Scales of the key if it is quantized, see [QuantizedKeyValue::value_scales]
+    pub key_scales: Option<&'a TensorHandleRef<'a, R>>,
+    /// Contiguous value of shape `[batch, num_kv_heads, seq_kv, val_dim / num_quants]`, where
+    /// `num_quants` values are packed in each stored element
+    pub value: &'a TensorHandleRef<'a, R>,
+    /// Scales of the value if it is quantized.
+    ///
+    /// A single scale for a tensor level quantization, otherwise one scale per block of shape
+    /// `[batch / b, num_kv_heads / h, seq_kv / s, dim / d]` for blocks of `[b, h, s, d]` values,
+    /// where dimensions of size 1 are broadcast. Blocks of `[1, 1, 1, dim]` with scales of shape
+    /// `[batch, num_kv_heads, seq_kv, 1]` give a scale per token, and scales of shape
+    /// `[1, num_kv_heads, 1, 1]` a scale per head.
+    pub value_scales: Option<&'a TensorHandleRef<'a, R>>,
+}
+
+/// Launches attention with key and value dequantized to their global types as they are read.
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
+pub fn launch_quantized_ref<R: Runtime>(
+    strategy: Strategy,
+    client: &ComputeClient<R>,
+    query: &TensorHandleRef<R>,
+    quantized: &QuantizedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    attention_global_types: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    let problem = quantized_attention_problem(
+        query,
+        quantized,
+        mask.is_some(),
+        attention_global_types,
+        attention_options,
+    )?;
+
+    let mask_layout = match mask {
+        Some(mask) => Some(broadcast_mask_layout(
+            mask,
+            problem.shape(AttentionIdent::Mask),
+        )?),
+        None => None,
+    };
+    let mask = &broadcast_mask(mask, &mask_layout);
+
+    match strategy {
+        Strategy::BlackboxAccelerated(strategy) => {
+            launch_quantized_attention::<R, BlackboxAcceleratedRoutine>(
+                client, &problem, query, quantized, mask, sinks, out, lse, strategy,
+            )
+        }
+        Strategy::Unit(strategy) => launch_quantized_attention::<R, UnitRoutine>(
+            client, &problem, query, quantized, mask, sinks, out, lse, strategy,
+        ),
+        Strategy::SplitKv(strategy) => launch_quantized_split_kv_attention(
+            client, &problem, query, quantized, mask, sinks, out, lse, strategy,
+        ),
+        Strategy::Auto => {
+            let strategy = if problem.dims.seq_q <= SPLIT_KV_MAX_SEQ_Q {
+                Strategy::SplitKv(BlueprintStrategy::Inferred(()))
+            } else {
+                Strategy::Unit(BlueprintStrategy::Inferred(()))
+            };
+
+            launch_quantized_ref(
+                strategy,
+                client,
+                query,
+                quantized,
+                mask,
+                sinks,
+                out,
+                lse,
+                attention_global_types,
+                problem.options,
+            )
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn launch_quantized_attention<R: Runtime, A: Routine>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    query: &TensorHandleRef<R>,
+    quantized: &QuantizedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    strategy: BlueprintStrategy<A>,
+) -> Result<(), AttentionSetupError> {
+    if let Some(lse) = lse {
+        validate_lse_shape(&lse.shape, &problem.dims)?;
+    }
+
+    validate_sinks(sinks, &problem.dims)?;
+    let (key_strides, value_strides) = scales_strides(problem, quantized)?;
+    let key_scales = broadcast_scales(quantized.key_scales, &key_strides);
+    let value_scales = broadcast_scales(quantized.value_scales, &value_strides);
+
+    launch_routine::<R, A, QuantizedTensorArgs>(
+        client,
+        problem,
+        strategy,
+        |line_sizes| {
+            (
+                QuantizedTensorInputsLaunch::new(
+                    query.as_tensor_arg(line_sizes.query),
+                    quantized_tensor(
+                        client,
+                        quantized.key,
+                        key_scales.as_ref(),
+                        &problem.global_dtypes.key_quant,
+                        problem.shape(AttentionIdent::Key),
+                        line_sizes.key,
+                    ),
+                    quantized_tensor(
+                        client,
+                        quantized.value,
+                        value_scales.as_ref(),
+                        &problem.global_dtypes.value_quant,
+                        problem.shape(AttentionIdent::Value),
+                        line_sizes.value,
+                    ),
+                    mask.as_ref()
+                        .map(|it| it.as_tensor_arg(line_sizes.mask))
+                        .into(),
+                ),
+                out.as_tensor_arg(line_sizes.out),
+            )
+        },
+        lse,
+        SequenceLengthsLaunch::full(),
+        ScoreArgsLaunch::from_problem(problem, sinks),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn launch_quantized_split_kv_attention<R: Runtime>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    query: &TensorHandleRef<R>,
+    quantized: &QuantizedKeyValue<'_, R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    strategy: BlueprintStrategy<SplitKvRoutine>,
+) -> Result<(), AttentionSetupError> {
+    validate_sinks(sinks, &problem.dims)?;
+    let (key_strides, value_strides) = scales_strides(problem, quantized)?;
+    let key_scales = broadcast_scales(quantized.key_scales, &key_strides);
+    let value_scales = broadcast_scales(quantized.value_scales, &value_strides);
+
+    let split_kv = SplitKvLaunch::new(client, problem, strategy, lse)?;
+    let line_sizes = &split_kv.line_sizes;
+
+    split_kv.launch::<QuantizedTensorArgs>(
+        client,
+        problem,
+        QuantizedTensorInputsLaunch::new(
+            query.as_tensor_arg(line_sizes.query),
+            quantized_tensor(
+                client,
+                quantized.key,
+                key_scales.as_ref(),
+                &problem.global_dtypes.key_quant,
+                problem.shape(AttentionIdent::Key),
+                line_sizes.key,
+            ),
+            quantized_tensor(
+                client,
+                quantized.value,
+                value_scales.as_ref(),
+                &problem.global_dtypes.value_quant,
+                problem.shape(AttentionIdent::Value),
+                line_sizes.value,
+            ),
+            mask.as_ref()
+                .map(|it| it.as_tensor_arg(line_sizes.mask))
+                .into(),
+        ),
+        out,
+        lse,
+        SequenceLengthsLaunch::full(),
+        ScoreArgsLaunch::from_problem(problem, sinks),
+    )
+}
+
+/// Describes the attention problem of a key and value seen as their dequantized tensors.
+fn quantized_attention_problem<R: Runtime>(
+    query: &TensorHandleRef<R>,
+    quantized: &QuantizedKeyValue<'_, R>,
+    masked: bool,
+    global_dtypes: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<AttentionProblem, AttentionSetupError> {
+    // Heads and sequences are validated on the stored tensors, which only differ from the
+    // dequantized ones in their last dimension
+    let dequantized_dtypes = AttentionGlobalTypes {
+        key_quant: None,
+        value_quant: None,
+        ..global_dtypes.clone()
+    };
+    let mut problem = attention_problem(
+        query,
+        quantized.key,
+        quantized.value,
+        masked,
+        &dequantized_dtypes,
+        attention_options,
+    )?;
+    problem.global_dtypes = global_dtypes.clone();
+
+    let num_quants = |scheme: &Option<QuantScheme>| scheme.as_ref().map_or(1, |it| it.num_quants());
+    problem.dims.val_dim = quantized.value.shape[3] * num_quants(&global_dtypes.value_quant);
+
+    let key_dim = quantized.key.shape[3] * num_quants(&global_dtypes.key_quant);
+    if key_dim != problem.dims.head_dim {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Key has a head dimension of {key_dim} once dequantized, expected {}",
+            problem.dims.head_dim
+        ))));
+    }
+    if quantized.value.shape[2] != problem.dims.seq_kv {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Key and value must have the same sequence length",
+        )));
+    }
+
+    Ok(problem)
+}
+
+/// Strides of the scales of the key and value once broadcast to one scale per block
+fn scales_strides<R: Runtime>(
+    problem: &AttentionProblem,
+    quantized: &QuantizedKeyValue<'_, R>,
+) -> Result<(Option<Vec<usize>>, Option<Vec<usize>>), AttentionSetupError> {
+    let dtypes = &problem.global_dtypes;
+
+    Ok((
+        broadcast_scales_strides(
+            "Key",
+            quantized.key,
+            quantized.key_scales,
+            &dtypes.key_quant,
+            problem.shape(AttentionIdent::Key),
+        )?,
+        broadcast_scales_strides(
+            "Value",
+            quantized.value,
+            quantized.value_scales,
+            &dtypes.value_quant,
+            problem.shape(AttentionIdent::Value),
+        )?,
+    ))
+}
+
+/// The scales with the given broadcast strides
+fn broadcast_scales<'a, R: Runtime>(
+    scales: Option<&TensorHandleRef<'a, R>>,
+    strides: &'a Option<Vec<usize>>,
+) -> Option<TensorHandleRef<'a, R>> {
+    scales
+        .zip(strides.as_ref())
+        .map(|(scales, strides)| unsafe {
+            TensorHandleRef::from_raw_parts(scales.handle, strides, scales.shape, scales.elem_size)
+        })
+}
+
+/// Validates the stored values and scales of a key or value against its scheme, returning the
+/// strides of the scales once broadcast to one scale per block.
+fn broadcast_scales_strides<R: Runtime>(
+    name: &str,
+    values: &TensorHandleRef<R>,
+    scales: Option<&TensorHandleRef<R>>,
+    scheme: &Option<QuantScheme>,
+    shape: [usize; 4],
+) -> Result<Option<Vec<usize>>, AttentionSetupError> {
+    let invalid = |reason: String| AttentionSetupError::InvalidConfig(Box::new(reason));
+
+    let (scheme, scales) = match (scheme, scales) {
+        (None, None) => return Ok(None),
+        (Some(scheme), Some(scales)) => (scheme, scales),
+        (None, Some(_)) => {
+            return Err(invalid(format!("{name} has scales but isn't quantized")));
+        }
+        (Some(_), None) => return Err(invalid(format!("{name} is quantized but has no scales"))),
+    };
+
+    let mut contiguous_stride = 1;
+    for axis in (0..values.shape.len()).rev() {
+        if values.shape[axis] != 1 && values.strides[axis] != contiguous_stride {
+            return Err(invalid(format!("Quantized {name} must be contiguous")));
+        }
+        contiguous_stride *= values.shape[axis];
+    }
+
+    let blocks = match &scheme.level {
+        QuantLevel::Tensor => {
+            if scales.shape.iter().product::<usize>() != 1 {
+                return Err(invalid(format!(
+                    "{name} is quantized per tensor, expected a single scale, got shape {:?}",
+                    scales.shape
+                )));
+            }
+            return Ok(Some(scales.strides.to_vec()));
+        }
+        QuantLevel::Block(block_size) => {
+            let block_size = block_size.as_slice();
+            let padding = 4 - block_size.len();
+            let block = |axis: usize| match axis.checked_sub(padding) {
+                Some(axis) => block_size[axis] as usize,
+                None => 1,
+            };
+
+            if !block(3).is_multiple_of(scheme.num_quants()) {
+                return Err(invalid(format!(
+                    "Block size of {name} must be a multiple of the {} values of a stored element",
+                    scheme.num_quants()
+                )));
+            }
+
+            core::array::from_fn::<usize, 4, _>(|axis| shape[axis].div_ceil(block(axis)))
+        }
+    };
+
+    if scales.shape.len() != 4 {
+        return Err(invalid(format!(
+            "Scales of {name} have shape {:?}, expected {blocks:?}",
+            scales.shape
+        )));
+    }
+
+    let mut strides = vec![0; 4];
+    for (axis, stride) in strides.iter_mut().enumerate() {
+        *stride = match scales.shape[axis] {
+            size if size == blocks[axis] => scales.strides[axis],
+            1 => 0,
+            _ => {
+                return Err(invalid(format!(
+                    "Scales of {name} have shape {:?}, which can't be broadcast to {blocks:?}",
+                    scales.shape
+                )));
+            }
+        };
+    }
+
+    Ok(Some(strides))
+}
+
+/// Launch argument of a key or value, dequantizing its values with their broadcast scales if
+/// it is quantized.
+///
+/// The scales buffer is read whole, as the broadcast only changes its strides.
+fn quantized_tensor<'a, R: Runtime>(
+    client: &ComputeClient<R>,
+    values: &'a TensorHandleRef<'a, R>,
+    scales: Option<&'a TensorHandleRef<'a, R>>,
+    scheme: &Option<QuantScheme>,
+    shape: [usize; 4],
+    line_size: u8,
+) -> QuantizedTensorLaunch<'a, R> {
+    let len = ScalarArg::new(shape.iter().product::<usize>() as u32);
+
+    let view = match (scheme, scales) {
+        (Some(scheme), Some(scales)) => {
+            let packing = scheme.num_quants() as u8;
+            let stored_line_size = line_size / packing;
+            let data_layout =
+                QuantizedValuesLayoutLaunch::new(len, stored_line_size as u32, packing as u32);
+            let data = ViewArg::new::<QuantizedValuesLayout>(
+                values.as_array_arg(stored_line_size),
+                data_layout,
+            );
+
+            let scales_layout = scales_layout(client, values, scales, 1, scheme);
+            let scales = ViewArg::new::<ScalesLayout>(scales.as_array_arg(1), scales_layout);
+
+            ViewArg::new_quantized(data, scales, *scheme)
+        }
+        _ => {
+            let layout = QuantizedValuesLayoutLaunch::new(len, line_size as u32, 1);
+            ViewArg::new::<QuantizedValuesLayout>(values.as_array_arg(line_size), layout)
+        }
+    };
+
+    QuantizedTensorLaunch::new(
+        view,
+        ScalarArg::new(shape[0] as u32),
+        ScalarArg::new(shape[1] as u32),
+        ScalarArg::new(shape[2] as u32),
+        ScalarArg::new(shape[3] as u32),
+    )
+}
@@ -0,0 +1,225 @@
+use cubecl::ir::StorageType;
+use cubecl::prelude::{Line, NumericExpand, Tensor};
+use cubecl::server::LaunchError;
+use cubecl::std::tensor::TensorHandle;
+use cubecl::{CubeCount, CubeDim, Runtime, client::ComputeClient, prelude::TensorHandleRef};
+
+use crate::components::batch::BatchAttentionFamily;
+use crate::components::batch::split_kv::attention_split_kv_combine;
+use crate::definition::{
+    AttentionDims, AttentionGlobalTypes, AttentionLineSizes, AttentionOptions, AttentionProblem,
+    AttentionSetupError, InputRuntimeArg, SplitKvBlueprint,
+};
+use crate::launch::args::{
+    AttentionArgs, ScoreArgsLaunch, SequenceLengthsLaunch, TensorArgs, TensorInputsLaunch,
+};
+use crate::launch::{
+    BlueprintStrategy, attention_problem, validate_lse_dtype, validate_lse_shape, validate_sinks,
+};
+use crate::routines::{DeviceSettings, LaunchInfo, Routine, split_kv::SplitKvRoutine};
+
+/// Number of output elements combined per cube
+const COMBINE_CUBE_DIM: u32 = 256;
+
+/// Launches attention with `seq_kv` split across cubes.
+///
+/// Partial outputs and log-sum-exps of every split are written to temporary tensors in the
+/// accumulator precision, then combined into `out` and the optional `lse`.
+#[allow(clippy::too_many_arguments)]
+pub fn launch_split_kv_attention<R: Runtime>(
+    client: &ComputeClient<R>,
+    query: &TensorHandleRef<R>,
+    key: &TensorHandleRef<R>,
+    value: &TensorHandleRef<R>,
+    mask: &Option<TensorHandleRef<R>>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    global_dtypes: &AttentionGlobalTypes,
+    strategy: BlueprintStrategy<SplitKvRoutine>,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    let definition = attention_problem(
+        query,
+        key,
+        value,
+        mask.is_some(),
+        global_dtypes,
+        attention_options,
+    )?;
+    validate_sinks(sinks, &definition.dims)?;
+
+    let split_kv = SplitKvLaunch::new(client, &definition, strategy, lse)?;
+    let line_sizes = &split_kv.line_sizes;
+
+    split_kv.launch::<TensorArgs>(
+        client,
+        &definition,
+        TensorInputsLaunch::new(
+            query.as_tensor_arg(line_sizes.query),
+            key.as_tensor_arg(line_sizes.key),
+            value.as_tensor_arg(line_sizes.value),
+            mask.as_ref()
+                .map(|it| it.as_tensor_arg(line_sizes.mask))
+                .into(),
+        ),
+        out,
+        lse,
+        SequenceLengthsLaunch::full(),
+        ScoreArgsLaunch::from_problem(&definition, sinks),
+    )
+}
+
+/// Split-kv attention prepared for a problem, shared by the launches of every kind of input.
+pub(crate) struct SplitKvLaunch<R: Runtime> {
+    pub line_sizes: AttentionLineSizes,
+    launch_info: LaunchInfo<SplitKvBlueprint>,
+    partials: SplitKvPartials<R>,
+}
+
+impl<R: Runtime> SplitKvLaunch<R> {
+    /// Selects the blueprint of the problem and allocates the partial results of its splits,
+    /// validating the shape and type of the optional `lse`.
+    pub fn new(
+        client: &ComputeClient<R>,
+        problem: &AttentionProblem,
+        strategy: BlueprintStrategy<SplitKvRoutine>,
+        lse: &Option<TensorHandleRef<R>>,
+    ) -> Result<Self, AttentionSetupError> {
+        if let Some(lse) = lse {
+            validate_lse_shape(&lse.shape, &problem.dims)?;
+        }
+
+        let device_settings = DeviceSettings::new(client, problem);
+        let launch_info = SplitKvRoutine::prepare(problem, &device_settings, strategy)?;
+        if let Some(lse) = lse {
+            validate_lse_dtype(lse, launch_info.dtypes.accumulator)?;
+        }
+        let partials = SplitKvPartials::new(client, &problem.dims, &launch_info);
+
+        Ok(Self {
+            line_sizes: device_settings.line_sizes,
+            launch_info,
+            partials,
+        })
+    }
+
+    /// Launches the split-kv kernel on `inputs`, then combines its partial results into `out`
+    /// and the optional `lse`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn launch<'a, AA>(
+        &'a self,
+        client: &ComputeClient<R>,
+        problem: &AttentionProblem,
+        inputs: InputRuntimeArg<'a, AA, R>,
+        out: &TensorHandleRef<R>,
+        lse: &Option<TensorHandleRef<R>>,
+        sequence_lengths: SequenceLengthsLaunch<'a, R>,
+        score_args: ScoreArgsLaunch<'a, R>,
+    ) -> Result<(), AttentionSetupError>
+    where
+        // Partial results are always written to plain tensors
+        AA: AttentionArgs<Output<NumericExpand<10>> = Tensor<Line<NumericExpand<10>>>>,
+    {
+        let launch_info = &self.launch_info;
+
+        let result = unsafe {
+            <SplitKvRoutine as Routine>::BatchAttention::launch_unchecked::<AA, R>(
+                client,
+                launch_info.cube_dim,
+                launch_info.cube_count_plan.resolve(),
+                inputs,
+                self.partials
+                    .out
+                    .as_ref()
+                    .as_tensor_arg(launch_info.blueprint.attention.line_sizes.out),
+                Some(self.partials.lse.as_ref().as_tensor_arg(1)).into(),
+                sequence_lengths,
+                score_args,
+                launch_info.cube_count_plan.as_args(),
+                &launch_info.dtypes,
+                launch_info.blueprint.clone(),
+            )
+            .and_then(|_| {
+                self.partials
+                    .combine(client, &problem.dims, out, lse, &problem.global_dtypes)
+            })
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(AttentionSetupError::Execution(err)),
+        }
+    }
+}
+
+/// Temporary tensors holding the output and log-sum-exp of every split, in the accumulator
+/// precision, stacked along the batch dimension.
+pub(crate) struct SplitKvPartials<R: Runtime> {
+    pub out: TensorHandle<R>,
+    pub lse: TensorHandle<R>,
+    accumulator: StorageType,
+}
+
+impl<R: Runtime> SplitKvPartials<R> {
+    pub fn new(
+        client: &ComputeClient<R>,
+        dims: &AttentionDims,
+        launch_info: &LaunchInfo<SplitKvBlueprint>,
+    ) -> Self {
+        let num_splits = launch_info.blueprint.num_kv_splits as usize;
+        let accumulator = launch_info.dtypes.accumulator;
+
+        Self {
+            out: TensorHandle::empty(
+                client,
+                vec![
+                    num_splits * dims.batch,
+                    dims.num_heads,
+                    dims.seq_q,
+                    dims.val_dim,
+                ],
+                accumulator,
+            ),
+            lse: TensorHandle::empty(
+                client,
+                vec![num_splits * dims.batch, dims.num_heads, dims.seq_q],
+                accumulator,
+            ),
+            accumulator,
+        }
+    }
+
+    /// Combines the partial results into `out` and the optional `lse`.
+    ///
+    /// # Safety
+    ///
+    /// The partial results must have been written by the split-kv attention kernel.
+    pub unsafe fn combine(
+        &self,
+        client: &ComputeClient<R>,
+        dims: &AttentionDims,
+        out: &TensorHandleRef<R>,
+        lse: &Option<TensorHandleRef<R>>,
+        global_dtypes: &AttentionGlobalTypes,
+    ) -> Result<(), LaunchError> {
+        let cube_count = CubeCount::Static(
+            ((dims.seq_q * dims.val_dim) as u32).div_ceil(COMBINE_CUBE_DIM),
+            (dims.batch * dims.num_heads) as u32,
+            1,
+        );
+
+        unsafe {
+            attention_split_kv_combine::launch_unchecked::<R>(
+                client,
+                cube_count,
+                CubeDim::new_1d(COMBINE_CUBE_DIM),
+                self.out.as_ref().as_tensor_arg(1),
+                self.lse.as_ref().as_tensor_arg(1),
+                out.as_tensor_arg(1),
+                lse.as_ref().map(|it| it.as_tensor_arg(1)).into(),
+                [global_dtypes.out, self.accumulator],
+            )
+        }
+    }
+}
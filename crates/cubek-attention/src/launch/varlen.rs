@@ -0,0 +1,181 @@
+use cubecl::{Runtime, client::ComputeClient, prelude::TensorHandleRef};
+
+use crate::definition::{
+    AttentionBlueprint, AttentionGlobalTypes, AttentionOptions, AttentionProblem,
+    AttentionSetupError,
+};
+use crate::launch::args::{ScoreArgsLaunch, SequenceLengthsLaunch, TensorArgs, TensorInputsLaunch};
+use crate::launch::{
+    BlueprintStrategy, Strategy, attention_problem, launch_routine, validate_sinks,
+};
+use crate::routines::{
+    DeviceSettings, Routine, blackbox_accelerated::BlackboxAcceleratedRoutine, unit::UnitRoutine,
+};
+
+/// Sequences of different lengths packed along the `seq_q` and `seq_kv` axes of a single batch
+pub struct VarlenSequences<'a, R: Runtime> {
+    /// Offsets of the query rows of each sequence, as `u32` of shape `[num_sequences + 1]`,
+    /// starting at 0 and ending at the total number of query rows
+    pub cu_seqlens_q: &'a TensorHandleRef<'a, R>,
+    /// Offsets of the key/value rows of each sequence, as `u32` of shape `[num_sequences + 1]`,
+    /// starting at 0 and ending at the total number of key/value rows
+    pub cu_seqlens_kv: &'a TensorHandleRef<'a, R>,
+    /// Number of query rows of the longest sequence
+    pub max_seqlen_q: usize,
+    /// Number of key/value rows of the longest sequence
+    pub max_seqlen_kv: usize,
+}
+
+/// Launches attention on sequences of different lengths packed without padding.
+///
+/// Query, key, value and out have shape `[1, heads, total_rows, dim]`, and the optional
+/// log-sum-exp has shape `[1, num_heads, total_q_rows]`. Each sequence only attends to its own
+/// key/value rows, and causal masking is relative to the start of each sequence.
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
+pub fn launch_varlen_ref<R: Runtime>(
+    strategy: Strategy,
+    client: &ComputeClient<R>,
+    query: &TensorHandleRef<R>,
+    key: &TensorHandleRef<R>,
+    value: &TensorHandleRef<R>,
+    sequences: &VarlenSequences<'_, R>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    attention_global_types: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<(), AttentionSetupError> {
+    let problem = varlen_attention_problem(
+        query,
+        key,
+        value,
+        sequences,
+        lse,
+        attention_global_types,
+        attention_options,
+    )?;
+
+    match strategy {
+        Strategy::BlackboxAccelerated(strategy) => {
+            launch_varlen_attention::<R, BlackboxAcceleratedRoutine>(
+                client, &problem, query, key, value, sequences, sinks, out, lse, strategy,
+            )
+        }
+        Strategy::Unit(strategy) => launch_varlen_attention::<R, UnitRoutine>(
+            client, &problem, query, key, value, sequences, sinks, out, lse, strategy,
+        ),
+        Strategy::SplitKv(_) => Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Split-KV doesn't support packed sequences",
+        ))),
+        Strategy::Auto => launch_varlen_attention::<R, UnitRoutine>(
+            client,
+            &problem,
+            query,
+            key,
+            value,
+            sequences,
+            sinks,
+            out,
+            lse,
+            BlueprintStrategy::Inferred(()),
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn launch_varlen_attention<R: Runtime, A: Routine<Blueprint = AttentionBlueprint>>(
+    client: &ComputeClient<R>,
+    problem: &AttentionProblem,
+    query: &TensorHandleRef<R>,
+    key: &TensorHandleRef<R>,
+    value: &TensorHandleRef<R>,
+    sequences: &VarlenSequences<'_, R>,
+    sinks: &Option<TensorHandleRef<R>>,
+    out: &TensorHandleRef<R>,
+    lse: &Option<TensorHandleRef<R>>,
+    strategy: BlueprintStrategy<A>,
+) -> Result<(), AttentionSetupError> {
+    let device_settings = DeviceSettings::new(client, problem);
+    let mut blueprint = A::prepare(problem, &device_settings, strategy)?.blueprint;
+
+    // Sequences end wherever their length falls, not only where the longest one does
+    blueprint.check_bounds.seq_q = true;
+    blueprint.check_bounds.seq_kv = true;
+
+    validate_sinks(sinks, &problem.dims)?;
+
+    launch_routine::<R, A, TensorArgs>(
+        client,
+        problem,
+        BlueprintStrategy::Forced(blueprint),
+        |line_sizes| {
+            (
+                TensorInputsLaunch::new(
+                    query.as_tensor_arg(line_sizes.query),
+                    key.as_tensor_arg(line_sizes.key),
+                    value.as_tensor_arg(line_sizes.value),
+                    None.into(),
+                ),
+                out.as_tensor_arg(line_sizes.out),
+            )
+        },
+        lse,
+        SequenceLengthsLaunch::packed(sequences.cu_seqlens_q, sequences.cu_seqlens_kv),
+        ScoreArgsLaunch::from_problem(problem, sinks),
+    )
+}
+
+/// Describes the attention problem of packed sequences as a batch of sequences padded to the
+/// longest one.
+fn varlen_attention_problem<R: Runtime>(
+    query: &TensorHandleRef<R>,
+    key: &TensorHandleRef<R>,
+    value: &TensorHandleRef<R>,
+    sequences: &VarlenSequences<'_, R>,
+    lse: &Option<TensorHandleRef<R>>,
+    global_dtypes: &AttentionGlobalTypes,
+    attention_options: AttentionOptions,
+) -> Result<AttentionProblem, AttentionSetupError> {
+    let mut problem =
+        attention_problem(query, key, value, false, global_dtypes, attention_options)?;
+
+    if query.shape[0] != 1 || key.shape[0] != 1 || value.shape[0] != 1 {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Packed sequences must be in a single batch",
+        )));
+    }
+
+    let offsets_shape = &sequences.cu_seqlens_q.shape;
+    if offsets_shape.len() != 1 || offsets_shape[0] < 2 {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Query offsets have shape {offsets_shape:?}, expected [num_sequences + 1]"
+        ))));
+    }
+    if sequences.cu_seqlens_kv.shape != *offsets_shape {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Key/value offsets have shape {:?}, expected {offsets_shape:?}",
+            sequences.cu_seqlens_kv.shape
+        ))));
+    }
+    if sequences.max_seqlen_q > query.shape[2] || sequences.max_seqlen_kv > key.shape[2] {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Longest sequence can't have more rows than the packed tensors",
+        )));
+    }
+
+    if let Some(lse) = lse {
+        let expected = [1, problem.dims.num_heads, query.shape[2]];
+        if lse.shape != expected && lse.shape != [expected[0], expected[1], expected[2], 1] {
+            return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+                "Log-sum-exp has shape {:?}, expected {expected:?}",
+                lse.shape
+            ))));
+        }
+    }
+
+    problem.dims.batch = offsets_shape[0] - 1;
+    problem.dims.seq_q = sequences.max_seqlen_q;
+    problem.dims.seq_kv = sequences.max_seqlen_kv;
+
+    Ok(problem)
+}
@@ -3,24 +3,30 @@ use std::fmt::Debug;
 use cubecl::client::ComputeClient;
 use cubecl::{CubeDim, Runtime};
 
+use crate::components::backward::AttentionBackwardConfig;
 use crate::components::tile::TileAttentionFamily;
 use crate::components::{
     batch::BatchAttentionFamily, global::GlobalAttentionFamily, stage::StageAttentionFamily,
 };
 use crate::definition::{
-    AttentionElems, AttentionLineSizes, AttentionProblem, AttentionSetupError, CubeCountPlan,
+    AttentionBackwardBlueprint, AttentionElems, AttentionLineSizes, AttentionProblem,
+    AttentionSetupError, CubeCountPlan,
 };
 use crate::launch::BlueprintStrategy;
 
-pub trait Routine: Debug + Clone {
+/// Settings a routine is launched with, either given as a full blueprint or inferred from a
+/// strategy, see [BlueprintStrategy].
+pub trait RoutineBlueprint: Debug + Clone {
+    type Strategy;
+    type Blueprint;
+}
+
+pub trait Routine: RoutineBlueprint {
     type TileAttention: TileAttentionFamily;
     type StageAttention: StageAttentionFamily;
     type GlobalAttention: GlobalAttentionFamily;
     type BatchAttention: BatchAttentionFamily<Blueprint = Self::Blueprint>;
 
-    type Strategy;
-    type Blueprint;
-
     fn prepare(
         problem: &AttentionProblem,
         device_settings: &DeviceSettings,
@@ -28,6 +34,15 @@ pub trait Routine: Debug + Clone {
     ) -> Result<LaunchInfo<Self::Blueprint>, AttentionSetupError>;
 }
 
+/// Routine computing the gradients of attention from the gradient of its output
+pub trait BackwardRoutine: RoutineBlueprint<Blueprint = AttentionBackwardBlueprint> {
+    fn prepare(
+        problem: &AttentionProblem,
+        device_settings: &DeviceSettings,
+        strategy: BlueprintStrategy<Self>,
+    ) -> Result<BackwardLaunchInfo, AttentionSetupError>;
+}
+
 pub struct LaunchInfo<B> {
     pub blueprint: B,
     pub dtypes: AttentionElems,
@@ -35,6 +50,11 @@ pub struct LaunchInfo<B> {
     pub cube_count_plan: CubeCountPlan,
 }
 
+pub struct BackwardLaunchInfo {
+    pub config: AttentionBackwardConfig,
+    pub dtypes: AttentionElems,
+}
+
 pub struct DeviceSettings {
     pub plane_dim: u32,
     pub line_sizes: AttentionLineSizes,
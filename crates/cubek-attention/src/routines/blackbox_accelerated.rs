@@ -9,7 +9,7 @@ use crate::definition::{
     AttentionSetupError, AttentionStageSize, AttentionTilingScheme, HypercubeBlueprint,
 };
 use crate::launch::BlueprintStrategy;
-use crate::routines::{DeviceSettings, LaunchInfo};
+use crate::routines::{DeviceSettings, LaunchInfo, RoutineBlueprint};
 use crate::{
     components::{
         batch::simple::SimpleBatchAttentionFamily, global::simple::SimpleGlobalAttentionFamily,
@@ -20,6 +20,11 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct BlackboxAcceleratedRoutine {}
 
+impl RoutineBlueprint for BlackboxAcceleratedRoutine {
+    type Strategy = ();
+    type Blueprint = AttentionBlueprint;
+}
+
 impl Routine for BlackboxAcceleratedRoutine {
     type TileAttention = BlackboxAcceleratedTileAttention;
     type StageAttention = PlanePartitionStageAttentionFamily<
@@ -31,9 +36,6 @@ impl Routine for BlackboxAcceleratedRoutine {
     type GlobalAttention = SimpleGlobalAttentionFamily<Self::StageAttention>;
     type BatchAttention = SimpleBatchAttentionFamily<Self::GlobalAttention>;
 
-    type Strategy = ();
-    type Blueprint = AttentionBlueprint;
-
     fn prepare(
         problem: &AttentionProblem,
         device_settings: &DeviceSettings,
@@ -106,7 +108,11 @@ fn blueprint(
                 two_rows_in_array_tile: false,
                 line_sizes: launch_settings.line_sizes.clone(),
                 masked: problem.masked,
+                additive_mask: problem.global_dtypes.additive_mask(),
                 causal: problem.options.causal,
+                window: problem.options.window,
+                alibi: problem.options.alibi,
+                softcap: problem.options.softcap.is_some(),
                 tiling_scheme,
                 check_bounds: tiling_scheme.check_bounds(&problem.dims),
             };
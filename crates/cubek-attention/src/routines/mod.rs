@@ -1,7 +1,11 @@
 /// Accelerated but using shared memory for rowwise operations
 pub mod blackbox_accelerated;
+/// Unit attention with `seq_kv` split across cubes
+pub mod split_kv;
 /// Unit attention
 pub mod unit;
+/// Unit attention backward pass
+pub mod unit_backward;
 
 mod base;
 
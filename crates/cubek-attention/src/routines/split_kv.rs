@@ -0,0 +1,76 @@
+use crate::components::batch::split_kv::SplitKvBatchAttentionFamily;
+use crate::definition::{AttentionProblem, AttentionSetupError, SplitKvBlueprint};
+use crate::launch::BlueprintStrategy;
+use crate::routines::unit::UnitRoutine;
+use crate::routines::{DeviceSettings, LaunchInfo, Routine, RoutineBlueprint};
+
+/// Minimum number of key/value rows each split should attend to when inferring the number of
+/// splits, below which combining the partial results outweighs the added parallelism
+const MIN_KV_PER_SPLIT: usize = 256;
+const MAX_KV_SPLITS: usize = 64;
+
+/// Unit attention where `seq_kv` is split across cubes, for problems with too few query rows to
+/// fill the device otherwise, such as decoding.
+///
+/// Each cube writes its partial output and log-sum-exp in the accumulator precision, which are
+/// then combined into the output.
+#[derive(Debug, Clone)]
+pub struct SplitKvRoutine {}
+
+impl RoutineBlueprint for SplitKvRoutine {
+    type Strategy = ();
+    type Blueprint = SplitKvBlueprint;
+}
+
+impl Routine for SplitKvRoutine {
+    type TileAttention = <UnitRoutine as Routine>::TileAttention;
+    type StageAttention = <UnitRoutine as Routine>::StageAttention;
+    type GlobalAttention = <UnitRoutine as Routine>::GlobalAttention;
+    type BatchAttention = SplitKvBatchAttentionFamily<Self::GlobalAttention>;
+
+    fn prepare(
+        problem: &AttentionProblem,
+        device_settings: &DeviceSettings,
+        strategy: BlueprintStrategy<Self>,
+    ) -> Result<LaunchInfo<Self::Blueprint>, AttentionSetupError> {
+        let (attention_strategy, num_kv_splits) = match strategy {
+            BlueprintStrategy::Forced(blueprint) => (
+                BlueprintStrategy::Forced(blueprint.attention),
+                blueprint.num_kv_splits,
+            ),
+            BlueprintStrategy::Inferred(_) => (
+                BlueprintStrategy::Inferred(()),
+                problem
+                    .dims
+                    .seq_kv
+                    .div_ceil(MIN_KV_PER_SPLIT)
+                    .clamp(1, MAX_KV_SPLITS) as u32,
+            ),
+        };
+
+        if num_kv_splits == 0 {
+            return Err(AttentionSetupError::InvalidConfig(Box::new(
+                "Number of key/value splits must be at least one".to_string(),
+            )));
+        }
+
+        let launch_info = UnitRoutine::prepare(problem, device_settings, attention_strategy)?;
+        let mut attention = launch_info.blueprint;
+        let mut dtypes = launch_info.dtypes;
+
+        // Partial outputs are written unlined in the accumulator precision
+        attention.line_sizes.out = 1;
+        dtypes.out_global = dtypes.accumulator;
+        dtypes.out_stage = dtypes.accumulator;
+
+        Ok(LaunchInfo {
+            blueprint: SplitKvBlueprint {
+                attention,
+                num_kv_splits,
+            },
+            dtypes,
+            cube_dim: launch_info.cube_dim,
+            cube_count_plan: launch_info.cube_count_plan.with_kv_splits(num_kv_splits),
+        })
+    }
+}
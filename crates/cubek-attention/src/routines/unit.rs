@@ -11,7 +11,7 @@ use crate::definition::{
     HypercubeBlueprint,
 };
 use crate::launch::BlueprintStrategy;
-use crate::routines::{DeviceSettings, LaunchInfo};
+use crate::routines::{DeviceSettings, LaunchInfo, RoutineBlueprint};
 use crate::{
     components::{
         batch::simple::SimpleBatchAttentionFamily, global::simple::SimpleGlobalAttentionFamily,
@@ -22,6 +22,11 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct UnitRoutine {}
 
+impl RoutineBlueprint for UnitRoutine {
+    type Strategy = ();
+    type Blueprint = AttentionBlueprint;
+}
+
 impl Routine for UnitRoutine {
     type TileAttention = UnitRegisterTileAttention;
     type StageAttention = UnitPartitionStageAttentionFamily<
@@ -33,9 +38,6 @@ impl Routine for UnitRoutine {
     type GlobalAttention = SimpleGlobalAttentionFamily<Self::StageAttention>;
     type BatchAttention = SimpleBatchAttentionFamily<Self::GlobalAttention>;
 
-    type Strategy = ();
-    type Blueprint = AttentionBlueprint;
-
     fn prepare(
         problem: &AttentionProblem,
         device_settings: &DeviceSettings,
@@ -113,7 +115,11 @@ fn blueprint(
                 two_rows_in_array_tile: false,
                 line_sizes: launch_settings.line_sizes.clone(),
                 masked: problem.masked,
+                additive_mask: problem.global_dtypes.additive_mask(),
                 causal: problem.options.causal,
+                window: problem.options.window,
+                alibi: problem.options.alibi,
+                softcap: problem.options.softcap.is_some(),
                 check_bounds: tiling_scheme.check_bounds(&problem.dims),
             };
 
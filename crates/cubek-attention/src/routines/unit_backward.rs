@@ -0,0 +1,125 @@
+use crate::components::backward::AttentionBackwardConfig;
+use crate::definition::{
+    AttentionBackwardBlueprint, AttentionElems, AttentionProblem, AttentionSetupError,
+    DiagonalAlignment,
+};
+use crate::launch::BlueprintStrategy;
+use crate::routines::{BackwardLaunchInfo, BackwardRoutine, DeviceSettings, RoutineBlueprint};
+
+/// Maximum head and value dimension, since each unit keeps entire rows in registers
+const MAX_ROW_SIZE: usize = 256;
+
+/// Reference backward pass where each unit computes entire rows with scalar arithmetic.
+///
+/// Unlike the forward routines, it isn't built on the tile components and is meant for
+/// correctness rather than speed. Only plain softmax attention is supported: windows, causal
+/// masking aligned other than top-left, ALiBi, additive masks, custom scales and soft-capping
+/// are rejected.
+#[derive(Debug, Clone)]
+pub struct UnitBackwardRoutine {}
+
+impl RoutineBlueprint for UnitBackwardRoutine {
+    type Strategy = ();
+    type Blueprint = AttentionBackwardBlueprint;
+}
+
+impl BackwardRoutine for UnitBackwardRoutine {
+    fn prepare(
+        problem: &AttentionProblem,
+        device_settings: &DeviceSettings,
+        strategy: BlueprintStrategy<Self>,
+    ) -> Result<BackwardLaunchInfo, AttentionSetupError> {
+        let blueprint = blueprint(problem, device_settings, strategy)?;
+
+        let dtypes = AttentionElems::from_global_types(
+            &problem.global_dtypes,
+            &problem.options.accumulator_precision,
+        );
+
+        Ok(BackwardLaunchInfo {
+            config: AttentionBackwardConfig::new(&blueprint, &problem.dims),
+            dtypes,
+        })
+    }
+}
+
+fn blueprint(
+    problem: &AttentionProblem,
+    device_settings: &DeviceSettings,
+    strategy: BlueprintStrategy<UnitBackwardRoutine>,
+) -> Result<AttentionBackwardBlueprint, AttentionSetupError> {
+    match strategy {
+        BlueprintStrategy::Forced(blueprint) => validate(problem, blueprint),
+        BlueprintStrategy::Inferred(_) => validate(
+            problem,
+            AttentionBackwardBlueprint {
+                block_size_q: device_settings.plane_dim,
+                block_size_kv: device_settings.plane_dim,
+                masked: problem.masked,
+                causal: problem.options.causal,
+            },
+        ),
+    }
+}
+
+fn validate(
+    problem: &AttentionProblem,
+    blueprint: AttentionBackwardBlueprint,
+) -> Result<AttentionBackwardBlueprint, AttentionSetupError> {
+    if blueprint.block_size_q == 0 || blueprint.block_size_kv == 0 {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Block sizes must be greater than zero".to_string(),
+        )));
+    }
+
+    if problem.dims.head_dim > MAX_ROW_SIZE || problem.dims.val_dim > MAX_ROW_SIZE {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(format!(
+            "Head dim and value dim must be at most {MAX_ROW_SIZE} for unit backward"
+        ))));
+    }
+
+    if blueprint.masked != problem.masked || blueprint.causal != problem.options.causal {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Blueprint mask settings must match the problem".to_string(),
+        )));
+    }
+
+    let options = &problem.options;
+    if !options.window.is_unbounded() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Windowed attention isn't supported by unit backward".to_string(),
+        )));
+    }
+
+    if options.window.alignment != DiagonalAlignment::TopLeft {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Only top-left aligned causal masking is supported by unit backward".to_string(),
+        )));
+    }
+
+    if options.alibi {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "ALiBi isn't supported by unit backward".to_string(),
+        )));
+    }
+
+    if problem.masked && problem.global_dtypes.additive_mask() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Additive masks aren't supported by unit backward".to_string(),
+        )));
+    }
+
+    if options.scale.is_some() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Custom scales aren't supported by unit backward".to_string(),
+        )));
+    }
+
+    if options.softcap.is_some() {
+        return Err(AttentionSetupError::InvalidConfig(Box::new(
+            "Soft-capping isn't supported by unit backward".to_string(),
+        )));
+    }
+
+    Ok(blueprint)
+}
@@ -0,0 +1,341 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime, client::ComputeClient};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionBackwardBlueprint, AttentionDims, AttentionElems,
+    AttentionGlobalTypes, AttentionIdent, AttentionOptions, AttentionProblem, AttentionSetupError,
+    AttentionWindow, DiagonalAlignment,
+};
+use cubek_attention::launch::{
+    AttentionBackwardInputs, AttentionGradients, BackwardStrategy, BlueprintStrategy,
+    launch_backward_ref,
+};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataType, StrideSpec, TestInput, assert_equals_approx,
+    current_test_mode,
+};
+
+use crate::attention::reference::attention_backward_reference;
+
+fn test_backward(
+    client: ComputeClient<TestRuntime>,
+    problem: AttentionProblem,
+    blueprint: AttentionBackwardBlueprint,
+) {
+    let random = |shape: [usize; 4], seed| {
+        TestInput::random(
+            client.clone(),
+            shape.to_vec(),
+            problem.global_dtypes.query,
+            seed,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+    let zeros = |shape: [usize; 4]| {
+        TestInput::zeros(
+            client.clone(),
+            shape.to_vec(),
+            problem.global_dtypes.query,
+            StrideSpec::RowMajor,
+        )
+        .generate_without_host_data()
+    };
+
+    let (query_handle, query_data) = random(problem.shape(AttentionIdent::Query), 12);
+    let (key_handle, key_data) = random(problem.shape(AttentionIdent::Key), 34);
+    let (value_handle, value_data) = random(problem.shape(AttentionIdent::Value), 56);
+    let (out_grad_handle, out_grad_data) = random(problem.shape(AttentionIdent::Out), 90);
+
+    let (mask_handle, mask_data) = if problem.masked {
+        let (mask_handle, mask_data) = TestInput::random(
+            client.clone(),
+            problem.shape(AttentionIdent::Mask).to_vec(),
+            problem.global_dtypes.mask,
+            78,
+            Distribution::Bernoulli(0.1),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_bool_host_data();
+
+        (Some(mask_handle), Some(mask_data))
+    } else {
+        (None, None)
+    };
+
+    let expected = attention_backward_reference(
+        &query_data,
+        &key_data,
+        &value_data,
+        mask_data.as_ref(),
+        &out_grad_data,
+        &problem,
+    );
+
+    let elems = AttentionElems::from_global_types(
+        &problem.global_dtypes,
+        &problem.options.accumulator_precision,
+    );
+
+    let out_handle = TestInput::custom(
+        client.clone(),
+        expected.out.shape.clone(),
+        problem.global_dtypes.out,
+        StrideSpec::RowMajor,
+        host_values(&expected.out),
+    )
+    .generate_without_host_data();
+    let lse_handle = TestInput::custom(
+        client.clone(),
+        expected.lse.shape.clone(),
+        elems.accumulator,
+        StrideSpec::RowMajor,
+        host_values(&expected.lse),
+    )
+    .generate_without_host_data();
+
+    let query_grad_handle = zeros(problem.shape(AttentionIdent::Query));
+    let key_grad_handle = zeros(problem.shape(AttentionIdent::Key));
+    let value_grad_handle = zeros(problem.shape(AttentionIdent::Value));
+
+    let result = launch_backward_ref(
+        BackwardStrategy::Unit(BlueprintStrategy::Forced(blueprint)),
+        &client,
+        &AttentionBackwardInputs {
+            query: &query_handle.as_ref(),
+            key: &key_handle.as_ref(),
+            value: &value_handle.as_ref(),
+            out: &out_handle.as_ref(),
+            out_grad: &out_grad_handle.as_ref(),
+            lse: &lse_handle.as_ref(),
+            mask: &mask_handle.as_ref().map(|it| it.as_ref()),
+        },
+        &AttentionGradients {
+            query_grad: &query_grad_handle.as_ref(),
+            key_grad: &key_grad_handle.as_ref(),
+            value_grad: &value_grad_handle.as_ref(),
+        },
+        &problem.global_dtypes,
+        problem.options.clone(),
+    );
+
+    if let Err(err) = result {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Test did not run: {}", err)
+        }
+        return;
+    }
+
+    let epsilon = 1e-3;
+    for (name, handle, expected) in [
+        ("query", &query_grad_handle, &expected.query_grad),
+        ("key", &key_grad_handle, &expected.key_grad),
+        ("value", &value_grad_handle, &expected.value_grad),
+    ] {
+        let actual = HostData::from_tensor_handle(&client, handle, HostDataType::F32);
+        if let Err(e) = assert_equals_approx(&actual, expected, epsilon) {
+            panic!("Wrong {name} gradient: {e}");
+        }
+    }
+}
+
+fn host_values(data: &HostData) -> Vec<f32> {
+    let num_elems = data.shape.iter().product::<usize>();
+    let mut index = vec![0; data.shape.len()];
+
+    (0..num_elems)
+        .map(|mut linear| {
+            for (dim, size) in data.shape.iter().enumerate().rev() {
+                index[dim] = linear % size;
+                linear /= size;
+            }
+            data.get_f32(&index)
+        })
+        .collect()
+}
+
+fn problem(dims: AttentionDims, masked: bool, causal: bool) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+fn blueprint(problem: &AttentionProblem) -> AttentionBackwardBlueprint {
+    AttentionBackwardBlueprint {
+        block_size_q: 8,
+        block_size_kv: 4,
+        masked: problem.masked,
+        causal: problem.options.causal,
+    }
+}
+
+fn dims(num_heads: usize, num_kv_heads: usize, seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads,
+        num_kv_heads,
+        seq_q,
+        seq_kv,
+        head_dim: 8,
+        val_dim: 8,
+    }
+}
+
+#[test]
+fn backward_simple() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(2, 2, 16, 16), false, false);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_causal() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(2, 2, 16, 16), false, true);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_masked() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(2, 2, 16, 16), true, false);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_grouped_query() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 2, 16, 16), false, false);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_multi_query_causal() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 1, 16, 16), false, true);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_unaligned_seq() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(2, 2, 13, 11), false, false);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_causal_several_blocks() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(2, 2, 40, 24), false, true);
+    let blueprint = blueprint(&problem);
+
+    test_backward(client, problem, blueprint)
+}
+
+#[test]
+fn backward_unsupported_options_rejected() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let plain = problem(dims(2, 2, 16, 16), false, true);
+    let with_options = |update: fn(&mut AttentionOptions)| {
+        let mut problem = plain.clone();
+        update(&mut problem.options);
+        problem
+    };
+
+    let unsupported = [
+        with_options(|options| options.window = AttentionWindow::sliding(4)),
+        with_options(|options| options.window.alignment = DiagonalAlignment::BottomRight),
+        with_options(|options| options.alibi = true),
+        with_options(|options| options.scale = Some(1.)),
+        with_options(|options| options.softcap = Some(2.)),
+        AttentionProblem {
+            masked: true,
+            global_dtypes: AttentionGlobalTypes {
+                mask: f32::as_type_native_unchecked(),
+                ..plain.global_dtypes.clone()
+            },
+            ..plain.clone()
+        },
+    ];
+
+    for problem in unsupported {
+        assert!(
+            launch_on_zeros(&client, &problem).is_err(),
+            "Unit backward should reject {:?}",
+            problem.options
+        );
+    }
+}
+
+/// Launches the unit backward pass on zeros
+fn launch_on_zeros(
+    client: &ComputeClient<TestRuntime>,
+    problem: &AttentionProblem,
+) -> Result<(), AttentionSetupError> {
+    let zeros = |shape: &[usize], dtype| {
+        TestInput::zeros(client.clone(), shape.to_vec(), dtype, StrideSpec::RowMajor)
+            .generate_without_host_data()
+    };
+    let dtype = problem.global_dtypes.query;
+    let query = zeros(&problem.shape(AttentionIdent::Query), dtype);
+    let key = zeros(&problem.shape(AttentionIdent::Key), dtype);
+    let value = zeros(&problem.shape(AttentionIdent::Value), dtype);
+    let out = zeros(&problem.shape(AttentionIdent::Out), dtype);
+    let out_grad = zeros(&problem.shape(AttentionIdent::Out), dtype);
+    let lse = zeros(
+        &problem.shape(AttentionIdent::Lse)[..3],
+        problem.options.accumulator_precision.storage_type(),
+    );
+    let mask = problem.masked.then(|| {
+        zeros(
+            &problem.shape(AttentionIdent::Mask),
+            problem.global_dtypes.mask,
+        )
+    });
+    let query_grad = zeros(&problem.shape(AttentionIdent::Query), dtype);
+    let key_grad = zeros(&problem.shape(AttentionIdent::Key), dtype);
+    let value_grad = zeros(&problem.shape(AttentionIdent::Value), dtype);
+
+    launch_backward_ref(
+        BackwardStrategy::Unit(BlueprintStrategy::Inferred(())),
+        client,
+        &AttentionBackwardInputs {
+            query: &query.as_ref(),
+            key: &key.as_ref(),
+            value: &value.as_ref(),
+            out: &out.as_ref(),
+            out_grad: &out_grad.as_ref(),
+            lse: &lse.as_ref(),
+            mask: &mask.as_ref().map(|it| it.as_ref()),
+        },
+        &AttentionGradients {
+            query_grad: &query_grad.as_ref(),
+            key_grad: &key_grad.as_ref(),
+            value_grad: &value_grad.as_ref(),
+        },
+        &problem.global_dtypes,
+        problem.options.clone(),
+    )
+}
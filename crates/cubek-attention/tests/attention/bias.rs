@@ -0,0 +1,311 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime, client::ComputeClient};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionElems, AttentionGlobalTypes, AttentionIdent,
+    AttentionOptions, AttentionProblem, AttentionWindow, DiagonalAlignment,
+};
+use cubek_attention::launch::{BlueprintStrategy, Strategy, launch};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataVec, StrideSpec, TestInput, current_test_mode,
+};
+
+use crate::attention::assert_result;
+
+/// Runs attention with a mask of the given shape, broadcast to the full mask shape, and compares
+/// against the reference on the broadcast mask.
+fn test_bias(
+    client: ComputeClient<TestRuntime>,
+    problem: AttentionProblem,
+    mask_shape: Option<[usize; 4]>,
+    strategy: Strategy,
+) {
+    assert_eq!(problem.masked, mask_shape.is_some());
+
+    let random = |shape: [usize; 4], seed| {
+        TestInput::random(
+            client.clone(),
+            shape.to_vec(),
+            problem.global_dtypes.query,
+            seed,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+
+    let (query_handle, query_data) = random(problem.shape(AttentionIdent::Query), 12);
+    let (key_handle, key_data) = random(problem.shape(AttentionIdent::Key), 34);
+    let (value_handle, value_data) = random(problem.shape(AttentionIdent::Value), 56);
+
+    let (mask_handle, mask_data) = match mask_shape {
+        Some(shape) => {
+            let input = |distribution| {
+                TestInput::random(
+                    client.clone(),
+                    shape.to_vec(),
+                    problem.global_dtypes.mask,
+                    78,
+                    distribution,
+                    StrideSpec::RowMajor,
+                )
+            };
+            let (mask_handle, mask_data) = if problem.global_dtypes.additive_mask() {
+                input(Distribution::Uniform(-2., 2.)).generate_with_f32_host_data()
+            } else {
+                input(Distribution::Bernoulli(0.1)).generate_with_bool_host_data()
+            };
+
+            let full_mask = broadcast(&mask_data, problem.shape(AttentionIdent::Mask));
+            (Some(mask_handle), Some(full_mask))
+        }
+        None => (None, None),
+    };
+
+    let elems = AttentionElems::from_global_types(
+        &problem.global_dtypes,
+        &problem.options.accumulator_precision,
+    );
+
+    let out_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Out).to_vec(),
+        problem.global_dtypes.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let lse_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Lse)[..3].to_vec(),
+        elems.accumulator,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    match launch(
+        strategy,
+        &client,
+        query_handle,
+        key_handle,
+        value_handle,
+        mask_handle,
+        None,
+        out_handle.clone(),
+        Some(lse_handle.clone()),
+        &problem.global_dtypes,
+        problem.options.clone(),
+    ) {
+        Ok(_) => assert_result(
+            &query_data,
+            &key_data,
+            &value_data,
+            mask_data.as_ref(),
+            None,
+            &problem,
+            &client,
+            out_handle,
+            lse_handle,
+            elems,
+        ),
+        Err(err) => {
+            if current_test_mode().should_fail_on_test_compilation_fail() {
+                panic!("Test did not run: {}", err)
+            }
+        }
+    }
+}
+
+/// Repeats the dimensions of size 1 of the mask to reach `shape`
+fn broadcast(mask: &HostData, shape: [usize; 4]) -> HostData {
+    let source = |index: [usize; 4]| -> [usize; 4] {
+        core::array::from_fn(|axis| index[axis] % mask.shape[axis])
+    };
+
+    let mut indices = Vec::with_capacity(shape.iter().product());
+    for b in 0..shape[0] {
+        for h in 0..shape[1] {
+            for i in 0..shape[2] {
+                for j in 0..shape[3] {
+                    indices.push(source([b, h, i, j]));
+                }
+            }
+        }
+    }
+
+    let data = match &mask.data {
+        HostDataVec::Bool(_) => {
+            HostDataVec::Bool(indices.iter().map(|it| mask.get_bool(it)).collect())
+        }
+        _ => HostDataVec::F32(indices.iter().map(|it| mask.get_f32(it)).collect()),
+    };
+
+    HostData {
+        data,
+        strides: StrideSpec::RowMajor.compute_strides(&shape),
+        shape: shape.to_vec(),
+    }
+}
+
+fn problem(
+    dims: AttentionDims,
+    mask_dtype: Option<AttentionGlobalTypes>,
+    causal: bool,
+    alibi: bool,
+    window: AttentionWindow,
+) -> AttentionProblem {
+    let default_dtypes = AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked());
+
+    AttentionProblem {
+        dims,
+        masked: mask_dtype.is_some(),
+        global_dtypes: mask_dtype.unwrap_or(default_dtypes),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window,
+            alibi,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+/// Global types where the mask is a float bias
+fn additive_dtypes() -> Option<AttentionGlobalTypes> {
+    let mut dtypes = AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked());
+    dtypes.mask = f32::as_type_native_unchecked();
+    Some(dtypes)
+}
+
+/// Global types where the mask is boolean
+fn boolean_dtypes() -> Option<AttentionGlobalTypes> {
+    Some(AttentionGlobalTypes::from_single_dtype(
+        f32::as_type_native_unchecked(),
+    ))
+}
+
+fn dims(num_heads: usize, seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads,
+        num_kv_heads: num_heads / 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+fn unit() -> Strategy {
+    Strategy::Unit(BlueprintStrategy::Inferred(()))
+}
+
+#[test]
+fn alibi() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 40, 56), None, false, true, Default::default());
+
+    test_bias(client, problem, None, unit())
+}
+
+#[test]
+fn alibi_heads_not_power_of_two() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(6, 32, 32), None, true, true, Default::default());
+
+    test_bias(client, problem, None, unit())
+}
+
+#[test]
+fn alibi_causal_bottom_right_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::BottomRight,
+        ..Default::default()
+    };
+    let problem = problem(dims(4, 1, 300), None, true, true, window);
+
+    test_bias(
+        client,
+        problem,
+        None,
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn additive_mask() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(4, 40, 56),
+        additive_dtypes(),
+        false,
+        false,
+        Default::default(),
+    );
+    let mask_shape = problem.shape(AttentionIdent::Mask);
+
+    test_bias(client, problem, Some(mask_shape), unit())
+}
+
+#[test]
+fn additive_mask_broadcast_over_batch_and_heads() {
+    // A relative position bias shared by all sequences and heads
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(4, 40, 56),
+        additive_dtypes(),
+        true,
+        false,
+        Default::default(),
+    );
+
+    test_bias(client, problem, Some([1, 1, 40, 56]), unit())
+}
+
+#[test]
+fn additive_mask_broadcast_over_heads_with_alibi() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(4, 32, 48),
+        additive_dtypes(),
+        false,
+        true,
+        Default::default(),
+    );
+
+    test_bias(client, problem, Some([2, 1, 32, 48]), unit())
+}
+
+#[test]
+fn boolean_mask_broadcast_over_query_rows() {
+    // A key padding mask, the same for every query row of a sequence
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(4, 40, 56),
+        boolean_dtypes(),
+        false,
+        false,
+        Default::default(),
+    );
+
+    test_bias(client, problem, Some([2, 1, 1, 56]), unit())
+}
+
+#[test]
+fn additive_mask_split_kv() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(4, 2, 256),
+        additive_dtypes(),
+        false,
+        false,
+        Default::default(),
+    );
+
+    test_bias(
+        client,
+        problem,
+        Some([1, 4, 2, 256]),
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
@@ -1,7 +1,8 @@
 use crate::attention::assert_result;
 use cubecl::TestRuntime;
+use cubecl::frontend::CubePrimitive;
 use cubek_attention::{
-    definition::{AttentionElems, AttentionIdent, AttentionOptions, AttentionProblem},
+    definition::{AttentionElems, AttentionIdent, AttentionProblem},
     launch::{Strategy, launch},
 };
 
@@ -12,12 +13,23 @@ pub fn test_launch(
     client: ComputeClient<TestRuntime>,
     problem: AttentionProblem,
     strategy: Strategy,
+) {
+    test_launch_with_sinks(client, problem, strategy, None)
+}
+
+/// Same as [test_launch], with the sink logit of each head
+pub fn test_launch_with_sinks(
+    client: ComputeClient<TestRuntime>,
+    problem: AttentionProblem,
+    strategy: Strategy,
+    sinks: Option<&[f32]>,
 ) {
     let query_shape = problem.shape(AttentionIdent::Query);
     let key_shape = problem.shape(AttentionIdent::Key);
     let value_shape = problem.shape(AttentionIdent::Value);
     let mask_shape = problem.shape(AttentionIdent::Mask);
     let out_shape = problem.shape(AttentionIdent::Out);
+    let lse_shape = &problem.shape(AttentionIdent::Lse)[..3];
 
     let (query_handle, query_data) = TestInput::random(
         client.clone(),
@@ -65,6 +77,17 @@ pub fn test_launch(
         (None, None)
     };
 
+    let sinks_handle = sinks.map(|sinks| {
+        TestInput::custom(
+            client.clone(),
+            vec![sinks.len()],
+            f32::as_type_native_unchecked(),
+            StrideSpec::RowMajor,
+            sinks.to_vec(),
+        )
+        .generate_without_host_data()
+    });
+
     let out_handle = TestInput::zeros(
         client.clone(),
         out_shape.to_vec(),
@@ -73,6 +96,20 @@ pub fn test_launch(
     )
     .generate_without_host_data();
 
+    let elems = AttentionElems::from_global_types(
+        &problem.global_dtypes,
+        &problem.options.accumulator_precision,
+    );
+
+    // The log-sum-exp is written in the accumulator type, which launch validates.
+    let lse_handle = TestInput::zeros(
+        client.clone(),
+        lse_shape.to_vec(),
+        problem.options.accumulator_precision.storage_type(),
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
     match launch(
         strategy,
         &client,
@@ -80,26 +117,23 @@ pub fn test_launch(
         key_handle,
         value_handle,
         mask_handle,
+        sinks_handle,
         out_handle.clone(),
+        Some(lse_handle.clone()),
         &problem.global_dtypes,
-        AttentionOptions {
-            causal: problem.options.causal,
-            accumulator_precision: problem.options.accumulator_precision,
-        },
+        problem.options.clone(),
     ) {
         Ok(_) => assert_result(
             &query_data,
             &key_data,
             &value_data,
             mask_data.as_ref(),
+            sinks,
             &problem,
             &client,
             out_handle,
-            // TODO this is not necessarily the dtypes selected by the algorithm
-            AttentionElems::from_global_types(
-                &problem.global_dtypes,
-                &problem.options.accumulator_precision,
-            ),
+            lse_handle,
+            elems,
         ),
         Err(err) => {
             if current_test_mode().should_fail_on_test_compilation_fail() {
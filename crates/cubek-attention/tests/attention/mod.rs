@@ -1,7 +1,15 @@
 pub(crate) mod launcher;
 
+mod backward;
+mod bias;
+mod paged;
+mod quantized;
 mod reference;
+mod softmax;
+mod split_kv;
 mod utils;
+mod varlen;
+mod window;
 
 pub(crate) use reference::assert_result;
 pub(crate) use utils::tiling_scheme_ops;
@@ -0,0 +1,294 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime, client::ComputeClient};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionElems, AttentionGlobalTypes, AttentionIdent,
+    AttentionOptions, AttentionProblem, AttentionWindow,
+};
+use cubek_attention::launch::{BlueprintStrategy, PagedKeyValue, Strategy, launch_paged_ref};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataVec, StrideSpec, TestInput, current_test_mode,
+};
+
+use crate::attention::assert_result;
+
+const PAGE_SIZE: usize = 8;
+
+/// Scatters a random dense key/value into shuffled pages, runs the paged launch and compares
+/// against the reference on the dense key/value, where rows past the context length are masked.
+fn test_paged(
+    client: ComputeClient<TestRuntime>,
+    problem: AttentionProblem,
+    context_lengths: &[usize],
+    strategy: Strategy,
+) {
+    let dims = &problem.dims;
+    assert_eq!(dims.seq_kv % PAGE_SIZE, 0);
+    assert_eq!(context_lengths.len(), dims.batch);
+
+    let max_pages = dims.seq_kv / PAGE_SIZE;
+    // One page is never referenced by the block table
+    let num_pages = dims.batch * max_pages + 1;
+
+    let random = |shape: [usize; 4], seed| {
+        TestInput::random(
+            client.clone(),
+            shape.to_vec(),
+            problem.global_dtypes.query,
+            seed,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+    let custom = |shape: Vec<usize>, dtype, data: Vec<f32>| {
+        TestInput::custom(client.clone(), shape, dtype, StrideSpec::RowMajor, data)
+            .generate_without_host_data()
+    };
+
+    let (query_handle, query_data) = random(problem.shape(AttentionIdent::Query), 12);
+    let (_, key_data) = random(problem.shape(AttentionIdent::Key), 34);
+    let (_, value_data) = random(problem.shape(AttentionIdent::Value), 56);
+
+    // Logical page `p` of sequence `b` is stored in a shuffled physical page
+    let physical_page = |b: usize, p: usize| (b * max_pages + p) * 5 % num_pages;
+
+    let mut block_table = vec![0.; dims.batch * max_pages];
+    for (b, context_length) in context_lengths.iter().enumerate() {
+        for p in 0..max_pages {
+            block_table[b * max_pages + p] = if p * PAGE_SIZE < *context_length {
+                physical_page(b, p) as f32
+            } else {
+                // Out of range, must never be followed
+                u16::MAX as f32
+            };
+        }
+    }
+
+    let scatter = |dense: &HostData, dim: usize| {
+        // Unreferenced pages hold values that would corrupt the output if read
+        let mut pages = vec![1e4; num_pages * dims.num_kv_heads * PAGE_SIZE * dim];
+        for b in 0..dims.batch {
+            for h in 0..dims.num_kv_heads {
+                for row in 0..dims.seq_kv {
+                    let page = physical_page(b, row / PAGE_SIZE);
+                    for d in 0..dim {
+                        let index = ((page * dims.num_kv_heads + h) * PAGE_SIZE + row % PAGE_SIZE)
+                            * dim
+                            + d;
+                        pages[index] = dense.get_f32(&[b, h, row, d]);
+                    }
+                }
+            }
+        }
+        pages
+    };
+
+    let key_handle = custom(
+        vec![num_pages, dims.num_kv_heads, PAGE_SIZE, dims.head_dim],
+        problem.global_dtypes.key,
+        scatter(&key_data, dims.head_dim),
+    );
+    let value_handle = custom(
+        vec![num_pages, dims.num_kv_heads, PAGE_SIZE, dims.val_dim],
+        problem.global_dtypes.value,
+        scatter(&value_data, dims.val_dim),
+    );
+    let block_table_handle = custom(
+        vec![dims.batch, max_pages],
+        u32::as_type_native_unchecked(),
+        block_table,
+    );
+    let context_lengths_handle = custom(
+        vec![dims.batch],
+        u32::as_type_native_unchecked(),
+        context_lengths.iter().map(|&it| it as f32).collect(),
+    );
+
+    let mask_shape = problem.shape(AttentionIdent::Mask);
+    let (mask_handle, mask_data) = if problem.masked {
+        let (mask_handle, mask_data) = TestInput::random(
+            client.clone(),
+            mask_shape.to_vec(),
+            problem.global_dtypes.mask,
+            78,
+            Distribution::Bernoulli(0.1),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_bool_host_data();
+
+        (Some(mask_handle), Some(mask_data))
+    } else {
+        (None, None)
+    };
+
+    // The reference sees rows past the context length as masked
+    let mut reference_mask = Vec::with_capacity(mask_shape.iter().product());
+    for b in 0..dims.batch {
+        for h in 0..dims.num_heads {
+            for i in 0..dims.seq_q {
+                for j in 0..dims.seq_kv {
+                    let masked = mask_data
+                        .as_ref()
+                        .is_some_and(|mask| mask.get_bool(&[b, h, i, j]));
+                    reference_mask.push(masked || j >= context_lengths[b]);
+                }
+            }
+        }
+    }
+    let reference_mask = HostData {
+        data: HostDataVec::Bool(reference_mask),
+        shape: mask_shape.to_vec(),
+        strides: StrideSpec::RowMajor.compute_strides(&mask_shape),
+    };
+
+    let elems = AttentionElems::from_global_types(
+        &problem.global_dtypes,
+        &problem.options.accumulator_precision,
+    );
+
+    let out_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Out).to_vec(),
+        problem.global_dtypes.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let lse_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Lse)[..3].to_vec(),
+        elems.accumulator,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let result = launch_paged_ref(
+        strategy,
+        &client,
+        &query_handle.as_ref(),
+        &PagedKeyValue {
+            key: &key_handle.as_ref(),
+            value: &value_handle.as_ref(),
+            block_table: &block_table_handle.as_ref(),
+            context_lengths: &context_lengths_handle.as_ref(),
+        },
+        &mask_handle.as_ref().map(|it| it.as_ref()),
+        &None,
+        &out_handle.as_ref(),
+        &Some(lse_handle.as_ref()),
+        &problem.global_dtypes,
+        problem.options.clone(),
+    );
+
+    if let Err(err) = result {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Test did not run: {}", err)
+        }
+        return;
+    }
+
+    let reference_problem = AttentionProblem {
+        masked: true,
+        ..problem
+    };
+
+    assert_result(
+        &query_data,
+        &key_data,
+        &value_data,
+        Some(&reference_mask),
+        None,
+        &reference_problem,
+        &client,
+        out_handle,
+        lse_handle,
+        elems,
+    );
+}
+
+fn problem(dims: AttentionDims, masked: bool, causal: bool) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+fn dims(seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads: 4,
+        num_kv_heads: 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+#[test]
+fn paged_unit() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 48), false, false);
+
+    test_paged(
+        client,
+        problem,
+        &[48, 21],
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn paged_unit_causal() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 32), false, true);
+
+    test_paged(
+        client,
+        problem,
+        &[32, 29],
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn paged_unit_masked() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 48), true, false);
+
+    test_paged(
+        client,
+        problem,
+        &[40, 9],
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn paged_split_kv_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 512), false, false);
+
+    test_paged(
+        client,
+        problem,
+        &[300, 512],
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn paged_auto_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 256), true, false);
+
+    test_paged(client, problem, &[17, 256], Strategy::Auto)
+}
@@ -0,0 +1,338 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::std::tensor::TensorHandle;
+use cubecl::{Runtime, TestRuntime, client::ComputeClient};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionElems, AttentionGlobalTypes, AttentionIdent,
+    AttentionOptions, AttentionProblem, AttentionWindow,
+};
+use cubek_attention::launch::{
+    BlueprintStrategy, QuantizedKeyValue, Strategy, launch_quantized_ref,
+};
+use cubek_quant::scheme::{QuantLevel, QuantMode, QuantParam, QuantScheme, QuantStore, QuantValue};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataVec, StrideSpec, TestInput, current_test_mode,
+};
+
+use crate::attention::assert_result;
+
+#[derive(Clone, Copy, Debug)]
+/// Granularity of the scales of a quantized key or value
+enum Scales {
+    PerTensor,
+    PerHead,
+    PerToken,
+}
+
+impl Scales {
+    fn scheme(&self, dim: usize) -> QuantScheme {
+        let level = match self {
+            Scales::PerTensor => QuantLevel::Tensor,
+            Scales::PerHead | Scales::PerToken => QuantLevel::block([1, dim as u8]),
+        };
+
+        QuantScheme::default()
+            .with_level(level)
+            .with_mode(QuantMode::Symmetric)
+            .with_value(QuantValue::Q8S)
+            .with_store(QuantStore::Native)
+            .with_param(QuantParam::F32)
+    }
+
+    /// Shape of the scales of a tensor of the given shape, before broadcasting
+    fn shape(&self, shape: [usize; 4]) -> Vec<usize> {
+        match self {
+            Scales::PerTensor => vec![1],
+            Scales::PerHead => vec![1, shape[1], 1, 1],
+            Scales::PerToken => vec![shape[0], shape[1], shape[2], 1],
+        }
+    }
+
+    /// Index of the scale of the element at `index`
+    fn index(&self, shape: [usize; 4], index: [usize; 4]) -> usize {
+        match self {
+            Scales::PerTensor => 0,
+            Scales::PerHead => index[1],
+            Scales::PerToken => (index[0] * shape[1] + index[1]) * shape[2] + index[2],
+        }
+    }
+}
+
+/// A key or value stored as int8 codes with their scales, along with its dequantized values
+struct Quantized {
+    codes: TensorHandle<TestRuntime>,
+    scales: TensorHandle<TestRuntime>,
+    dequantized: HostData,
+}
+
+fn quantize(
+    client: &ComputeClient<TestRuntime>,
+    shape: [usize; 4],
+    scales: Scales,
+    seed: usize,
+) -> Quantized {
+    let len = shape.iter().product::<usize>();
+    let codes: Vec<f32> = (0..len)
+        .map(|i| ((i * 37 + seed) % 255) as f32 - 127.)
+        .collect();
+
+    let scales_shape = scales.shape(shape);
+    let scale_values: Vec<f32> = (0..scales_shape.iter().product())
+        .map(|i| 0.002 * ((i * 5 + seed) % 7 + 1) as f32)
+        .collect();
+
+    let strides = StrideSpec::RowMajor.compute_strides(&shape);
+    let mut dequantized = Vec::with_capacity(len);
+    for b in 0..shape[0] {
+        for h in 0..shape[1] {
+            for s in 0..shape[2] {
+                for d in 0..shape[3] {
+                    let index = [b, h, s, d];
+                    let offset: usize = index.iter().zip(&strides).map(|(i, s)| i * s).sum();
+                    dequantized.push(codes[offset] * scale_values[scales.index(shape, index)]);
+                }
+            }
+        }
+    }
+
+    Quantized {
+        codes: TestInput::custom(
+            client.clone(),
+            shape.to_vec(),
+            i8::as_type_native_unchecked(),
+            StrideSpec::RowMajor,
+            codes,
+        )
+        .generate_without_host_data(),
+        scales: TestInput::custom(
+            client.clone(),
+            scales_shape,
+            f32::as_type_native_unchecked(),
+            StrideSpec::RowMajor,
+            scale_values,
+        )
+        .generate_without_host_data(),
+        dequantized: HostData {
+            data: HostDataVec::F32(dequantized),
+            shape: shape.to_vec(),
+            strides,
+        },
+    }
+}
+
+/// Runs attention on a key and value quantized with the given scales, or kept as floats if
+/// `None`, and compares against the reference on their dequantized values.
+fn test_quantized(
+    client: ComputeClient<TestRuntime>,
+    mut problem: AttentionProblem,
+    key_scales: Option<Scales>,
+    value_scales: Option<Scales>,
+    strategy: Strategy,
+) {
+    let key_shape = problem.shape(AttentionIdent::Key);
+    let value_shape = problem.shape(AttentionIdent::Value);
+    problem.global_dtypes.key_quant = key_scales.map(|it| it.scheme(problem.dims.head_dim));
+    problem.global_dtypes.value_quant = value_scales.map(|it| it.scheme(problem.dims.val_dim));
+
+    let random = |shape: [usize; 4], seed| {
+        TestInput::random(
+            client.clone(),
+            shape.to_vec(),
+            problem.global_dtypes.query,
+            seed,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+    let input = |shape: [usize; 4], scales: Option<Scales>, seed| match scales {
+        Some(scales) => {
+            let quantized = quantize(&client, shape, scales, seed);
+            (
+                quantized.codes,
+                Some(quantized.scales),
+                quantized.dequantized,
+            )
+        }
+        None => {
+            let (handle, data) = random(shape, seed as u64);
+            (handle, None, data)
+        }
+    };
+
+    let (query_handle, query_data) = random(problem.shape(AttentionIdent::Query), 12);
+    let (key_handle, key_scales_handle, key_data) = input(key_shape, key_scales, 34);
+    let (value_handle, value_scales_handle, value_data) = input(value_shape, value_scales, 56);
+
+    let (mask_handle, mask_data) = if problem.masked {
+        let (mask_handle, mask_data) = TestInput::random(
+            client.clone(),
+            problem.shape(AttentionIdent::Mask).to_vec(),
+            problem.global_dtypes.mask,
+            78,
+            Distribution::Bernoulli(0.1),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_bool_host_data();
+
+        (Some(mask_handle), Some(mask_data))
+    } else {
+        (None, None)
+    };
+
+    let elems = AttentionElems::from_global_types(
+        &problem.global_dtypes,
+        &problem.options.accumulator_precision,
+    );
+
+    let out_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Out).to_vec(),
+        problem.global_dtypes.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let lse_handle = TestInput::zeros(
+        client.clone(),
+        problem.shape(AttentionIdent::Lse)[..3].to_vec(),
+        elems.accumulator,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let key_scales_ref = key_scales_handle.as_ref().map(|it| it.as_ref());
+    let value_scales_ref = value_scales_handle.as_ref().map(|it| it.as_ref());
+
+    let result = launch_quantized_ref(
+        strategy,
+        &client,
+        &query_handle.as_ref(),
+        &QuantizedKeyValue {
+            key: &key_handle.as_ref(),
+            key_scales: key_scales_ref.as_ref(),
+            value: &value_handle.as_ref(),
+            value_scales: value_scales_ref.as_ref(),
+        },
+        &mask_handle.as_ref().map(|it| it.as_ref()),
+        &None,
+        &out_handle.as_ref(),
+        &Some(lse_handle.as_ref()),
+        &problem.global_dtypes,
+        problem.options.clone(),
+    );
+
+    if let Err(err) = result {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Test did not run: {}", err)
+        }
+        return;
+    }
+
+    assert_result(
+        &query_data,
+        &key_data,
+        &value_data,
+        mask_data.as_ref(),
+        None,
+        &problem,
+        &client,
+        out_handle,
+        lse_handle,
+        elems,
+    );
+}
+
+fn problem(dims: AttentionDims, masked: bool, causal: bool) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+fn dims(seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads: 4,
+        num_kv_heads: 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+fn unit() -> Strategy {
+    Strategy::Unit(BlueprintStrategy::Inferred(()))
+}
+
+#[test]
+fn quantized_per_head() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 48), false, false);
+
+    test_quantized(
+        client,
+        problem,
+        Some(Scales::PerHead),
+        Some(Scales::PerHead),
+        unit(),
+    )
+}
+
+#[test]
+fn quantized_per_token() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 48), true, false);
+
+    test_quantized(
+        client,
+        problem,
+        Some(Scales::PerToken),
+        Some(Scales::PerToken),
+        unit(),
+    )
+}
+
+#[test]
+fn quantized_per_tensor_causal() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 32), false, true);
+
+    test_quantized(
+        client,
+        problem,
+        Some(Scales::PerTensor),
+        Some(Scales::PerTensor),
+        unit(),
+    )
+}
+
+#[test]
+fn quantized_key_only() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(32, 48), false, false);
+
+    test_quantized(client, problem, Some(Scales::PerToken), None, unit())
+}
+
+#[test]
+fn quantized_split_kv_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 512), false, false);
+
+    test_quantized(
+        client,
+        problem,
+        Some(Scales::PerHead),
+        Some(Scales::PerToken),
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
@@ -2,7 +2,9 @@ use core::f32;
 
 use cubecl::{TestRuntime, client::ComputeClient, std::tensor::TensorHandle};
 
-use cubek_attention::definition::{AttentionElems, AttentionProblem};
+use cubek_attention::definition::{
+    AttentionElems, AttentionIdent, AttentionProblem, DiagonalAlignment,
+};
 use cubek_test_utils::{HostData, HostDataType, HostDataVec, StrideSpec, assert_equals_approx};
 
 #[allow(clippy::too_many_arguments)]
@@ -11,19 +13,28 @@ pub fn assert_result(
     key: &HostData,
     value: &HostData,
     mask: Option<&HostData>,
+    sinks: Option<&[f32]>,
     problem: &AttentionProblem,
     client: &ComputeClient<TestRuntime>,
     out: TensorHandle<TestRuntime>,
+    lse: TensorHandle<TestRuntime>,
     elems: AttentionElems,
 ) {
     let epsilon = attention_epsilon(&elems, 0.1);
-    let expected = flash_attention_v2_reference(query, key, value, mask, problem);
+    let (expected, expected_lse) =
+        flash_attention_v2_reference(query, key, value, mask, sinks, problem);
 
     let actual = HostData::from_tensor_handle(client, &out, HostDataType::F32);
 
     if let Err(e) = assert_equals_approx(&actual, &expected, epsilon) {
         panic!("{}", e);
     }
+
+    let actual_lse = HostData::from_tensor_handle(client, &lse, HostDataType::F32);
+
+    if let Err(e) = assert_equals_approx(&actual_lse, &expected_lse, epsilon) {
+        panic!("Wrong log-sum-exp: {}", e);
+    }
 }
 
 fn attention_epsilon(elems: &AttentionElems, safety_factor: f32) -> f32 {
@@ -46,28 +57,81 @@ fn attention_epsilon(elems: &AttentionElems, safety_factor: f32) -> f32 {
 
     total_eps as f32 * safety_factor
 }
+/// Positions of query row `i` and key/value row `j`, equal when aligned on the diagonal
+fn aligned_positions(problem: &AttentionProblem, i: usize, j: usize) -> (usize, usize) {
+    let (seq_q, seq_kv) = (problem.dims.seq_q, problem.dims.seq_kv);
+
+    let (q_shift, kv_shift) = match problem.options.window.alignment {
+        DiagonalAlignment::TopLeft => (0, 0),
+        DiagonalAlignment::BottomRight => {
+            (seq_kv.saturating_sub(seq_q), seq_q.saturating_sub(seq_kv))
+        }
+        DiagonalAlignment::Offset(offset) => (offset as usize, 0),
+    };
+
+    (i + q_shift, j + kv_shift)
+}
+
+/// Whether key/value row `j` is outside the window of query row `i`, causal mask included
+pub fn outside_window(problem: &AttentionProblem, i: usize, j: usize) -> bool {
+    let window = problem.options.window.with_causal(problem.options.causal);
+    let (q_pos, kv_pos) = aligned_positions(problem, i, j);
+
+    window
+        .right
+        .is_some_and(|right| kv_pos > q_pos + right as usize)
+        || window
+            .left
+            .is_some_and(|left| kv_pos + (left as usize) < q_pos)
+}
+
+/// ALiBi bias of the score of query row `i` and key/value row `j` in head `h`, zero without ALiBi
+pub fn alibi_bias(problem: &AttentionProblem, h: usize, i: usize, j: usize) -> f32 {
+    if !problem.options.alibi {
+        return 0.;
+    }
+
+    let num_heads = problem.dims.num_heads;
+    let pow2 = 1 << num_heads.ilog2();
+    let exponent = if h < pow2 {
+        8 * (h + 1)
+    } else {
+        4 * (2 * (h - pow2) + 1)
+    };
+    let slope = 2f32.powf(-(exponent as f32) / pow2 as f32);
+
+    let (q_pos, kv_pos) = aligned_positions(problem, i, j);
+    -slope * q_pos.abs_diff(kv_pos) as f32
+}
+
 pub fn flash_attention_v2_reference(
     query: &HostData,
     key: &HostData,
     value: &HostData,
     mask: Option<&HostData>,
+    sinks: Option<&[f32]>,
     problem: &AttentionProblem,
-) -> HostData {
+) -> (HostData, HostData) {
     let batch = problem.dims.batch;
     let seq_q = problem.dims.seq_q;
     let seq_kv = problem.dims.seq_kv;
     let num_heads = problem.dims.num_heads;
+    let num_heads_per_kv_head = problem.dims.num_heads_per_kv_head();
     let head_dim = problem.dims.head_dim;
     let val_dim = problem.dims.val_dim;
 
     let masked = mask.is_some();
     assert!(problem.masked == masked);
+    let additive_mask = problem.global_dtypes.additive_mask();
 
     // Output shape: [batch, num_heads, seq_q, val_dim]
     let out_shape = vec![batch, num_heads, seq_q, val_dim];
     let mut out = vec![0.; batch * num_heads * seq_q * val_dim];
+    // Log-sum-exp shape: [batch, num_heads, seq_q]
+    let lse_shape = vec![batch, num_heads, seq_q];
+    let mut lse = vec![0.; batch * num_heads * seq_q];
 
-    let scale = (head_dim as f32).sqrt().recip();
+    let scale = problem.options.score_scale(head_dim);
 
     // Use fixed-size arrays instead of heap Vec
     let mut q_index: [usize; 4];
@@ -78,10 +142,15 @@ pub fn flash_attention_v2_reference(
 
     for b in 0..batch {
         for h in 0..num_heads {
+            // Query heads in the same group share a key/value head
+            let kv_h = h / num_heads_per_kv_head;
+
             for i in 0..seq_q {
-                // initialize running row accumulator
-                let mut m = f32::NEG_INFINITY;
-                let mut l = 0.;
+                // initialize running row accumulator, which starts with the sink of the head
+                let (mut m, mut l) = match sinks {
+                    Some(sinks) => (sinks[h], 1.),
+                    None => (f32::NEG_INFINITY, 0.),
+                };
                 let mut acc_row = vec![0.; val_dim];
 
                 for j in 0..seq_kv {
@@ -89,17 +158,22 @@ pub fn flash_attention_v2_reference(
                     let mut dot = 0.;
                     for d in 0..head_dim {
                         q_index = [b, h, i, d];
-                        k_index = [b, h, j, d];
+                        k_index = [b, kv_h, j, d];
                         dot += query.get_f32(&q_index) * key.get_f32(&k_index);
                     }
                     dot *= scale;
+                    if let Some(softcap) = problem.options.softcap {
+                        dot = softcap * f32::tanh(dot / softcap);
+                    }
 
-                    // apply causal/external mask
-                    let s_val = if problem.options.causal && j > i {
+                    // apply window/external mask and positional bias
+                    let s_val = if outside_window(problem, i, j) {
                         f32::NEG_INFINITY
                     } else if let Some(mask) = mask {
                         m_index = [b, h, i, j];
-                        if mask.get_bool(&m_index) {
+                        if additive_mask {
+                            dot + mask.get_f32(&m_index)
+                        } else if mask.get_bool(&m_index) {
                             f32::NEG_INFINITY
                         } else {
                             dot
@@ -107,6 +181,7 @@ pub fn flash_attention_v2_reference(
                     } else {
                         dot
                     };
+                    let s_val = s_val + alibi_bias(problem, h, i, j);
 
                     // skip update if row is fully masked (prevent NaNs)
                     if s_val == f32::NEG_INFINITY && m == f32::NEG_INFINITY {
@@ -126,7 +201,7 @@ pub fn flash_attention_v2_reference(
                     let scale_old = f32::exp(m - m_new);
                     for d in 0..val_dim {
                         acc_row[d] *= scale_old;
-                        v_index = [b, h, j, d];
+                        v_index = [b, kv_h, j, d];
                         acc_row[d] += p_tilde * value.get_f32(&v_index);
                     }
 
@@ -135,6 +210,9 @@ pub fn flash_attention_v2_reference(
                     l = l_new;
                 }
 
+                // log-sum-exp of the row including the sink, negative infinity if fully masked
+                lse[(b * num_heads + h) * seq_q + i] = m + f32::ln(l);
+
                 // normalize and write output
                 out_index[0] = b;
                 out_index[1] = h;
@@ -153,10 +231,138 @@ pub fn flash_attention_v2_reference(
         }
     }
 
-    let strides = StrideSpec::RowMajor.compute_strides(&out_shape);
+    (
+        row_major_host_data(out, out_shape),
+        row_major_host_data(lse, lse_shape),
+    )
+}
+
+/// Forward outputs and gradients of attention computed on the CPU
+pub struct BackwardReference {
+    pub out: HostData,
+    /// Log-sum-exp of the scaled scores of each query row, or negative infinity when the row is
+    /// fully masked
+    pub lse: HostData,
+    pub query_grad: HostData,
+    pub key_grad: HostData,
+    pub value_grad: HostData,
+}
+
+pub fn attention_backward_reference(
+    query: &HostData,
+    key: &HostData,
+    value: &HostData,
+    mask: Option<&HostData>,
+    out_grad: &HostData,
+    problem: &AttentionProblem,
+) -> BackwardReference {
+    let dims = &problem.dims;
+    let (batch, num_heads, num_kv_heads) = (dims.batch, dims.num_heads, dims.num_kv_heads);
+    let (seq_q, seq_kv, head_dim, val_dim) = (dims.seq_q, dims.seq_kv, dims.head_dim, dims.val_dim);
+    let num_heads_per_kv_head = dims.num_heads_per_kv_head();
+
+    let scale = (head_dim as f32).sqrt().recip();
+
+    let mut out = vec![0.; batch * num_heads * seq_q * val_dim];
+    let mut lse = vec![0.; batch * num_heads * seq_q];
+    let mut query_grad = vec![0.; batch * num_heads * seq_q * head_dim];
+    let mut key_grad = vec![0.; batch * num_kv_heads * seq_kv * head_dim];
+    let mut value_grad = vec![0.; batch * num_kv_heads * seq_kv * val_dim];
+
+    for b in 0..batch {
+        for h in 0..num_heads {
+            let kv_h = h / num_heads_per_kv_head;
+            let row_index = (b * num_heads + h) * seq_q;
+            let kv_row_index = (b * num_kv_heads + kv_h) * seq_kv;
+
+            for i in 0..seq_q {
+                // Scaled scores, with masked scores at negative infinity
+                let scores: Vec<f32> = (0..seq_kv)
+                    .map(|j| {
+                        let masked = (problem.options.causal && j > i)
+                            || mask.is_some_and(|mask| mask.get_bool(&[b, h, i, j]));
+                        if masked {
+                            return f32::NEG_INFINITY;
+                        }
+                        (0..head_dim)
+                            .map(|d| query.get_f32(&[b, h, i, d]) * key.get_f32(&[b, kv_h, j, d]))
+                            .sum::<f32>()
+                            * scale
+                    })
+                    .collect();
+
+                let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                let row_lse = if max == f32::NEG_INFINITY {
+                    f32::NEG_INFINITY
+                } else {
+                    max + scores.iter().map(|s| f32::exp(s - max)).sum::<f32>().ln()
+                };
+                lse[row_index + i] = row_lse;
+
+                let probabilities: Vec<f32> = scores
+                    .iter()
+                    .map(|&s| {
+                        if s == f32::NEG_INFINITY {
+                            0.
+                        } else {
+                            f32::exp(s - row_lse)
+                        }
+                    })
+                    .collect();
+
+                for d in 0..val_dim {
+                    out[(row_index + i) * val_dim + d] = (0..seq_kv)
+                        .map(|j| probabilities[j] * value.get_f32(&[b, kv_h, j, d]))
+                        .sum();
+                }
+
+                // Gradient of the probabilities, dP = dO V^T
+                let probability_grads: Vec<f32> = (0..seq_kv)
+                    .map(|j| {
+                        (0..val_dim)
+                            .map(|d| {
+                                out_grad.get_f32(&[b, h, i, d]) * value.get_f32(&[b, kv_h, j, d])
+                            })
+                            .sum()
+                    })
+                    .collect();
+                let delta: f32 = (0..seq_kv)
+                    .map(|j| probabilities[j] * probability_grads[j])
+                    .sum();
+
+                for j in 0..seq_kv {
+                    for d in 0..val_dim {
+                        value_grad[(kv_row_index + j) * val_dim + d] +=
+                            probabilities[j] * out_grad.get_f32(&[b, h, i, d]);
+                    }
+
+                    // Gradient of the unscaled score
+                    let score_grad = probabilities[j] * (probability_grads[j] - delta) * scale;
+                    for d in 0..head_dim {
+                        query_grad[(row_index + i) * head_dim + d] +=
+                            score_grad * key.get_f32(&[b, kv_h, j, d]);
+                        key_grad[(kv_row_index + j) * head_dim + d] +=
+                            score_grad * query.get_f32(&[b, h, i, d]);
+                    }
+                }
+            }
+        }
+    }
+
+    BackwardReference {
+        out: row_major_host_data(out, problem.shape(AttentionIdent::Out).to_vec()),
+        lse: row_major_host_data(lse, vec![batch, num_heads, seq_q]),
+        query_grad: row_major_host_data(query_grad, problem.shape(AttentionIdent::Query).to_vec()),
+        key_grad: row_major_host_data(key_grad, problem.shape(AttentionIdent::Key).to_vec()),
+        value_grad: row_major_host_data(value_grad, problem.shape(AttentionIdent::Value).to_vec()),
+    }
+}
+
+fn row_major_host_data(data: Vec<f32>, shape: Vec<usize>) -> HostData {
+    let strides = StrideSpec::RowMajor.compute_strides(&shape);
     HostData {
-        data: HostDataVec::F32(out),
-        shape: out_shape,
+        data: HostDataVec::F32(data),
+        shape,
         strides,
     }
 }
@@ -0,0 +1,211 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::ir::StorageType;
+use cubecl::{Runtime, TestRuntime};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionGlobalTypes, AttentionIdent, AttentionOptions,
+    AttentionProblem, AttentionSetupError, AttentionWindow, DiagonalAlignment,
+};
+use cubek_attention::launch::{BlueprintStrategy, Strategy, launch};
+use cubek_test_utils::{StrideSpec, TestInput};
+
+use crate::attention::launcher::{test_launch, test_launch_with_sinks};
+
+fn problem(
+    dims: AttentionDims,
+    causal: bool,
+    window: AttentionWindow,
+    scale: Option<f32>,
+    softcap: Option<f32>,
+) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked: false,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window,
+            alibi: false,
+            scale,
+            softcap,
+        },
+    }
+}
+
+fn dims(seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads: 4,
+        num_kv_heads: 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+/// Sink logits on both sides of the scores, including one that dominates them
+const SINKS: [f32; 4] = [0.5, -1.5, 3., 8.];
+
+fn unit() -> Strategy {
+    Strategy::Unit(BlueprintStrategy::Inferred(()))
+}
+
+fn split_kv() -> Strategy {
+    Strategy::SplitKv(BlueprintStrategy::Inferred(()))
+}
+
+#[test]
+fn custom_scale() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(40, 56), false, Default::default(), Some(1.), None);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn softcap() {
+    // A scale large enough for the cap to matter
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(40, 56), true, Default::default(), Some(4.), Some(2.));
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn sinks_per_head() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(40, 56), false, Default::default(), None, None);
+
+    test_launch_with_sinks(client, problem, unit(), Some(&SINKS))
+}
+
+#[test]
+fn sinks_with_fully_masked_rows() {
+    // The first query rows attend to no key/value row, leaving only the sink
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::BottomRight,
+        ..Default::default()
+    };
+    let problem = problem(dims(48, 32), true, window, None, None);
+
+    test_launch_with_sinks(client, problem, unit(), Some(&SINKS))
+}
+
+#[test]
+fn sinks_split_kv_decoding() {
+    // The sink must be counted once across splits
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 512), false, Default::default(), None, None);
+
+    test_launch_with_sinks(client, problem, split_kv(), Some(&SINKS))
+}
+
+#[test]
+fn softcap_and_sinks_split_kv() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(
+        dims(2, 300),
+        true,
+        AttentionWindow {
+            alignment: DiagonalAlignment::BottomRight,
+            ..Default::default()
+        },
+        Some(0.5),
+        Some(5.),
+    );
+
+    test_launch_with_sinks(client, problem, split_kv(), Some(&SINKS))
+}
+
+#[test]
+fn sinks_of_wrong_shape_rejected() {
+    assert!(launch_with_sinks(vec![3], f32::as_type_native_unchecked()).is_err());
+}
+
+#[test]
+fn sinks_not_f32_rejected() {
+    assert!(launch_with_sinks(vec![4], half::f16::as_type_native_unchecked()).is_err());
+}
+
+#[test]
+fn lse_not_accumulator_rejected() {
+    let dtype = half::f16::as_type_native_unchecked();
+    assert!(launch_with_lse(unit(), dtype).is_err());
+    assert!(launch_with_lse(split_kv(), dtype).is_err());
+}
+
+/// Launches on zeros with sinks of the given shape and dtype
+fn launch_with_sinks(shape: Vec<usize>, dtype: StorageType) -> Result<(), AttentionSetupError> {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(8, 8), false, Default::default(), None, None);
+    let zeros = |shape: [usize; 4], dtype| {
+        TestInput::zeros(client.clone(), shape.to_vec(), dtype, StrideSpec::RowMajor)
+            .generate_without_host_data()
+    };
+    let sinks = TestInput::zeros(client.clone(), shape, dtype, StrideSpec::RowMajor)
+        .generate_without_host_data();
+
+    launch(
+        unit(),
+        &client,
+        zeros(
+            problem.shape(AttentionIdent::Query),
+            problem.global_dtypes.query,
+        ),
+        zeros(
+            problem.shape(AttentionIdent::Key),
+            problem.global_dtypes.key,
+        ),
+        zeros(
+            problem.shape(AttentionIdent::Value),
+            problem.global_dtypes.value,
+        ),
+        None,
+        Some(sinks),
+        zeros(
+            problem.shape(AttentionIdent::Out),
+            problem.global_dtypes.out,
+        ),
+        None,
+        &problem.global_dtypes,
+        problem.options.clone(),
+    )
+}
+
+/// Launches on zeros with a log-sum-exp of the given dtype
+fn launch_with_lse(strategy: Strategy, dtype: StorageType) -> Result<(), AttentionSetupError> {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(8, 8), false, Default::default(), None, None);
+    let zeros = |shape: &[usize], dtype| {
+        TestInput::zeros(client.clone(), shape.to_vec(), dtype, StrideSpec::RowMajor)
+            .generate_without_host_data()
+    };
+
+    launch(
+        strategy,
+        &client,
+        zeros(
+            &problem.shape(AttentionIdent::Query),
+            problem.global_dtypes.query,
+        ),
+        zeros(
+            &problem.shape(AttentionIdent::Key),
+            problem.global_dtypes.key,
+        ),
+        zeros(
+            &problem.shape(AttentionIdent::Value),
+            problem.global_dtypes.value,
+        ),
+        None,
+        None,
+        zeros(
+            &problem.shape(AttentionIdent::Out),
+            problem.global_dtypes.out,
+        ),
+        Some(zeros(&problem.shape(AttentionIdent::Lse)[..3], dtype)),
+        &problem.global_dtypes,
+        problem.options.clone(),
+    )
+}
@@ -0,0 +1,148 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionBlueprint, AttentionDims, AttentionGlobalTypes,
+    AttentionOptions, AttentionPartitionSize, AttentionProblem, AttentionStageSize,
+    AttentionTileSize, AttentionTilingScheme, AttentionWindow, HypercubeBlueprint,
+    SplitKvBlueprint,
+};
+use cubek_attention::launch::{BlueprintStrategy, Strategy};
+use cubek_attention::routines::DeviceSettings;
+
+use crate::attention::launcher::test_launch;
+
+fn problem(dims: AttentionDims, masked: bool, causal: bool) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+fn dims(seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads: 4,
+        num_kv_heads: 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+fn strategy(problem: &AttentionProblem, num_kv_splits: u32) -> Strategy {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let launch_settings = DeviceSettings::new(&client, problem);
+
+    let tile_size = AttentionTileSize {
+        seq_q: 4,
+        seq_kv: 4,
+        head_dim: 4,
+        val_dim: 4,
+    };
+    let tiling_scheme = AttentionTilingScheme {
+        tile_size,
+        partition_size: AttentionPartitionSize {
+            seq_q: 1,
+            seq_kv: 1,
+            head_dim: problem.dims.head_dim as u32 / tile_size.head_dim,
+            val_dim: problem.dims.val_dim as u32 / tile_size.val_dim,
+        },
+        stage_size: AttentionStageSize { seq_q: 32 },
+    };
+
+    Strategy::SplitKv(BlueprintStrategy::Forced(SplitKvBlueprint {
+        attention: AttentionBlueprint {
+            hypercube_blueprint: HypercubeBlueprint {},
+            tiling_scheme,
+            plane_dim: launch_settings.plane_dim,
+            reuse_key_value: false,
+            two_rows_in_array_tile: false,
+            line_sizes: launch_settings.line_sizes,
+            masked: problem.masked,
+            additive_mask: problem.global_dtypes.additive_mask(),
+            causal: problem.options.causal,
+            window: problem.options.window,
+            alibi: problem.options.alibi,
+            softcap: problem.options.softcap.is_some(),
+            check_bounds: tiling_scheme.check_bounds(&problem.dims),
+        },
+        num_kv_splits,
+    }))
+}
+
+#[test]
+fn split_kv_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 512), false, false);
+    let strategy = strategy(&problem, 8);
+
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn split_kv_single_split() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 64), false, false);
+    let strategy = strategy(&problem, 1);
+
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn split_kv_unaligned_with_empty_split() {
+    // 100 rows in 8 splits of 16 rows, leaving the last split empty
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(3, 100), false, false);
+    let strategy = strategy(&problem, 8);
+
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn split_kv_masked() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 256), true, false);
+    let strategy = strategy(&problem, 4);
+
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn split_kv_causal_with_fully_masked_splits() {
+    // Query rows only attend to the first keys, so all but the first split are fully masked
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(4, 128), false, true);
+    let strategy = strategy(&problem, 4);
+
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn split_kv_inferred() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 1024), false, false);
+
+    test_launch(
+        client,
+        problem,
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn auto_decoding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(1, 1024), false, false);
+
+    test_launch(client, problem, Strategy::Auto)
+}
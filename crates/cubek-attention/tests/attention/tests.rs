@@ -3,7 +3,8 @@ use crate::attention::tiling_scheme_ops::*;
 use cubecl::{Runtime, TestRuntime};
 use cubek_attention::definition::{
     AccumulatorPrecision, AttentionDims, AttentionOptions, AttentionPartitionSize,
-    AttentionProblem, AttentionStageSize, AttentionTilingScheme, HypercubeBlueprint,
+    AttentionProblem, AttentionStageSize, AttentionTilingScheme, AttentionWindow,
+    HypercubeBlueprint,
 };
 use cubek_attention::routines::DeviceSettings;
 
@@ -28,6 +29,7 @@ fn one_tile_simple() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -38,6 +40,10 @@ fn one_tile_simple() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
 
@@ -51,7 +57,11 @@ fn one_tile_simple() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
 
@@ -81,6 +91,7 @@ fn one_partition_several_planes() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -91,6 +102,10 @@ fn one_partition_several_planes() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
 
@@ -104,7 +119,11 @@ fn one_partition_several_planes() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
 
@@ -136,6 +155,7 @@ fn problem_smaller_than_one_tile_seq_q_seq_kv_val_dim() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv,
             head_dim,
@@ -146,6 +166,10 @@ fn problem_smaller_than_one_tile_seq_q_seq_kv_val_dim() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -157,7 +181,11 @@ fn problem_smaller_than_one_tile_seq_q_seq_kv_val_dim() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -184,6 +212,7 @@ fn head_dim_oob() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim,
@@ -194,6 +223,10 @@ fn head_dim_oob() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -205,7 +238,11 @@ fn head_dim_oob() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -231,6 +268,7 @@ fn two_rows_in_array_tile() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -241,6 +279,10 @@ fn two_rows_in_array_tile() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -252,7 +294,11 @@ fn two_rows_in_array_tile() {
         two_rows_in_array_tile: true,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -279,6 +325,7 @@ fn one_tile_seqq16() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -289,6 +336,10 @@ fn one_tile_seqq16() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -300,7 +351,11 @@ fn one_tile_seqq16() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -327,6 +382,7 @@ fn one_tile_seqq4() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -337,6 +393,10 @@ fn one_tile_seqq4() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -348,7 +408,11 @@ fn one_tile_seqq4() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -374,6 +438,7 @@ fn seqq2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -384,6 +449,10 @@ fn seqq2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -395,7 +464,11 @@ fn seqq2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -421,6 +494,7 @@ fn hd2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -431,6 +505,10 @@ fn hd2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -442,7 +520,11 @@ fn hd2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -468,6 +550,7 @@ fn kv2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -478,6 +561,10 @@ fn kv2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -489,7 +576,11 @@ fn kv2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -515,6 +606,7 @@ fn vd2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -525,6 +617,10 @@ fn vd2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -536,7 +632,11 @@ fn vd2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -562,6 +662,7 @@ fn hd2_vd2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -572,6 +673,10 @@ fn hd2_vd2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -583,7 +688,11 @@ fn hd2_vd2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -609,6 +718,7 @@ fn all2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -619,6 +729,10 @@ fn all2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -630,7 +744,11 @@ fn all2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -657,6 +775,7 @@ fn global_iterations_2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -667,6 +786,10 @@ fn global_iterations_2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -678,7 +801,11 @@ fn global_iterations_2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -705,6 +832,7 @@ fn global_iterations_2_kv2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -715,6 +843,10 @@ fn global_iterations_2_kv2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -726,7 +858,11 @@ fn global_iterations_2_kv2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -753,6 +889,7 @@ fn partition_kv1_global1_with_oob() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -763,6 +900,10 @@ fn partition_kv1_global1_with_oob() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -774,7 +915,11 @@ fn partition_kv1_global1_with_oob() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -801,6 +946,7 @@ fn partition_seqq2_global2_kv2_global2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -811,6 +957,10 @@ fn partition_seqq2_global2_kv2_global2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -822,7 +972,11 @@ fn partition_seqq2_global2_kv2_global2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -848,6 +1002,7 @@ fn partition_many_planes() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -858,6 +1013,10 @@ fn partition_many_planes() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -869,7 +1028,11 @@ fn partition_many_planes() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -896,6 +1059,7 @@ fn partition_kv1_global3_with_oob() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -906,6 +1070,10 @@ fn partition_kv1_global3_with_oob() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -917,7 +1085,11 @@ fn partition_kv1_global3_with_oob() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -944,6 +1116,7 @@ fn partition_oob_in_q() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -954,6 +1127,10 @@ fn partition_oob_in_q() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -965,7 +1142,11 @@ fn partition_oob_in_q() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -991,6 +1172,7 @@ fn partition_kv2_with_oob() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1001,6 +1183,10 @@ fn partition_kv2_with_oob() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1012,7 +1198,11 @@ fn partition_kv2_with_oob() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1038,6 +1228,7 @@ fn partition_kv2_causal() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1048,6 +1239,10 @@ fn partition_kv2_causal() {
         options: AttentionOptions {
             causal: true,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1059,7 +1254,11 @@ fn partition_kv2_causal() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1085,6 +1284,7 @@ fn partition_kv2_masked() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1095,6 +1295,10 @@ fn partition_kv2_masked() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1106,7 +1310,11 @@ fn partition_kv2_masked() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1132,6 +1340,7 @@ fn stage2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1142,6 +1351,10 @@ fn stage2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1153,7 +1366,11 @@ fn stage2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1179,6 +1396,7 @@ fn stage4() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1189,6 +1407,10 @@ fn stage4() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1200,7 +1422,11 @@ fn stage4() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1228,6 +1454,7 @@ fn stage2_problem4() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1238,6 +1465,10 @@ fn stage2_problem4() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1249,7 +1480,11 @@ fn stage2_problem4() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1275,6 +1510,7 @@ fn reuse_key_value() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1285,6 +1521,10 @@ fn reuse_key_value() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1296,7 +1536,11 @@ fn reuse_key_value() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1322,6 +1566,7 @@ fn double_row_wise() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1332,6 +1577,10 @@ fn double_row_wise() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1343,7 +1592,11 @@ fn double_row_wise() {
         two_rows_in_array_tile: true,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1369,6 +1622,7 @@ fn one_tile_masked() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1379,6 +1633,10 @@ fn one_tile_masked() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1390,7 +1648,11 @@ fn one_tile_masked() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1416,6 +1678,7 @@ fn one_tile_causal() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1426,6 +1689,10 @@ fn one_tile_causal() {
         options: AttentionOptions {
             causal: true,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1437,7 +1704,11 @@ fn one_tile_causal() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1463,6 +1734,7 @@ fn one_tile_masked_causal() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1473,6 +1745,10 @@ fn one_tile_masked_causal() {
         options: AttentionOptions {
             causal: true,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1484,7 +1760,11 @@ fn one_tile_masked_causal() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1511,6 +1791,7 @@ fn masked_oob() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1521,6 +1802,10 @@ fn masked_oob() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1532,7 +1817,11 @@ fn masked_oob() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1559,6 +1848,7 @@ fn masked_larger() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv,
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1569,6 +1859,10 @@ fn masked_larger() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1580,7 +1874,11 @@ fn masked_larger() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1606,6 +1904,7 @@ fn num_heads_2() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 2,
+            num_kv_heads: 2,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1616,6 +1915,10 @@ fn num_heads_2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1627,7 +1930,11 @@ fn num_heads_2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1653,6 +1960,7 @@ fn batch_2() {
         dims: AttentionDims {
             batch: 2,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1663,6 +1971,10 @@ fn batch_2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1674,7 +1986,11 @@ fn batch_2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1700,6 +2016,7 @@ fn batch_2_seqq2() {
         dims: AttentionDims {
             batch: 2,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1710,6 +2027,10 @@ fn batch_2_seqq2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1721,7 +2042,11 @@ fn batch_2_seqq2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1747,6 +2072,7 @@ fn num_heads_2_batch_2() {
         dims: AttentionDims {
             batch: 2,
             num_heads: 2,
+            num_kv_heads: 2,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1757,6 +2083,10 @@ fn num_heads_2_batch_2() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1768,7 +2098,123 @@ fn num_heads_2_batch_2() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
+        check_bounds: tiling_scheme.check_bounds(&problem.dims),
+    };
+    let strategy = strategy(blueprint);
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn grouped_query_batch_2() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let tiling_scheme = AttentionTilingScheme {
+        tile_size: tile_size(),
+        partition_size: AttentionPartitionSize {
+            seq_q: 1,
+            seq_kv: 1,
+            head_dim: 1,
+            val_dim: 1,
+        },
+        stage_size: AttentionStageSize {
+            seq_q: minimal_seq_q_stage(),
+        },
+    };
+    let problem = AttentionProblem {
+        dims: AttentionDims {
+            batch: 2,
+            num_heads: 4,
+            num_kv_heads: 2,
+            seq_q: elements_in_stage_seq_q(&tiling_scheme),
+            seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
+            head_dim: elements_in_partition_head_dim(&tiling_scheme),
+            val_dim: elements_in_partition_val_dim(&tiling_scheme),
+        },
+        masked: false,
+        global_dtypes: global_dtypes(),
+        options: AttentionOptions {
+            causal: false,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    };
+    let launch_settings = DeviceSettings::new(&client, &problem);
+    let blueprint = AttentionBlueprint {
+        hypercube_blueprint: HypercubeBlueprint {},
+        tiling_scheme,
+        plane_dim: launch_settings.plane_dim,
+        reuse_key_value: false,
+        two_rows_in_array_tile: false,
+        line_sizes: launch_settings.line_sizes,
+        masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
+        causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
+        check_bounds: tiling_scheme.check_bounds(&problem.dims),
+    };
+    let strategy = strategy(blueprint);
+    test_launch(client, problem, strategy)
+}
+
+#[test]
+fn multi_query() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let tiling_scheme = AttentionTilingScheme {
+        tile_size: tile_size(),
+        partition_size: AttentionPartitionSize {
+            seq_q: 1,
+            seq_kv: 1,
+            head_dim: 1,
+            val_dim: 1,
+        },
+        stage_size: AttentionStageSize {
+            seq_q: minimal_seq_q_stage(),
+        },
+    };
+    let problem = AttentionProblem {
+        dims: AttentionDims {
+            batch: 1,
+            num_heads: 4,
+            num_kv_heads: 1,
+            seq_q: elements_in_stage_seq_q(&tiling_scheme),
+            seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
+            head_dim: elements_in_partition_head_dim(&tiling_scheme),
+            val_dim: elements_in_partition_val_dim(&tiling_scheme),
+        },
+        masked: false,
+        global_dtypes: global_dtypes(),
+        options: AttentionOptions {
+            causal: false,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    };
+    let launch_settings = DeviceSettings::new(&client, &problem);
+    let blueprint = AttentionBlueprint {
+        hypercube_blueprint: HypercubeBlueprint {},
+        tiling_scheme,
+        plane_dim: launch_settings.plane_dim,
+        reuse_key_value: false,
+        two_rows_in_array_tile: false,
+        line_sizes: launch_settings.line_sizes,
+        masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
+        causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1794,6 +2240,7 @@ fn num_heads_2_masked() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 2,
+            num_kv_heads: 2,
             seq_q: elements_in_stage_seq_q(&tiling_scheme),
             seq_kv: elements_in_partition_seq_kv(&tiling_scheme),
             head_dim: elements_in_partition_head_dim(&tiling_scheme),
@@ -1804,6 +2251,10 @@ fn num_heads_2_masked() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1815,7 +2266,11 @@ fn num_heads_2_masked() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
@@ -1846,6 +2301,7 @@ fn huge_problem() {
         dims: AttentionDims {
             batch: 1,
             num_heads: 1,
+            num_kv_heads: 1,
             seq_q,
             seq_kv,
             head_dim,
@@ -1856,6 +2312,10 @@ fn huge_problem() {
         options: AttentionOptions {
             causal: false,
             accumulator_precision: AccumulatorPrecision::default(),
+            window: AttentionWindow::default(),
+            alibi: false,
+            scale: None,
+            softcap: None,
         },
     };
     let launch_settings = DeviceSettings::new(&client, &problem);
@@ -1867,7 +2327,11 @@ fn huge_problem() {
         two_rows_in_array_tile: false,
         line_sizes: launch_settings.line_sizes,
         masked: problem.masked,
+        additive_mask: problem.global_dtypes.additive_mask(),
         causal: problem.options.causal,
+        window: problem.options.window,
+        alibi: problem.options.alibi,
+        softcap: problem.options.softcap.is_some(),
         check_bounds: tiling_scheme.check_bounds(&problem.dims),
     };
     let strategy = strategy(blueprint);
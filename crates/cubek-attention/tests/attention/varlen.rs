@@ -0,0 +1,249 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime, client::ComputeClient};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionElems, AttentionGlobalTypes, AttentionOptions,
+    AttentionProblem, AttentionWindow,
+};
+use cubek_attention::launch::{BlueprintStrategy, Strategy, VarlenSequences, launch_varlen_ref};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataType, HostDataVec, StrideSpec, TestInput, assert_equals_approx,
+    current_test_mode,
+};
+
+use crate::attention::reference::flash_attention_v2_reference;
+
+const NUM_HEADS: usize = 4;
+const NUM_KV_HEADS: usize = 2;
+const DIM: usize = 16;
+
+/// Packs random sequences of the given query and key/value lengths, runs the varlen launch and
+/// compares each sequence against the reference run on that sequence alone.
+fn test_varlen(
+    client: ComputeClient<TestRuntime>,
+    seq_lengths: &[(usize, usize)],
+    causal: bool,
+    strategy: Strategy,
+) {
+    let global_dtypes = AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked());
+    let options = AttentionOptions {
+        causal,
+        accumulator_precision: AccumulatorPrecision::default(),
+        window: AttentionWindow::default(),
+        alibi: false,
+        scale: None,
+        softcap: None,
+    };
+
+    let offsets = |lengths: Vec<usize>| {
+        let mut offsets = vec![0];
+        for length in lengths {
+            offsets.push(offsets.last().unwrap() + length);
+        }
+        offsets
+    };
+    let offsets_q = offsets(seq_lengths.iter().map(|(q, _)| *q).collect());
+    let offsets_kv = offsets(seq_lengths.iter().map(|(_, kv)| *kv).collect());
+    let total_q = *offsets_q.last().unwrap();
+    let total_kv = *offsets_kv.last().unwrap();
+
+    let random = |shape: [usize; 4], seed| {
+        TestInput::random(
+            client.clone(),
+            shape.to_vec(),
+            global_dtypes.query,
+            seed,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+    let offsets_handle = |offsets: &[usize]| {
+        TestInput::custom(
+            client.clone(),
+            vec![offsets.len()],
+            u32::as_type_native_unchecked(),
+            StrideSpec::RowMajor,
+            offsets.iter().map(|&it| it as f32).collect(),
+        )
+        .generate_without_host_data()
+    };
+
+    let (query_handle, query_data) = random([1, NUM_HEADS, total_q, DIM], 12);
+    let (key_handle, key_data) = random([1, NUM_KV_HEADS, total_kv, DIM], 34);
+    let (value_handle, value_data) = random([1, NUM_KV_HEADS, total_kv, DIM], 56);
+    let cu_seqlens_q = offsets_handle(&offsets_q);
+    let cu_seqlens_kv = offsets_handle(&offsets_kv);
+
+    let elems = AttentionElems::from_global_types(&global_dtypes, &options.accumulator_precision);
+
+    let out_handle = TestInput::zeros(
+        client.clone(),
+        vec![1, NUM_HEADS, total_q, DIM],
+        global_dtypes.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let lse_handle = TestInput::zeros(
+        client.clone(),
+        vec![1, NUM_HEADS, total_q],
+        elems.accumulator,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let result = launch_varlen_ref(
+        strategy,
+        &client,
+        &query_handle.as_ref(),
+        &key_handle.as_ref(),
+        &value_handle.as_ref(),
+        &VarlenSequences {
+            cu_seqlens_q: &cu_seqlens_q.as_ref(),
+            cu_seqlens_kv: &cu_seqlens_kv.as_ref(),
+            max_seqlen_q: seq_lengths.iter().map(|(q, _)| *q).max().unwrap(),
+            max_seqlen_kv: seq_lengths.iter().map(|(_, kv)| *kv).max().unwrap(),
+        },
+        &None,
+        &out_handle.as_ref(),
+        &Some(lse_handle.as_ref()),
+        &global_dtypes,
+        options.clone(),
+    );
+
+    if let Err(err) = result {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Test did not run: {}", err)
+        }
+        return;
+    }
+
+    let mut expected_out = vec![0.; NUM_HEADS * total_q * DIM];
+    let mut expected_lse = vec![0.; NUM_HEADS * total_q];
+
+    for (index, (seq_q, seq_kv)) in seq_lengths.iter().copied().enumerate() {
+        let problem = AttentionProblem {
+            dims: AttentionDims {
+                batch: 1,
+                num_heads: NUM_HEADS,
+                num_kv_heads: NUM_KV_HEADS,
+                seq_q,
+                seq_kv,
+                head_dim: DIM,
+                val_dim: DIM,
+            },
+            masked: false,
+            global_dtypes: global_dtypes.clone(),
+            options: options.clone(),
+        };
+
+        let (out, lse) = flash_attention_v2_reference(
+            &rows(&query_data, offsets_q[index], seq_q),
+            &rows(&key_data, offsets_kv[index], seq_kv),
+            &rows(&value_data, offsets_kv[index], seq_kv),
+            None,
+            None,
+            &problem,
+        );
+
+        for h in 0..NUM_HEADS {
+            for i in 0..seq_q {
+                let row = h * total_q + offsets_q[index] + i;
+                for d in 0..DIM {
+                    expected_out[row * DIM + d] = out.get_f32(&[0, h, i, d]);
+                }
+                expected_lse[row] = lse.get_f32(&[0, h, i]);
+            }
+        }
+    }
+
+    let epsilon = 1e-4;
+    for (name, handle, expected, shape) in [
+        (
+            "output",
+            &out_handle,
+            expected_out,
+            vec![1, NUM_HEADS, total_q, DIM],
+        ),
+        (
+            "log-sum-exp",
+            &lse_handle,
+            expected_lse,
+            vec![1, NUM_HEADS, total_q],
+        ),
+    ] {
+        let expected = HostData {
+            data: HostDataVec::F32(expected),
+            strides: StrideSpec::RowMajor.compute_strides(&shape),
+            shape,
+        };
+        let actual = HostData::from_tensor_handle(&client, handle, HostDataType::F32);
+        if let Err(e) = assert_equals_approx(&actual, &expected, epsilon) {
+            panic!("Wrong {name}: {e}");
+        }
+    }
+}
+
+/// Copies the `len` rows starting at `start` of a `[1, heads, rows, dim]` tensor
+fn rows(data: &HostData, start: usize, len: usize) -> HostData {
+    let (heads, dim) = (data.shape[1], data.shape[3]);
+    let shape = vec![1, heads, len, dim];
+
+    let mut values = Vec::with_capacity(heads * len * dim);
+    for h in 0..heads {
+        for row in start..start + len {
+            for d in 0..dim {
+                values.push(data.get_f32(&[0, h, row, d]));
+            }
+        }
+    }
+
+    HostData {
+        data: HostDataVec::F32(values),
+        strides: StrideSpec::RowMajor.compute_strides(&shape),
+        shape,
+    }
+}
+
+#[test]
+fn varlen_unit() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+
+    test_varlen(
+        client,
+        &[(5, 17), (40, 33), (1, 8)],
+        false,
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn varlen_unit_causal() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+
+    test_varlen(
+        client,
+        &[(12, 12), (37, 37), (3, 3)],
+        true,
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn varlen_aligned_lengths() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+
+    // The longest sequence fills whole stages, while the others don't
+    test_varlen(
+        client,
+        &[(32, 32), (7, 9)],
+        false,
+        Strategy::Unit(BlueprintStrategy::Inferred(())),
+    )
+}
+
+#[test]
+fn varlen_auto() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+
+    test_varlen(client, &[(1, 64), (16, 20)], false, Strategy::Auto)
+}
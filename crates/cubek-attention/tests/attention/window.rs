@@ -0,0 +1,135 @@
+use cubecl::frontend::CubePrimitive;
+use cubecl::{Runtime, TestRuntime};
+use cubek_attention::definition::{
+    AccumulatorPrecision, AttentionDims, AttentionGlobalTypes, AttentionOptions, AttentionProblem,
+    AttentionWindow, DiagonalAlignment,
+};
+use cubek_attention::launch::{BlueprintStrategy, Strategy};
+
+use crate::attention::launcher::test_launch;
+
+fn problem(
+    dims: AttentionDims,
+    masked: bool,
+    causal: bool,
+    window: AttentionWindow,
+) -> AttentionProblem {
+    AttentionProblem {
+        dims,
+        masked,
+        global_dtypes: AttentionGlobalTypes::from_single_dtype(f32::as_type_native_unchecked()),
+        options: AttentionOptions {
+            causal,
+            accumulator_precision: AccumulatorPrecision::default(),
+            window,
+            alibi: false,
+            scale: None,
+            softcap: None,
+        },
+    }
+}
+
+fn dims(seq_q: usize, seq_kv: usize) -> AttentionDims {
+    AttentionDims {
+        batch: 2,
+        num_heads: 4,
+        num_kv_heads: 2,
+        seq_q,
+        seq_kv,
+        head_dim: 16,
+        val_dim: 16,
+    }
+}
+
+fn unit() -> Strategy {
+    Strategy::Unit(BlueprintStrategy::Inferred(()))
+}
+
+#[test]
+fn window_sliding() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let problem = problem(dims(96, 96), false, false, AttentionWindow::sliding(20));
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_band_both_sides_masked() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        left: Some(5),
+        right: Some(3),
+        alignment: DiagonalAlignment::TopLeft,
+    };
+    let problem = problem(dims(64, 80), true, false, window);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_only_right() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        left: None,
+        right: Some(7),
+        alignment: DiagonalAlignment::TopLeft,
+    };
+    let problem = problem(dims(48, 64), false, false, window);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_causal_bottom_right() {
+    // The queries are the last rows of the key/value sequence
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::BottomRight,
+        ..Default::default()
+    };
+    let problem = problem(dims(12, 70), false, true, window);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_causal_offset_chunked_prefill() {
+    // Second chunk of a prompt, whose first 40 rows are already in the key/value cache
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::Offset(40),
+        ..Default::default()
+    };
+    let problem = problem(dims(32, 72), false, true, window);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_sliding_offset() {
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::Offset(40),
+        ..AttentionWindow::sliding(24)
+    };
+    let problem = problem(dims(32, 72), false, false, window);
+
+    test_launch(client, problem, unit())
+}
+
+#[test]
+fn window_sliding_split_kv_decoding() {
+    // Only the splits covering the last rows of the key/value sequence have work to do
+    let client = <TestRuntime as Runtime>::client(&Default::default());
+    let window = AttentionWindow {
+        alignment: DiagonalAlignment::BottomRight,
+        ..AttentionWindow::sliding(100)
+    };
+    let problem = problem(dims(1, 512), false, false, window);
+
+    test_launch(
+        client,
+        problem,
+        Strategy::SplitKv(BlueprintStrategy::Inferred(())),
+    )
+}
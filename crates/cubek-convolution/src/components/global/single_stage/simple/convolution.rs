@@ -5,8 +5,8 @@ use cubecl::std::{
 };
 use cubek_matmul::components::{
     global::{
-        GlobalConfig, GlobalWriter, PartitionedStage, PlaneWriter, SharedGlobalMatmulConfig,
-        read::SyncStrategy,
+        EpilogueInputs, GlobalConfig, GlobalWriter, PartitionedStage, PlaneWriter,
+        SharedGlobalMatmulConfig, read::SyncStrategy,
     },
     stage::{StageConfig, StageMatmul, StridedStageMemory},
 };
@@ -156,7 +156,7 @@ where
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter {
-        Self::GlobalWriter::new(out, config.writer_config)
+        Self::GlobalWriter::new(out, EpilogueInputs::none(), config.writer_config)
     }
 
     fn init_accumulator(#[comptime] config: Self::Config) -> Self::Accumulators {
@@ -162,6 +162,7 @@ where
             smem_config: stage_config.out_smem_config(),
             role_rule_config: plane_role_config.rule,
             plane_dim: selection.plane_dim,
+            epilogue: Default::default(),
         };
 
         let matmul_config = SharedGlobalMatmulConfig {
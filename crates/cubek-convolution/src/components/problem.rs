@@ -1,6 +1,7 @@
 use cubek_matmul::definition::{MatmulGlobalElems, MatmulProblem, MatrixLayout};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ConvolutionOperation {
     Forward,
     BackwardData,
@@ -84,6 +85,9 @@ impl ConvolutionProblem {
             out_strides: MatrixLayout::RowMajor.to_strides(&[self.m, self.n]),
             out_layout: MatrixLayout::RowMajor,
             global_dtypes: self.global_dtypes.clone(),
+            epilogue: Default::default(),
+            num_groups: None,
+            rhs_scale_block_k: None,
         }
     }
 
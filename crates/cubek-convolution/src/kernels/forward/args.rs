@@ -215,7 +215,15 @@ impl<EG: Numeric> ConcreteOutputFactory for TensorOutput<EG> {
         let layout = ChainLaunch::new(global, layout);
         let view = ViewArg::new::<Layout>(out.as_array_arg(line_sizes.out), layout);
         let batch = VirtualLayoutLaunch::new::<NoopLayout>(NoopLayoutLaunch::new());
-        TensorOutputLaunch::new(view, batch)
+        TensorOutputLaunch::new(
+            view,
+            batch,
+            CubeOptionArgs::None,
+            CubeOptionArgs::None,
+            CubeOptionArgs::None,
+            ScalarArg::new(1.0),
+            ScalarArg::new(0.0),
+        )
     }
 }
 
@@ -9,6 +9,7 @@ pub struct ConvolutionArgs<const N_SPATIAL: usize> {
     pub dilation: [usize; N_SPATIAL],
 }
 
+#[derive(Debug)]
 pub enum Strategy {
     Simple {
         read_strategy: ReadingStrategy,
@@ -58,3 +59,14 @@ impl Display for ReadingStrategy {
         }
     }
 }
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::Simple {
+                read_strategy,
+                tile_kind,
+            } => write!(f, "conv_simple_{read_strategy}_{tile_kind}"),
+        }
+    }
+}
@@ -5,5 +5,7 @@ pub mod backward_weight;
 /// Kernels for forward convolution
 pub mod forward;
 mod launch;
+mod tune_key;
 
 pub use launch::*;
+pub use tune_key::*;
@@ -0,0 +1,73 @@
+use cubecl::AutotuneKey;
+use cubek_matmul::definition::MatmulElemType;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConvolutionArgs, components::ConvolutionOperation};
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize, AutotuneKey)]
+/// Autotune key representative of convolution versions
+pub struct ConvAutotuneKey {
+    pub operation: ConvolutionOperation,
+    pub kernel_size: Vec<usize>,
+    pub stride: Vec<usize>,
+    pub padding: Vec<usize>,
+    pub dilation: Vec<usize>,
+    #[autotune(anchor)]
+    pub batch_size: usize,
+    #[autotune(anchor)]
+    pub in_channels: usize,
+    #[autotune(anchor)]
+    pub out_channels: usize,
+    /// Spatial shape of the input, with each dim anchored to the next power of two
+    pub spatial_shape: Vec<usize>,
+    pub has_bias: bool,
+    pub elem_lhs: MatmulElemType,
+    pub elem_rhs: MatmulElemType,
+    pub elem_out: MatmulElemType,
+}
+
+impl ConvAutotuneKey {
+    /// Create the autotune key based on the shape of the input and weight, with layouts
+    /// `[batches, spatial..., in_channels]` and `[out_channels, kernel..., in_channels]`, as well
+    /// as the convolution arguments and element types.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate<const N_SPATIAL: usize>(
+        operation: ConvolutionOperation,
+        input_shape: &[usize],
+        weight_shape: &[usize],
+        args: &ConvolutionArgs<N_SPATIAL>,
+        has_bias: bool,
+        elem_lhs: MatmulElemType,
+        elem_rhs: MatmulElemType,
+        elem_out: MatmulElemType,
+    ) -> Self {
+        let rank = input_shape.len();
+        if rank != N_SPATIAL + 2 || weight_shape.len() != N_SPATIAL + 2 {
+            panic!(
+                "Expected input and weight to have a rank of {}",
+                N_SPATIAL + 2
+            );
+        }
+
+        let spatial_shape = input_shape[1..rank - 1]
+            .iter()
+            .map(|size| size.next_power_of_two())
+            .collect();
+
+        ConvAutotuneKey::new(
+            operation,
+            weight_shape[1..rank - 1].to_vec(),
+            args.stride.to_vec(),
+            args.padding.to_vec(),
+            args.dilation.to_vec(),
+            input_shape[0],
+            input_shape[rank - 1],
+            weight_shape[0],
+            spatial_shape,
+            has_bias,
+            elem_lhs,
+            elem_rhs,
+            elem_out,
+        )
+    }
+}
@@ -0,0 +1,70 @@
+use cubecl::{CubeCount, CubeDim};
+
+use crate::components::global::GlobalConfig;
+use crate::components::global::memory::GlobalLayoutConfig;
+use crate::components::global::read::ScaleEncoding;
+use crate::components::stage::StageConfig as _;
+use crate::definition::{MatmulLineSizes, MatmulProblem, MatmulSetupError};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration for block scaled batch matmul
+pub struct BlockScaledBatchConfig<G: GlobalConfig> {
+    pub global_config: G,
+    /// How the rhs scales are encoded
+    pub scale_encoding: ScaleEncoding,
+}
+
+impl<G: GlobalConfig> BlockScaledBatchConfig<G> {
+    /// Create a new config for block scaled batch matmul, whose rhs scales are encoded with
+    /// `scale_encoding`
+    pub fn new(global_config: G, scale_encoding: ScaleEncoding) -> Self {
+        Self {
+            global_config,
+            scale_encoding,
+        }
+    }
+
+    /// May return an error if:
+    /// - the problem doesn't have rhs scale blocks
+    pub fn validate(self, problem: &MatmulProblem) -> Result<Self, MatmulSetupError> {
+        if problem.rhs_scale_block_k.is_none() {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Block scaled matmul requires the rhs scale block size",
+            )));
+        }
+        Ok(self)
+    }
+
+    pub fn cube_dim(&self) -> CubeDim {
+        self.global_config.cube_dim()
+    }
+
+    /// One cube per output tile of each batch.
+    pub fn cube_count(&self, problem: &MatmulProblem) -> CubeCount {
+        let stage_config = self.global_config.stage_config();
+        let stage_m = stage_config.elements_in_stage_m();
+        let stage_n = stage_config.elements_in_stage_n();
+
+        CubeCount::Static(
+            (problem.m as u32).div_ceil(stage_m),
+            (problem.n as u32).div_ceil(stage_n),
+            problem.num_batches() as u32,
+        )
+    }
+
+    pub fn line_sizes(&self) -> MatmulLineSizes {
+        self.global_config.global_line_sizes()
+    }
+
+    pub fn lhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.lhs_reader_config().gmem_config.into()
+    }
+
+    pub fn rhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.rhs_reader_config().gmem_config.into()
+    }
+
+    pub fn out_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.writer_config().gmem_config.into()
+    }
+}
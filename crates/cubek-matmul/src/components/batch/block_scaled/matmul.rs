@@ -0,0 +1,127 @@
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption,
+    tensor::{View, layout::Coords3d},
+};
+use std::marker::PhantomData;
+
+use crate::components::batch::SliceIndex;
+use crate::components::batch::block_scaled::config::BlockScaledBatchConfig;
+use crate::components::global::single_stage::block_scaled::{
+    BlockScaledGlobalMatmul, BlockScaledGlobalMatmulFamily,
+};
+use crate::components::global::{EpilogueInputs, GlobalConfig, GlobalMatmul};
+use crate::components::stage::StageConfig as _;
+use crate::definition::{AccG, LhsG, MatmulPrecision, RhsG};
+use crate::launch::MatmulArgs;
+
+#[cube(launch_unchecked)]
+/// Launches the block scaled matmul kernel
+pub(crate) fn block_scaled_matmul_entry<
+    Args: MatmulArgs,
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    LhsR: Numeric,
+    RhsR: Numeric,
+    AccR: Numeric,
+    Scale: Numeric,
+    GMMF: BlockScaledGlobalMatmulFamily,
+>(
+    inputs: &<Args as MatmulArgs>::Input<LhsG, RhsG, AccG>,
+    output: &mut <Args as MatmulArgs>::Output<AccG>,
+    rhs_scales: &View<Line<Scale>, Coords3d>,
+    #[comptime] config: BlockScaledBatchConfig<GMMF::Config>,
+    #[define(LhsG, RhsG, AccG)] _global: [StorageType; 3],
+    #[define(LhsS, RhsS, AccS)] _stage: [StorageType; 3],
+    #[define(LhsR, RhsR, AccR)] _register: [StorageType; 3],
+    #[define(Scale)] _scale: StorageType,
+) {
+    let mut state = Args::init_state::<LhsG, RhsG, AccG>(
+        inputs,
+        output,
+        config.lhs_global_layout_config(),
+        config.rhs_global_layout_config(),
+        config.out_global_layout_config(),
+    );
+
+    BlockScaledBatchMatmul::<
+        ((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR)),
+        Scale,
+        GMMF::BlockScaledMatmul<
+            ((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR)),
+            Scale,
+        >,
+    >::execute::<Args>(&mut state, rhs_scales, config);
+}
+
+/// Executes matrix multiplications where rhs holds block quantized values, whose scales of type
+/// `SC` are given separately.
+///
+/// Each cube computes a single output tile of a single batch.
+pub struct BlockScaledBatchMatmul<
+    MP: MatmulPrecision,
+    SC: Numeric,
+    GMM: BlockScaledGlobalMatmul<MP, SC>,
+> {
+    _mp: PhantomData<MP>,
+    _sc: PhantomData<SC>,
+    _gmm: PhantomData<GMM>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, SC: Numeric, GMM: BlockScaledGlobalMatmul<MP, SC>>
+    BlockScaledBatchMatmul<MP, SC, GMM>
+{
+    pub fn execute<Args: MatmulArgs>(
+        state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+        rhs_scales: &View<Line<SC>, Coords3d>,
+        #[comptime] config: BlockScaledBatchConfig<GMM::Config>,
+    ) {
+        let global_config = config.global_config;
+        let stage_m = global_config.stage_config().elements_in_stage_m().runtime();
+        let stage_n = global_config.stage_config().elements_in_stage_n().runtime();
+
+        let m_offset = CUBE_POS_X * stage_m;
+        let n_offset = CUBE_POS_Y * stage_n;
+        let nth_batch = CUBE_POS_Z;
+
+        let a = Args::view_lhs(state);
+        let b = Args::view_rhs(state);
+        let out = Args::view_out(state);
+
+        let (_, _, k_size) = a.shape();
+
+        let a_batch = Args::batch_lhs(state, nth_batch);
+        let a = a.view(SliceIndex::new(a_batch, a.shape()));
+        let b_batch = Args::batch_rhs(state, nth_batch);
+        let b = b.view(SliceIndex::new(b_batch, b.shape()));
+        let scales = rhs_scales.view(SliceIndex::new(b_batch, rhs_scales.shape()));
+        let out_batch = Args::batch_out(state, nth_batch);
+        let out = out.view_mut(SliceIndex::new(out_batch, out.shape()));
+
+        GMM::execute(
+            GMM::init_lhs_global_reader(
+                a.slice_unchecked((m_offset, 0), (stage_m, k_size)),
+                global_config,
+            ),
+            GMM::init_rhs_block_scaled_reader(
+                b.slice_unchecked((0, n_offset), (k_size, stage_n)),
+                scales.slice_unchecked((0, n_offset), (k_size, stage_n)),
+                config.scale_encoding,
+                global_config,
+            ),
+            GMM::init_acc_global_reader(CubeOption::new_None(), global_config),
+            GMM::init_global_writer(
+                out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
+                EpilogueInputs::none(),
+                global_config,
+            ),
+            (0, k_size),
+            global_config,
+        );
+    }
+}
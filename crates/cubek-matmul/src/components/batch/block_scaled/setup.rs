@@ -0,0 +1,57 @@
+use std::marker::PhantomData;
+
+use crate::components::batch::block_scaled::config::BlockScaledBatchConfig;
+use crate::components::batch::block_scaled::matmul::block_scaled_matmul_entry;
+use crate::components::global::single_stage::block_scaled::BlockScaledGlobalMatmulFamily;
+use crate::definition::MatmulElems;
+use crate::launch::{InputRuntimeArg, MatmulArgs, OutputRuntimeArg};
+use cubecl::prelude::*;
+use cubecl::std::tensor::{View, layout::Coords3d};
+
+/// Block scaled batch matmul family for any precision.
+///
+/// Unlike a [BatchMatmulFamily](crate::components::batch::BatchMatmulFamily), the scales of the
+/// rhs blocks are given as a separate view, which must be provided at launch.
+pub struct BlockScaledBatchMatmulFamily<GMM: BlockScaledGlobalMatmulFamily> {
+    _gmm: PhantomData<GMM>,
+}
+
+impl<GMM: BlockScaledGlobalMatmulFamily> BlockScaledBatchMatmulFamily<GMM> {
+    /// Entry point
+    ///
+    /// # Safety
+    ///
+    /// Out-of-bounds can happen
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch_unchecked<'a, MA: MatmulArgs, R: Runtime>(
+        client: &ComputeClient<R>,
+        cube_dim: CubeDim,
+        cube_count: CubeCount,
+        input: InputRuntimeArg<'a, MA, R>,
+        output: OutputRuntimeArg<'a, MA, R>,
+        rhs_scales: <View<Line<NumericExpand<9>>, Coords3d> as LaunchArg>::RuntimeArg<'a, R>,
+        config: BlockScaledBatchConfig<GMM::Config>,
+        dtypes: &MatmulElems,
+        scale_dtype: StorageType,
+    ) -> Result<(), LaunchError> {
+        unsafe {
+            block_scaled_matmul_entry::launch_unchecked::<MA, GMM, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input,
+                output,
+                rhs_scales,
+                config,
+                [*dtypes.lhs_global, *dtypes.rhs_global, *dtypes.acc_global],
+                [*dtypes.lhs_stage, *dtypes.rhs_stage, *dtypes.acc_stage],
+                [
+                    *dtypes.lhs_register,
+                    *dtypes.rhs_register,
+                    *dtypes.acc_register,
+                ],
+                scale_dtype,
+            )
+        }
+    }
+}
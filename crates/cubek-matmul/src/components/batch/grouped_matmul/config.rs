@@ -0,0 +1,95 @@
+use cubecl::{CubeCount, CubeDim};
+
+use crate::components::global::GlobalConfig;
+use crate::components::global::memory::GlobalLayoutConfig;
+use crate::components::stage::StageConfig as _;
+use crate::definition::{MatmulLineSizes, MatmulProblem, MatmulSetupError};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration for grouped batch matmul
+pub struct GroupedBatchConfig<G: GlobalConfig> {
+    pub global_config: G,
+}
+
+impl<G: GlobalConfig> GroupedBatchConfig<G> {
+    /// Create a new config for grouped batch matmul
+    pub fn new(global_config: G) -> Self {
+        Self { global_config }
+    }
+
+    /// May return an error if:
+    /// - the problem isn't split into groups
+    /// - there are more output tiles than cubes that can be launched
+    pub fn validate(
+        self,
+        problem: &MatmulProblem,
+        max_cube_count: &CubeCount,
+    ) -> Result<Self, MatmulSetupError> {
+        if problem.num_groups.is_none() {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul requires the problem to be split into groups",
+            )));
+        }
+
+        let (max_x, max_y) = max_cube_xy(max_cube_count);
+        let num_tiles = self.num_tiles(problem);
+        if num_tiles.div_ceil(max_x) > max_y {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul has more output tiles than cubes that can be launched",
+            )));
+        }
+
+        Ok(self)
+    }
+
+    pub fn cube_dim(&self) -> CubeDim {
+        self.global_config.cube_dim()
+    }
+
+    /// One cube per output tile, spread over the x and y dimensions to stay within
+    /// `max_cube_count`.
+    ///
+    /// Tiles are numbered along n first, then along m across all groups. Groups don't have to
+    /// start on a tile boundary, so each group may add at most one partial tile to those needed
+    /// for all rows. Extra cubes terminate early.
+    pub fn cube_count(&self, problem: &MatmulProblem, max_cube_count: &CubeCount) -> CubeCount {
+        let (max_x, _) = max_cube_xy(max_cube_count);
+        let num_tiles = self.num_tiles(problem);
+        let cubes_x = num_tiles.min(max_x);
+
+        CubeCount::Static(cubes_x, num_tiles.div_ceil(cubes_x), 1)
+    }
+
+    /// Upper bound on the number of output tiles across all groups
+    fn num_tiles(&self, problem: &MatmulProblem) -> u32 {
+        let stage_config = self.global_config.stage_config();
+        let stage_m = stage_config.elements_in_stage_m();
+        let stage_n = stage_config.elements_in_stage_n();
+        let num_groups = problem.num_groups.unwrap_or(1) as u32;
+
+        ((problem.m as u32).div_ceil(stage_m) + num_groups) * (problem.n as u32).div_ceil(stage_n)
+    }
+
+    pub fn line_sizes(&self) -> MatmulLineSizes {
+        self.global_config.global_line_sizes()
+    }
+
+    pub fn lhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.lhs_reader_config().gmem_config.into()
+    }
+
+    pub fn rhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.rhs_reader_config().gmem_config.into()
+    }
+
+    pub fn out_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.writer_config().gmem_config.into()
+    }
+}
+
+fn max_cube_xy(max_cube_count: &CubeCount) -> (u32, u32) {
+    match max_cube_count {
+        CubeCount::Static(x, y, _) => (*x, *y),
+        CubeCount::Dynamic(_) => panic!("Dynamic cube count not supported for grouped matmul"),
+    }
+}
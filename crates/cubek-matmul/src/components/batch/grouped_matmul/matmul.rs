@@ -0,0 +1,179 @@
+use cubecl::prelude::*;
+use cubecl::std::CubeOption;
+use std::marker::PhantomData;
+
+use crate::components::batch::SliceIndex;
+use crate::components::batch::grouped_matmul::config::GroupedBatchConfig;
+use crate::components::global::{
+    self, EpilogueInputs, GlobalConfig, GlobalMatmul, GlobalMatmulFamily,
+};
+use crate::components::stage::StageConfig as _;
+use crate::definition::{AccG, LhsG, MatmulPrecision, RhsG};
+use crate::launch::MatmulArgs;
+
+#[cube(launch_unchecked)]
+/// Launches the grouped matmul kernel
+pub(crate) fn grouped_matmul_entry<
+    Args: MatmulArgs,
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    LhsR: Numeric,
+    RhsR: Numeric,
+    AccR: Numeric,
+    GMMF: GlobalMatmulFamily,
+>(
+    inputs: &<Args as MatmulArgs>::Input<LhsG, RhsG, AccG>,
+    output: &mut <Args as MatmulArgs>::Output<AccG>,
+    group_offsets: &Tensor<u32>,
+    #[comptime] config: GroupedBatchConfig<GMMF::Config>,
+    #[define(LhsG, RhsG, AccG)] _global: [StorageType; 3],
+    #[define(LhsS, RhsS, AccS)] _stage: [StorageType; 3],
+    #[define(LhsR, RhsR, AccR)] _register: [StorageType; 3],
+) {
+    let mut state = Args::init_state::<LhsG, RhsG, AccG>(
+        inputs,
+        output,
+        config.lhs_global_layout_config(),
+        config.rhs_global_layout_config(),
+        config.out_global_layout_config(),
+    );
+
+    GroupedBatchMatmul::<
+        ((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR)),
+        GMMF::Matmul<((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR))>,
+    >::execute::<Args>(&mut state, group_offsets, config);
+}
+
+/// Executes matrix multiplications where the rows of lhs and out are split into groups,
+/// each multiplied with its own rhs batch.
+///
+/// Groups are described by `group_offsets`, where group `g` covers rows
+/// `group_offsets[g - 1]..group_offsets[g]` (starting at zero for the first group).
+/// Each cube computes a single output tile of a single group. Offsets are clamped to stay
+/// non-decreasing and within the rows of out.
+pub struct GroupedBatchMatmul<MP: MatmulPrecision, GMM: global::GlobalMatmul<MP>> {
+    _mp: PhantomData<MP>,
+    _gmm: PhantomData<GMM>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, GMM: GlobalMatmul<MP>> GroupedBatchMatmul<MP, GMM> {
+    pub fn execute<Args: MatmulArgs>(
+        state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+        group_offsets: &Tensor<u32>,
+        #[comptime] config: GroupedBatchConfig<GMM::Config>,
+    ) {
+        let stage_m = config
+            .global_config
+            .stage_config()
+            .elements_in_stage_m()
+            .runtime();
+        let stage_n = config
+            .global_config
+            .stage_config()
+            .elements_in_stage_n()
+            .runtime();
+
+        let (_, total_rows, n_size) = Args::view_out(state).shape();
+        let n_tiles = (n_size + stage_n - 1) / stage_n;
+
+        // Find the group containing the tile of this cube, tiles being numbered along n first,
+        // then along m across all groups in order.
+        let cube_index = CUBE_POS_Y * CUBE_COUNT_X + CUBE_POS_X;
+        let tile_index = cube_index / n_tiles;
+        let n_tile = cube_index % n_tiles;
+        let mut tiles_before = 0u32;
+        let mut group_start = 0u32;
+        let mut found = false;
+        let mut group = 0u32;
+        let mut row_start = 0u32;
+        let mut num_rows = 0u32;
+        let mut local_tile = 0u32;
+
+        for g in 0..group_offsets.len() {
+            // Offsets aren't validated at launch, clamping keeps invalid ones from reaching
+            // outside of lhs and out.
+            let group_end = Min::min(Max::max(group_offsets[g], group_start), total_rows);
+            let group_rows = group_end - group_start;
+            let group_tiles = (group_rows + stage_m - 1) / stage_m;
+
+            if !found && tile_index < tiles_before + group_tiles {
+                found = true;
+                group = g;
+                row_start = group_start;
+                num_rows = group_rows;
+                local_tile = tile_index - tiles_before;
+            }
+
+            tiles_before += group_tiles;
+            group_start = group_end;
+        }
+
+        if !found {
+            terminate!()
+        }
+
+        execute_group_matmul::<Args, MP, GMM>(
+            state,
+            group,
+            row_start,
+            num_rows,
+            local_tile * stage_m,
+            n_tile * stage_n,
+            config.global_config,
+        );
+    }
+}
+
+#[cube]
+/// Execute global matmul on the rows `row_start..row_start + num_rows` of lhs and out,
+/// with the rhs of `group`.
+/// m offset is relative to the group, n offset is absolute.
+fn execute_group_matmul<Args: MatmulArgs, MP: MatmulPrecision, GMM: global::GlobalMatmul<MP>>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    group: u32,
+    row_start: u32,
+    num_rows: u32,
+    m_offset: u32,
+    n_offset: u32,
+    #[comptime] config: GMM::Config,
+) {
+    let stage_m = config.stage_config().elements_in_stage_m().runtime();
+    let stage_n = config.stage_config().elements_in_stage_n().runtime();
+
+    let a = Args::view_lhs(state);
+    let b = Args::view_rhs(state);
+    let out = Args::view_out(state);
+
+    let (_, _, k_size) = a.shape();
+    let (_, _, n_size) = out.shape();
+
+    // Lhs and out are broadcast over groups, rhs is indexed by the group
+    let a_batch = Args::batch_lhs(state, group);
+    let a = a.view(SliceIndex::new(a_batch, a.shape()));
+    let b_batch = Args::batch_rhs(state, group);
+    let b = b.view(SliceIndex::new(b_batch, b.shape()));
+    let out_batch = Args::batch_out(state, group);
+    let out = out.view_mut(SliceIndex::new(out_batch, out.shape()));
+
+    // Checked slices, so the last tile of a group never touches the rows of the next one
+    let a = a.slice((row_start, 0), (num_rows, k_size));
+    let out = out.slice_mut((row_start, 0), (num_rows, n_size));
+
+    GMM::execute(
+        GMM::init_lhs_global_reader(a.slice_unchecked((m_offset, 0), (stage_m, k_size)), config),
+        GMM::init_rhs_global_reader(b.slice_unchecked((0, n_offset), (k_size, stage_n)), config),
+        GMM::init_acc_global_reader(CubeOption::new_None(), config),
+        GMM::init_global_writer(
+            out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
+            EpilogueInputs::none(),
+            config,
+        ),
+        (0, k_size),
+        config,
+    );
+}
@@ -0,0 +1,6 @@
+mod config;
+mod matmul;
+mod setup;
+
+pub use config::GroupedBatchConfig;
+pub use setup::GroupedBatchMatmulFamily;
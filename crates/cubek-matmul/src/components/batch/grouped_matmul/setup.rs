@@ -0,0 +1,57 @@
+use std::marker::PhantomData;
+
+use crate::components::batch::grouped_matmul::config::GroupedBatchConfig;
+use crate::components::batch::grouped_matmul::matmul::grouped_matmul_entry;
+use crate::components::global::GlobalMatmulFamily;
+use crate::definition::MatmulElems;
+use crate::launch::{InputRuntimeArg, MatmulArgs, OutputRuntimeArg};
+use cubecl::prelude::*;
+
+/// Grouped batch matmul family for any precision.
+///
+/// Unlike a [BatchMatmulFamily](crate::components::batch::BatchMatmulFamily), group boundaries
+/// are read from a tensor of offsets at runtime, which must be provided at launch.
+/// The global matmul config is the one expanded for a
+/// [partitioned](crate::components::batch::PartitionedBatchMatmulFamily) matmul on the same
+/// problem.
+pub struct GroupedBatchMatmulFamily<GMM: GlobalMatmulFamily> {
+    _gmm: PhantomData<GMM>,
+}
+
+impl<GMM: GlobalMatmulFamily> GroupedBatchMatmulFamily<GMM> {
+    /// Entry point
+    ///
+    /// # Safety
+    ///
+    /// Out-of-bounds can happen
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch_unchecked<'a, MA: MatmulArgs, R: Runtime>(
+        client: &ComputeClient<R>,
+        cube_dim: CubeDim,
+        cube_count: CubeCount,
+        input: InputRuntimeArg<'a, MA, R>,
+        output: OutputRuntimeArg<'a, MA, R>,
+        group_offsets: TensorArg<'a, R>,
+        config: GroupedBatchConfig<GMM::Config>,
+        dtypes: &MatmulElems,
+    ) -> Result<(), LaunchError> {
+        unsafe {
+            grouped_matmul_entry::launch_unchecked::<MA, GMM, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input,
+                output,
+                group_offsets,
+                config,
+                [*dtypes.lhs_global, *dtypes.rhs_global, *dtypes.acc_global],
+                [*dtypes.lhs_stage, *dtypes.rhs_stage, *dtypes.acc_stage],
+                [
+                    *dtypes.lhs_register,
+                    *dtypes.rhs_register,
+                    *dtypes.acc_register,
+                ],
+            )
+        }
+    }
+}
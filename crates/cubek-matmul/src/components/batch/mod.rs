@@ -3,9 +3,17 @@
 pub mod naive;
 
 mod base;
+mod block_scaled;
+mod grouped_matmul;
 mod layout;
 mod partitioned_matmul;
+mod sparse_24;
+mod split_k;
 
 pub use base::*;
+pub use block_scaled::*;
+pub use grouped_matmul::*;
 pub use layout::*;
 pub use partitioned_matmul::*;
+pub use sparse_24::*;
+pub use split_k::*;
@@ -15,6 +15,9 @@ pub struct PartitionedBatchConfig<G: GlobalConfig> {
     pub global_config: G,
     pub hypercube_config: HypercubeConfig,
     pub global_partition_size: GlobalPartitionSize,
+    /// Whether persistent cubes overlap the loads of a tile with the epilogue of the previous
+    /// one, see [CubeCountPlanBlueprint::Persistent](crate::definition::CubeCountPlanBlueprint)
+    pub overlap_tiles: bool,
 }
 
 impl<G: GlobalConfig> BatchConfig for PartitionedBatchConfig<G> {
@@ -60,11 +63,13 @@ impl<G: GlobalConfig> PartitionedBatchConfig<G> {
         global_config: G,
         hypercube_config: HypercubeConfig,
         global_partition_size: GlobalPartitionSize,
+        overlap_tiles: bool,
     ) -> Self {
         Self {
             global_config,
             hypercube_config,
             global_partition_size,
+            overlap_tiles,
         }
     }
 
@@ -1,9 +1,11 @@
 use cubecl::prelude::*;
+use cubecl::std::CubeOption;
 use std::marker::PhantomData;
 
 use crate::components::batch::partitioned_matmul::config::PartitionedBatchConfig;
 use crate::components::batch::partitioned_matmul::partition::{
-    GlobalPartitionMatmul, PartitionRangeDim, PartitionRanges,
+    GlobalPartitionMatmul, PartitionRangeDim, PartitionRanges, init_global_readers,
+    init_global_writer,
 };
 use crate::components::batch::{BatchConfig as _, BatchMatmul};
 use crate::components::global::{self, GlobalConfig, GlobalMatmul, GlobalMatmulFamily};
@@ -85,24 +87,168 @@ impl<MP: MatmulPrecision, GMM: GlobalMatmul<MP>, GPMM: GlobalPartitionMatmul> Ba
     ) {
         let (_, _, problem_k) = Args::view_lhs(state).shape();
         let k_range = (0, problem_k);
+        let global_order = config.hypercube_config.global_order;
 
-        let (m_index, n_index, batch_index) =
-            cube_count_args.cube_pos_to_tensor_pos(config.hypercube_config.global_order);
+        if comptime!(
+            config
+                .hypercube_config
+                .cube_count_plan_blueprint
+                .is_persistent()
+        ) {
+            if comptime!(config.overlap_tiles) {
+                execute_persistent_overlapped::<Args, MP, GMM>(
+                    state,
+                    &cube_count_args,
+                    k_range,
+                    config,
+                );
+            } else {
+                // Persistent cubes loop over tiles until all are done
+                let num_tiles = cube_count_args.num_valid_cubes();
+                let mut next_tile = SharedMemory::<u32>::new(1);
+                let mut tile = CUBE_POS_X;
+
+                while tile < num_tiles {
+                    let (m_index, n_index, batch_index) =
+                        cube_count_args.tile_to_tensor_pos(tile, global_order);
+
+                    execute_partition::<Args, MP, GMM, GPMM>(
+                        state,
+                        m_index,
+                        n_index,
+                        batch_index,
+                        k_range,
+                        config,
+                    );
+
+                    // Stage memory is reused by the next tile
+                    sync_cube();
+                    tile = cube_count_args.next_tile(tile, &mut next_tile);
+                }
+            }
+        } else {
+            let (m_index, n_index, batch_index) =
+                cube_count_args.cube_pos_to_tensor_pos(global_order);
 
-        let ranges = PartitionRanges::new(
-            PartitionRangeDim::new(
+            execute_partition::<Args, MP, GMM, GPMM>(
+                state,
                 m_index,
-                config.global_config.stage_config().elements_in_stage_m(),
-                config.global_partition_size.m,
-            ),
-            PartitionRangeDim::new(
                 n_index,
-                config.global_config.stage_config().elements_in_stage_n(),
-                config.global_partition_size.n,
-            ),
-            PartitionRangeDim::new(batch_index, 1u32, config.global_partition_size.batches),
+                batch_index,
+                k_range,
+                config,
+            );
+        }
+    }
+}
+
+#[cube]
+/// Execute the global matmuls of the partition at the given cube indices
+fn execute_partition<
+    Args: MatmulArgs,
+    MP: MatmulPrecision,
+    GMM: GlobalMatmul<MP>,
+    GPMM: GlobalPartitionMatmul,
+>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    m_index: u32,
+    n_index: u32,
+    batch_index: u32,
+    k_range: (u32, u32),
+    #[comptime] config: PartitionedBatchConfig<GMM::Config>,
+) {
+    let ranges = PartitionRanges::new(
+        PartitionRangeDim::new(
+            m_index,
+            config.global_config.stage_config().elements_in_stage_m(),
+            config.global_partition_size.m,
+        ),
+        PartitionRangeDim::new(
+            n_index,
+            config.global_config.stage_config().elements_in_stage_n(),
+            config.global_partition_size.n,
+        ),
+        PartitionRangeDim::new(batch_index, 1u32, config.global_partition_size.batches),
+    );
+
+    GPMM::execute::<Args, MP, GMM>(state, ranges, k_range, config.global_config);
+}
+
+#[cube]
+/// Loop of persistent cubes over tiles of a single global matmul, where the first stage of
+/// each tile is loaded before writing the results of the previous one, so that the loads
+/// overlap with its epilogue.
+///
+/// Each iteration prefetches the current tile, writes the previous one and computes the
+/// current one. Only one reader and writer is created per iteration, so their shared memory
+/// is the same for all tiles.
+fn execute_persistent_overlapped<Args: MatmulArgs, MP: MatmulPrecision, GMM: GlobalMatmul<MP>>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    cube_count_args: &CubeCountInput,
+    k_range: (u32, u32),
+    #[comptime] config: PartitionedBatchConfig<GMM::Config>,
+) {
+    let global_order = config.hypercube_config.global_order;
+    let stage_m = config.global_config.stage_config().elements_in_stage_m();
+    let stage_n = config.global_config.stage_config().elements_in_stage_n();
+
+    let num_tiles = cube_count_args.num_valid_cubes();
+    let mut next_tile = SharedMemory::<u32>::new(1);
+    let mut barrier = GMM::init_prefetch_barrier();
+    let mut acc = GMM::init_accumulators(config.global_config);
+
+    let mut tile = CUBE_POS_X;
+    // No results to write before the first tile is computed
+    let mut prev_tile = num_tiles;
+
+    while tile < num_tiles || prev_tile < num_tiles {
+        // Readers are still created once the cube is out of tiles, at a valid position
+        let (m_index, n_index, batch_index) =
+            cube_count_args.tile_to_tensor_pos(Min::min(tile, num_tiles - 1), global_order);
+        let (mut lhs_reader, mut rhs_reader) = init_global_readers::<Args, MP, GMM>(
+            state,
+            batch_index,
+            m_index * stage_m,
+            n_index * stage_n,
+            k_range,
+            config.global_config,
         );
 
-        GPMM::execute::<Args, MP, GMM>(state, ranges, k_range, config.global_config);
+        if tile < num_tiles {
+            GMM::prefetch(
+                &mut lhs_reader,
+                &mut rhs_reader,
+                &mut barrier,
+                k_range,
+                config.global_config,
+            );
+        }
+
+        if prev_tile < num_tiles {
+            let (m_index, n_index, batch_index) =
+                cube_count_args.tile_to_tensor_pos(prev_tile, global_order);
+            let writer = init_global_writer::<Args, MP, GMM>(
+                state,
+                batch_index,
+                m_index * stage_m,
+                n_index * stage_n,
+                config.global_config,
+            );
+            GMM::write_accumulators(&acc, writer, config.global_config);
+        }
+
+        prev_tile = tile;
+        if tile < num_tiles {
+            GMM::execute_prefetched(
+                lhs_reader,
+                rhs_reader,
+                GMM::init_acc_global_reader(CubeOption::new_None(), config.global_config),
+                &mut acc,
+                &mut barrier,
+                k_range,
+                config.global_config,
+            );
+            tile = cube_count_args.next_tile(tile, &mut next_tile);
+        }
     }
 }
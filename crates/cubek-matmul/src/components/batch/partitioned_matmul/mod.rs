@@ -3,5 +3,7 @@ mod matmul;
 mod partition;
 mod setup;
 
-pub use partition::{ColMajorGlobalPartitionMatmul, RowMajorGlobalPartitionMatmul};
+pub use partition::{
+    ColMajorGlobalPartitionMatmul, GlobalPartitionMatmul, RowMajorGlobalPartitionMatmul,
+};
 pub use setup::PartitionedBatchMatmulFamily;
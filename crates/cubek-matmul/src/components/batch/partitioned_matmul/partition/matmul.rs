@@ -4,7 +4,7 @@ use crate::definition::{AccG, LhsG, MatmulPrecision, RhsG};
 use crate::{
     components::{
         batch::SliceIndex,
-        global::{self, GlobalConfig},
+        global::{self, EpilogueInputs, GlobalConfig},
         stage::StageConfig,
     },
     launch::MatmulArgs,
@@ -158,19 +158,81 @@ pub(crate) fn execute_global_matmul<
     k_range: (u32, u32),
     #[comptime] config: GMM::Config,
 ) {
+    let (lhs_reader, rhs_reader) =
+        init_global_readers::<Args, MP, GMM>(state, nth_batch, m_offset, n_offset, k_range, config);
+
+    GMM::execute(
+        lhs_reader,
+        rhs_reader,
+        // `c` is scaled by `beta` and added by the epilogue rather than loaded as the initial
+        // accumulator: `alpha` and the operand scales only apply to `lhs @ rhs`, and the partial
+        // accumulators of split-K would each add `c` again. The global matmuls only have a zero
+        // accumulator reader, so they all start from zero.
+        GMM::init_acc_global_reader(CubeOption::new_None(), config),
+        init_global_writer::<Args, MP, GMM>(state, nth_batch, m_offset, n_offset, config),
+        k_range,
+        config,
+    );
+}
+
+#[cube]
+/// Readers of lhs and rhs for the global matmul at the given batch and absolute offsets
+pub(crate) fn init_global_readers<
+    Args: MatmulArgs,
+    MP: MatmulPrecision,
+    GMM: global::GlobalMatmul<MP>,
+>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    nth_batch: u32,
+    m_offset: u32,
+    n_offset: u32,
+    k_range: (u32, u32),
+    #[comptime] config: GMM::Config,
+) -> (GMM::LhsGlobalReader, GMM::RhsGlobalReader) {
     let stage_m = config.stage_config().elements_in_stage_m().runtime();
     let stage_n = config.stage_config().elements_in_stage_n().runtime();
     let k_size = k_range.1 - k_range.0;
 
     let a = Args::view_lhs(state);
     let b = Args::view_rhs(state);
-    let c = Args::view_acc(state);
-    let out = Args::view_out(state);
 
     let a_batch = Args::batch_lhs(state, nth_batch);
     let a = a.view(SliceIndex::new(a_batch, a.shape()));
     let b_batch = Args::batch_rhs(state, nth_batch);
     let b = b.view(SliceIndex::new(b_batch, b.shape()));
+
+    (
+        GMM::init_lhs_global_reader(
+            a.slice_unchecked((m_offset, k_range.0), (stage_m, k_size)),
+            config,
+        ),
+        GMM::init_rhs_global_reader(
+            b.slice_unchecked((k_range.0, n_offset), (k_size, stage_n)),
+            config,
+        ),
+    )
+}
+
+#[cube]
+/// Writer of the global matmul at the given batch and absolute offsets, with the inputs of its
+/// epilogue
+pub(crate) fn init_global_writer<
+    Args: MatmulArgs,
+    MP: MatmulPrecision,
+    GMM: global::GlobalMatmul<MP>,
+>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    nth_batch: u32,
+    m_offset: u32,
+    n_offset: u32,
+    #[comptime] config: GMM::Config,
+) -> GMM::GlobalWriter {
+    let stage_m = config.stage_config().elements_in_stage_m().runtime();
+    let stage_n = config.stage_config().elements_in_stage_n().runtime();
+
+    let c = Args::view_acc(state);
+    let out = Args::view_out(state);
+
     let c_batch = Args::batch_acc(state, nth_batch);
     let c = match c {
         CubeOption::Some(c) => {
@@ -182,21 +244,48 @@ pub(crate) fn execute_global_matmul<
     let out_batch = Args::batch_out(state, nth_batch);
     let out = out.view_mut(SliceIndex::new(out_batch, out.shape()));
 
-    GMM::execute(
-        GMM::init_lhs_global_reader(
-            a.slice_unchecked((m_offset, k_range.0), (stage_m, k_size)),
-            config,
-        ),
-        GMM::init_rhs_global_reader(
-            b.slice_unchecked((k_range.0, n_offset), (k_size, stage_n)),
-            config,
-        ),
-        GMM::init_acc_global_reader(c, config),
-        GMM::init_global_writer(
-            out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
-            config,
+    let bias = match Args::view_bias(state) {
+        CubeOption::Some(bias) => {
+            let bias = bias.view(SliceIndex::new(0, bias.shape()));
+            CubeOption::new_Some(bias.slice_unchecked((m_offset, n_offset), (stage_m, stage_n)))
+        }
+        CubeOption::None => CubeOption::new_None(),
+    };
+    let residual_batch = Args::batch_residual(state, nth_batch);
+    let residual = match Args::view_residual(state) {
+        CubeOption::Some(residual) => {
+            let residual = residual.view(SliceIndex::new(residual_batch, residual.shape()));
+            CubeOption::new_Some(residual.slice_unchecked((m_offset, n_offset), (stage_m, stage_n)))
+        }
+        CubeOption::None => CubeOption::new_None(),
+    };
+
+    let lhs_scale = match Args::view_lhs_scale(state) {
+        CubeOption::Some(scale) => {
+            let scale = scale.view(SliceIndex::new(0, scale.shape()));
+            CubeOption::new_Some(scale.slice_unchecked((m_offset, n_offset), (stage_m, stage_n)))
+        }
+        CubeOption::None => CubeOption::new_None(),
+    };
+    let rhs_scale = match Args::view_rhs_scale(state) {
+        CubeOption::Some(scale) => {
+            let scale = scale.view(SliceIndex::new(0, scale.shape()));
+            CubeOption::new_Some(scale.slice_unchecked((m_offset, n_offset), (stage_m, stage_n)))
+        }
+        CubeOption::None => CubeOption::new_None(),
+    };
+
+    GMM::init_global_writer(
+        out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
+        EpilogueInputs::new(
+            c,
+            bias,
+            residual,
+            lhs_scale,
+            rhs_scale,
+            Args::alpha(state),
+            Args::beta(state),
         ),
-        k_range,
         config,
-    );
+    )
 }
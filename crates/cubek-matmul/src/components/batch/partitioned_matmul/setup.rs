@@ -5,13 +5,16 @@ use crate::components::batch::partitioned_matmul::config::PartitionedBatchConfig
 use crate::components::batch::partitioned_matmul::matmul::PartitionedBatchMatmul;
 use crate::components::batch::partitioned_matmul::matmul::matmul_entry;
 use crate::components::batch::partitioned_matmul::partition::GlobalPartitionMatmul;
-use crate::components::global::GlobalMatmulFamily;
+use crate::components::global::{GlobalConfig, GlobalMatmulFamily};
+use crate::components::stage::{StageConfig, StageMemoryConfig};
 use crate::definition::CubeCountInputArgs;
 use crate::definition::TilingBlueprint;
 use crate::definition::{
-    MatmulElems, MatmulLineSizes, MatmulPrecision, MatmulProblem, MatmulSetupError,
+    MatmulAvailabilityError, MatmulElems, MatmulLineSizes, MatmulPrecision, MatmulProblem,
+    MatmulSetupError,
 };
 use crate::launch::{InputRuntimeArg, MatmulArgs, OutputRuntimeArg};
+use cubecl::features::TypeUsage;
 use cubecl::prelude::*;
 
 /// Simple partitioned batch matmul family for any precision
@@ -35,13 +38,40 @@ impl<GMM: GlobalMatmulFamily, S: GlobalPartitionMatmul> BatchMatmulFamily
         dtypes: &MatmulElems,
     ) -> Result<Self::Config, MatmulSetupError> {
         let global_config = GMM::expand_config(client, problem, blueprint, line_sizes, dtypes)?;
+        let hypercube_config = blueprint.hypercube_selection.to_hypercube_config(
+            problem,
+            client.properties().hardware.max_cube_count.clone(),
+            max_persistent_cubes(client, &global_config, dtypes),
+        );
+
+        if hypercube_config
+            .cube_count_plan_blueprint
+            .needs_tile_counter()
+            && !client
+                .properties()
+                .type_usage(StorageType::Atomic(ElemType::UInt(
+                    cubecl::ir::UIntKind::U32,
+                )))
+                .contains(TypeUsage::AtomicAdd)
+        {
+            return Err(MatmulSetupError::Unavailable(
+                MatmulAvailabilityError::AtomicUnavailable,
+            ));
+        }
+
+        // Overlapping keeps the results of a single global matmul while loading the next one
+        let global_partition_size = blueprint.tiling_scheme.global_partition_size;
+        let overlap_tiles = GMM::CAN_PREFETCH
+            && hypercube_config.cube_count_plan_blueprint.is_persistent()
+            && global_partition_size.m == 1
+            && global_partition_size.n == 1
+            && global_partition_size.batches == 1;
 
         PartitionedBatchConfig::new(
             global_config,
-            blueprint
-                .hypercube_selection
-                .to_hypercube_config(problem, client.properties().hardware.max_cube_count.clone()),
-            blueprint.tiling_scheme.global_partition_size,
+            hypercube_config,
+            global_partition_size,
+            overlap_tiles,
         )
         .validate(problem)
     }
@@ -76,3 +106,32 @@ impl<GMM: GlobalMatmulFamily, S: GlobalPartitionMatmul> BatchMatmulFamily
         }
     }
 }
+
+/// Number of cubes all SMs hold at once, or `None` if the device doesn't report its number of
+/// SMs.
+///
+/// A cube is bounded by the shared memory of its stages and by its number of units. The
+/// capacity of an SM isn't reported, so the maximum for a single cube is used for both, which
+/// underestimates the occupancy on devices with larger SMs.
+fn max_persistent_cubes<R: Runtime, G: GlobalConfig>(
+    client: &ComputeClient<R>,
+    global_config: &G,
+    dtypes: &MatmulElems,
+) -> Option<u32> {
+    let hardware = &client.properties().hardware;
+    let num_sms = hardware.num_streaming_multiprocessors?;
+
+    let stage_config = global_config.stage_config();
+    let stage_bytes = |smem_config: StageMemoryConfig, elem_size: usize| {
+        (smem_config.elements_per_stage() * smem_config.num_stages) as usize * elem_size
+    };
+    let shared_memory = stage_bytes(stage_config.lhs_smem_config(), dtypes.lhs_stage.size())
+        + stage_bytes(stage_config.rhs_smem_config(), dtypes.rhs_stage.size())
+        + stage_bytes(stage_config.out_smem_config(), dtypes.acc_stage.size());
+
+    let by_shared_memory = hardware.max_shared_memory_size / shared_memory.max(1);
+    let by_units = hardware.max_units_per_cube / global_config.cube_dim().num_elems().max(1);
+    let cubes_per_sm = (by_shared_memory as u32).min(by_units).max(1);
+
+    Some(num_sms * cubes_per_sm)
+}
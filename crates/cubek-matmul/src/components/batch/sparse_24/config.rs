@@ -0,0 +1,52 @@
+use cubecl::{CubeCount, CubeDim};
+
+use crate::components::global::GlobalConfig;
+use crate::components::global::memory::GlobalLayoutConfig;
+use crate::components::stage::StageConfig as _;
+use crate::definition::{MatmulLineSizes, MatmulProblem};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration for 2:4 sparse batch matmul
+pub struct Sparse24BatchConfig<G: GlobalConfig> {
+    pub global_config: G,
+}
+
+impl<G: GlobalConfig> Sparse24BatchConfig<G> {
+    /// Create a new config for 2:4 sparse batch matmul
+    pub fn new(global_config: G) -> Self {
+        Self { global_config }
+    }
+
+    pub fn cube_dim(&self) -> CubeDim {
+        self.global_config.cube_dim()
+    }
+
+    /// One cube per output tile of each batch.
+    pub fn cube_count(&self, problem: &MatmulProblem) -> CubeCount {
+        let stage_config = self.global_config.stage_config();
+        let stage_m = stage_config.elements_in_stage_m();
+        let stage_n = stage_config.elements_in_stage_n();
+
+        CubeCount::Static(
+            (problem.m as u32).div_ceil(stage_m),
+            (problem.n as u32).div_ceil(stage_n),
+            problem.num_batches() as u32,
+        )
+    }
+
+    pub fn line_sizes(&self) -> MatmulLineSizes {
+        self.global_config.global_line_sizes()
+    }
+
+    pub fn lhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.lhs_reader_config().gmem_config.into()
+    }
+
+    pub fn rhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.rhs_reader_config().gmem_config.into()
+    }
+
+    pub fn out_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.writer_config().gmem_config.into()
+    }
+}
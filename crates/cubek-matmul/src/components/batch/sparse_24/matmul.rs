@@ -0,0 +1,114 @@
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption,
+    tensor::{View, layout::Coords3d},
+};
+use std::marker::PhantomData;
+
+use crate::components::batch::SliceIndex;
+use crate::components::batch::sparse_24::config::Sparse24BatchConfig;
+use crate::components::global::single_stage::sparse_24::{
+    Sparse24GlobalMatmul, Sparse24GlobalMatmulFamily,
+};
+use crate::components::global::{EpilogueInputs, GlobalConfig, GlobalMatmul};
+use crate::components::stage::StageConfig as _;
+use crate::definition::{AccG, LhsG, MatmulPrecision, RhsG};
+use crate::launch::MatmulArgs;
+
+#[cube(launch_unchecked)]
+/// Launches the 2:4 sparse matmul kernel
+pub(crate) fn sparse_24_matmul_entry<
+    Args: MatmulArgs,
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    LhsR: Numeric,
+    RhsR: Numeric,
+    AccR: Numeric,
+    GMMF: Sparse24GlobalMatmulFamily,
+>(
+    inputs: &<Args as MatmulArgs>::Input<LhsG, RhsG, AccG>,
+    output: &mut <Args as MatmulArgs>::Output<AccG>,
+    rhs_metadata: &View<Line<u32>, Coords3d>,
+    #[comptime] config: Sparse24BatchConfig<GMMF::Config>,
+    #[define(LhsG, RhsG, AccG)] _global: [StorageType; 3],
+    #[define(LhsS, RhsS, AccS)] _stage: [StorageType; 3],
+    #[define(LhsR, RhsR, AccR)] _register: [StorageType; 3],
+) {
+    let mut state = Args::init_state::<LhsG, RhsG, AccG>(
+        inputs,
+        output,
+        config.lhs_global_layout_config(),
+        config.rhs_global_layout_config(),
+        config.out_global_layout_config(),
+    );
+
+    Sparse24BatchMatmul::<
+        ((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR)),
+        GMMF::Sparse24Matmul<((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR))>,
+    >::execute::<Args>(&mut state, rhs_metadata, config);
+}
+
+/// Executes matrix multiplications where rhs is 2:4 sparse along k, with the kept values of rhs
+/// as its view and their positions given separately.
+///
+/// Each cube computes a single output tile of a single batch.
+pub struct Sparse24BatchMatmul<MP: MatmulPrecision, GMM: Sparse24GlobalMatmul<MP>> {
+    _mp: PhantomData<MP>,
+    _gmm: PhantomData<GMM>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, GMM: Sparse24GlobalMatmul<MP>> Sparse24BatchMatmul<MP, GMM> {
+    pub fn execute<Args: MatmulArgs>(
+        state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+        rhs_metadata: &View<Line<u32>, Coords3d>,
+        #[comptime] config: Sparse24BatchConfig<GMM::Config>,
+    ) {
+        let global_config = config.global_config;
+        let stage_m = global_config.stage_config().elements_in_stage_m().runtime();
+        let stage_n = global_config.stage_config().elements_in_stage_n().runtime();
+
+        let m_offset = CUBE_POS_X * stage_m;
+        let n_offset = CUBE_POS_Y * stage_n;
+        let nth_batch = CUBE_POS_Z;
+
+        let a = Args::view_lhs(state);
+        let b = Args::view_rhs(state);
+        let out = Args::view_out(state);
+
+        let (_, _, k_size) = a.shape();
+
+        let a_batch = Args::batch_lhs(state, nth_batch);
+        let a = a.view(SliceIndex::new(a_batch, a.shape()));
+        let b_batch = Args::batch_rhs(state, nth_batch);
+        let b = b.view(SliceIndex::new(b_batch, b.shape()));
+        // Metadata has its own batch layout, so it's indexed by batch rather than offset
+        let metadata = rhs_metadata.view(SliceIndex::new(nth_batch, rhs_metadata.shape()));
+        let out_batch = Args::batch_out(state, nth_batch);
+        let out = out.view_mut(SliceIndex::new(out_batch, out.shape()));
+
+        GMM::execute(
+            GMM::init_lhs_global_reader(
+                a.slice_unchecked((m_offset, 0), (stage_m, k_size)),
+                global_config,
+            ),
+            GMM::init_rhs_sparse_reader(
+                b.slice_unchecked((0, n_offset), (k_size / 2, stage_n)),
+                metadata.slice_unchecked((0, n_offset), (k_size / 4, stage_n)),
+                global_config,
+            ),
+            GMM::init_acc_global_reader(CubeOption::new_None(), global_config),
+            GMM::init_global_writer(
+                out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
+                EpilogueInputs::none(),
+                global_config,
+            ),
+            (0, k_size),
+            global_config,
+        );
+    }
+}
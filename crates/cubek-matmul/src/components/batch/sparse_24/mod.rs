@@ -0,0 +1,6 @@
+mod config;
+mod matmul;
+mod setup;
+
+pub use config::Sparse24BatchConfig;
+pub use setup::Sparse24BatchMatmulFamily;
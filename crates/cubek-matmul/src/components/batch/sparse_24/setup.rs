@@ -0,0 +1,55 @@
+use std::marker::PhantomData;
+
+use crate::components::batch::sparse_24::config::Sparse24BatchConfig;
+use crate::components::batch::sparse_24::matmul::sparse_24_matmul_entry;
+use crate::components::global::single_stage::sparse_24::Sparse24GlobalMatmulFamily;
+use crate::definition::MatmulElems;
+use crate::launch::{InputRuntimeArg, MatmulArgs, OutputRuntimeArg};
+use cubecl::prelude::*;
+use cubecl::std::tensor::{View, layout::Coords3d};
+
+/// 2:4 sparse batch matmul family for any precision.
+///
+/// Unlike a [BatchMatmulFamily](crate::components::batch::BatchMatmulFamily), the metadata of the
+/// sparse rhs is given as a separate view, which must be provided at launch.
+pub struct Sparse24BatchMatmulFamily<GMM: Sparse24GlobalMatmulFamily> {
+    _gmm: PhantomData<GMM>,
+}
+
+impl<GMM: Sparse24GlobalMatmulFamily> Sparse24BatchMatmulFamily<GMM> {
+    /// Entry point
+    ///
+    /// # Safety
+    ///
+    /// Out-of-bounds can happen
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch_unchecked<'a, MA: MatmulArgs, R: Runtime>(
+        client: &ComputeClient<R>,
+        cube_dim: CubeDim,
+        cube_count: CubeCount,
+        input: InputRuntimeArg<'a, MA, R>,
+        output: OutputRuntimeArg<'a, MA, R>,
+        rhs_metadata: <View<Line<u32>, Coords3d> as LaunchArg>::RuntimeArg<'a, R>,
+        config: Sparse24BatchConfig<GMM::Config>,
+        dtypes: &MatmulElems,
+    ) -> Result<(), LaunchError> {
+        unsafe {
+            sparse_24_matmul_entry::launch_unchecked::<MA, GMM, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input,
+                output,
+                rhs_metadata,
+                config,
+                [*dtypes.lhs_global, *dtypes.rhs_global, *dtypes.acc_global],
+                [*dtypes.lhs_stage, *dtypes.rhs_stage, *dtypes.acc_stage],
+                [
+                    *dtypes.lhs_register,
+                    *dtypes.rhs_register,
+                    *dtypes.acc_register,
+                ],
+            )
+        }
+    }
+}
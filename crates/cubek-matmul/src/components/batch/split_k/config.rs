@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+use cubecl::{CubeCount, CubeDim, Runtime, client::ComputeClient};
+
+use crate::components::global::GlobalConfig;
+use crate::components::global::memory::GlobalLayoutConfig;
+use crate::components::stage::StageConfig as _;
+use crate::definition::{MatmulLineSizes, MatmulProblem, MatmulSetupError};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// How the k dimension is partitioned across cubes
+pub enum SplitKMode {
+    /// Each output tile is computed by `num_splits` cubes, each covering a contiguous
+    /// range of k.
+    SplitK { num_splits: u32 },
+    /// A fixed number of persistent cubes share all stage iterations of all output tiles
+    /// evenly, so a cube may finish one tile and start another.
+    StreamK { num_cubes: u32 },
+}
+
+impl SplitKMode {
+    /// Stream-K with one persistent cube per streaming multiprocessor, or split-K in four
+    /// when the device doesn't report its number of multiprocessors.
+    pub fn from_device<R: Runtime>(client: &ComputeClient<R>) -> Self {
+        match client.properties().hardware.num_streaming_multiprocessors {
+            Some(num_sms) => SplitKMode::StreamK { num_cubes: num_sms },
+            None => SplitKMode::SplitK { num_splits: 4 },
+        }
+    }
+}
+
+impl Display for SplitKMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitKMode::SplitK { num_splits } => write!(f, "_split{num_splits}"),
+            SplitKMode::StreamK { num_cubes } => write!(f, "_stream{num_cubes}"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration for split-K batch matmul
+pub struct SplitKBatchConfig<G: GlobalConfig> {
+    pub global_config: G,
+    pub mode: SplitKMode,
+}
+
+/// How the work of a split-K matmul is laid out for a given problem
+#[derive(Clone, Debug)]
+pub struct SplitKPlan {
+    /// Number of output tiles along m
+    pub m_tiles: u32,
+    /// Number of output tiles along n
+    pub n_tiles: u32,
+    /// Number of stage iterations along k for a full output tile
+    pub k_iters: u32,
+    /// Number of stage iterations along k computed by each cube
+    pub iters_per_cube: u32,
+    /// Number of slabs of partial results that get summed in the reduction
+    pub num_slabs: u32,
+    /// Whether some slabs may not be written by any cube and must start from zero
+    pub needs_zeroed_workspace: bool,
+    pub cube_count: CubeCount,
+}
+
+impl<G: GlobalConfig> SplitKBatchConfig<G> {
+    /// Create a new config for split-K batch matmul
+    pub fn new(global_config: G, mode: SplitKMode) -> Self {
+        Self {
+            global_config,
+            mode,
+        }
+    }
+
+    /// May return an error if:
+    /// - the problem is split into groups
+    /// - the mode doesn't use any split or cube
+    pub fn validate(self, problem: &MatmulProblem) -> Result<Self, MatmulSetupError> {
+        if problem.num_groups.is_some() {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Split-K matmul doesn't support grouped problems",
+            )));
+        }
+
+        let count = match self.mode {
+            SplitKMode::SplitK { num_splits } => num_splits,
+            SplitKMode::StreamK { num_cubes } => num_cubes,
+        };
+        if count == 0 {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Split-K matmul needs at least one split or cube",
+            )));
+        }
+
+        Ok(self)
+    }
+
+    pub fn cube_dim(&self) -> CubeDim {
+        self.global_config.cube_dim()
+    }
+
+    /// Distribute the stage iterations of all output tiles over the cubes.
+    pub fn plan(&self, problem: &MatmulProblem) -> SplitKPlan {
+        let stage_config = self.global_config.stage_config();
+        let m_tiles = (problem.m as u32).div_ceil(stage_config.elements_in_stage_m());
+        let n_tiles = (problem.n as u32).div_ceil(stage_config.elements_in_stage_n());
+        let k_iters = (problem.k as u32)
+            .div_ceil(stage_config.elements_in_stage_k())
+            .max(1);
+        let num_tiles = problem.num_batches() as u32 * m_tiles * n_tiles;
+
+        match self.mode {
+            SplitKMode::SplitK { num_splits } => {
+                let num_splits = num_splits.min(k_iters);
+                SplitKPlan {
+                    m_tiles,
+                    n_tiles,
+                    k_iters,
+                    iters_per_cube: k_iters.div_ceil(num_splits),
+                    num_slabs: num_splits,
+                    needs_zeroed_workspace: false,
+                    cube_count: CubeCount::Static(num_tiles, num_splits, 1),
+                }
+            }
+            SplitKMode::StreamK { num_cubes } => {
+                let total_iters = num_tiles * k_iters;
+                let iters_per_cube = total_iters.div_ceil(num_cubes);
+                let num_cubes = total_iters.div_ceil(iters_per_cube);
+                // A tile spans at most this many cubes, the slab being the rank of the cube
+                // among those working on the tile.
+                let num_slabs = (k_iters.div_ceil(iters_per_cube) + 1).min(num_cubes);
+
+                SplitKPlan {
+                    m_tiles,
+                    n_tiles,
+                    k_iters,
+                    iters_per_cube,
+                    num_slabs,
+                    needs_zeroed_workspace: true,
+                    cube_count: CubeCount::Static(num_cubes, 1, 1),
+                }
+            }
+        }
+    }
+
+    pub fn line_sizes(&self) -> MatmulLineSizes {
+        self.global_config.global_line_sizes()
+    }
+
+    pub fn lhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.lhs_reader_config().gmem_config.into()
+    }
+
+    pub fn rhs_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.rhs_reader_config().gmem_config.into()
+    }
+
+    pub fn out_global_layout_config(&self) -> GlobalLayoutConfig {
+        self.global_config.writer_config().gmem_config.into()
+    }
+}
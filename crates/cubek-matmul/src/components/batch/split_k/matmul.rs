@@ -0,0 +1,184 @@
+use cubecl::prelude::*;
+use cubecl::std::CubeOption;
+use std::marker::PhantomData;
+
+use crate::components::batch::SliceIndex;
+use crate::components::batch::split_k::config::{SplitKBatchConfig, SplitKMode};
+use crate::components::global::{
+    self, EpilogueInputs, GlobalConfig, GlobalMatmul, GlobalMatmulFamily,
+};
+use crate::components::stage::StageConfig as _;
+use crate::definition::{AccG, LhsG, MatmulPrecision, RhsG};
+use crate::launch::MatmulArgs;
+
+#[derive(CubeType, CubeLaunch, Clone)]
+/// Problem dependent values of a split-K matmul, see [SplitKPlan](super::SplitKPlan)
+pub struct SplitKArgs {
+    pub m_tiles: u32,
+    pub n_tiles: u32,
+    pub k_iters: u32,
+    pub iters_per_cube: u32,
+    /// Total number of output tiles, across all batches
+    pub num_tiles: u32,
+    /// Number of elements between two slabs of partial results
+    pub slab_stride: u32,
+}
+
+#[cube(launch_unchecked)]
+/// Launches the split-K matmul kernel, writing partial results to slabs of the output
+pub(crate) fn split_k_matmul_entry<
+    Args: MatmulArgs,
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    LhsR: Numeric,
+    RhsR: Numeric,
+    AccR: Numeric,
+    GMMF: GlobalMatmulFamily,
+>(
+    inputs: &<Args as MatmulArgs>::Input<LhsG, RhsG, AccG>,
+    output: &mut <Args as MatmulArgs>::Output<AccG>,
+    args: SplitKArgs,
+    #[comptime] config: SplitKBatchConfig<GMMF::Config>,
+    #[define(LhsG, RhsG, AccG)] _global: [StorageType; 3],
+    #[define(LhsS, RhsS, AccS)] _stage: [StorageType; 3],
+    #[define(LhsR, RhsR, AccR)] _register: [StorageType; 3],
+) {
+    let mut state = Args::init_state::<LhsG, RhsG, AccG>(
+        inputs,
+        output,
+        config.lhs_global_layout_config(),
+        config.rhs_global_layout_config(),
+        config.out_global_layout_config(),
+    );
+
+    SplitKBatchMatmul::<
+        ((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR)),
+        GMMF::Matmul<((LhsG, LhsS, LhsR), (RhsG, RhsS, RhsR), (AccG, AccS, AccR))>,
+    >::execute::<Args>(&mut state, &args, config);
+}
+
+/// Executes matrix multiplications where the k dimension of each output tile is
+/// partitioned across cubes.
+///
+/// Each cube writes the partial result of its k range to a slab of the output, which
+/// must then be summed to get the final result.
+pub struct SplitKBatchMatmul<MP: MatmulPrecision, GMM: global::GlobalMatmul<MP>> {
+    _mp: PhantomData<MP>,
+    _gmm: PhantomData<GMM>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, GMM: GlobalMatmul<MP>> SplitKBatchMatmul<MP, GMM> {
+    pub fn execute<Args: MatmulArgs>(
+        state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+        args: &SplitKArgs,
+        #[comptime] config: SplitKBatchConfig<GMM::Config>,
+    ) {
+        if comptime![matches!(config.mode, SplitKMode::StreamK { .. })] {
+            // Cubes take contiguous ranges of iterations over all tiles, so a range may end
+            // in the middle of a tile and continue in the next one.
+            let start = CUBE_POS_X * args.iters_per_cube;
+            let end = Min::min(start + args.iters_per_cube, args.num_tiles * args.k_iters);
+            let mut iter = start;
+
+            loop {
+                if iter >= end {
+                    break;
+                }
+
+                let tile = iter / args.k_iters;
+                let tile_start = tile * args.k_iters;
+                let segment_end = Min::min(end, tile_start + args.k_iters);
+                // Rank of this cube among the ones working on the tile
+                let slab = CUBE_POS_X - tile_start / args.iters_per_cube;
+
+                execute_tile_segment::<Args, MP, GMM>(
+                    state,
+                    args,
+                    tile,
+                    (iter - tile_start, segment_end - tile_start),
+                    slab,
+                    config.global_config,
+                );
+
+                iter = segment_end;
+            }
+        } else {
+            let start = CUBE_POS_Y * args.iters_per_cube;
+            let end = Min::min(start + args.iters_per_cube, args.k_iters);
+            // Trailing splits may be empty, they still write zeros to their slab
+            let start = Min::min(start, end);
+
+            execute_tile_segment::<Args, MP, GMM>(
+                state,
+                args,
+                CUBE_POS_X,
+                (start, end),
+                CUBE_POS_Y,
+                config.global_config,
+            );
+        }
+    }
+}
+
+#[cube]
+/// Execute global matmul on the stage iterations `iter_range` of `tile`, writing to `slab`.
+fn execute_tile_segment<Args: MatmulArgs, MP: MatmulPrecision, GMM: global::GlobalMatmul<MP>>(
+    state: &mut Args::State<LhsG<MP>, RhsG<MP>, AccG<MP>>,
+    args: &SplitKArgs,
+    tile: u32,
+    iter_range: (u32, u32),
+    slab: u32,
+    #[comptime] config: GMM::Config,
+) {
+    let stage_m = config.stage_config().elements_in_stage_m().runtime();
+    let stage_n = config.stage_config().elements_in_stage_n().runtime();
+    let stage_k = config.stage_config().elements_in_stage_k().runtime();
+
+    let tiles_per_batch = args.m_tiles * args.n_tiles;
+    let nth_batch = tile / tiles_per_batch;
+    let tile_in_batch = tile % tiles_per_batch;
+    let m_offset = (tile_in_batch / args.n_tiles) * stage_m;
+    let n_offset = (tile_in_batch % args.n_tiles) * stage_n;
+
+    let a = Args::view_lhs(state);
+    let b = Args::view_rhs(state);
+    let out = Args::view_out(state);
+
+    let (_, _, k_size) = a.shape();
+    let k_range = (
+        Min::min(iter_range.0 * stage_k, k_size),
+        Min::min(iter_range.1 * stage_k, k_size),
+    );
+    let k_len = k_range.1 - k_range.0;
+
+    let a_batch = Args::batch_lhs(state, nth_batch);
+    let a = a.view(SliceIndex::new(a_batch, a.shape()));
+    let b_batch = Args::batch_rhs(state, nth_batch);
+    let b = b.view(SliceIndex::new(b_batch, b.shape()));
+    let out_batch = Args::batch_out(state, nth_batch) + slab * args.slab_stride;
+    let out = out.view_mut(SliceIndex::new(out_batch, out.shape()));
+
+    GMM::execute(
+        GMM::init_lhs_global_reader(
+            a.slice_unchecked((m_offset, k_range.0), (stage_m, k_len)),
+            config,
+        ),
+        GMM::init_rhs_global_reader(
+            b.slice_unchecked((k_range.0, n_offset), (k_len, stage_n)),
+            config,
+        ),
+        GMM::init_acc_global_reader(CubeOption::new_None(), config),
+        GMM::init_global_writer(
+            out.slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n)),
+            EpilogueInputs::none(),
+            config,
+        ),
+        k_range,
+        config,
+    );
+}
@@ -0,0 +1,8 @@
+mod config;
+mod matmul;
+mod reduce;
+mod setup;
+
+pub use config::{SplitKBatchConfig, SplitKMode, SplitKPlan};
+pub use matmul::{SplitKArgs, SplitKArgsLaunch};
+pub use setup::SplitKBatchMatmulFamily;
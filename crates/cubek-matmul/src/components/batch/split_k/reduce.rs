@@ -0,0 +1,57 @@
+use cubecl::prelude::*;
+use cubecl::std::{CubeOption, CubeOptionExpand};
+
+#[cube(launch_unchecked)]
+/// Sums the slabs of partial results into the output, computing `alpha * sum + beta * c`.
+///
+/// `partials` is contiguous with shape `[num_slabs, ..out.shape]`, while `output` and `c` may
+/// have any strides as long as their last dimension is contiguous. `c` may be the output itself.
+pub(crate) fn reduce_partials<Acc: Numeric, Out: Numeric>(
+    partials: &Tensor<Line<Acc>>,
+    c: &CubeOption<Tensor<Line<Out>>>,
+    output: &mut Tensor<Line<Out>>,
+    num_slabs: u32,
+    alpha: f32,
+    beta: f32,
+    #[define(Acc, Out)] _types: [StorageType; 2],
+) {
+    let line_size = output.line_size();
+    let slab_len = partials.len() / num_slabs;
+
+    if ABSOLUTE_POS >= slab_len {
+        terminate!();
+    }
+
+    let mut sum = Line::empty(line_size).fill(Acc::from_int(0));
+    for slab in 0..num_slabs {
+        sum += partials[slab * slab_len + ABSOLUTE_POS];
+    }
+    let mut value = sum * Line::empty(line_size).fill(Acc::cast_from(alpha));
+
+    // Partials are contiguous, so the position can be unravelled with the output shape
+    let rank = output.rank();
+    let mut remaining = ABSOLUTE_POS * line_size;
+    let mut offset = 0;
+    let mut c_offset = 0;
+    for i in 0..rank {
+        let dim = rank - 1 - i;
+        let shape = output.shape(dim);
+        let index = remaining % shape;
+        offset += index * output.stride(dim);
+        match c {
+            CubeOption::Some(c) => c_offset += index * c.stride(dim),
+            CubeOption::None => {}
+        }
+        remaining /= shape;
+    }
+
+    match c {
+        CubeOption::Some(c) => {
+            value += Line::<Acc>::cast_from(c[c_offset / line_size])
+                * Line::empty(line_size).fill(Acc::cast_from(beta));
+        }
+        CubeOption::None => {}
+    }
+
+    output[offset / line_size] = Line::cast_from(value);
+}
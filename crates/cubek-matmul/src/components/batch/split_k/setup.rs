@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+use crate::components::batch::split_k::config::SplitKBatchConfig;
+use crate::components::batch::split_k::matmul::{SplitKArgsLaunch, split_k_matmul_entry};
+use crate::components::batch::split_k::reduce::reduce_partials;
+use crate::components::global::GlobalMatmulFamily;
+use crate::definition::MatmulElems;
+use crate::launch::{InputRuntimeArg, MatmulArgs, OutputRuntimeArg};
+use cubecl::prelude::*;
+use cubecl::std::CubeOptionArgs;
+
+/// Split-K batch matmul family for any precision.
+///
+/// Unlike a [BatchMatmulFamily](crate::components::batch::BatchMatmulFamily), the matmul only
+/// writes partial results, which must be summed with [Self::launch_reduce] afterwards.
+/// The global matmul config is the one expanded for a
+/// [partitioned](crate::components::batch::PartitionedBatchMatmulFamily) matmul on the same
+/// problem, and the global matmul must not read past the end of its k range.
+pub struct SplitKBatchMatmulFamily<GMM: GlobalMatmulFamily> {
+    _gmm: PhantomData<GMM>,
+}
+
+impl<GMM: GlobalMatmulFamily> SplitKBatchMatmulFamily<GMM> {
+    /// Entry point of the matmul pass, `output` being the workspace of partial results.
+    ///
+    /// # Safety
+    ///
+    /// Out-of-bounds can happen
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch_unchecked<'a, MA: MatmulArgs, R: Runtime>(
+        client: &ComputeClient<R>,
+        cube_dim: CubeDim,
+        cube_count: CubeCount,
+        input: InputRuntimeArg<'a, MA, R>,
+        output: OutputRuntimeArg<'a, MA, R>,
+        args: SplitKArgsLaunch<'a, R>,
+        config: SplitKBatchConfig<GMM::Config>,
+        dtypes: &MatmulElems,
+    ) -> Result<(), LaunchError> {
+        unsafe {
+            split_k_matmul_entry::launch_unchecked::<MA, GMM, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input,
+                output,
+                args,
+                config,
+                [*dtypes.lhs_global, *dtypes.rhs_global, *dtypes.acc_global],
+                [*dtypes.lhs_stage, *dtypes.rhs_stage, *dtypes.acc_stage],
+                [
+                    *dtypes.lhs_register,
+                    *dtypes.rhs_register,
+                    *dtypes.acc_register,
+                ],
+            )
+        }
+    }
+
+    /// Entry point of the reduction pass, writing `alpha * sum + beta * c` into `out`, where `sum`
+    /// is the sum of the `num_slabs` slabs of `partials`.
+    ///
+    /// # Safety
+    ///
+    /// Out-of-bounds can happen if `partials` isn't contiguous with shape
+    /// `[num_slabs, ..out.shape]`, or if `c` doesn't have the shape of `out`
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch_reduce<R: Runtime>(
+        client: &ComputeClient<R>,
+        partials: &TensorHandleRef<'_, R>,
+        c: Option<&TensorHandleRef<'_, R>>,
+        out: &TensorHandleRef<'_, R>,
+        num_slabs: u32,
+        alpha: f32,
+        beta: f32,
+        line_size: u8,
+        partials_dtype: StorageType,
+        out_dtype: StorageType,
+    ) -> Result<(), LaunchError> {
+        let num_lines = out.shape.iter().product::<usize>() as u32 / line_size as u32;
+        let cube_dim = CubeDim::new(client, num_lines as usize);
+        let cube_count = CubeCount::new_1d(num_lines.div_ceil(cube_dim.num_elems()));
+
+        unsafe {
+            reduce_partials::launch_unchecked::<R>(
+                client,
+                cube_count,
+                cube_dim,
+                partials.as_tensor_arg(line_size),
+                match c {
+                    Some(c) => CubeOptionArgs::Some(c.as_tensor_arg(line_size)),
+                    None => CubeOptionArgs::None,
+                },
+                out.as_tensor_arg(line_size),
+                ScalarArg::new(num_slabs),
+                ScalarArg::new(alpha),
+                ScalarArg::new(beta),
+                [partials_dtype, out_dtype],
+            )
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use cubecl::prelude::*;
+use cubecl::unexpanded;
 
 use crate::components::global::memory::GlobalMemoryConfig;
 use crate::components::global::multi_stage::EventLoadingMode;
 use crate::components::global::read::ReaderMode;
 use crate::components::global::{
-    GlobalWriterConfig, LoadSpecializationConfig, PlaneRoleConfig, SpecializationTensorConfig,
-    SpecializedLoadingSides,
+    EpilogueInputs, GlobalWriterConfig, LoadSpecializationConfig, PlaneRoleConfig,
+    SpecializationTensorConfig, SpecializedLoadingSides,
 };
 use crate::components::stage::{StageConfig, StageMemoryConfig};
 use crate::definition::TilingBlueprint;
@@ -39,6 +40,12 @@ pub trait GlobalMatmulFamily: Send + Sync + 'static {
         dtypes: &MatmulElems,
     ) -> Result<Self::Config, MatmulSetupError>;
 
+    /// Whether the matmuls implement [prefetch](GlobalMatmul::prefetch),
+    /// [execute_prefetched](GlobalMatmul::execute_prefetched) and
+    /// [write_accumulators](GlobalMatmul::write_accumulators), so that persistent cubes can
+    /// overlap the loads of a tile with the epilogue of the previous one.
+    const CAN_PREFETCH: bool = false;
+
     /// Filters out line sizes that are incompatible with this matmul family.
     ///
     /// By default, returns the input unchanged.
@@ -80,6 +87,8 @@ pub trait GlobalMatmul<MP: MatmulPrecision>: 'static + Send + Sync {
 
     /// The accumulator type for the tile matmul
     type Accumulators: CubeType;
+    /// Barrier waited on for the loads started by [prefetch](Self::prefetch)
+    type PrefetchBarrier: CubeType;
 
     /// Performs the matrix multiplication over data loaded by the
     /// Lhs and Rhs readers, over the range given for K, and stores with
@@ -117,11 +126,58 @@ pub trait GlobalMatmul<MP: MatmulPrecision>: 'static + Send + Sync {
     /// Initialize the accumulator without data
     fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators;
 
-    /// Initialize the global writer at row m and column n
+    /// Initialize the global writer at row m and column n, along with the inputs of its epilogue
     fn init_global_writer(
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter;
+
+    /// Create the barrier shared by [prefetch](Self::prefetch) and
+    /// [execute_prefetched](Self::execute_prefetched).
+    ///
+    /// Only available when [GlobalMatmulFamily::CAN_PREFETCH] is set, like the following methods.
+    fn init_prefetch_barrier() -> Self::PrefetchBarrier {
+        unexpanded!()
+    }
+
+    /// Start loading the first stage of the inputs, so that the loads overlap with other work
+    /// such as writing the results of a previous tile.
+    fn prefetch(
+        _lhs_reader: &mut Self::LhsGlobalReader,
+        _rhs_reader: &mut Self::RhsGlobalReader,
+        _barrier: &mut Self::PrefetchBarrier,
+        _k_range: (u32, u32),
+        #[comptime] _config: Self::Config,
+    ) {
+        unexpanded!()
+    }
+
+    /// Performs the matrix multiplication like [execute](Self::execute) on inputs whose first
+    /// stage was started by [prefetch](Self::prefetch), but leaves the results in `acc`.
+    ///
+    /// The input stages aren't freed, so the next inputs can be prefetched while `acc` is
+    /// written by [write_accumulators](Self::write_accumulators).
+    fn execute_prefetched(
+        _lhs_reader: Self::LhsGlobalReader,
+        _rhs_reader: Self::RhsGlobalReader,
+        _acc_reader: Self::AccGlobalReader,
+        _acc: &mut Self::Accumulators,
+        _barrier: &mut Self::PrefetchBarrier,
+        _k_range: (u32, u32),
+        #[comptime] _config: Self::Config,
+    ) {
+        unexpanded!()
+    }
+
+    /// Writes the results computed by [execute_prefetched](Self::execute_prefetched).
+    fn write_accumulators(
+        _acc: &Self::Accumulators,
+        _writer: Self::GlobalWriter,
+        #[comptime] _config: Self::Config,
+    ) {
+        unexpanded!()
+    }
 }
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -8,7 +8,7 @@ use cubecl::std::{
 use cubecl_common::quant::scheme::{QuantLevel, QuantScheme};
 
 use crate::components::global::memory::GlobalMemoryConfig;
-use crate::definition::{MatmulProblem, MatrixLayout};
+use crate::definition::{MatmulProblem, MatrixLayout, ScaleGranularity};
 
 /// Global layout that uses the last two dimensions and ignores all others.
 #[derive(CubeType, CubeLaunch, Clone, Copy)]
@@ -343,22 +343,148 @@ impl Layout for NoopLayout {
     }
 }
 
+/// Layout for a 1D bias along `n`, broadcast to all rows and batches of the output.
+#[derive(CubeType, CubeLaunch)]
+pub struct BiasLayout {
+    shape: u32,
+    #[cube(comptime)]
+    line_size: u32,
+}
+
+#[cube]
+impl Layout for BiasLayout {
+    type Coordinates = Coords3d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, pos: Self::Coordinates) -> Self::SourceCoordinates {
+        let (_, _, n) = pos;
+        n / self.line_size
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        let (_, _, n) = pos;
+        n < self.shape
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        (u32::MAX.runtime(), u32::MAX.runtime(), self.shape)
+    }
+
+    fn to_source_pos_checked(&self, pos: Self::Coordinates) -> (Self::SourceCoordinates, bool) {
+        (self.to_source_pos(pos), self.is_in_bounds(pos))
+    }
+}
+
+impl<'a, R: Runtime> BiasLayoutLaunch<'a, R> {
+    pub fn from_handle(handle: &TensorHandleRef<'a, R>, line_size: u8) -> Self {
+        let rank = handle.shape.len();
+        BiasLayoutLaunch::new(
+            ScalarArg::new(handle.shape[rank - 1] as u32),
+            line_size as u32,
+        )
+    }
+}
+
+/// Layout for the scales of an operand, broadcast to all batches of the output.
+///
+/// Channel scales are indexed by the row of the output for lhs, and by the column for rhs.
+/// Scales read per row or per tensor must use a line size of 1.
+#[derive(CubeType, CubeLaunch)]
+pub struct OperandScaleLayout {
+    shape: u32,
+    #[cube(comptime)]
+    granularity: ScaleGranularity,
+    #[cube(comptime)]
+    per_row: bool,
+    #[cube(comptime)]
+    line_size: u32,
+}
+
+#[cube]
+impl Layout for OperandScaleLayout {
+    type Coordinates = Coords3d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, pos: Self::Coordinates) -> Self::SourceCoordinates {
+        let (_, m, n) = pos;
+        match self.granularity {
+            ScaleGranularity::Tensor => 0u32.runtime(),
+            ScaleGranularity::Channel => {
+                if comptime![self.per_row] {
+                    m
+                } else {
+                    n / self.line_size
+                }
+            }
+        }
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        let (_, m, n) = pos;
+        match self.granularity {
+            ScaleGranularity::Tensor => true.runtime(),
+            ScaleGranularity::Channel => {
+                if comptime![self.per_row] {
+                    m < self.shape
+                } else {
+                    n < self.shape
+                }
+            }
+        }
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        (u32::MAX.runtime(), u32::MAX.runtime(), u32::MAX.runtime())
+    }
+
+    fn to_source_pos_checked(&self, pos: Self::Coordinates) -> (Self::SourceCoordinates, bool) {
+        (self.to_source_pos(pos), self.is_in_bounds(pos))
+    }
+}
+
+impl<'a, R: Runtime> OperandScaleLayoutLaunch<'a, R> {
+    /// Layout of the scales of lhs (`per_row`) or rhs, read with `line_size`
+    pub fn from_handle(
+        handle: &TensorHandleRef<'a, R>,
+        granularity: ScaleGranularity,
+        per_row: bool,
+        line_size: u8,
+    ) -> Self {
+        let len = handle.shape.iter().product::<usize>();
+        OperandScaleLayoutLaunch::new(
+            ScalarArg::new(len as u32),
+            granularity,
+            per_row,
+            line_size as u32,
+        )
+    }
+}
+
 impl<'a, R: Runtime> BatchLayoutLaunch<'a, R> {
+    /// Maps the batches of the output to the batches of `handle`, with NumPy broadcasting.
+    ///
+    /// Batch dims are aligned from the right, and dims that are missing from `handle` or have a
+    /// size of one are broadcast with a stride of zero. Other batch strides are used as is, so
+    /// batch dims may be permuted or already broadcast with a zero stride.
     pub fn from_handle(
         client: &ComputeClient<R>,
         handle: &TensorHandleRef<'a, R>,
         problem: &MatmulProblem,
     ) -> Self {
-        let rank = handle.shape.len();
+        let num_batch_dims = handle.shape.len() - 2;
+        let num_out_batch_dims = problem.out_batches.len();
         let batch_shape = problem
             .out_batches
             .iter()
             .map(|shape| FastDivmodArgs::new(client, *shape as u32))
             .collect();
-        let batch_strides = handle.strides[..rank - 2]
-            .iter()
-            .zip(&handle.shape[..rank - 2])
-            .map(|(stride, shape)| if *shape == 1 { 0 } else { *stride })
+        let batch_strides = (0..num_out_batch_dims)
+            .map(
+                |out_dim| match (out_dim + num_batch_dims).checked_sub(num_out_batch_dims) {
+                    Some(dim) if handle.shape[dim] != 1 => handle.strides[dim],
+                    _ => 0,
+                },
+            )
             .map(|stride| ScalarArg::new(stride as u32))
             .collect();
         BatchLayoutLaunch::new(batch_shape, batch_strides)
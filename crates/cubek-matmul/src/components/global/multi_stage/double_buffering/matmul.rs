@@ -4,7 +4,9 @@ use crate::components::global::multi_stage::double_buffer_execution::{
 use crate::components::global::read::{
     PartialLoadingStrategy, PartialStageGlobalReader, StageBuffer, ZeroGlobalReader,
 };
-use crate::components::global::{GlobalMatmul, GlobalWriter, SharedGlobalMatmulConfig};
+use crate::components::global::{
+    EpilogueInputs, GlobalMatmul, GlobalWriter, SharedGlobalMatmulConfig,
+};
 use crate::components::global::{Specializer, read::SyncStrategy};
 use crate::components::stage;
 use crate::components::stage::{FilledStage, StridedStageMemory};
@@ -65,6 +67,7 @@ where
 
     type GlobalWriter = GW;
     type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = ();
 
     fn execute(
         mut lhs_reader: Self::LhsGlobalReader,
@@ -245,9 +248,10 @@ where
 
     fn init_global_writer(
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter {
-        Self::GlobalWriter::init(out, config.writer_config)
+        Self::GlobalWriter::init(out, epilogue, config.writer_config)
     }
 
     fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
@@ -3,7 +3,7 @@ use crate::components::global::read::{
     FullLoadingStrategy, FullStageGlobalReader, PartialLoadingStrategy, PartialStageGlobalReader,
     StageBuffer, ZeroGlobalReader,
 };
-use crate::components::global::{self, GlobalWriter, SharedGlobalMatmulConfig};
+use crate::components::global::{self, EpilogueInputs, GlobalWriter, SharedGlobalMatmulConfig};
 use crate::components::global::{Specializer, read::sync::Synchronous};
 use crate::components::stage::StageConfig as _;
 use crate::components::stage::StridedStageFamily;
@@ -66,6 +66,7 @@ where
     type AccGlobalReader = ZeroGlobalReader<MP::Acc>;
     type GlobalWriter = GW;
     type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = ();
 
     fn execute(
         mut lhs_reader: Self::LhsGlobalReader,
@@ -247,9 +248,10 @@ where
 
     fn init_global_writer(
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter {
-        Self::GlobalWriter::init(out, config.writer_config)
+        Self::GlobalWriter::init(out, epilogue, config.writer_config)
     }
 
     fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
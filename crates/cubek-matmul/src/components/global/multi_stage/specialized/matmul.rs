@@ -1,6 +1,6 @@
 use crate::components::global::read::LoaderStage;
 use crate::components::global::read::{PartialStageGlobalReader, StageBuffer, ZeroGlobalReader};
-use crate::components::global::{GlobalConfig, GlobalWriter};
+use crate::components::global::{EpilogueInputs, GlobalConfig, GlobalWriter};
 use crate::components::global::{GlobalMatmul, SharedGlobalMatmulConfig};
 use crate::components::global::{RoleRule, read::AsyncPartialLoadingStrategy};
 use crate::components::stage;
@@ -62,6 +62,7 @@ where
 
     type GlobalWriter = GW;
     type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = ();
 
     fn execute(
         mut lhs_reader: Self::LhsGlobalReader,
@@ -239,9 +240,10 @@ where
 
     fn init_global_writer(
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter {
-        Self::GlobalWriter::init(out, config.writer_config)
+        Self::GlobalWriter::init(out, epilogue, config.writer_config)
     }
 
     fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
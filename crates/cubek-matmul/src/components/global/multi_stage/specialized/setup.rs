@@ -95,7 +95,8 @@ where
         let stage_shape_n = stage_config.elements_in_stage_n();
         let stage_shape_k = stage_config.elements_in_stage_k();
 
-        let check_m_bounds = !(problem.m as u32).is_multiple_of(stage_shape_m);
+        let check_m_bounds =
+            problem.num_groups.is_some() || !(problem.m as u32).is_multiple_of(stage_shape_m);
         let check_n_bounds = !(problem.n as u32).is_multiple_of(stage_shape_n);
         let check_k_bounds = !(problem.k as u32).is_multiple_of(2 * stage_shape_k);
 
@@ -157,6 +158,7 @@ where
             smem_config: stage_config.out_smem_config(),
             role_rule_config: plane_role_config.rule,
             plane_dim: selection.plane_dim,
+            epilogue: problem.epilogue,
         };
 
         let config = SharedGlobalMatmulConfig {
@@ -0,0 +1,181 @@
+use crate::components::global::GlobalReaderConfig;
+use crate::components::global::RoleRule;
+use crate::components::global::memory::{GlobalIterator, ViewDirection};
+use crate::components::global::read::{FullLoadingStrategy, FullStageGlobalReader, SyncBarrier};
+use crate::components::stage::{BlockScaledStageMemory, num_scales};
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption, CubeOptionExpand,
+    tensor::{View, layout::Coords2d},
+};
+use cubecl_common::quant::scheme::QuantParam;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// Encoding of the scales of a block quantized operand
+pub enum ScaleEncoding {
+    /// Regular float, converted with a cast
+    Float,
+    /// Unsigned power of two with a bias of 127, as used by MX formats
+    Ue8m0,
+    /// Unsigned float with 4 exponent and 3 mantissa bits, as used by NVFP4
+    Ue4m3,
+}
+
+impl ScaleEncoding {
+    pub fn from_param(param: QuantParam) -> Self {
+        match param {
+            QuantParam::UE8M0 => ScaleEncoding::Ue8m0,
+            QuantParam::UE4M3 => ScaleEncoding::Ue4m3,
+            _ => ScaleEncoding::Float,
+        }
+    }
+}
+
+#[derive(Clone, CubeType)]
+/// Loads the entire stage memory of block quantized values, along with the scales of the blocks
+/// overlapping the stage.
+///
+/// Values are loaded as is by a [full stage reader](FullStageGlobalReader), scales are decoded to
+/// `f32` when staged and applied by the tile matmul when filling its fragments. Without scales,
+/// all blocks are scaled by one.
+pub struct BlockScaledGlobalReader<EG: Numeric, ES: Numeric, SC: Numeric, L: FullLoadingStrategy> {
+    data_reader: FullStageGlobalReader<EG, ES, L>,
+    scales_iter: CubeOption<GlobalIterator<Line<SC>>>,
+    stage: BlockScaledStageMemory<ES, L::TilingLayout>,
+    #[cube(comptime)]
+    encoding: ScaleEncoding,
+}
+
+#[cube]
+impl<EG: Numeric, ES: Numeric, SC: Numeric, L: FullLoadingStrategy>
+    BlockScaledGlobalReader<EG, ES, SC, L>
+{
+    /// Create a new BlockScaledGlobalReader
+    ///
+    /// `scales` is a view of the same shape as the values, returning the scale of each value
+    /// encoded with `encoding`.
+    pub fn new(
+        view: View<Line<EG>, Coords2d>,
+        scales: CubeOption<View<Line<SC>, Coords2d>>,
+        k_step: u32,
+        #[comptime] block_rows: u32,
+        #[comptime] encoding: ScaleEncoding,
+        #[comptime] config: GlobalReaderConfig,
+    ) -> Self {
+        let data_reader = FullStageGlobalReader::<EG, ES, L>::new(view, k_step, config);
+        let scales_iter = match scales {
+            CubeOption::Some(scales) => CubeOption::new_Some(GlobalIterator::new(
+                scales,
+                k_step,
+                ViewDirection::Row,
+                true,
+            )),
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let stage =
+            BlockScaledStageMemory::new(data_reader.stage(), block_rows, config.smem_config);
+
+        BlockScaledGlobalReader::<EG, ES, SC, L> {
+            data_reader,
+            scales_iter,
+            stage,
+            encoding,
+        }
+    }
+
+    /// Give a reader to the loaded stage memory.
+    pub fn stage(&self) -> BlockScaledStageMemory<ES, L::TilingLayout> {
+        self.stage
+    }
+
+    pub fn clear_stage(&mut self, #[comptime] config: GlobalReaderConfig) {
+        self.data_reader.clear_stage(config);
+    }
+
+    pub fn free_stage(self) {
+        unsafe { self.stage.free() };
+    }
+
+    /// Advance the views over values and scales along the k dimension
+    pub fn advance_view(&mut self) {
+        self.data_reader.advance_view();
+        match &self.scales_iter {
+            CubeOption::Some(scales_iter) => scales_iter.advance(),
+            CubeOption::None => {}
+        }
+    }
+
+    /// Load the values and the scales of the current stage
+    pub fn load_stage(
+        &mut self,
+        barrier: &mut SyncBarrier<L::SyncStrategy>,
+        #[comptime] config: GlobalReaderConfig,
+    ) {
+        self.data_reader.load_stage(barrier, config);
+        self.load_scales(config);
+    }
+
+    /// Each scale of the stage is read once from the first row of its block, and decoded to
+    /// `f32`.
+    fn load_scales(&mut self, #[comptime] config: GlobalReaderConfig) {
+        let block_rows = comptime![self.stage.block_rows];
+        let encoding = comptime![self.encoding];
+        let num_cols = comptime![config.smem_config.elements_per_stage_along_col()];
+        let num_scales = comptime![num_scales(block_rows, config.smem_config)];
+        let unit_count = config.loading_units_count();
+        let num_reads_per_unit = comptime![num_scales.div_ceil(unit_count)];
+
+        let unit_base_position = RoleRule::new(config.plane_role_config.rule)
+            .load_index(config.specialization_tensor_config)
+            * config.plane_dim
+            + UNIT_POS_X;
+
+        for i in 0..num_reads_per_unit {
+            let index = unit_base_position + i * unit_count;
+
+            if index < num_scales {
+                let row = index / num_cols;
+                let col = index % num_cols;
+
+                let scale = match &self.scales_iter {
+                    CubeOption::Some(scales_iter) => {
+                        let line = scales_iter.view().read_checked((row * block_rows, col));
+                        decode_scale::<SC>(line[0], encoding)
+                    }
+                    CubeOption::None => f32::new(1.0),
+                };
+                self.stage.scales[index] = scale;
+            }
+        }
+    }
+}
+
+/// Decodes a scale encoded with `encoding`. Byte encoded scales are read as `u8`.
+#[cube]
+fn decode_scale<SC: Numeric>(scale: SC, #[comptime] encoding: ScaleEncoding) -> f32 {
+    match encoding {
+        ScaleEncoding::Float => f32::cast_from(scale),
+        ScaleEncoding::Ue8m0 => decode_ue8m0(u32::cast_from(scale)),
+        ScaleEncoding::Ue4m3 => decode_ue4m3(u32::cast_from(scale)),
+    }
+}
+
+/// `2^(bits - 127)`
+#[cube]
+fn decode_ue8m0(bits: u32) -> f32 {
+    Powf::powf(f32::new(2.0), f32::cast_from(bits) - f32::new(127.0))
+}
+
+/// `(1 + m / 8) * 2^(e - 7)`, or `m / 8 * 2^-6` for subnormals
+#[cube]
+fn decode_ue4m3(bits: u32) -> f32 {
+    let exponent = (bits >> 3) & 0xF;
+    let mantissa = f32::cast_from(bits & 0x7) / f32::new(8.0);
+
+    let mut scale = mantissa * Powf::powf(f32::new(2.0), f32::new(-6.0));
+    if exponent > 0 {
+        scale = (f32::new(1.0) + mantissa)
+            * Powf::powf(f32::new(2.0), f32::cast_from(exponent) - f32::new(7.0));
+    }
+    scale
+}
@@ -1,9 +1,13 @@
+mod block_scaled_reader;
 mod fill_reader;
 mod full_reader;
 mod partial_reader;
 mod shared;
+mod sparse_24_reader;
 
+pub use block_scaled_reader::*;
 pub use fill_reader::*;
 pub use full_reader::*;
 pub use partial_reader::*;
 pub use shared::*;
+pub use sparse_24_reader::*;
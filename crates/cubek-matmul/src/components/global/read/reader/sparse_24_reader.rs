@@ -0,0 +1,114 @@
+use crate::components::global::GlobalReaderConfig;
+use crate::components::global::RoleRule;
+use crate::components::global::memory::{GlobalIterator, ViewDirection};
+use crate::components::global::read::{FullLoadingStrategy, FullStageGlobalReader, SyncBarrier};
+use crate::components::stage::{
+    Sparse24StageMemory, sparse_24_num_metadata, sparse_24_values_config,
+};
+use cubecl::prelude::*;
+use cubecl::std::tensor::{View, layout::Coords2d};
+
+/// Config of the reader loading the kept values of a 2:4 sparse stage, from the config of the
+/// dense stage.
+pub fn sparse_24_values_reader_config(config: GlobalReaderConfig) -> GlobalReaderConfig {
+    GlobalReaderConfig {
+        smem_config: sparse_24_values_config(config.smem_config),
+        ..config
+    }
+}
+
+#[derive(Clone, CubeType)]
+/// Loads the entire stage memory of a 2:4 sparse operand, sparse along its rows.
+///
+/// The kept values are loaded as is by a [full stage reader](FullStageGlobalReader) over a stage
+/// with half the rows, the metadata is loaded along with them and used by the tile matmul to
+/// expand the values when filling its fragments.
+pub struct Sparse24GlobalReader<EG: Numeric, ES: Numeric, L: FullLoadingStrategy> {
+    values_reader: FullStageGlobalReader<EG, ES, L>,
+    metadata_iter: GlobalIterator<Line<u32>>,
+    stage: Sparse24StageMemory<ES, L::TilingLayout>,
+}
+
+#[cube]
+impl<EG: Numeric, ES: Numeric, L: FullLoadingStrategy> Sparse24GlobalReader<EG, ES, L> {
+    /// Create a new Sparse24GlobalReader
+    ///
+    /// `values` has half the rows of the dense operand and `metadata` a quarter of them.
+    /// `k_step` and `config` are those of the dense operand.
+    pub fn new(
+        values: View<Line<EG>, Coords2d>,
+        metadata: View<Line<u32>, Coords2d>,
+        k_step: u32,
+        #[comptime] config: GlobalReaderConfig,
+    ) -> Self {
+        let values_reader = FullStageGlobalReader::<EG, ES, L>::new(
+            values,
+            k_step / 2,
+            comptime!(sparse_24_values_reader_config(config)),
+        );
+        let metadata_iter = GlobalIterator::new(metadata, k_step / 4, ViewDirection::Row, true);
+        let stage = Sparse24StageMemory::new(values_reader.stage(), config.smem_config);
+
+        Sparse24GlobalReader::<EG, ES, L> {
+            values_reader,
+            metadata_iter,
+            stage,
+        }
+    }
+
+    /// Give a reader to the loaded stage memory.
+    pub fn stage(&self) -> Sparse24StageMemory<ES, L::TilingLayout> {
+        self.stage
+    }
+
+    pub fn clear_stage(&mut self, #[comptime] config: GlobalReaderConfig) {
+        self.values_reader
+            .clear_stage(comptime!(sparse_24_values_reader_config(config)));
+    }
+
+    pub fn free_stage(self) {
+        unsafe { self.stage.free() };
+    }
+
+    /// Advance the views over values and metadata along the k dimension
+    pub fn advance_view(&mut self) {
+        self.values_reader.advance_view();
+        self.metadata_iter.advance();
+    }
+
+    /// Load the values and the metadata of the current stage
+    pub fn load_stage(
+        &mut self,
+        barrier: &mut SyncBarrier<L::SyncStrategy>,
+        #[comptime] config: GlobalReaderConfig,
+    ) {
+        self.values_reader
+            .load_stage(barrier, comptime!(sparse_24_values_reader_config(config)));
+        self.load_metadata(config);
+    }
+
+    /// Out of bounds metadata reads as zero, pointing to values zeroed by the values reader.
+    fn load_metadata(&mut self, #[comptime] config: GlobalReaderConfig) {
+        let num_cols = comptime![config.smem_config.elements_per_stage_along_col()];
+        let num_metadata = comptime![sparse_24_num_metadata(config.smem_config)];
+        let unit_count = config.loading_units_count();
+        let num_reads_per_unit = comptime![num_metadata.div_ceil(unit_count)];
+
+        let unit_base_position = RoleRule::new(config.plane_role_config.rule)
+            .load_index(config.specialization_tensor_config)
+            * config.plane_dim
+            + UNIT_POS_X;
+
+        for i in 0..num_reads_per_unit {
+            let index = unit_base_position + i * unit_count;
+
+            if index < num_metadata {
+                let row = index / num_cols;
+                let col = index % num_cols;
+
+                let line = self.metadata_iter.view().read_checked((row, col));
+                self.stage.metadata[index] = line[0];
+            }
+        }
+    }
+}
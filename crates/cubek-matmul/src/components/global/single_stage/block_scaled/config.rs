@@ -0,0 +1,47 @@
+use cubecl::prelude::*;
+
+use crate::components::global::{
+    GlobalConfig, GlobalReaderConfig, GlobalWriterConfig, SharedGlobalMatmulConfig,
+};
+use crate::components::stage::StageConfig;
+use crate::definition::MatmulLineSizes;
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Configuration of the [block scaled matmul](super::BlockScaledMatmul)
+pub struct BlockScaledMatmulConfig<S: StageConfig> {
+    pub shared: SharedGlobalMatmulConfig<S>,
+    /// Number of consecutive rows of rhs (along k) sharing the same scale
+    pub block_rows: u32,
+}
+
+impl<S: StageConfig> GlobalConfig for BlockScaledMatmulConfig<S> {
+    type StageConfig = S;
+
+    fn stage_config(&self) -> Self::StageConfig {
+        self.shared.stage_config()
+    }
+
+    fn lhs_reader_config(&self) -> GlobalReaderConfig {
+        self.shared.lhs_reader_config()
+    }
+
+    fn rhs_reader_config(&self) -> GlobalReaderConfig {
+        self.shared.rhs_reader_config()
+    }
+
+    fn writer_config(&self) -> GlobalWriterConfig {
+        self.shared.writer_config()
+    }
+
+    fn cube_dim(&self) -> CubeDim {
+        self.shared.cube_dim()
+    }
+
+    fn global_line_sizes(&self) -> MatmulLineSizes {
+        self.shared.global_line_sizes()
+    }
+
+    fn must_sync_plane_after_execution(&self) -> bool {
+        self.shared.must_sync_plane_after_execution()
+    }
+}
@@ -0,0 +1,230 @@
+use crate::components::{
+    global::{
+        EpilogueInputs, GlobalMatmul, GlobalWriter,
+        read::{
+            BlockScaledGlobalReader, FullLoadingStrategy, FullStageGlobalReader, ScaleEncoding,
+            SyncStrategy, ZeroGlobalReader,
+        },
+        single_stage::block_scaled::BlockScaledMatmulConfig,
+    },
+    stage::{BlockScaledStageMemory, FilledStage, StageConfig, StageMatmul, StridedStageMemory},
+};
+use crate::definition::{AccG, AccS, LhsG, LhsS, MatmulPrecision, MatrixPrecision, RhsG, RhsS};
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption,
+    tensor::{View, layout::Coords2d},
+};
+use std::marker::PhantomData;
+
+#[cube]
+/// A [global matmul](GlobalMatmul) whose rhs holds block quantized values, with scales of type
+/// `SC` shared by blocks of consecutive rows along k.
+pub trait BlockScaledGlobalMatmul<MP: MatmulPrecision, SC: Numeric>: GlobalMatmul<MP> {
+    /// Initialize the global reader for Rhs, along with the view returning the scale of each
+    /// value of Rhs, encoded with `encoding`
+    fn init_rhs_block_scaled_reader(
+        rhs: View<Line<RhsG<MP>>, Coords2d>,
+        scales: View<Line<SC>, Coords2d>,
+        #[comptime] encoding: ScaleEncoding,
+        #[comptime] config: Self::Config,
+    ) -> Self::RhsGlobalReader;
+}
+
+/// Performs matrix multiplication at the global level, with block quantized rhs.
+///
+/// Same flow as the [simple matmul](crate::components::global::single_stage::simple::SimpleMatmulFamily),
+/// except that the scales of the rhs blocks are staged along with the rhs values and applied by
+/// the tile matmul.
+pub struct BlockScaledMatmul<
+    MP: MatmulPrecision,
+    SMM: StageMatmul<MP>,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy,
+    GW: GlobalWriter<MP::Acc>,
+    SC: Numeric,
+> {
+    _phantom: PhantomData<(MP, SMM, LL, RL, GW, SC)>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, SMM, LL, RL, GW, SC: Numeric> GlobalMatmul<MP>
+    for BlockScaledMatmul<MP, SMM, LL, RL, GW, SC>
+where
+    SMM: StageMatmul<
+            MP,
+            LhsStage = StridedStageMemory<LhsS<MP>, LL::TilingLayout>,
+            RhsStage = BlockScaledStageMemory<RhsS<MP>, RL::TilingLayout>,
+            AccStage = FilledStage<AccS<MP>>,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriter<MP::Acc>,
+{
+    type Config = BlockScaledMatmulConfig<SMM::Config>;
+    type LhsGlobalReader = FullStageGlobalReader<
+        <MP::Lhs as MatrixPrecision>::Global,
+        <MP::Lhs as MatrixPrecision>::Stage,
+        LL,
+    >;
+    type RhsGlobalReader = BlockScaledGlobalReader<
+        <MP::Rhs as MatrixPrecision>::Global,
+        <MP::Rhs as MatrixPrecision>::Stage,
+        SC,
+        RL,
+    >;
+    type AccGlobalReader = ZeroGlobalReader<MP::Acc>;
+    type GlobalWriter = GW;
+    type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = ();
+
+    fn execute(
+        mut lhs_reader: Self::LhsGlobalReader,
+        mut rhs_reader: Self::RhsGlobalReader,
+        acc_reader: Self::AccGlobalReader,
+        mut out_writer: Self::GlobalWriter,
+        k_range: (u32, u32),
+        #[comptime] config: Self::Config,
+    ) {
+        let shared = config.shared;
+        let k_step = shared.stage_config.elements_in_stage_k();
+        let range = k_range.1 - k_range.0;
+        let num_loops = range.div_ceil(k_step);
+
+        let mut acc = SMM::init_accumulators(shared.stage_config);
+
+        let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(shared.stage_config);
+        let partition_scheduler = SMM::init_scheduler(shared.stage_config);
+
+        SMM::load_accumulators(&acc_reader.stage(), &mut acc, shared.stage_config);
+
+        let lhs_stage = &lhs_reader.stage();
+        let rhs_stage = &rhs_reader.stage();
+
+        let mut barrier = LL::SyncStrategy::create_barrier();
+
+        for i in 0..num_loops {
+            sync_cube();
+
+            #[allow(clippy::collapsible_if)]
+            if comptime![(LL::SHOULD_CLEAR || RL::SHOULD_CLEAR) && shared.check_k_bounds()] {
+                if i == num_loops - 1 {
+                    lhs_reader.clear_stage(shared.lhs_reader_config);
+                    rhs_reader.clear_stage(shared.rhs_reader_config);
+                }
+            }
+
+            lhs_reader.load_stage(&mut barrier, shared.lhs_reader_config);
+            rhs_reader.load_stage(&mut barrier, shared.rhs_reader_config);
+
+            LL::SyncStrategy::sync::<MP, _>(&mut barrier, shared);
+
+            SMM::execute(
+                lhs_stage,
+                rhs_stage,
+                &mut lhs_tile,
+                &mut rhs_tile,
+                &mut acc,
+                shared.stage_config,
+                &partition_scheduler,
+            );
+
+            lhs_reader.advance_view();
+            rhs_reader.advance_view();
+        }
+
+        // Frees input stages for reuse, see `SimpleMatmul`
+        sync_cube();
+        lhs_reader.free_stage();
+        rhs_reader.free_stage();
+
+        let mut out_stage = Self::GlobalWriter::stage(&out_writer);
+
+        SMM::write_results::<Self::GlobalWriter>(
+            &acc,
+            &mut out_stage,
+            &mut out_writer,
+            &partition_scheduler,
+            shared.stage_config,
+        );
+    }
+
+    fn init_lhs_global_reader(
+        lhs: View<Line<LhsG<MP>>, Coords2d>,
+        #[comptime] config: Self::Config,
+    ) -> Self::LhsGlobalReader {
+        Self::LhsGlobalReader::new(
+            lhs,
+            config.shared.stage_config.elements_in_stage_k(),
+            config.shared.lhs_reader_config,
+        )
+    }
+
+    /// Without scales, rhs is read as is
+    fn init_rhs_global_reader(
+        rhs: View<Line<RhsG<MP>>, Coords2d>,
+        #[comptime] config: Self::Config,
+    ) -> Self::RhsGlobalReader {
+        Self::RhsGlobalReader::new(
+            rhs,
+            CubeOption::new_None(),
+            config.shared.stage_config.elements_in_stage_k(),
+            config.block_rows,
+            ScaleEncoding::Float,
+            config.shared.rhs_reader_config,
+        )
+    }
+
+    /// An accumulator is only given with `c`, which is rejected when expanding the config
+    fn init_acc_global_reader(
+        _acc: CubeOption<View<Line<AccG<MP>>, Coords2d>>,
+        #[comptime] _config: Self::Config,
+    ) -> Self::AccGlobalReader {
+        ZeroGlobalReader::new()
+    }
+
+    fn init_global_writer(
+        out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
+        #[comptime] config: Self::Config,
+    ) -> Self::GlobalWriter {
+        Self::GlobalWriter::init(out, epilogue, config.shared.writer_config)
+    }
+
+    fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
+        SMM::init_accumulators(config.shared.stage_config)
+    }
+}
+
+#[cube]
+impl<MP: MatmulPrecision, SMM, LL, RL, GW, SC: Numeric> BlockScaledGlobalMatmul<MP, SC>
+    for BlockScaledMatmul<MP, SMM, LL, RL, GW, SC>
+where
+    SMM: StageMatmul<
+            MP,
+            LhsStage = StridedStageMemory<LhsS<MP>, LL::TilingLayout>,
+            RhsStage = BlockScaledStageMemory<RhsS<MP>, RL::TilingLayout>,
+            AccStage = FilledStage<AccS<MP>>,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriter<MP::Acc>,
+{
+    fn init_rhs_block_scaled_reader(
+        rhs: View<Line<RhsG<MP>>, Coords2d>,
+        scales: View<Line<SC>, Coords2d>,
+        #[comptime] encoding: ScaleEncoding,
+        #[comptime] config: Self::Config,
+    ) -> Self::RhsGlobalReader {
+        Self::RhsGlobalReader::new(
+            rhs,
+            CubeOption::new_Some(scales),
+            config.shared.stage_config.elements_in_stage_k(),
+            config.block_rows,
+            encoding,
+            config.shared.rhs_reader_config,
+        )
+    }
+}
@@ -0,0 +1,7 @@
+mod config;
+mod matmul;
+mod setup;
+
+pub use config::BlockScaledMatmulConfig;
+pub use matmul::{BlockScaledGlobalMatmul, BlockScaledMatmul};
+pub use setup::{BlockScaledGlobalMatmulFamily, BlockScaledMatmulFamily};
@@ -0,0 +1,119 @@
+use crate::components::{
+    global::{
+        GlobalMatmulFamily, GlobalWriterFamily, WriteTiling,
+        read::FullLoadingStrategy,
+        single_stage::{
+            block_scaled::{BlockScaledGlobalMatmul, BlockScaledMatmul, BlockScaledMatmulConfig},
+            simple::expand_simple_config,
+        },
+    },
+    stage::{
+        self, BlockScaledStageFamily, FilledStageFamily, NoTilingLayout, StageConfig,
+        StridedStageFamily,
+    },
+};
+use crate::definition::{
+    MatmulElems, MatmulLineSizes, MatmulPrecision, MatmulProblem, MatmulSetupError, TilingBlueprint,
+};
+use cubecl::prelude::*;
+use std::marker::PhantomData;
+
+/// A [global matmul family](GlobalMatmulFamily) whose matmuls can be given the scales of a block
+/// quantized rhs.
+pub trait BlockScaledGlobalMatmulFamily: GlobalMatmulFamily {
+    /// The matmul of this family, accepting rhs scales of type `SC`
+    type BlockScaledMatmul<MP: MatmulPrecision, SC: Numeric>: BlockScaledGlobalMatmul<MP, SC, Config = Self::Config>;
+}
+
+/// Block scaled matmul family for any precision
+pub struct BlockScaledMatmulFamily<
+    SMM: stage::StageMatmulFamily,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy,
+    GW: GlobalWriterFamily,
+> {
+    _stage_matmul: PhantomData<SMM>,
+    _lhs_loading: PhantomData<LL>,
+    _rhs_loading: PhantomData<RL>,
+    _writer: PhantomData<GW>,
+}
+
+impl<SMM, LL, RL, GW> GlobalMatmulFamily for BlockScaledMatmulFamily<SMM, LL, RL, GW>
+where
+    SMM: stage::StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = BlockScaledStageFamily,
+            AccStage = FilledStageFamily,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriterFamily,
+{
+    /// Without scales, the scale type is unused
+    type Matmul<MP: MatmulPrecision> = BlockScaledMatmul<
+        MP,
+        SMM::Matmul<MP, LL::TilingLayout, RL::TilingLayout, NoTilingLayout, WriteTiling>,
+        LL,
+        RL,
+        GW::Writer<MP::Acc>,
+        f32,
+    >;
+    type Config = BlockScaledMatmulConfig<SMM::Config>;
+
+    fn expand_config<R: Runtime>(
+        client: &ComputeClient<R>,
+        problem: &MatmulProblem,
+        selection: &TilingBlueprint,
+        line_sizes: &MatmulLineSizes,
+        dtypes: &MatmulElems,
+    ) -> Result<Self::Config, MatmulSetupError> {
+        if problem.epilogue.has_c {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Block scaled matmul doesn't support accumulating into an existing output",
+            )));
+        }
+
+        let shared =
+            expand_simple_config::<SMM, LL, RL, R>(client, problem, selection, line_sizes, dtypes)?;
+
+        let stage_k = shared.stage_config.elements_in_stage_k();
+        let block_rows = problem
+            .rhs_scale_block_k
+            .map(|block_k| block_k as u32)
+            .unwrap_or(stage_k);
+
+        // A stage must either contain whole blocks or be contained in a single block
+        if block_rows == 0
+            || !(stage_k.is_multiple_of(block_rows) || block_rows.is_multiple_of(stage_k))
+        {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Rhs scale blocks of {block_rows} rows don't align with stages of {stage_k} rows"
+            ))));
+        }
+
+        Ok(BlockScaledMatmulConfig { shared, block_rows })
+    }
+}
+
+impl<SMM, LL, RL, GW> BlockScaledGlobalMatmulFamily for BlockScaledMatmulFamily<SMM, LL, RL, GW>
+where
+    SMM: stage::StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = BlockScaledStageFamily,
+            AccStage = FilledStageFamily,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriterFamily,
+{
+    type BlockScaledMatmul<MP: MatmulPrecision, SC: Numeric> = BlockScaledMatmul<
+        MP,
+        SMM::Matmul<MP, LL::TilingLayout, RL::TilingLayout, NoTilingLayout, WriteTiling>,
+        LL,
+        RL,
+        GW::Writer<MP::Acc>,
+        SC,
+    >;
+}
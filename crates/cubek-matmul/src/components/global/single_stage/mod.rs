@@ -1 +1,3 @@
+pub mod block_scaled;
 pub mod simple;
+pub mod sparse_24;
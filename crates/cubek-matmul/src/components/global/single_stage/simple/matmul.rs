@@ -1,6 +1,6 @@
 use crate::components::{
     global::{
-        GlobalMatmul, GlobalWriter, SharedGlobalMatmulConfig,
+        EpilogueInputs, GlobalMatmul, GlobalWriter, SharedGlobalMatmulConfig,
         read::{FullLoadingStrategy, FullStageGlobalReader, SyncStrategy, ZeroGlobalReader},
     },
     stage::StridedStageMemory,
@@ -56,6 +56,7 @@ where
     type AccGlobalReader = ZeroGlobalReader<MP::Acc>;
     type GlobalWriter = GW;
     type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = <LL::SyncStrategy as SyncStrategy>::Barrier;
 
     fn execute(
         mut lhs_reader: Self::LhsGlobalReader,
@@ -167,12 +168,118 @@ where
 
     fn init_global_writer(
         out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
         #[comptime] config: Self::Config,
     ) -> Self::GlobalWriter {
-        Self::GlobalWriter::init(out, config.writer_config)
+        Self::GlobalWriter::init(out, epilogue, config.writer_config)
     }
 
     fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
         SMM::init_accumulators(config.stage_config)
     }
+
+    fn init_prefetch_barrier() -> Self::PrefetchBarrier {
+        LL::SyncStrategy::create_barrier()
+    }
+
+    fn prefetch(
+        lhs_reader: &mut Self::LhsGlobalReader,
+        rhs_reader: &mut Self::RhsGlobalReader,
+        barrier: &mut Self::PrefetchBarrier,
+        k_range: (u32, u32),
+        #[comptime] config: Self::Config,
+    ) {
+        let k_step = config.stage_config.elements_in_stage_k();
+        let num_loops = (k_range.1 - k_range.0).div_ceil(k_step);
+
+        // Same as the first iteration of `execute`
+        if num_loops > 0 {
+            #[allow(clippy::collapsible_if)]
+            if comptime![(LL::SHOULD_CLEAR || RL::SHOULD_CLEAR) && config.check_k_bounds()] {
+                if num_loops == 1 {
+                    lhs_reader.clear_stage(config.lhs_reader_config);
+                    rhs_reader.clear_stage(config.rhs_reader_config);
+                }
+            }
+
+            lhs_reader.load_stage(barrier, config.lhs_reader_config);
+            rhs_reader.load_stage(barrier, config.rhs_reader_config);
+        }
+    }
+
+    fn execute_prefetched(
+        mut lhs_reader: Self::LhsGlobalReader,
+        mut rhs_reader: Self::RhsGlobalReader,
+        acc_reader: Self::AccGlobalReader,
+        acc: &mut Self::Accumulators,
+        barrier: &mut Self::PrefetchBarrier,
+        k_range: (u32, u32),
+        #[comptime] config: Self::Config,
+    ) {
+        let k_step = config.stage_config.elements_in_stage_k();
+        let range = k_range.1 - k_range.0;
+        let num_loops = range.div_ceil(k_step);
+
+        let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config);
+        let partition_scheduler = SMM::init_scheduler(config.stage_config);
+
+        SMM::load_accumulators(&acc_reader.stage(), acc, config.stage_config);
+
+        let lhs_stage = &lhs_reader.stage();
+        let rhs_stage = &rhs_reader.stage();
+
+        for i in 0..num_loops {
+            sync_cube();
+
+            // The first stage was loaded by `prefetch`
+            if i > 0 {
+                #[allow(clippy::collapsible_if)]
+                if comptime![(LL::SHOULD_CLEAR || RL::SHOULD_CLEAR) && config.check_k_bounds()] {
+                    if i == num_loops - 1 {
+                        lhs_reader.clear_stage(config.lhs_reader_config);
+                        rhs_reader.clear_stage(config.rhs_reader_config);
+                    }
+                }
+
+                lhs_reader.load_stage(barrier, config.lhs_reader_config);
+                rhs_reader.load_stage(barrier, config.rhs_reader_config);
+            }
+
+            LL::SyncStrategy::sync::<MP, _>(barrier, config);
+
+            SMM::execute(
+                lhs_stage,
+                rhs_stage,
+                &mut lhs_tile,
+                &mut rhs_tile,
+                acc,
+                config.stage_config,
+                &partition_scheduler,
+            );
+
+            lhs_reader.advance_view();
+            rhs_reader.advance_view();
+        }
+
+        // Other planes must be done reading the stages before the next prefetch overwrites them.
+        // They aren't freed, so the output stage doesn't alias them.
+        sync_cube();
+    }
+
+    fn write_accumulators(
+        acc: &Self::Accumulators,
+        mut writer: Self::GlobalWriter,
+        #[comptime] config: Self::Config,
+    ) {
+        let partition_scheduler = SMM::init_scheduler(config.stage_config);
+        let mut out_stage = Self::GlobalWriter::stage(&writer);
+
+        SMM::write_results::<Self::GlobalWriter>(
+            acc,
+            &mut out_stage,
+            &mut writer,
+            &partition_scheduler,
+            config.stage_config,
+        );
+    }
 }
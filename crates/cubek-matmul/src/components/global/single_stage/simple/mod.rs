@@ -2,3 +2,4 @@ mod matmul;
 mod setup;
 
 pub use setup::SimpleMatmulFamily;
+pub(crate) use setup::expand_simple_config;
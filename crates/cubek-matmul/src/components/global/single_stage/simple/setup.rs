@@ -55,6 +55,8 @@ where
     >;
     type Config = SharedGlobalMatmulConfig<SMM::Config>;
 
+    const CAN_PREFETCH: bool = true;
+
     fn expand_config<R: Runtime>(
         client: &ComputeClient<R>,
         problem: &MatmulProblem,
@@ -62,109 +64,127 @@ where
         line_sizes: &MatmulLineSizes,
         dtypes: &MatmulElems,
     ) -> Result<Self::Config, MatmulSetupError> {
-        let stage_config = SMM::expand_config(
-            client,
-            problem,
-            selection,
-            line_sizes,
-            (1, 1).into(),
-            None,
-            dtypes,
-        )?;
-
-        let stage_shape_m = stage_config.elements_in_stage_m();
-        let stage_shape_n = stage_config.elements_in_stage_n();
-        let stage_shape_k = stage_config.elements_in_stage_k();
-
-        let check_k_bounds = !(problem.k as u32).is_multiple_of(stage_shape_k);
-        let check_m_bounds = !(problem.m as u32).is_multiple_of(stage_shape_m);
-        let check_n_bounds = !(problem.n as u32).is_multiple_of(stage_shape_n);
-
-        let num_planes = if !selection.load_specialization_config.has_specialization() {
-            stage_config.num_main_flow_planes()
-        } else {
-            return Err(MatmulSetupError::InvalidConfig(Box::new(
-                "Error: Specialization is unavailable for simple tma matmul.",
-            )));
-        };
-
-        let plane_role_config = stage_config.plane_role_config();
-        let precompute_job = selection.loading_precompute_strategy.into();
-        let reader_mode = selection.reader_mode;
-        let plane_dim = selection.plane_dim;
-        let specialization_tensor_config = SpecializationTensorConfig::MainFlowOnly;
-
-        // Not used in simple
-        let event_loading_mode = EventLoadingMode::Relaxed;
-
-        let lhs_gmem_config = GlobalMemoryConfig {
-            line_size: line_sizes.lhs as u32,
-            check_row_bounds: check_m_bounds,
-            check_col_bounds: check_k_bounds,
-            matrix_layout: problem.lhs_layout,
-            view_direction: ViewDirection::Col,
-        };
-
-        let rhs_gmem_config = GlobalMemoryConfig {
-            line_size: line_sizes.rhs as u32,
-            check_row_bounds: check_k_bounds,
-            check_col_bounds: check_n_bounds,
-            matrix_layout: problem.rhs_layout,
-            view_direction: ViewDirection::Row,
-        };
-
-        let out_gmem_config = GlobalMemoryConfig {
-            line_size: line_sizes.out as u32,
-            matrix_layout: MatrixLayout::RowMajor,
-            check_row_bounds: check_m_bounds,
-            check_col_bounds: check_n_bounds,
-            view_direction: ViewDirection::None,
-        };
-
-        let lhs_reader_config = GlobalReaderConfig {
-            gmem_config: lhs_gmem_config,
-            smem_config: stage_config.lhs_smem_config(),
-            precompute_job,
-            plane_dim,
-            plane_role_config,
-            reader_mode,
-            stage_ident: StageIdent::Lhs,
-            event_loading_mode,
-            specialization_tensor_config,
-        };
-
-        let rhs_reader_config = GlobalReaderConfig {
-            gmem_config: rhs_gmem_config,
-            smem_config: stage_config.rhs_smem_config(),
-            precompute_job,
-            plane_dim,
-            plane_role_config,
-            reader_mode,
-            stage_ident: StageIdent::Rhs,
-            event_loading_mode,
-            specialization_tensor_config,
-        };
-
-        let writer_config = GlobalWriterConfig {
-            gmem_config: out_gmem_config,
-            smem_config: stage_config.out_smem_config(),
-            role_rule_config: plane_role_config.rule,
-            plane_dim: selection.plane_dim,
-        };
-
-        let config = SharedGlobalMatmulConfig {
-            stage_config,
-            num_planes,
-            lhs_reader_config,
-            rhs_reader_config,
-            writer_config,
-            must_sync_plane_after_execution: false,
-        };
-
-        validate::<LL, RL, SMM::Config, R>(config, client, problem, dtypes)
+        expand_simple_config::<SMM, LL, RL, R>(client, problem, selection, line_sizes, dtypes)
     }
 }
 
+/// Expands the config of a single stage global matmul that fully loads its stages.
+pub(crate) fn expand_simple_config<
+    SMM: stage::StageMatmulFamily,
+    LL: LoadingValidation,
+    RL: LoadingValidation,
+    R: Runtime,
+>(
+    client: &ComputeClient<R>,
+    problem: &MatmulProblem,
+    selection: &TilingBlueprint,
+    line_sizes: &MatmulLineSizes,
+    dtypes: &MatmulElems,
+) -> Result<SharedGlobalMatmulConfig<SMM::Config>, MatmulSetupError> {
+    let stage_config = SMM::expand_config(
+        client,
+        problem,
+        selection,
+        line_sizes,
+        (1, 1).into(),
+        None,
+        dtypes,
+    )?;
+
+    let stage_shape_m = stage_config.elements_in_stage_m();
+    let stage_shape_n = stage_config.elements_in_stage_n();
+    let stage_shape_k = stage_config.elements_in_stage_k();
+
+    let check_k_bounds = !(problem.k as u32).is_multiple_of(stage_shape_k);
+    let check_m_bounds =
+        problem.num_groups.is_some() || !(problem.m as u32).is_multiple_of(stage_shape_m);
+    let check_n_bounds = !(problem.n as u32).is_multiple_of(stage_shape_n);
+
+    let num_planes = if !selection.load_specialization_config.has_specialization() {
+        stage_config.num_main_flow_planes()
+    } else {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Error: Specialization is unavailable for simple tma matmul.",
+        )));
+    };
+
+    let plane_role_config = stage_config.plane_role_config();
+    let precompute_job = selection.loading_precompute_strategy.into();
+    let reader_mode = selection.reader_mode;
+    let plane_dim = selection.plane_dim;
+    let specialization_tensor_config = SpecializationTensorConfig::MainFlowOnly;
+
+    // Not used in simple
+    let event_loading_mode = EventLoadingMode::Relaxed;
+
+    let lhs_gmem_config = GlobalMemoryConfig {
+        line_size: line_sizes.lhs as u32,
+        check_row_bounds: check_m_bounds,
+        check_col_bounds: check_k_bounds,
+        matrix_layout: problem.lhs_layout,
+        view_direction: ViewDirection::Col,
+    };
+
+    let rhs_gmem_config = GlobalMemoryConfig {
+        line_size: line_sizes.rhs as u32,
+        check_row_bounds: check_k_bounds,
+        check_col_bounds: check_n_bounds,
+        matrix_layout: problem.rhs_layout,
+        view_direction: ViewDirection::Row,
+    };
+
+    let out_gmem_config = GlobalMemoryConfig {
+        line_size: line_sizes.out as u32,
+        matrix_layout: MatrixLayout::RowMajor,
+        check_row_bounds: check_m_bounds,
+        check_col_bounds: check_n_bounds,
+        view_direction: ViewDirection::None,
+    };
+
+    let lhs_reader_config = GlobalReaderConfig {
+        gmem_config: lhs_gmem_config,
+        smem_config: stage_config.lhs_smem_config(),
+        precompute_job,
+        plane_dim,
+        plane_role_config,
+        reader_mode,
+        stage_ident: StageIdent::Lhs,
+        event_loading_mode,
+        specialization_tensor_config,
+    };
+
+    let rhs_reader_config = GlobalReaderConfig {
+        gmem_config: rhs_gmem_config,
+        smem_config: stage_config.rhs_smem_config(),
+        precompute_job,
+        plane_dim,
+        plane_role_config,
+        reader_mode,
+        stage_ident: StageIdent::Rhs,
+        event_loading_mode,
+        specialization_tensor_config,
+    };
+
+    let writer_config = GlobalWriterConfig {
+        gmem_config: out_gmem_config,
+        smem_config: stage_config.out_smem_config(),
+        role_rule_config: plane_role_config.rule,
+        plane_dim: selection.plane_dim,
+        epilogue: problem.epilogue,
+    };
+
+    let config = SharedGlobalMatmulConfig {
+        stage_config,
+        num_planes,
+        lhs_reader_config,
+        rhs_reader_config,
+        writer_config,
+        must_sync_plane_after_execution: false,
+    };
+
+    validate::<LL, RL, SMM::Config, R>(config, client, problem, dtypes)
+}
+
 fn validate<LL: LoadingValidation, RL: LoadingValidation, S: StageConfig, R: Runtime>(
     config: SharedGlobalMatmulConfig<S>,
     client: &ComputeClient<R>,
@@ -0,0 +1,216 @@
+use crate::components::{
+    global::{
+        EpilogueInputs, GlobalMatmul, GlobalWriter, SharedGlobalMatmulConfig,
+        read::{
+            FullLoadingStrategy, FullStageGlobalReader, Sparse24GlobalReader, SyncStrategy,
+            ZeroGlobalReader,
+        },
+    },
+    stage::{FilledStage, Sparse24StageMemory, StageConfig, StageMatmul, StridedStageMemory},
+};
+use crate::definition::{AccG, AccS, LhsG, LhsS, MatmulPrecision, MatrixPrecision, RhsG, RhsS};
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption, CubeOptionExpand,
+    tensor::{View, layout::Coords2d},
+};
+use std::marker::PhantomData;
+
+#[cube]
+/// A [global matmul](GlobalMatmul) whose rhs is 2:4 sparse along k, with at most two non-zero
+/// values in each group of four consecutive rows.
+pub trait Sparse24GlobalMatmul<MP: MatmulPrecision>: GlobalMatmul<MP> {
+    /// Initialize the global reader for Rhs, from the view of its kept values and the view of
+    /// the positions of these values within their group
+    fn init_rhs_sparse_reader(
+        values: View<Line<RhsG<MP>>, Coords2d>,
+        metadata: View<Line<u32>, Coords2d>,
+        #[comptime] config: Self::Config,
+    ) -> Self::RhsGlobalReader;
+}
+
+/// Performs matrix multiplication at the global level, with 2:4 sparse rhs.
+///
+/// Same flow as the [simple matmul](crate::components::global::single_stage::simple::SimpleMatmulFamily),
+/// except that only the kept values of rhs are staged, along with their positions, and expanded
+/// by the tile matmul.
+pub struct Sparse24Matmul<
+    MP: MatmulPrecision,
+    SMM: StageMatmul<MP>,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy,
+    GW: GlobalWriter<MP::Acc>,
+> {
+    _phantom: PhantomData<(MP, SMM, LL, RL, GW)>,
+}
+
+#[cube]
+impl<MP: MatmulPrecision, SMM, LL, RL, GW> GlobalMatmul<MP> for Sparse24Matmul<MP, SMM, LL, RL, GW>
+where
+    SMM: StageMatmul<
+            MP,
+            LhsStage = StridedStageMemory<LhsS<MP>, LL::TilingLayout>,
+            RhsStage = Sparse24StageMemory<RhsS<MP>, RL::TilingLayout>,
+            AccStage = FilledStage<AccS<MP>>,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriter<MP::Acc>,
+{
+    type Config = SharedGlobalMatmulConfig<SMM::Config>;
+    type LhsGlobalReader = FullStageGlobalReader<
+        <MP::Lhs as MatrixPrecision>::Global,
+        <MP::Lhs as MatrixPrecision>::Stage,
+        LL,
+    >;
+    type RhsGlobalReader = Sparse24GlobalReader<
+        <MP::Rhs as MatrixPrecision>::Global,
+        <MP::Rhs as MatrixPrecision>::Stage,
+        RL,
+    >;
+    type AccGlobalReader = ZeroGlobalReader<MP::Acc>;
+    type GlobalWriter = GW;
+    type Accumulators = SMM::Accumulators;
+    type PrefetchBarrier = ();
+
+    fn execute(
+        mut lhs_reader: Self::LhsGlobalReader,
+        mut rhs_reader: Self::RhsGlobalReader,
+        acc_reader: Self::AccGlobalReader,
+        mut out_writer: Self::GlobalWriter,
+        k_range: (u32, u32),
+        #[comptime] config: Self::Config,
+    ) {
+        let k_step = config.stage_config.elements_in_stage_k();
+        let range = k_range.1 - k_range.0;
+        let num_loops = range.div_ceil(k_step);
+
+        let mut acc = SMM::init_accumulators(config.stage_config);
+
+        let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config);
+        let partition_scheduler = SMM::init_scheduler(config.stage_config);
+
+        SMM::load_accumulators(&acc_reader.stage(), &mut acc, config.stage_config);
+
+        let lhs_stage = &lhs_reader.stage();
+        let rhs_stage = &rhs_reader.stage();
+
+        let mut barrier = LL::SyncStrategy::create_barrier();
+
+        for i in 0..num_loops {
+            sync_cube();
+
+            #[allow(clippy::collapsible_if)]
+            if comptime![(LL::SHOULD_CLEAR || RL::SHOULD_CLEAR) && config.check_k_bounds()] {
+                if i == num_loops - 1 {
+                    lhs_reader.clear_stage(config.lhs_reader_config);
+                    rhs_reader.clear_stage(config.rhs_reader_config);
+                }
+            }
+
+            lhs_reader.load_stage(&mut barrier, config.lhs_reader_config);
+            rhs_reader.load_stage(&mut barrier, config.rhs_reader_config);
+
+            LL::SyncStrategy::sync::<MP, _>(&mut barrier, config);
+
+            SMM::execute(
+                lhs_stage,
+                rhs_stage,
+                &mut lhs_tile,
+                &mut rhs_tile,
+                &mut acc,
+                config.stage_config,
+                &partition_scheduler,
+            );
+
+            lhs_reader.advance_view();
+            rhs_reader.advance_view();
+        }
+
+        // Frees input stages for reuse, see `SimpleMatmul`
+        sync_cube();
+        lhs_reader.free_stage();
+        rhs_reader.free_stage();
+
+        let mut out_stage = Self::GlobalWriter::stage(&out_writer);
+
+        SMM::write_results::<Self::GlobalWriter>(
+            &acc,
+            &mut out_stage,
+            &mut out_writer,
+            &partition_scheduler,
+            config.stage_config,
+        );
+    }
+
+    fn init_lhs_global_reader(
+        lhs: View<Line<LhsG<MP>>, Coords2d>,
+        #[comptime] config: Self::Config,
+    ) -> Self::LhsGlobalReader {
+        Self::LhsGlobalReader::new(
+            lhs,
+            config.stage_config.elements_in_stage_k(),
+            config.lhs_reader_config,
+        )
+    }
+
+    /// Rhs can't be read without its metadata, see `init_rhs_sparse_reader`
+    fn init_rhs_global_reader(
+        _rhs: View<Line<RhsG<MP>>, Coords2d>,
+        #[comptime] _config: Self::Config,
+    ) -> Self::RhsGlobalReader {
+        panic!("Sparse rhs requires its metadata")
+    }
+
+    fn init_acc_global_reader(
+        acc: CubeOption<View<Line<AccG<MP>>, Coords2d>>,
+        #[comptime] _config: Self::Config,
+    ) -> Self::AccGlobalReader {
+        match acc {
+            CubeOption::None => ZeroGlobalReader::new(),
+            CubeOption::Some(_) => panic!("Accumulator loading is not yet supported"),
+        }
+    }
+
+    fn init_global_writer(
+        out: View<Line<AccG<MP>>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<AccG<MP>>,
+        #[comptime] config: Self::Config,
+    ) -> Self::GlobalWriter {
+        Self::GlobalWriter::init(out, epilogue, config.writer_config)
+    }
+
+    fn init_accumulators(#[comptime] config: Self::Config) -> Self::Accumulators {
+        SMM::init_accumulators(config.stage_config)
+    }
+}
+
+#[cube]
+impl<MP: MatmulPrecision, SMM, LL, RL, GW> Sparse24GlobalMatmul<MP>
+    for Sparse24Matmul<MP, SMM, LL, RL, GW>
+where
+    SMM: StageMatmul<
+            MP,
+            LhsStage = StridedStageMemory<LhsS<MP>, LL::TilingLayout>,
+            RhsStage = Sparse24StageMemory<RhsS<MP>, RL::TilingLayout>,
+            AccStage = FilledStage<AccS<MP>>,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriter<MP::Acc>,
+{
+    fn init_rhs_sparse_reader(
+        values: View<Line<RhsG<MP>>, Coords2d>,
+        metadata: View<Line<u32>, Coords2d>,
+        #[comptime] config: Self::Config,
+    ) -> Self::RhsGlobalReader {
+        Self::RhsGlobalReader::new(
+            values,
+            metadata,
+            config.stage_config.elements_in_stage_k(),
+            config.rhs_reader_config,
+        )
+    }
+}
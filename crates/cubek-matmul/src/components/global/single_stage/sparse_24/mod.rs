@@ -0,0 +1,5 @@
+mod matmul;
+mod setup;
+
+pub use matmul::{Sparse24GlobalMatmul, Sparse24Matmul};
+pub use setup::{Sparse24GlobalMatmulFamily, Sparse24MatmulFamily};
@@ -0,0 +1,113 @@
+use crate::components::{
+    global::{
+        GlobalMatmulFamily, GlobalWriterFamily, SharedGlobalMatmulConfig, WriteTiling,
+        read::{FullLoadingStrategy, LoadingValidation, sparse_24_values_reader_config},
+        single_stage::{
+            simple::expand_simple_config,
+            sparse_24::{Sparse24GlobalMatmul, Sparse24Matmul},
+        },
+    },
+    stage::{
+        self, FilledStageFamily, NoTilingLayout, Sparse24StageFamily, StageConfig,
+        StridedStageFamily,
+    },
+};
+use crate::definition::{
+    MatmulElems, MatmulLineSizes, MatmulPrecision, MatmulProblem, MatmulSetupError, MatrixLayout,
+    TilingBlueprint,
+};
+use cubecl::prelude::*;
+use std::marker::PhantomData;
+
+/// A [global matmul family](GlobalMatmulFamily) whose matmuls can be given the metadata of a 2:4
+/// sparse rhs.
+pub trait Sparse24GlobalMatmulFamily: GlobalMatmulFamily {
+    /// The matmul of this family, accepting rhs metadata
+    type Sparse24Matmul<MP: MatmulPrecision>: Sparse24GlobalMatmul<MP, Config = Self::Config>;
+}
+
+/// 2:4 sparse matmul family for any precision
+pub struct Sparse24MatmulFamily<
+    SMM: stage::StageMatmulFamily,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy,
+    GW: GlobalWriterFamily,
+> {
+    _stage_matmul: PhantomData<SMM>,
+    _lhs_loading: PhantomData<LL>,
+    _rhs_loading: PhantomData<RL>,
+    _writer: PhantomData<GW>,
+}
+
+impl<SMM, LL, RL, GW> GlobalMatmulFamily for Sparse24MatmulFamily<SMM, LL, RL, GW>
+where
+    SMM: stage::StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = Sparse24StageFamily,
+            AccStage = FilledStageFamily,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriterFamily,
+{
+    type Matmul<MP: MatmulPrecision> = Sparse24Matmul<
+        MP,
+        SMM::Matmul<MP, LL::TilingLayout, RL::TilingLayout, NoTilingLayout, WriteTiling>,
+        LL,
+        RL,
+        GW::Writer<MP::Acc>,
+    >;
+    type Config = SharedGlobalMatmulConfig<SMM::Config>;
+
+    fn expand_config<R: Runtime>(
+        client: &ComputeClient<R>,
+        problem: &MatmulProblem,
+        selection: &TilingBlueprint,
+        line_sizes: &MatmulLineSizes,
+        dtypes: &MatmulElems,
+    ) -> Result<Self::Config, MatmulSetupError> {
+        let config =
+            expand_simple_config::<SMM, LL, RL, R>(client, problem, selection, line_sizes, dtypes)?;
+
+        if problem.rhs_layout != MatrixLayout::RowMajor {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "2:4 sparse rhs must be row major",
+            )));
+        }
+
+        // Groups of four rows must not straddle tiles
+        let tile_k = config.stage_config.elements_in_tile_k();
+        if !tile_k.is_multiple_of(4) || !(problem.k as u32).is_multiple_of(4) {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "2:4 sparse rhs requires k ({}) and tile k ({tile_k}) to be multiples of 4",
+                problem.k
+            ))));
+        }
+
+        // Rhs values are staged with half the rows
+        RL::check(
+            client,
+            problem,
+            &sparse_24_values_reader_config(config.rhs_reader_config),
+            dtypes,
+        )?;
+
+        Ok(config)
+    }
+}
+
+impl<SMM, LL, RL, GW> Sparse24GlobalMatmulFamily for Sparse24MatmulFamily<SMM, LL, RL, GW>
+where
+    SMM: stage::StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = Sparse24StageFamily,
+            AccStage = FilledStageFamily,
+            OutStage = GW::Stage,
+        >,
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+    GW: GlobalWriterFamily,
+{
+    type Sparse24Matmul<MP: MatmulPrecision> = Self::Matmul<MP>;
+}
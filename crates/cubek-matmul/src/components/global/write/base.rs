@@ -1,9 +1,12 @@
 use crate::{
     components::{
-        global::{RoleRuleConfig, WriteEventListener, WriteTiling, memory::GlobalMemoryConfig},
+        global::{
+            EpilogueInputs, RoleRuleConfig, WriteEventListener, WriteTiling,
+            memory::GlobalMemoryConfig,
+        },
         stage::{Stage, StageFamily, StageMemoryConfig},
     },
-    definition::MatrixPrecision,
+    definition::{EpilogueConfig, MatrixPrecision},
 };
 use cubecl::prelude::*;
 use cubecl::std::tensor::{View, layout::Coords2d};
@@ -25,9 +28,10 @@ pub trait GlobalWriter<IP: MatrixPrecision>:
     /// Tile stage that stores the data for this writer
     type Stage: Stage<IP::Stage, ReadWrite>;
 
-    /// Init this writer from a global tensor and config
+    /// Init this writer from a global tensor, the tensors read by the epilogue and config
     fn init(
         tensor: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<IP::Global>,
         #[comptime] config: GlobalWriterConfig,
     ) -> Self;
 
@@ -41,4 +45,5 @@ pub struct GlobalWriterConfig {
     pub smem_config: StageMemoryConfig,
     pub role_rule_config: RoleRuleConfig,
     pub plane_dim: u32,
+    pub epilogue: EpilogueConfig,
 }
@@ -0,0 +1,234 @@
+use core::f32::consts::FRAC_1_SQRT_2;
+use cubecl::prelude::*;
+use cubecl::std::{
+    CubeOption, CubeOptionExpand,
+    tensor::{View, layout::Coords2d},
+};
+
+use crate::components::{
+    global::read::tiled::{TiledCoords, TiledLayout},
+    stage::StageMemoryConfig,
+};
+use crate::definition::{Activation, EpilogueConfig, ScaleGranularity, StageIdent};
+
+#[derive(CubeType, Clone, Copy)]
+/// Global views read by the epilogue, sliced to the same region as the output view
+pub struct EpilogueInputs<EG: Numeric> {
+    pub c: CubeOption<View<Line<EG>, Coords2d>>,
+    pub bias: CubeOption<View<Line<EG>, Coords2d>>,
+    pub residual: CubeOption<View<Line<EG>, Coords2d>>,
+    pub lhs_scale: CubeOption<View<Line<f32>, Coords2d>>,
+    pub rhs_scale: CubeOption<View<Line<f32>, Coords2d>>,
+    pub alpha: f32,
+    pub beta: f32,
+}
+
+#[cube]
+impl<EG: Numeric> EpilogueInputs<EG> {
+    pub fn new(
+        c: CubeOption<View<Line<EG>, Coords2d>>,
+        bias: CubeOption<View<Line<EG>, Coords2d>>,
+        residual: CubeOption<View<Line<EG>, Coords2d>>,
+        lhs_scale: CubeOption<View<Line<f32>, Coords2d>>,
+        rhs_scale: CubeOption<View<Line<f32>, Coords2d>>,
+        alpha: f32,
+        beta: f32,
+    ) -> Self {
+        EpilogueInputs::<EG> {
+            c,
+            bias,
+            residual,
+            lhs_scale,
+            rhs_scale,
+            alpha,
+            beta,
+        }
+    }
+
+    /// No tensor is read by the epilogue
+    pub fn none() -> Self {
+        EpilogueInputs::<EG> {
+            c: CubeOption::new_None(),
+            bias: CubeOption::new_None(),
+            residual: CubeOption::new_None(),
+            lhs_scale: CubeOption::new_None(),
+            rhs_scale: CubeOption::new_None(),
+            alpha: 1.0f32.runtime(),
+            beta: 0.0f32.runtime(),
+        }
+    }
+}
+
+#[derive(CubeType)]
+/// Applies the [epilogue](EpilogueConfig) to output lines, addressed the same way as the writers
+pub struct EpilogueReader<EG: Numeric> {
+    c: CubeOption<View<Line<EG>, TiledCoords>>,
+    bias: CubeOption<View<Line<EG>, TiledCoords>>,
+    residual: CubeOption<View<Line<EG>, TiledCoords>>,
+    lhs_scale: CubeOption<View<Line<f32>, TiledCoords>>,
+    rhs_scale: CubeOption<View<Line<f32>, TiledCoords>>,
+    alpha: f32,
+    beta: f32,
+
+    #[cube(comptime)]
+    config: EpilogueConfig,
+}
+
+#[cube]
+impl<EG: Numeric> EpilogueReader<EG> {
+    pub fn new(
+        inputs: EpilogueInputs<EG>,
+        #[comptime] smem_config: StageMemoryConfig,
+        #[comptime] config: EpilogueConfig,
+    ) -> Self {
+        let c = match inputs.c {
+            CubeOption::Some(c) => {
+                CubeOption::new_Some(c.view(TiledLayout::new(StageIdent::Out, smem_config)))
+            }
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let bias = match inputs.bias {
+            CubeOption::Some(bias) => {
+                CubeOption::new_Some(bias.view(TiledLayout::new(StageIdent::Out, smem_config)))
+            }
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let residual = match inputs.residual {
+            CubeOption::Some(residual) => {
+                CubeOption::new_Some(residual.view(TiledLayout::new(StageIdent::Out, smem_config)))
+            }
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let lhs_scale = match inputs.lhs_scale {
+            CubeOption::Some(scale) => {
+                CubeOption::new_Some(scale.view(TiledLayout::new(StageIdent::Out, smem_config)))
+            }
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let rhs_scale = match inputs.rhs_scale {
+            CubeOption::Some(scale) => {
+                CubeOption::new_Some(scale.view(TiledLayout::new(StageIdent::Out, smem_config)))
+            }
+            CubeOption::None => CubeOption::new_None(),
+        };
+
+        EpilogueReader::<EG> {
+            c,
+            bias,
+            residual,
+            lhs_scale,
+            rhs_scale,
+            alpha: inputs.alpha,
+            beta: inputs.beta,
+            config,
+        }
+    }
+
+    /// Epilogue that leaves the output untouched
+    pub fn none() -> Self {
+        EpilogueReader::<EG> {
+            c: CubeOption::new_None(),
+            bias: CubeOption::new_None(),
+            residual: CubeOption::new_None(),
+            lhs_scale: CubeOption::new_None(),
+            rhs_scale: CubeOption::new_None(),
+            alpha: 1.0f32.runtime(),
+            beta: 0.0f32.runtime(),
+            config: comptime![EpilogueConfig::default()],
+        }
+    }
+
+    /// Apply the epilogue to the output line that will be written at `pos`
+    pub fn apply(&self, value: Line<EG>, pos: TiledCoords) -> Line<EG> {
+        if comptime![self.config.is_identity()] {
+            value
+        } else {
+            let mut acc = Line::<f32>::cast_from(value);
+
+            if comptime![self.config.has_alpha] {
+                acc *= Line::cast_from(self.alpha);
+            }
+
+            match self.lhs_scale.clone() {
+                CubeOption::Some(scale) => {
+                    acc *= read_scale(&scale, pos, self.config.lhs_scale, true);
+                }
+                CubeOption::None => {}
+            }
+
+            match self.rhs_scale.clone() {
+                CubeOption::Some(scale) => {
+                    acc *= read_scale(&scale, pos, self.config.rhs_scale, false);
+                }
+                CubeOption::None => {}
+            }
+
+            match self.c.clone() {
+                CubeOption::Some(c) => {
+                    acc += Line::cast_from(self.beta) * Line::cast_from(c.read_checked(pos));
+                }
+                CubeOption::None => {}
+            }
+
+            match self.bias.clone() {
+                CubeOption::Some(bias) => {
+                    acc += Line::cast_from(bias.read_checked(pos));
+                }
+                CubeOption::None => {}
+            }
+
+            acc = apply_activation(acc, comptime![self.config.activation]);
+
+            match self.residual.clone() {
+                CubeOption::Some(residual) => {
+                    acc += Line::cast_from(residual.read_checked(pos));
+                }
+                CubeOption::None => {}
+            }
+
+            Line::cast_from(acc)
+        }
+    }
+}
+
+#[cube]
+/// Read the scales of an operand for the output line at `pos`, broadcasting them when a single
+/// scale covers the whole line
+fn read_scale(
+    scale: &View<Line<f32>, TiledCoords>,
+    pos: TiledCoords,
+    #[comptime] granularity: Option<ScaleGranularity>,
+    #[comptime] per_row: bool,
+) -> Line<f32> {
+    let value = scale.read_checked(pos);
+    if comptime![granularity == Some(ScaleGranularity::Channel) && !per_row] {
+        value
+    } else {
+        Line::cast_from(value[0])
+    }
+}
+
+#[cube]
+/// Apply the activation elementwise
+pub fn apply_activation(value: Line<f32>, #[comptime] activation: Activation) -> Line<f32> {
+    match activation {
+        Activation::None => value,
+        _ => {
+            let line_size = value.size();
+            let mut out = Line::empty(line_size);
+
+            #[unroll]
+            for i in 0..line_size {
+                let x = value[i];
+                out[i] = match activation {
+                    Activation::Relu => select(x > 0.0, x, 0.0),
+                    Activation::Gelu => 0.5 * x * (1.0 + Erf::erf(x * FRAC_1_SQRT_2)),
+                    Activation::Silu => x / (1.0 + Exp::exp(-x)),
+                    Activation::None => x,
+                };
+            }
+
+            out
+        }
+    }
+}
@@ -1,10 +1,12 @@
 mod base;
+mod epilogue;
 mod event;
 mod plane;
 mod stage;
 mod unit;
 
 pub use base::*;
+pub use epilogue::*;
 pub use event::*;
 pub use plane::*;
 pub use stage::*;
@@ -1,8 +1,9 @@
 use crate::{
     components::{
         global::{
-            GlobalWriter, GlobalWriterConfig, GlobalWriterFamily, PartitionedStage,
-            PartitionedStageFamily, WriteEvent, WriteEventExpand, WriteEventListener,
+            EpilogueInputs, EpilogueReader, GlobalWriter, GlobalWriterConfig, GlobalWriterFamily,
+            PartitionedStage, PartitionedStageFamily, WriteEvent, WriteEventExpand,
+            WriteEventListener,
             read::tiled::{TiledCoords, TiledLayout},
         },
         stage::{PlanePartitioner, StageMemoryConfig, StagePartitioner},
@@ -20,6 +21,7 @@ use cubecl::std::tensor::layout::Coords2d;
 pub struct PlaneWriter<IP: MatrixPrecision> {
     global: View<Line<IP::Global>, TiledCoords, ReadWrite>,
     stage: PartitionedStage<IP::Stage>,
+    epilogue: EpilogueReader<IP::Global>,
 
     #[cube(comptime)]
     plane_dim: u32,
@@ -31,6 +33,7 @@ pub struct PlaneWriter<IP: MatrixPrecision> {
 impl<IP: MatrixPrecision> PlaneWriter<IP> {
     pub fn new(
         global: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<IP::Global>,
         #[comptime] config: GlobalWriterConfig,
     ) -> Self {
         let stage = PartitionedStage::new(
@@ -45,6 +48,7 @@ impl<IP: MatrixPrecision> PlaneWriter<IP> {
         PlaneWriter::<IP> {
             global: global.view_mut(TiledLayout::new(StageIdent::Out, config.smem_config)),
             stage,
+            epilogue: EpilogueReader::new(epilogue, config.smem_config, config.epilogue),
             plane_dim: config.plane_dim,
             smem_config: config.smem_config,
         }
@@ -54,6 +58,7 @@ impl<IP: MatrixPrecision> PlaneWriter<IP> {
         plane_write::<IP::Stage, IP::Global>(
             &mut self.global,
             &self.stage.unit_tile,
+            &self.epilogue,
             tile_pos,
             comptime!(self.plane_dim),
             comptime!(self.smem_config.elements_per_tile()),
@@ -80,9 +85,10 @@ impl<IP: MatrixPrecision> GlobalWriter<IP> for PlaneWriter<IP> {
 
     fn init(
         tensor: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<IP::Global>,
         #[comptime] config: GlobalWriterConfig,
     ) -> Self {
-        Self::new(tensor, config)
+        Self::new(tensor, epilogue, config)
     }
 
     fn stage(this: &Self) -> Self::Stage {
@@ -94,6 +100,7 @@ impl<IP: MatrixPrecision> GlobalWriter<IP> for PlaneWriter<IP> {
 pub fn plane_write<ES: Numeric, EG: Numeric>(
     global: &mut View<Line<EG>, TiledCoords, ReadWrite>,
     smem_tile: &StridedTile<ES, ReadWrite>,
+    epilogue: &EpilogueReader<EG>,
     tile_pos: Coords2d,
     #[comptime] plane_dim: u32,
     #[comptime] elements_in_tile: u32,
@@ -110,10 +117,10 @@ pub fn plane_write<ES: Numeric, EG: Numeric>(
 
         #[allow(clippy::collapsible_else_if)]
         if comptime!(balanced_workload) {
-            write_line(global, smem_tile, unit_write, tile_pos);
+            write_line(global, smem_tile, epilogue, unit_write, tile_pos);
         } else {
             if unit_write < elements_in_tile {
-                write_line(global, smem_tile, unit_write, tile_pos);
+                write_line(global, smem_tile, epilogue, unit_write, tile_pos);
             }
         }
     }
@@ -123,6 +130,7 @@ pub fn plane_write<ES: Numeric, EG: Numeric>(
 fn write_line<ES: Numeric, EG: Numeric>(
     view: &mut View<Line<EG>, TiledCoords, ReadWrite>,
     out_smem_tile: &StridedTile<ES, ReadWrite>,
+    epilogue: &EpilogueReader<EG>,
     unit_write: u32,
     tile: Coords2d,
 ) {
@@ -149,7 +157,8 @@ fn write_line<ES: Numeric, EG: Numeric>(
         unimplemented!()
     };
 
-    view.write_checked((tile, unit_write), Line::cast_from(value));
+    let pos = (tile, unit_write);
+    view.write_checked(pos, epilogue.apply(Line::cast_from(value), pos));
 }
 
 pub struct PlaneWriterFamily;
@@ -3,8 +3,8 @@ use cubecl::std::tensor::{View, layout::Coords2d};
 
 use crate::components::{
     global::{
-        GlobalWriter, GlobalWriterConfig, GlobalWriterFamily, PartitionedStage,
-        PartitionedStageFamily, WriteEvent, WriteEventExpand, WriteEventListener,
+        EpilogueInputs, EpilogueReader, GlobalWriter, GlobalWriterConfig, GlobalWriterFamily,
+        PartitionedStage, PartitionedStageFamily, WriteEvent, WriteEventExpand, WriteEventListener,
         read::tiled::{TiledCoords, TiledLayout},
     },
     stage::{StageMemoryConfig, StagePartitioner, UnitPartitioner},
@@ -18,6 +18,7 @@ use crate::definition::{MatrixPrecision, StageIdent};
 pub struct UnitWriter<IP: MatrixPrecision> {
     global: View<Line<IP::Global>, TiledCoords, ReadWrite>,
     stage: PartitionedStage<IP::Stage>,
+    epilogue: EpilogueReader<IP::Global>,
 
     #[cube(comptime)]
     smem_config: StageMemoryConfig,
@@ -27,6 +28,7 @@ pub struct UnitWriter<IP: MatrixPrecision> {
 impl<IP: MatrixPrecision> UnitWriter<IP> {
     pub fn new(
         global: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<IP::Global>,
         #[comptime] config: GlobalWriterConfig,
     ) -> Self {
         let smem_config = config.smem_config;
@@ -42,6 +44,7 @@ impl<IP: MatrixPrecision> UnitWriter<IP> {
         UnitWriter::<IP> {
             global: global.view_mut(TiledLayout::new(StageIdent::Out, smem_config)),
             stage,
+            epilogue: EpilogueReader::new(epilogue, smem_config, config.epilogue),
             smem_config,
         }
     }
@@ -50,6 +53,7 @@ impl<IP: MatrixPrecision> UnitWriter<IP> {
         unit_write(
             &mut self.global,
             &self.stage.unit_tile,
+            &self.epilogue,
             tile,
             comptime!(self.smem_config.elements_per_tile()),
         )
@@ -60,6 +64,7 @@ impl<IP: MatrixPrecision> UnitWriter<IP> {
 pub fn unit_write<ES: Numeric, EG: Numeric>(
     global: &mut View<Line<EG>, TiledCoords, ReadWrite>,
     smem_tile: &StridedTile<ES, ReadWrite>,
+    epilogue: &EpilogueReader<EG>,
     tile_pos: Coords2d,
     #[comptime] elements_in_tile: u32,
 ) {
@@ -70,7 +75,8 @@ pub fn unit_write<ES: Numeric, EG: Numeric>(
 
     for i in 0..num_lines {
         let value = out_smem_stage[smem_tile.stage_offset(i)];
-        global.write_checked((tile_pos, i * output_line_size), Line::cast_from(value));
+        let pos = (tile_pos, i * output_line_size);
+        global.write_checked(pos, epilogue.apply(Line::cast_from(value), pos));
     }
 }
 
@@ -91,9 +97,10 @@ impl<IP: MatrixPrecision> GlobalWriter<IP> for UnitWriter<IP> {
 
     fn init(
         tensor: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        epilogue: EpilogueInputs<IP::Global>,
         #[comptime] config: GlobalWriterConfig,
     ) -> Self {
-        Self::new(tensor, config)
+        Self::new(tensor, epilogue, config)
     }
 
     fn stage(this: &Self) -> Self::Stage {
@@ -33,23 +33,31 @@ use core::marker::PhantomData;
 use cubecl::prelude::*;
 
 /// Unit Matmul family for any precision
-pub struct UnitMatmulFamily<TM: TileMatmulFamily, StageIn: StageFamily, StageAcc: StageFamily> {
-    _phantom: PhantomData<(TM, StageIn, StageAcc)>,
+///
+/// Rhs uses the same stage as lhs unless specified, i.e. to stage scales along with the data.
+pub struct UnitMatmulFamily<
+    TM: TileMatmulFamily,
+    StageIn: StageFamily,
+    StageAcc: StageFamily,
+    StageRhs: StageFamily = StageIn,
+> {
+    _phantom: PhantomData<(TM, StageIn, StageAcc, StageRhs)>,
 }
 
 impl<
     TM: TileMatmulFamily<
             LhsTile = StageIn::TileKind,
-            RhsTile = StageIn::TileKind,
+            RhsTile = StageRhs::TileKind,
             AccTile = StageAcc::TileKind,
             OutTile = Strided,
         >,
     StageIn: StageFamily,
     StageAcc: StageFamily,
-> StageMatmulFamily for UnitMatmulFamily<TM, StageIn, StageAcc>
+    StageRhs: StageFamily,
+> StageMatmulFamily for UnitMatmulFamily<TM, StageIn, StageAcc, StageRhs>
 {
     type LhsStage = StageIn;
-    type RhsStage = StageIn;
+    type RhsStage = StageRhs;
     type AccStage = StageAcc;
     type OutStage = PartitionedStageFamily;
 
@@ -67,7 +75,7 @@ impl<
             <MP::Acc as MatrixPrecision>::Register,
         >,
         StageIn::Stage<LhsS<MP>, TL>,
-        StageIn::Stage<RhsS<MP>, TR>,
+        StageRhs::Stage<RhsS<MP>, TR>,
         StageAcc::Stage<AccS<MP>, TA>,
         PartitionedStage<AccS<MP>>,
     >;
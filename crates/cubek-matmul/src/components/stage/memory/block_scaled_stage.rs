@@ -0,0 +1,108 @@
+use cubecl::prelude::*;
+use cubecl::std::tensor::layout::Coords2d;
+
+use crate::components::global::GlobalReaderConfig;
+use crate::components::stage::TilingLayout;
+use crate::components::stage::{Stage, StageFamily, StageMemoryConfig, StridedStageMemory};
+use crate::components::tile::{BlockScaledTile, io::BlockScaled};
+
+pub struct BlockScaledStageFamily;
+
+impl StageFamily for BlockScaledStageFamily {
+    type TileKind = BlockScaled;
+
+    type Stage<ES: Numeric, T: TilingLayout> = BlockScaledStageMemory<ES, T>;
+}
+
+/// Number of rows of scales needed for a stage, when `block_rows` consecutive rows share the same
+/// scale.
+///
+/// Stages must not straddle blocks, so one of the stage and block sizes must be a multiple of the
+/// other.
+pub fn num_scale_rows(block_rows: u32, config: StageMemoryConfig) -> u32 {
+    config.elements_per_stage_along_row().div_ceil(block_rows)
+}
+
+/// Number of scales held by a block scaled stage
+pub fn num_scales(block_rows: u32, config: StageMemoryConfig) -> u32 {
+    num_scale_rows(block_rows, config) * config.elements_per_stage_along_col()
+}
+
+#[derive(CubeType, Clone, Copy)]
+/// Stage memory holding block quantized values, along with the scales of the blocks
+/// overlapping the stage.
+pub struct BlockScaledStageMemory<ES: Numeric, T: TilingLayout> {
+    /// Values of the stage, without their scales applied
+    pub data: StridedStageMemory<ES, T>,
+    /// Scales of the stage in `f32`, one row per block along the rows of the stage and one
+    /// column per column of the stage
+    pub scales: SharedMemory<f32>,
+
+    /// Number of consecutive rows sharing the same scale
+    #[cube(comptime)]
+    pub block_rows: u32,
+    #[cube(comptime)]
+    config: StageMemoryConfig,
+}
+
+#[cube]
+impl<ES: Numeric, T: TilingLayout> BlockScaledStageMemory<ES, T> {
+    /// Instantiate a new block scaled stage, where `block_rows` consecutive rows share the same
+    /// scale
+    pub fn new(
+        data: StridedStageMemory<ES, T>,
+        #[comptime] block_rows: u32,
+        #[comptime] config: StageMemoryConfig,
+    ) -> BlockScaledStageMemory<ES, T> {
+        let scales = SharedMemory::new(comptime!(num_scales(block_rows, config)));
+
+        BlockScaledStageMemory::<ES, T> {
+            data,
+            scales,
+            block_rows,
+            config,
+        }
+    }
+
+    /// Get the tile at position (row, col)
+    pub fn get_tile(&self, tile: Coords2d) -> BlockScaledTile<ES> {
+        let (row, col) = tile;
+        let len = comptime!(num_scales(self.block_rows, self.config));
+
+        BlockScaledTile::<ES> {
+            data: self.data.get_tile(tile),
+            scales: self.scales.slice(0, len),
+            scales_start: col * comptime!(self.config.elements_per_tile_along_col),
+            scales_stride: comptime!(self.config.elements_per_stage_along_col()),
+            row_offset: row * comptime!(self.config.elements_per_tile_along_row),
+            block_rows: self.block_rows,
+        }
+    }
+
+    /// Zero out the values of the stage, scales are overwritten on each load
+    pub fn clear_all(&mut self, #[comptime] config: GlobalReaderConfig) {
+        self.data.clear_all(config);
+    }
+
+    /// Frees the shared memory of both values and scales for reuse, if possible on the target
+    /// runtime.
+    ///
+    /// # Safety
+    /// *Must* be used in uniform control flow
+    /// *Must not* have any dangling references to this shared memory
+    pub unsafe fn free(self) {
+        unsafe {
+            self.data.free();
+            self.scales.free();
+        };
+    }
+}
+
+#[cube]
+impl<ES: Numeric, T: TilingLayout> Stage<ES, ReadOnly> for BlockScaledStageMemory<ES, T> {
+    type TileKind = BlockScaled;
+
+    fn tile(this: &Self, tile: Coords2d) -> BlockScaledTile<ES> {
+        this.get_tile(tile)
+    }
+}
@@ -1,7 +1,11 @@
+mod block_scaled_stage;
 mod config;
 mod layout;
+mod sparse_24_stage;
 mod stage_memory;
 
+pub use block_scaled_stage::*;
 pub use config::*;
 pub use layout::*;
+pub use sparse_24_stage::*;
 pub use stage_memory::*;
@@ -0,0 +1,104 @@
+use cubecl::prelude::*;
+use cubecl::std::tensor::layout::Coords2d;
+
+use crate::components::global::GlobalReaderConfig;
+use crate::components::stage::TilingLayout;
+use crate::components::stage::{Stage, StageFamily, StageMemoryConfig, StridedStageMemory};
+use crate::components::tile::{Sparse24Tile, io::Sparse24};
+
+pub struct Sparse24StageFamily;
+
+impl StageFamily for Sparse24StageFamily {
+    type TileKind = Sparse24;
+
+    type Stage<ES: Numeric, T: TilingLayout> = Sparse24StageMemory<ES, T>;
+}
+
+/// Config of the stage holding the kept values of a 2:4 sparse stage, which has half the rows
+/// of the dense stage.
+pub fn sparse_24_values_config(config: StageMemoryConfig) -> StageMemoryConfig {
+    StageMemoryConfig {
+        elements_per_tile_along_row: config.elements_per_tile_along_row / 2,
+        ..config
+    }
+}
+
+/// Number of metadata elements held by a 2:4 sparse stage, one per group of four rows
+pub fn sparse_24_num_metadata(config: StageMemoryConfig) -> u32 {
+    (config.elements_per_stage_along_row() / 4) * config.elements_per_stage_along_col()
+}
+
+#[derive(CubeType, Clone, Copy)]
+/// Stage memory holding a 2:4 sparse operand, sparse along its rows, as the two kept values of
+/// each group of four rows and their positions within the group.
+pub struct Sparse24StageMemory<ES: Numeric, T: TilingLayout> {
+    /// Kept values of the stage, with half the rows of the dense stage
+    pub values: StridedStageMemory<ES, T>,
+    /// Positions of the kept values, one row per group of four rows of the stage and one
+    /// column per column of the stage
+    pub metadata: SharedMemory<u32>,
+
+    /// Config of the dense stage
+    #[cube(comptime)]
+    config: StageMemoryConfig,
+}
+
+#[cube]
+impl<ES: Numeric, T: TilingLayout> Sparse24StageMemory<ES, T> {
+    /// Instantiate a new sparse stage, where `config` is the config of the dense stage
+    pub fn new(
+        values: StridedStageMemory<ES, T>,
+        #[comptime] config: StageMemoryConfig,
+    ) -> Sparse24StageMemory<ES, T> {
+        let metadata = SharedMemory::new(comptime!(sparse_24_num_metadata(config)));
+
+        Sparse24StageMemory::<ES, T> {
+            values,
+            metadata,
+            config,
+        }
+    }
+
+    /// Get the tile at position (row, col)
+    pub fn get_tile(&self, tile: Coords2d) -> Sparse24Tile<ES> {
+        let (row, col) = tile;
+        let len = comptime!(sparse_24_num_metadata(self.config));
+        let metadata_stride = comptime!(self.config.elements_per_stage_along_col());
+        let groups_per_tile = comptime!(self.config.elements_per_tile_along_row / 4);
+
+        Sparse24Tile::<ES> {
+            values: self.values.get_tile(tile),
+            metadata: self.metadata.slice(0, len),
+            metadata_start: row * groups_per_tile * metadata_stride
+                + col * comptime!(self.config.elements_per_tile_along_col),
+            metadata_stride,
+        }
+    }
+
+    /// Zero out the values of the stage, metadata is overwritten on each load
+    pub fn clear_all(&mut self, #[comptime] config: GlobalReaderConfig) {
+        self.values.clear_all(config);
+    }
+
+    /// Frees the shared memory of both values and metadata for reuse, if possible on the target
+    /// runtime.
+    ///
+    /// # Safety
+    /// *Must* be used in uniform control flow
+    /// *Must not* have any dangling references to this shared memory
+    pub unsafe fn free(self) {
+        unsafe {
+            self.values.free();
+            self.metadata.free();
+        };
+    }
+}
+
+#[cube]
+impl<ES: Numeric, T: TilingLayout> Stage<ES, ReadOnly> for Sparse24StageMemory<ES, T> {
+    type TileKind = Sparse24;
+
+    fn tile(this: &Self, tile: Coords2d) -> Sparse24Tile<ES> {
+        this.get_tile(tile)
+    }
+}
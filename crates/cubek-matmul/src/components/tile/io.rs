@@ -2,7 +2,7 @@ use cubecl::prelude::*;
 
 use cubecl::std::CubeOption;
 
-use crate::components::tile::StridedTile;
+use crate::components::tile::{BlockScaledTile, Sparse24Tile, StridedTile};
 
 /// Kind (family) of the tiles returned by a stage and ingested by a tile matmul reader
 pub trait TileKind<IO: SliceVisibility = ReadOnly>: CubeType + Send + Sync + 'static {
@@ -18,6 +18,16 @@ pub struct Strided {}
 #[derive(CubeType)]
 pub struct Filled {}
 
+/// Tile is a slice of memory with a stride, holding block quantized values along with the
+/// scales of their blocks
+#[derive(CubeType)]
+pub struct BlockScaled {}
+
+/// Tile is a slice of memory with a stride, holding the kept values of a 2:4 sparse tile along
+/// with their positions
+#[derive(CubeType)]
+pub struct Sparse24 {}
+
 impl<IO: SliceVisibility> TileKind<IO> for Strided {
     type Tile<E: Numeric> = StridedTile<E, IO>;
 }
@@ -26,6 +36,14 @@ impl TileKind<ReadOnly> for Filled {
     type Tile<E: Numeric> = E;
 }
 
+impl TileKind<ReadOnly> for BlockScaled {
+    type Tile<E: Numeric> = BlockScaledTile<E>;
+}
+
+impl TileKind<ReadOnly> for Sparse24 {
+    type Tile<E: Numeric> = Sparse24Tile<E>;
+}
+
 impl<Inner: TileKind<IO>, IO: SliceVisibility> TileKind<IO> for CubeOption<Inner> {
     type Tile<E: Numeric> = CubeOption<Inner::Tile<E>>;
 }
@@ -9,8 +9,12 @@ use crate::components::tile::{io::TileKind, tile_data::StridedTile};
 use crate::definition::{MatrixLayout, StageIdent};
 
 /// Uses one unit to perform a small matmul directly in registers
-pub struct RegisterMatmul<Acc: TileKind = Filled> {
-    _ty: PhantomData<Acc>,
+///
+/// Rhs tiles are usually strided, but may also be block scaled, in which case scales are applied
+/// when filling the fragment, or 2:4 sparse, in which case kept values are expanded when filling
+/// the fragment.
+pub struct RegisterMatmul<Acc: TileKind = Filled, Rhs: TileKind = Strided> {
+    _ty: PhantomData<(Acc, Rhs)>,
 }
 
 /// Doesn't impact performance much, but may increase kernel size too much when true (often ~6X).
@@ -26,10 +30,11 @@ pub struct UnitFragment<E: Numeric> {
 }
 
 #[cube]
-impl<L: Numeric, R: Numeric, A: Numeric, AccTile: TileKind> TileMatmul<L, R, A>
-    for RegisterMatmul<AccTile>
+impl<L: Numeric, R: Numeric, A: Numeric, AccTile: TileKind, RhsTile: TileKind> TileMatmul<L, R, A>
+    for RegisterMatmul<AccTile, RhsTile>
 where
     RegisterStageReader<AccTile>: RegisterFragmentReader<TileKind = AccTile>,
+    RegisterStageReader<RhsTile>: RegisterFragmentReader<TileKind = RhsTile>,
 {
     type Config = RegisterMatmulConfig;
 
@@ -38,7 +43,7 @@ where
     type AccFragment = UnitFragment<A>;
 
     type LhsTile = Strided;
-    type RhsTile = Strided;
+    type RhsTile = RhsTile;
     type AccTile = AccTile;
     type OutTile = Strided;
 
@@ -97,11 +102,11 @@ where
     }
 
     fn load_rhs<E: Numeric>(
-        tile: &StridedTile<E>,
+        tile: &RhsTile::Tile<E>,
         rhs: &mut Self::RhsFragment,
         #[comptime] config: Self::Config,
     ) {
-        RegisterStageReader::<Strided>::load_fragment(tile, rhs, StageIdent::Rhs, config)
+        RegisterStageReader::<RhsTile>::load_fragment(tile, rhs, StageIdent::Rhs, config)
     }
 
     fn load_acc<E: Numeric>(
@@ -122,7 +127,7 @@ where
 }
 
 #[cube]
-impl<Acc: TileKind> RegisterMatmul<Acc> {
+impl<Acc: TileKind, Rhs: TileKind> RegisterMatmul<Acc, Rhs> {
     fn inner_product<Lhs: Numeric, Rhs: Numeric, EA: Numeric>(
         lhs: &Array<Lhs>,
         rhs: &Array<Rhs>,
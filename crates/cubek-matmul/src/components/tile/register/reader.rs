@@ -3,8 +3,8 @@ use cubecl::std::{CubeOption, CubeOptionExpand};
 use std::marker::PhantomData;
 
 use crate::components::tile::{
-    StridedTile,
-    io::{Filled, Strided, TileKind},
+    BlockScaledTile, Sparse24Tile, StridedTile,
+    io::{BlockScaled, Filled, Sparse24, Strided, TileKind},
     register::{
         RegisterMatmul, UnitFragment,
         config::{ProductType, RegisterMatmulConfig},
@@ -12,6 +12,8 @@ use crate::components::tile::{
 };
 use crate::definition::{MatrixLayout, StageIdent};
 
+use super::matmul::UNROLL;
+
 /// Reader for the register matmul fragments. Implementation depends on the tile kind.
 #[derive(CubeType)]
 pub struct RegisterStageReader<Kind: TileKind> {
@@ -179,3 +181,97 @@ where
         }
     }
 }
+
+#[cube]
+impl RegisterFragmentReader for RegisterStageReader<BlockScaled> {
+    type TileKind = BlockScaled;
+
+    fn load_fragment<E: Numeric, V: Numeric>(
+        tile: &BlockScaledTile<V>,
+        fragment: &mut UnitFragment<E>,
+        #[comptime] ident: StageIdent,
+        #[comptime] config: RegisterMatmulConfig,
+    ) {
+        RegisterStageReader::<Strided>::load_fragment(&tile.data, fragment, ident, config);
+
+        match ident {
+            StageIdent::Rhs => apply_rhs_scales(tile, fragment, config),
+            _ => panic!("Only rhs can be block scaled"),
+        }
+    }
+}
+
+/// Multiplies each value of the rhs fragment by the `f32` scale of its block.
+///
+/// The product is in the precision of the fragment, which the block scaled launcher sets to the
+/// accumulator precision.
+#[cube]
+fn apply_rhs_scales<E: Numeric, V: Numeric>(
+    tile: &BlockScaledTile<V>,
+    frag: &mut UnitFragment<E>,
+    #[comptime] config: RegisterMatmulConfig,
+) {
+    let size = config.shared.tile_size;
+    let (k, n) = comptime![(size.k(), size.n())];
+
+    // Position of (k, n) in the fragment, which is always laid out for the product type
+    let (stride_k, stride_n) = comptime! {
+        match config.product_type {
+            ProductType::Inner => (1u32, k),
+            ProductType::Outer => (n, 1u32),
+        }
+    };
+
+    #[unroll(UNROLL)]
+    for k_ in 0..k {
+        #[unroll(UNROLL)]
+        for n_ in 0..n {
+            let index = k_ * stride_k + n_ * stride_n;
+            frag.array[index] = frag.array[index] * E::cast_from(tile.scale(k_, n_));
+        }
+    }
+}
+
+#[cube]
+impl RegisterFragmentReader for RegisterStageReader<Sparse24> {
+    type TileKind = Sparse24;
+
+    fn load_fragment<E: Numeric, V: Numeric>(
+        tile: &Sparse24Tile<V>,
+        frag: &mut UnitFragment<E>,
+        #[comptime] ident: StageIdent,
+        #[comptime] config: RegisterMatmulConfig,
+    ) {
+        match ident {
+            StageIdent::Rhs => load_rhs_sparse(tile, frag, config),
+            _ => panic!("Only rhs can be sparse"),
+        }
+    }
+}
+
+/// Expands the kept values of a 2:4 sparse tile into the dense rhs fragment.
+#[cube]
+fn load_rhs_sparse<E: Numeric, V: Numeric>(
+    tile: &Sparse24Tile<V>,
+    frag: &mut UnitFragment<E>,
+    #[comptime] config: RegisterMatmulConfig,
+) {
+    let size = config.shared.tile_size;
+    let (k, n) = comptime![(size.k(), size.n())];
+
+    // Same positions as in `apply_rhs_scales`
+    let (stride_k, stride_n) = comptime! {
+        match config.product_type {
+            ProductType::Inner => (1u32, k),
+            ProductType::Outer => (n, 1u32),
+        }
+    };
+
+    #[unroll(UNROLL)]
+    for k_ in 0..k {
+        #[unroll(UNROLL)]
+        for n_ in 0..n {
+            frag.array[k_ * stride_k + n_ * stride_n] = E::cast_from(tile.get(k_, n_));
+        }
+    }
+}
@@ -17,15 +17,16 @@ use cubecl::features::TypeUsage;
 use cubecl::ir::{ElemType, FloatKind};
 use cubecl::prelude::*;
 
-impl<AccTile: TileKind> TileMatmulFamily for RegisterMatmul<AccTile>
+impl<AccTile: TileKind, RhsTile: TileKind> TileMatmulFamily for RegisterMatmul<AccTile, RhsTile>
 where
     RegisterStageReader<AccTile>: RegisterFragmentReader<TileKind = AccTile>,
+    RegisterStageReader<RhsTile>: RegisterFragmentReader<TileKind = RhsTile>,
 {
     type Config = RegisterMatmulConfig;
-    type Matmul<L: Numeric, R: Numeric, A: Numeric> = RegisterMatmul<AccTile>;
+    type Matmul<L: Numeric, R: Numeric, A: Numeric> = RegisterMatmul<AccTile, RhsTile>;
 
     type LhsTile = Strided;
-    type RhsTile = Strided;
+    type RhsTile = RhsTile;
     type AccTile = AccTile;
     type OutTile = Strided;
 
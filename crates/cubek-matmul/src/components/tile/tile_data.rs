@@ -225,3 +225,75 @@ impl<ES: Numeric, IO: SliceVisibility> StridedTile<ES, IO> {
         })
     }
 }
+
+#[derive(CubeType, Clone, Copy)]
+/// Strided tile of block quantized values, along with the scales of the blocks it overlaps.
+///
+/// Scales are stored row-major for the whole stage, with one row per block along the rows of the
+/// stage and one column per column of the stage.
+pub struct BlockScaledTile<ES: Numeric> {
+    /// Values of the tile, without their scales applied
+    pub data: StridedTile<ES>,
+    /// Slice containing the `f32` scales of the whole stage
+    pub scales: Slice<f32>,
+    /// Offset of the first column of the tile in the scales
+    pub scales_start: u32,
+    /// Stride between two rows of scales
+    pub scales_stride: u32,
+    /// Row of the stage at which the tile starts
+    pub row_offset: u32,
+    #[cube(comptime)]
+    /// Number of consecutive rows sharing the same scale
+    pub block_rows: u32,
+}
+
+#[cube]
+impl<ES: Numeric> BlockScaledTile<ES> {
+    /// Returns the scale of the element at (`row`, `col`) of the tile
+    pub fn scale(&self, row: u32, col: u32) -> f32 {
+        let block_row = (self.row_offset + row) / self.block_rows;
+        self.scales[self.scales_start + block_row * self.scales_stride + col]
+    }
+}
+
+#[derive(CubeType, Clone, Copy)]
+/// Tile of a 2:4 sparse operand, sparse along its rows.
+///
+/// Holds the two kept values of each group of four rows, along with metadata giving their
+/// positions in the group. Metadata is stored row-major for the whole stage, with one row per
+/// group of four rows of the stage and one column per column of the stage.
+pub struct Sparse24Tile<ES: Numeric> {
+    /// Kept values of the tile, with half the rows of the dense tile
+    pub values: StridedTile<ES>,
+    /// Slice containing the metadata of the whole stage
+    pub metadata: Slice<u32>,
+    /// Offset of the first group of the tile in the metadata
+    pub metadata_start: u32,
+    /// Stride between two rows of metadata
+    pub metadata_stride: u32,
+}
+
+#[cube]
+impl<ES: Numeric> Sparse24Tile<ES> {
+    /// Returns the element at (`row`, `col`) of the dense tile, which is zero if it was pruned
+    pub fn get(&self, row: u32, col: u32) -> ES {
+        let line_size = self.values.line_size;
+        let group = row / 4;
+        let position = row % 4;
+
+        let meta = self.metadata[self.metadata_start + group * self.metadata_stride + col];
+        let first = meta & 3;
+        let second = (meta >> 2) & 3;
+
+        let line_index = col / line_size;
+        let within_line = col % line_size;
+        let first_value = self.values.get_line(group * 2, line_index)[within_line];
+        let second_value = self.values.get_line(group * 2 + 1, line_index)[within_line];
+
+        select(
+            position == first,
+            first_value,
+            select(position == second, second_value, ES::from_int(0)),
+        )
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     components::global::memory::ViewDirection,
-    definition::{MatmulGlobalElems, MatmulProblemSize},
+    definition::{EpilogueConfig, MatmulGlobalElems, MatmulProblemSize, MatmulSetupError},
 };
 use cubecl::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -44,6 +44,16 @@ pub struct MatmulProblem {
     pub out_layout: MatrixLayout,
 
     pub global_dtypes: MatmulGlobalElems,
+
+    /// Operations fused after the matmul, before storing the output
+    pub epilogue: EpilogueConfig,
+
+    /// Number of groups the rows of lhs and out are split into, for grouped matmuls.
+    /// Group sizes are only known at runtime, so rows are always bounds checked.
+    pub num_groups: Option<usize>,
+    /// Number of consecutive rows of rhs (along k) sharing the same scale, for block scaled
+    /// matmuls.
+    pub rhs_scale_block_k: Option<usize>,
 }
 
 impl MatmulProblem {
@@ -56,15 +66,17 @@ impl MatmulProblem {
         out_strides: Vec<usize>,
         global_dtypes: MatmulGlobalElems,
     ) -> Self {
-        let rank = out_shape.len();
+        // Inputs may have fewer batch dims than the output when they're broadcast
+        let lhs_rank = lhs_shape.len();
+        let rhs_rank = rhs_shape.len();
         let lhs_layout = MatrixLayout::from_shape_and_strides(&lhs_shape, &lhs_strides);
         let rhs_layout = MatrixLayout::from_shape_and_strides(&rhs_shape, &rhs_strides);
         let out_layout = MatrixLayout::from_shape_and_strides(&out_shape, &out_strides);
 
         Self {
-            m: lhs_shape[rank - 2],
-            n: rhs_shape[rank - 1],
-            k: lhs_shape[rank - 1],
+            m: lhs_shape[lhs_rank - 2],
+            n: rhs_shape[rhs_rank - 1],
+            k: lhs_shape[lhs_rank - 1],
             lhs_batches: lhs_shape[..lhs_shape.len() - 2].to_vec(),
             rhs_batches: rhs_shape[..rhs_shape.len() - 2].to_vec(),
             out_batches: out_shape[..out_shape.len() - 2].to_vec(),
@@ -78,6 +90,9 @@ impl MatmulProblem {
             rhs_layout,
             out_layout,
             global_dtypes,
+            epilogue: EpilogueConfig::default(),
+            num_groups: None,
+            rhs_scale_block_k: None,
         }
     }
 
@@ -117,13 +132,63 @@ impl MatmulProblem {
             rhs_layout,
             out_layout,
             global_dtypes,
+            epilogue: EpilogueConfig::default(),
+            num_groups: None,
+            rhs_scale_block_k: None,
         }
     }
 
+    /// Fuse the given epilogue to the problem
+    pub fn with_epilogue(mut self, epilogue: EpilogueConfig) -> Self {
+        self.epilogue = epilogue;
+        self
+    }
+
+    /// Split the rows of lhs and out into `num_groups` groups of runtime sizes
+    pub fn with_groups(mut self, num_groups: usize) -> Self {
+        self.num_groups = Some(num_groups);
+        self
+    }
+
+    /// Scale rhs with blocks of `block_k` consecutive rows along k sharing the same scale
+    pub fn with_rhs_block_scales(mut self, block_k: usize) -> Self {
+        self.rhs_scale_block_k = Some(block_k);
+        self
+    }
+
     /// Returns the total number of batches of the output
     pub fn num_batches(&self) -> usize {
         self.out_batches.iter().product()
     }
+
+    /// Checks that the batches of lhs and rhs broadcast to the batches of the output, following
+    /// NumPy semantics: batch dims are aligned from the right, and missing or unit dims are
+    /// broadcast.
+    pub fn check_batch_broadcast(&self) -> Result<(), MatmulSetupError> {
+        let broadcasts = |batches: &[usize]| {
+            batches.len() <= self.out_batches.len()
+                && batches
+                    .iter()
+                    .rev()
+                    .zip(self.out_batches.iter().rev())
+                    .all(|(dim, out_dim)| dim == out_dim || *dim == 1)
+        };
+
+        if !broadcasts(&self.lhs_batches) || !broadcasts(&self.rhs_batches) {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Batches of lhs {:?} and rhs {:?} don't broadcast to the output batches {:?}",
+                self.lhs_batches, self.rhs_batches, self.out_batches
+            ))));
+        }
+
+        Ok(())
+    }
+
+    /// Whether rhs is shared by all batches of the output, either because it has no batch dims
+    /// or because they're all broadcast
+    pub fn rhs_is_broadcast(&self) -> bool {
+        self.rhs_batches.iter().all(|dim| *dim == 1)
+    }
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -217,6 +282,18 @@ pub enum MatrixLayout {
 
 impl MatrixLayout {
     pub fn from_shape_and_strides(shape: &[usize], strides: &[usize]) -> Self {
+        match Self::try_from_shape_and_strides(shape, strides) {
+            Some(layout) => layout,
+            None => panic!(
+                "Invalid or non-contiguous matrix layout: shape={:?}, strides={:?}",
+                shape, strides
+            ),
+        }
+    }
+
+    /// Layout of the matrices of a tensor, or `None` if neither of its last two dims is
+    /// contiguous. Batch strides are arbitrary and don't affect the layout.
+    pub fn try_from_shape_and_strides(shape: &[usize], strides: &[usize]) -> Option<Self> {
         assert!(
             shape.len() >= 2 && shape.len() == strides.len(),
             "Shape/stride mismatch or not a matrix"
@@ -239,18 +316,15 @@ impl MatrixLayout {
 
         // Row-major: inner dimension is contiguous
         if stride_inner == 1 && stride_outer >= inner {
-            return MatrixLayout::RowMajor;
+            return Some(MatrixLayout::RowMajor);
         }
 
         // Col-major: outer dimension is contiguous
         if stride_outer == 1 && stride_inner >= outer {
-            return MatrixLayout::ColMajor;
+            return Some(MatrixLayout::ColMajor);
         }
 
-        panic!(
-            "Invalid or non-contiguous matrix layout: shape={:?}, strides={:?}",
-            shape, strides
-        );
+        None
     }
 
     pub fn to_strides(&self, shape: &[usize]) -> Vec<usize> {
@@ -1,4 +1,11 @@
-use cubecl::{Runtime, client::ComputeClient, flex32, prelude::CubePrimitive, tf32};
+use cubecl::{
+    Runtime,
+    client::ComputeClient,
+    flex32,
+    ir::{ElemType, FloatKind, StorageType},
+    prelude::CubePrimitive,
+    tf32,
+};
 
 use crate::{
     components::{
@@ -50,6 +57,69 @@ pub fn adjust_dtypes<R: Runtime>(
             dtypes.rhs_register.dtype = f16_dtype;
         }
     }
+
+    adjust_fp8_dtypes(client, dtypes, requires_accelerator);
+}
+
+/// Dequantizes fp8 inputs to f16 before they reach the tile matmul, unless it runs on
+/// an accelerator with native fp8 instructions for the given element types.
+///
+/// Only the register types are changed, the stage types are chosen by [select_stage_dtypes].
+pub fn adjust_fp8_dtypes<R: Runtime>(
+    client: &ComputeClient<R>,
+    dtypes: &mut MatmulElems,
+    requires_accelerator: bool,
+) {
+    if !is_fp8(*dtypes.lhs_register) && !is_fp8(*dtypes.rhs_register) {
+        return;
+    }
+
+    let features = &client.properties().features;
+    let has_fp8_instruction = features
+        .cmma
+        .iter()
+        .chain(features.mma.iter())
+        .any(|config| {
+            config.a_type == *dtypes.lhs_register
+                && config.b_type == *dtypes.rhs_register
+                && config.cd_type == *dtypes.acc_register
+        });
+
+    if !requires_accelerator || !has_fp8_instruction {
+        let f16_dtype = half::f16::as_type_native_unchecked();
+        dtypes.lhs_register.dtype = f16_dtype;
+        dtypes.rhs_register.dtype = f16_dtype;
+    }
+}
+
+/// Chooses the stage element types, once the register types are final.
+///
+/// When the tile matmul can cast while reading the stage, the stage keeps the global types:
+/// they are the same size at best but often smaller, and it enables things like TMA. An f16
+/// stage for the output also enables using `stmatrix` on the registers after casting.
+///
+/// Fp8 inputs dequantized by [adjust_fp8_dtypes] are always staged in their register type, so
+/// they are converted once while filling the stage.
+pub fn select_stage_dtypes(dtypes: &mut MatmulElems, can_cast_stage_element: bool) {
+    if can_cast_stage_element {
+        dtypes.lhs_stage.dtype = dtypes.lhs_global.dtype;
+        dtypes.rhs_stage.dtype = dtypes.rhs_global.dtype;
+        dtypes.acc_stage.dtype = dtypes.acc_global.dtype;
+    }
+
+    if is_fp8(*dtypes.lhs_global) && !is_fp8(*dtypes.lhs_register) {
+        dtypes.lhs_stage.dtype = dtypes.lhs_register.dtype;
+    }
+    if is_fp8(*dtypes.rhs_global) && !is_fp8(*dtypes.rhs_register) {
+        dtypes.rhs_stage.dtype = dtypes.rhs_register.dtype;
+    }
+}
+
+fn is_fp8(dtype: StorageType) -> bool {
+    matches!(
+        dtype,
+        StorageType::Scalar(ElemType::Float(FloatKind::E4M3 | FloatKind::E5M2))
+    )
 }
 
 #[derive(Default, Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -0,0 +1,57 @@
+use cubecl::prelude::*;
+
+#[derive(CubeType, Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+/// Elementwise activation applied to the output before it is stored
+pub enum Activation {
+    #[default]
+    None,
+    /// `max(x, 0)`
+    Relu,
+    /// Exact GELU, `x * Φ(x)`
+    Gelu,
+    /// `x * sigmoid(x)`
+    Silu,
+}
+
+#[derive(CubeType, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// Granularity of the scales of a low precision operand, applied to the output by the epilogue
+pub enum ScaleGranularity {
+    /// A single scale for the whole operand
+    Tensor,
+    /// One scale per row of lhs, or per column of rhs
+    Channel,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+/// Operations fused at the end of the matmul, applied by the global writers right before storing.
+///
+/// The output is computed as
+/// `activation(alpha * lhs_scale * rhs_scale * (lhs @ rhs) + beta * c + bias) + residual`,
+/// where bias is broadcast along the rows of the output, and `c` and residual have the same
+/// shape as the output. `c` may alias the output to accumulate into it.
+pub struct EpilogueConfig {
+    pub activation: Activation,
+    /// Whether `lhs @ rhs` is scaled by `alpha`
+    pub has_alpha: bool,
+    /// Whether `beta * c` is added, which is never the case when `beta` is zero
+    pub has_c: bool,
+    pub has_bias: bool,
+    pub has_residual: bool,
+    /// Scales of lhs, typically used to dequantize fp8 inputs
+    pub lhs_scale: Option<ScaleGranularity>,
+    /// Scales of rhs, typically used to dequantize fp8 inputs
+    pub rhs_scale: Option<ScaleGranularity>,
+}
+
+impl EpilogueConfig {
+    /// Whether the epilogue does anything, i.e. the output is not plain `lhs @ rhs`
+    pub fn is_identity(&self) -> bool {
+        matches!(self.activation, Activation::None)
+            && !self.has_alpha
+            && !self.has_c
+            && !self.has_bias
+            && !self.has_residual
+            && self.lhs_scale.is_none()
+            && self.rhs_scale.is_none()
+    }
+}
@@ -70,6 +70,9 @@ pub enum MatmulAvailabilityError {
 
     /// Plane operations like plane_sum are unavailable
     PlaneOpsUnavailable,
+
+    /// Atomic addition on `u32`, needed to schedule tiles with a work counter, is unavailable
+    AtomicUnavailable,
 }
 impl From<MatmulAvailabilityError> for MatmulSetupError {
     fn from(value: MatmulAvailabilityError) -> Self {
@@ -187,6 +190,9 @@ impl Debug for MatmulAvailabilityError {
             MatmulAvailabilityError::PlaneOpsUnavailable => {
                 writeln!(f, "Plane-wide operations like plane_sum are not available.")
             }
+            MatmulAvailabilityError::AtomicUnavailable => {
+                writeln!(f, "Atomic addition on u32 is not available.")
+            }
             MatmulAvailabilityError::TileSizeNotFound => {
                 writeln!(f, "No tile size is available for the problem.")
             }
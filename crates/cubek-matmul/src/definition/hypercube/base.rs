@@ -45,12 +45,16 @@ impl HypercubeBlueprint {
         HypercubeBlueprintBuilder::new(tiling_scheme)
     }
 
+    /// `max_persistent_cubes` is the number of cubes all SMs hold at once, used by the
+    /// [persistent](CubeCountPlanBlueprint::Persistent) plan. It falls back to spread without it.
     pub(crate) fn to_hypercube_config(
         &self,
         problem: &MatmulProblem,
         max_cube_count: CubeCount,
+        max_persistent_cubes: Option<u32>,
     ) -> HypercubeConfig {
-        let cube_count_plan = CubeCountPlan::from_selection(self, problem, max_cube_count);
+        let cube_count_plan =
+            CubeCountPlan::from_selection(self, problem, max_cube_count, max_persistent_cubes);
         let cube_count_plan_config = CubeCountPlanConfig::from_cube_count_plan(cube_count_plan);
 
         HypercubeConfig {
@@ -63,7 +67,7 @@ impl HypercubeBlueprint {
 
 impl HypercubeConfig {
     /// Returns an error if:
-    /// - The global order is swizzle but its assumptions are not met
+    /// - The global order is swizzle or hilbert but its assumptions are not met
     pub fn validate(&self, problem: &MatmulProblem) -> Result<(), MatmulSetupError> {
         let m_cubes = (problem.m as u32).div_ceil(self.cube_span.m);
         let n_cubes = (problem.n as u32).div_ceil(self.cube_span.n);
@@ -85,6 +89,10 @@ impl HypercubeConfig {
                 ))))
             }
 
+            Hilbert(b) if !b.is_power_of_two() => Err(MatmulSetupError::InvalidConfig(Box::new(
+                format!("In hilbert order, block size {b:?} must be a power of two."),
+            ))),
+
             _ => Ok(()),
         }
     }
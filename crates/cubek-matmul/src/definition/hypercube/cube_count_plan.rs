@@ -1,9 +1,12 @@
 use cubecl::prelude::*;
+use cubecl::server::Handle;
 
 use crate::definition::{
     MatmulProblem,
     hypercube::{
-        GlobalOrder, HypercubeBlueprint, HypercubeConfig, SmAllocation, global_order::swizzle,
+        GlobalOrder, HypercubeBlueprint, HypercubeConfig, SmAllocation, TileScheduler,
+        global_order::{hilbert, swizzle},
+        tile_scheduler::{next_tile_atomic, next_tile_static},
     },
 };
 
@@ -28,6 +31,15 @@ pub enum CubeCountPlanBlueprint {
 
     /// Heuristically find a balance for X, Y, Z that respects hardware limits
     Spread,
+
+    /// X: num SMs * num cubes per SM, each cube looping over output tiles until all are done
+    ///
+    /// The number of cubes each SM holds at once is derived from the shared memory and units
+    /// used by a cube. Falls back to spread when the device doesn't report its number of SMs.
+    ///
+    /// When the global matmul supports it, the first stage of the next tile is loaded before
+    /// writing the results of the current one, so the loads overlap with the epilogue.
+    Persistent { scheduler: TileScheduler },
 }
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -65,13 +77,25 @@ pub enum CubeCountPlan {
         y: u32,
         z: u32,
     },
+    Persistent {
+        num_cubes: u32,
+        m_cubes: u32,
+        n_cubes: u32,
+        batch_cubes: u32,
+        /// Number of cubes all SMs hold at once
+        max_cubes: u32,
+        scheduler: TileScheduler,
+    },
 }
 
 impl CubeCountPlan {
     /// Whether the CubeCount will have more cubes than strictly necessary.
     pub fn can_yield_extra_cubes(&self) -> bool {
         match self {
-            CubeCountPlan::FromProblem { .. } | CubeCountPlan::Flattened { .. } => false,
+            // Persistent cubes never exceed the number of tiles
+            CubeCountPlan::FromProblem { .. }
+            | CubeCountPlan::Flattened { .. }
+            | CubeCountPlan::Persistent { .. } => false,
             CubeCountPlan::Sm {
                 num_sms_used,
                 cubes_per_sm,
@@ -111,6 +135,11 @@ pub enum CubeCountPlanConfig {
     Spread {
         can_yield_extra_cubes: bool,
     },
+
+    Persistent {
+        max_cubes: u32,
+        scheduler: TileScheduler,
+    },
 }
 
 #[derive(CubeType, CubeLaunch)]
@@ -138,14 +167,29 @@ pub enum CubeCountInput {
         n_cubes: u32,
         batch_cubes: u32,
     },
+    PersistentStride {
+        m_cubes: u32,
+        n_cubes: u32,
+        batch_cubes: u32,
+    },
+    PersistentAtomic {
+        m_cubes: u32,
+        n_cubes: u32,
+        batch_cubes: u32,
+        /// Number of tiles handed out after the first one of each cube, starts at zero
+        counter: Array<Atomic<u32>>,
+    },
 }
 
 impl CubeCountPlan {
     // Will check if the wanted cube count plan is possible, otherwise will fallback to spread
+    //
+    // `max_persistent_cubes` is the number of cubes all SMs hold at once, if known.
     pub fn from_selection(
         selection: &HypercubeBlueprint,
         problem: &MatmulProblem,
         max_cube_count: CubeCount,
+        max_persistent_cubes: Option<u32>,
     ) -> CubeCountPlan {
         let (max_x, max_y, max_z) = match max_cube_count {
             CubeCount::Static(x, y, z) => (x, y, z),
@@ -205,6 +249,18 @@ impl CubeCountPlan {
                 }
             }
             CubeCountPlanBlueprint::Spread => None,
+            CubeCountPlanBlueprint::Persistent { scheduler } => {
+                max_persistent_cubes.map(|max_cubes| {
+                    persistent_cube_count_plan(
+                        m_cubes,
+                        n_cubes,
+                        batch_cubes,
+                        max_cubes,
+                        scheduler,
+                        max_x,
+                    )
+                })
+            }
         };
 
         plan.unwrap_or_else(|| {
@@ -262,6 +318,17 @@ impl CubeCountPlan {
             CubeCountPlanConfig::Spread { .. } => {
                 spread_cube_count_plan(m_cubes, n_cubes, batch_cubes, *max_x, *max_y, *max_z)
             }
+            CubeCountPlanConfig::Persistent {
+                max_cubes,
+                scheduler,
+            } => persistent_cube_count_plan(
+                m_cubes,
+                n_cubes,
+                batch_cubes,
+                max_cubes,
+                scheduler,
+                *max_x,
+            ),
         }
     }
 }
@@ -270,7 +337,9 @@ impl CubeCountPlanConfig {
     /// Whether the CubeCount will have more cubes than strictly necessary.
     pub fn can_yield_extra_cubes(&self) -> bool {
         match self {
-            CubeCountPlanConfig::FromProblem | CubeCountPlanConfig::Flattened => false,
+            CubeCountPlanConfig::FromProblem
+            | CubeCountPlanConfig::Flattened
+            | CubeCountPlanConfig::Persistent { .. } => false,
             CubeCountPlanConfig::Sm {
                 can_yield_extra_cubes,
                 ..
@@ -299,8 +368,57 @@ impl CubeCountPlanConfig {
             CubeCountPlan::Spread { .. } => CubeCountPlanConfig::Spread {
                 can_yield_extra_cubes: cube_count_plan.can_yield_extra_cubes(),
             },
+            CubeCountPlan::Persistent {
+                max_cubes,
+                scheduler,
+                ..
+            } => CubeCountPlanConfig::Persistent {
+                max_cubes,
+                scheduler,
+            },
         }
     }
+
+    /// Whether cubes loop over several output tiles.
+    pub fn is_persistent(&self) -> bool {
+        matches!(self, CubeCountPlanConfig::Persistent { .. })
+    }
+
+    /// Whether the launch needs a zeroed tile counter, see [CubeCountPlan::tile_counter].
+    pub fn needs_tile_counter(&self) -> bool {
+        matches!(
+            self,
+            CubeCountPlanConfig::Persistent {
+                scheduler: TileScheduler::AtomicCounter,
+                ..
+            }
+        )
+    }
+}
+
+/// Launch as many persistent cubes as the SMs can hold at once, but never more than there are
+/// output tiles.
+pub(crate) fn persistent_cube_count_plan(
+    m_cubes: u32,
+    n_cubes: u32,
+    batch_cubes: u32,
+    max_cubes: u32,
+    scheduler: TileScheduler,
+    max_x: u32,
+) -> CubeCountPlan {
+    let num_cubes = max_cubes
+        .min(m_cubes * n_cubes * batch_cubes)
+        .min(max_x)
+        .max(1);
+
+    CubeCountPlan::Persistent {
+        num_cubes,
+        m_cubes,
+        n_cubes,
+        batch_cubes,
+        max_cubes,
+        scheduler,
+    }
 }
 
 /// Heuristic algorithm to factor the total number of cubes into (x, y, z) dimensions
@@ -383,11 +501,29 @@ impl CubeCountPlan {
                 batch_cubes,
             } => CubeCount::Static(*m_cubes * *n_cubes * *batch_cubes, 1, 1),
             CubeCountPlan::Spread { x, y, z, .. } => CubeCount::Static(*x, *y, *z),
+            CubeCountPlan::Persistent { num_cubes, .. } => CubeCount::Static(*num_cubes, 1, 1),
+        }
+    }
+
+    /// Allocates the zeroed tile counter needed by persistent cubes scheduled with an atomic
+    /// counter, which must be kept alive until the kernel is launched.
+    pub fn tile_counter<R: Runtime>(&self, client: &ComputeClient<R>) -> Option<Handle> {
+        match self {
+            CubeCountPlan::Persistent {
+                scheduler: TileScheduler::AtomicCounter,
+                ..
+            } => Some(client.create_from_slice(u32::as_bytes(&[0]))),
+            _ => None,
         }
     }
 
     /// Make a CubeCountInput from CubeCountPlan
-    pub fn as_args<'a, R: Runtime>(&self) -> CubeCountInputArgs<'a, R> {
+    ///
+    /// The tile counter must come from [Self::tile_counter].
+    pub fn as_args<'a, R: Runtime>(
+        &self,
+        tile_counter: Option<&'a Handle>,
+    ) -> CubeCountInputArgs<'a, R> {
         match self {
             CubeCountPlan::FromProblem { .. } => CubeCountInputArgs::FromProblem,
             CubeCountPlan::Sm {
@@ -424,6 +560,31 @@ impl CubeCountPlan {
                 n_cubes: ScalarArg::new(*n_cubes),
                 batch_cubes: ScalarArg::new(*batch_cubes),
             },
+            CubeCountPlan::Persistent {
+                m_cubes,
+                n_cubes,
+                batch_cubes,
+                scheduler,
+                ..
+            } => match scheduler {
+                TileScheduler::StaticStride => CubeCountInputArgs::PersistentStride {
+                    m_cubes: ScalarArg::new(*m_cubes),
+                    n_cubes: ScalarArg::new(*n_cubes),
+                    batch_cubes: ScalarArg::new(*batch_cubes),
+                },
+                TileScheduler::AtomicCounter => CubeCountInputArgs::PersistentAtomic {
+                    m_cubes: ScalarArg::new(*m_cubes),
+                    n_cubes: ScalarArg::new(*n_cubes),
+                    batch_cubes: ScalarArg::new(*batch_cubes),
+                    counter: unsafe {
+                        ArrayArg::from_raw_parts::<u32>(
+                            tile_counter.expect("Atomic tile scheduling requires a tile counter"),
+                            1,
+                            1,
+                        )
+                    },
+                },
+            },
         }
     }
 }
@@ -451,6 +612,49 @@ impl CubeCountInput {
                 n_cubes,
                 batch_cubes,
             } => *m_cubes * *n_cubes * *batch_cubes,
+            CubeCountInput::PersistentStride {
+                m_cubes,
+                n_cubes,
+                batch_cubes,
+            } => *m_cubes * *n_cubes * *batch_cubes,
+            CubeCountInput::PersistentAtomic {
+                m_cubes,
+                n_cubes,
+                batch_cubes,
+                ..
+            } => *m_cubes * *n_cubes * *batch_cubes,
+        }
+    }
+
+    /// For persistent cubes, returns the tile to process after `current`.
+    ///
+    /// Tiles are numbered in the global order, so
+    /// [tile_to_tensor_pos](Self::tile_to_tensor_pos) gives their position.
+    /// `next_tile` is only used with an atomic counter, to broadcast the tile to the whole cube.
+    pub fn next_tile(&self, current: u32, next_tile: &mut SharedMemory<u32>) -> u32 {
+        match self {
+            CubeCountInput::PersistentStride { .. } => next_tile_static(current),
+            CubeCountInput::PersistentAtomic { counter, .. } => {
+                next_tile_atomic(counter, next_tile)
+            }
+            _ => panic!("Only persistent cubes can process more than one tile"),
+        }
+    }
+
+    /// Given the index of a tile, returns its tensor coordinates (m, n, batch).
+    pub fn tile_to_tensor_pos(
+        &self,
+        tile: u32,
+        #[comptime] global_order: GlobalOrder,
+    ) -> (u32, u32, u32) {
+        match self {
+            CubeCountInput::PersistentStride {
+                m_cubes, n_cubes, ..
+            } => self.absolute_index_to_m_n_batch(tile, *m_cubes, *n_cubes, global_order),
+            CubeCountInput::PersistentAtomic {
+                m_cubes, n_cubes, ..
+            } => self.absolute_index_to_m_n_batch(tile, *m_cubes, *n_cubes, global_order),
+            _ => panic!("Tiles are only indexed with persistent cubes"),
         }
     }
 
@@ -475,6 +679,12 @@ impl CubeCountInput {
             CubeCountInput::Spread {
                 m_cubes, n_cubes, ..
             } => self.absolute_index_to_m_n_batch(CUBE_POS, *m_cubes, *n_cubes, global_order),
+            CubeCountInput::PersistentStride {
+                m_cubes, n_cubes, ..
+            } => self.absolute_index_to_m_n_batch(CUBE_POS_X, *m_cubes, *n_cubes, global_order),
+            CubeCountInput::PersistentAtomic {
+                m_cubes, n_cubes, ..
+            } => self.absolute_index_to_m_n_batch(CUBE_POS_X, *m_cubes, *n_cubes, global_order),
         }
     }
 
@@ -497,6 +707,7 @@ impl CubeCountInput {
                 (y, x)
             }
             GlobalOrder::SwizzleColMajor(w) => swizzle(matrix_pos, m_cubes, w),
+            GlobalOrder::Hilbert(b) => hilbert(matrix_pos, m_cubes, n_cubes, b),
         };
 
         (m_pos, n_pos, batch_pos)
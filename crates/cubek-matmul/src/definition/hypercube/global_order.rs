@@ -10,10 +10,12 @@ use crate::definition::hypercube::base::CubeSpan;
 /// - `ColMajor`: standard column-first traversal
 /// - `SwizzleColMajor(w)`: zigzag pattern down columns, with `w`-wide steps
 /// - `SwizzleRowMajor(w)`: zigzag pattern across rows, with `w`-wide steps
+/// - `Hilbert(b)`: Hilbert curve within `b`-by-`b` blocks, blocks being visited in row-major order
 ///
 /// Special cases:
 /// - `SwizzleColMajor(1)` is equivalent to `ColMajor`
 /// - `SwizzleRowMajor(1)` is equivalent to `RowMajor`
+/// - `Hilbert(1)` is equivalent to `RowMajor`
 #[allow(clippy::enum_variant_names)]
 pub enum GlobalOrder {
     #[default]
@@ -21,16 +23,18 @@ pub enum GlobalOrder {
     ColMajor,
     SwizzleRowMajor(u32),
     SwizzleColMajor(u32),
+    Hilbert(u32),
 }
 
 impl GlobalOrder {
     /// Since they are equivalent but the latter form will skip some calculations,
     /// - `SwizzleColMajor(1)` becomes `ColMajor`
     /// - `SwizzleRowMajor(1)` becomes `RowMajor`
+    /// - `Hilbert(1)` becomes `RowMajor`
     pub fn canonicalize(self) -> Self {
         match self {
             GlobalOrder::SwizzleColMajor(1) => GlobalOrder::ColMajor,
-            GlobalOrder::SwizzleRowMajor(1) => GlobalOrder::RowMajor,
+            GlobalOrder::SwizzleRowMajor(1) | GlobalOrder::Hilbert(1) => GlobalOrder::RowMajor,
             _ => self,
         }
     }
@@ -128,3 +132,76 @@ pub fn swizzle(index: u32, num_steps: u32, #[comptime] step_length: u32) -> Coor
 
     (step_index, pos_in_step + strip_offset)
 }
+
+#[cube]
+/// Maps a linear `index` to `(row, col)` coordinates in a `rows` by `cols` grid.
+///
+/// The grid is cut into square blocks of side `block_size`, visited in row-major order.
+/// Inside a block, coordinates follow a Hilbert curve so that consecutive indices stay close
+/// along both dimensions. Blocks cut by the edges of the grid are traversed in row-major order.
+///
+/// # Parameters
+/// - `index`: linear input index
+/// - `rows`, `cols`: shape of the grid
+/// - `block_size`: side of the blocks, must be a power of two
+pub fn hilbert(index: u32, rows: u32, cols: u32, #[comptime] block_size: u32) -> Coords2d {
+    comptime!(assert!(block_size.is_power_of_two()));
+
+    // All blocks of a block row have the same number of rows
+    let elements_per_block_row = block_size * cols;
+    let block_row = index / elements_per_block_row;
+    let pos_in_block_row = index % elements_per_block_row;
+    let block_rows = Min::min(block_size, rows - block_row * block_size);
+
+    let elements_per_block = block_rows * block_size;
+    let block_col = pos_in_block_row / elements_per_block;
+    let pos_in_block = pos_in_block_row % elements_per_block;
+    let block_cols = Min::min(block_size, cols - block_col * block_size);
+
+    let mut row = pos_in_block / block_cols;
+    let mut col = pos_in_block % block_cols;
+
+    if block_rows == block_size && block_cols == block_size {
+        let (hilbert_row, hilbert_col) = hilbert_in_square(pos_in_block, block_size);
+        row = hilbert_row;
+        col = hilbert_col;
+    }
+
+    (block_row * block_size + row, block_col * block_size + col)
+}
+
+#[cube]
+/// Maps a linear `index` to its `(row, col)` coordinates along the Hilbert curve filling a
+/// square of side `side`, a power of two.
+fn hilbert_in_square(index: u32, #[comptime] side: u32) -> Coords2d {
+    let num_levels = comptime!(side.trailing_zeros());
+
+    let mut x = 0u32;
+    let mut y = 0u32;
+    let mut remaining = index;
+    let mut level_side = 1u32;
+
+    #[unroll]
+    for _ in 0..num_levels {
+        let rx = (remaining / 2) & 1;
+        let ry = (remaining ^ rx) & 1;
+
+        // Rotate the quadrant so that sub-curves connect
+        if ry == 0 {
+            if rx == 1 {
+                x = level_side - 1 - x;
+                y = level_side - 1 - y;
+            }
+            let tmp = x;
+            x = y;
+            y = tmp;
+        }
+
+        x += level_side * rx;
+        y += level_side * ry;
+        remaining /= 4;
+        level_side *= 2;
+    }
+
+    (y, x)
+}
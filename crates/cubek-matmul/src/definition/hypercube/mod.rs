@@ -2,6 +2,7 @@ mod base;
 mod cube_count_plan;
 mod global_order;
 mod sm_allocation;
+mod tile_scheduler;
 
 pub use base::{HypercubeBlueprint, HypercubeConfig};
 pub use cube_count_plan::{
@@ -10,3 +11,4 @@ pub use cube_count_plan::{
 pub use global_order::GlobalOrder;
 pub use global_order::GlobalOrderBlueprint;
 pub use sm_allocation::SmAllocation;
+pub use tile_scheduler::TileScheduler;
@@ -0,0 +1,39 @@
+use cubecl::prelude::*;
+
+#[derive(Default, Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// How persistent cubes pick the next output tile once they finish one.
+///
+/// In both cases, the cube at position `c` starts with tile `c`.
+pub enum TileScheduler {
+    #[default]
+    /// Cube `c` processes tiles `c`, `c + num_cubes`, `c + 2 * num_cubes`, ...
+    StaticStride,
+
+    /// Tiles are handed out by a global atomic counter, so cubes finishing early take
+    /// more tiles.
+    AtomicCounter,
+}
+
+#[cube]
+/// Returns the tile following `current` when scheduling with a static stride.
+pub(crate) fn next_tile_static(current: u32) -> u32 {
+    current + CUBE_COUNT
+}
+
+#[cube]
+/// Returns the next tile handed out by the atomic `counter`, broadcast to all units of the cube
+/// through `next_tile`.
+pub(crate) fn next_tile_atomic(
+    counter: &Array<Atomic<u32>>,
+    next_tile: &mut SharedMemory<u32>,
+) -> u32 {
+    // Make sure all units read the previous tile before overwriting it
+    sync_cube();
+
+    if UNIT_POS == 0 {
+        next_tile[0] = CUBE_COUNT + Atomic::add(&counter[0], 1u32);
+    }
+
+    sync_cube();
+    next_tile[0]
+}
@@ -1,5 +1,6 @@
 mod base;
 mod blueprint;
+mod epilogue;
 mod error;
 mod hypercube;
 mod line_size;
@@ -9,6 +10,7 @@ mod tiling_scheme;
 
 pub use base::*;
 pub use blueprint::*;
+pub use epilogue::*;
 pub use error::*;
 pub use hypercube::*;
 pub use line_size::*;
@@ -75,6 +75,23 @@ impl MatmulPrecision for bf16 {
     type Acc = (bf16, f32);
 }
 
+/// Fp8 inputs with the e4m3 format, computed natively where the hardware supports it,
+/// otherwise dequantized to f16 before the tile matmul.
+///
+/// Scales of the inputs are applied to the output by the epilogue.
+impl MatmulPrecision for e4m3 {
+    type Lhs = (e4m3, e4m3);
+    type Rhs = (e4m3, e4m3);
+    type Acc = (bf16, f32);
+}
+
+/// Fp8 inputs with the e5m2 format, see [e4m3].
+impl MatmulPrecision for e5m2 {
+    type Lhs = (e5m2, e5m2);
+    type Rhs = (e5m2, e5m2);
+    type Acc = (bf16, f32);
+}
+
 impl MatmulPrecision for f32 {
     type Lhs = (f32, f32);
     type Rhs = (f32, f32);
@@ -14,14 +14,17 @@ use cubecl::{server::TensorMapMeta, unexpanded};
 use crate::components::{
     batch::BatchConfig,
     global::memory::{
-        BatchLayout, BatchLayoutLaunch, GlobalLayout, GlobalLayoutConfig, GlobalLayoutLaunch,
-        GlobalScaleLayout, NoopLayout, NoopLayoutLaunch, SimpleTmaGlobalLayout,
+        BatchLayout, BatchLayoutLaunch, BiasLayout, BiasLayoutLaunch, GlobalLayout,
+        GlobalLayoutConfig, GlobalLayoutLaunch, GlobalScaleLayout, NoopLayout, NoopLayoutLaunch,
+        OperandScaleLayout, OperandScaleLayoutLaunch, SimpleTmaGlobalLayout,
         SimpleTmaGlobalLayoutLaunch,
     },
     stage::SwizzleMode,
 };
-use crate::definition::{self, MatmulElems, MatmulLineSizes, MatmulProblem, TilingBlueprint};
-use crate::launch::handle::MatmulInputHandleRef;
+use crate::definition::{
+    self, MatmulElems, MatmulLineSizes, MatmulProblem, ScaleGranularity, TilingBlueprint,
+};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
 use crate::routines::Routine;
 
 /// Input argument
@@ -45,6 +48,7 @@ pub trait ConcreteInputsFactory<A: Routine>: LaunchArg {
         client: &ComputeClient<R>,
         lhs: &'a MatmulInputHandleRef<'a, R>,
         rhs: &'a MatmulInputHandleRef<'a, R>,
+        epilogue: &'a MatmulEpilogueHandleRef<'a, R>,
         blueprint: &A::Blueprint,
         problem: &MatmulProblem,
         line_sizes: &MatmulLineSizes,
@@ -60,6 +64,7 @@ pub trait ConcreteOutputFactory<A: Routine>: LaunchArg {
     fn create<'a, R: Runtime>(
         client: &ComputeClient<R>,
         out: &'a TensorHandleRef<'a, R>,
+        epilogue: &'a MatmulEpilogueHandleRef<'a, R>,
         blueprint: &A::Blueprint,
         problem: &MatmulProblem,
         line_sizes: &MatmulLineSizes,
@@ -134,6 +139,44 @@ pub trait MatmulArgs: Send + Sync + 'static + Clone {
     ) -> u32 {
         unexpanded!()
     }
+    /// Bias added by the epilogue, broadcast along rows and batches
+    fn view_bias<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        _state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        CubeOption::new_None()
+    }
+    /// Residual added by the epilogue, with the same shape as the output
+    fn view_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        _state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        CubeOption::new_None()
+    }
+    fn batch_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        _state: &Self::State<Lhs, Rhs, EO>,
+        batch: u32,
+    ) -> u32 {
+        batch
+    }
+    /// Scales of lhs applied by the epilogue, broadcast along columns and batches
+    fn view_lhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        _state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        CubeOption::new_None()
+    }
+    /// Scales of rhs applied by the epilogue, broadcast along rows and batches
+    fn view_rhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        _state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        CubeOption::new_None()
+    }
+    /// Scale applied to `lhs @ rhs` by the epilogue
+    fn alpha<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(_state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        1.0f32.runtime()
+    }
+    /// Scale applied to the accumulator input by the epilogue
+    fn beta<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(_state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        0.0f32.runtime()
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -158,7 +201,7 @@ pub struct TensorInputs<Lhs: Numeric, Rhs: Numeric, Acc: Numeric> {
     /// The rhs tensor.
     rhs: View<Line<Rhs>, Coords3d>,
     rhs_batch: VirtualLayout<Coords1d, Coords1d>,
-    /// The tensor for loading the accumulator, if present
+    /// The `c` tensor added to the output by the epilogue, scaled by `beta`, if present
     acc: CubeOption<View<Line<Acc>, Coords3d>>,
     acc_batch: CubeOption<VirtualLayout<Coords1d, Coords1d>>,
 }
@@ -170,6 +213,7 @@ impl<Lhs: Numeric, Rhs: Numeric, Acc: Numeric, A: Routine> ConcreteInputsFactory
         client: &ComputeClient<R>,
         lhs: &'a MatmulInputHandleRef<'a, R>,
         rhs: &'a MatmulInputHandleRef<'a, R>,
+        epilogue: &'a MatmulEpilogueHandleRef<'a, R>,
         _blueprint: &A::Blueprint,
         problem: &MatmulProblem,
         line_sizes: &MatmulLineSizes,
@@ -199,9 +243,15 @@ impl<Lhs: Numeric, Rhs: Numeric, Acc: Numeric, A: Routine> ConcreteInputsFactory
                     ViewArg::new::<GlobalScaleLayout>(scale.as_array_arg(1), scales_layout);
                 ViewArg::new_quantized(data_view, scales_view, **scheme)
             }
+            // Only the kept values are viewed, expanding them is left to the matmul
+            MatmulInputHandleRef::Sparse24 { values, .. } => {
+                let layout = GlobalLayoutLaunch::from_handle(values, line_size, config);
+                ViewArg::new::<GlobalLayout>(values.as_array_arg(line_size), layout)
+            }
         };
         let batch_layout = |handle: &'a MatmulInputHandleRef<'a, R>| match handle {
-            MatmulInputHandleRef::Normal(handle, _dtype) => {
+            MatmulInputHandleRef::Normal(handle, _dtype)
+            | MatmulInputHandleRef::Sparse24 { values: handle, .. } => {
                 let layout = BatchLayoutLaunch::from_handle(client, handle, problem);
                 VirtualLayoutLaunch::new::<BatchLayout>(layout)
             }
@@ -210,13 +260,32 @@ impl<Lhs: Numeric, Rhs: Numeric, Acc: Numeric, A: Routine> ConcreteInputsFactory
             }
         };
 
+        let (acc, acc_batch) = match epilogue.scaled_c() {
+            Some(c) => {
+                let layout = GlobalLayoutLaunch::from_handle(
+                    c,
+                    line_sizes.out,
+                    config.out_global_layout_config(),
+                );
+                let batch = BatchLayoutLaunch::from_handle(client, c, problem);
+                (
+                    CubeOptionArgs::Some(ViewArg::new::<GlobalLayout>(
+                        c.as_array_arg(line_sizes.out),
+                        layout,
+                    )),
+                    CubeOptionArgs::Some(VirtualLayoutLaunch::new::<BatchLayout>(batch)),
+                )
+            }
+            None => (CubeOptionArgs::None, CubeOptionArgs::None),
+        };
+
         TensorInputsLaunch::new(
             view(lhs, config.lhs_global_layout_config(), line_sizes.lhs),
             batch_layout(lhs),
             view(rhs, config.rhs_global_layout_config(), line_sizes.rhs),
             batch_layout(rhs),
-            CubeOptionArgs::None,
-            CubeOptionArgs::None,
+            acc,
+            acc_batch,
         )
     }
 }
@@ -225,23 +294,99 @@ impl<Lhs: Numeric, Rhs: Numeric, Acc: Numeric, A: Routine> ConcreteInputsFactory
 pub struct TensorOutput<EG: Numeric> {
     view: View<Line<EG>, Coords3d, ReadWrite>,
     batch: VirtualLayout<Coords1d, Coords1d>,
+    /// The bias read by the epilogue, if present
+    bias: CubeOption<View<Line<EG>, Coords3d>>,
+    /// The residual read by the epilogue, if present
+    residual: CubeOption<View<Line<EG>, Coords3d>>,
+    residual_batch: CubeOption<VirtualLayout<Coords1d, Coords1d>>,
+    /// The scales of lhs read by the epilogue, if present
+    lhs_scale: CubeOption<View<Line<f32>, Coords3d>>,
+    /// The scales of rhs read by the epilogue, if present
+    rhs_scale: CubeOption<View<Line<f32>, Coords3d>>,
+    /// Scale of `lhs @ rhs`
+    alpha: f32,
+    /// Scale of the accumulator input
+    beta: f32,
 }
 
 impl<EG: Numeric, A: Routine> ConcreteOutputFactory<A> for TensorOutput<EG> {
     fn create<'a, R: Runtime>(
         client: &ComputeClient<R>,
         out: &'a TensorHandleRef<'a, R>,
+        epilogue: &'a MatmulEpilogueHandleRef<'a, R>,
         _blueprint: &A::Blueprint,
         problem: &MatmulProblem,
         line_sizes: &MatmulLineSizes,
         config: A::Config,
         _dtypes: &MatmulElems,
     ) -> Self::RuntimeArg<'a, R> {
-        let layout =
-            GlobalLayoutLaunch::from_handle(out, line_sizes.out, config.out_global_layout_config());
-        let batch = BatchLayoutLaunch::from_handle(client, out, problem);
-        let view = ViewArg::new::<GlobalLayout>(out.as_array_arg(line_sizes.out), layout);
-        TensorOutputLaunch::new(view, VirtualLayoutLaunch::new::<BatchLayout>(batch))
+        let view = |handle: &'a TensorHandleRef<'a, R>| {
+            let layout = GlobalLayoutLaunch::from_handle(
+                handle,
+                line_sizes.out,
+                config.out_global_layout_config(),
+            );
+            ViewArg::new::<GlobalLayout>(handle.as_array_arg(line_sizes.out), layout)
+        };
+        let batch_layout = |handle: &'a TensorHandleRef<'a, R>| {
+            let layout = BatchLayoutLaunch::from_handle(client, handle, problem);
+            VirtualLayoutLaunch::new::<BatchLayout>(layout)
+        };
+
+        let bias = match &epilogue.bias {
+            Some(bias) => {
+                let layout = BiasLayoutLaunch::from_handle(bias, line_sizes.out);
+                CubeOptionArgs::Some(ViewArg::new::<BiasLayout>(
+                    bias.as_array_arg(line_sizes.out),
+                    layout,
+                ))
+            }
+            None => CubeOptionArgs::None,
+        };
+        let (residual, residual_batch) = match &epilogue.residual {
+            Some(residual) => (
+                CubeOptionArgs::Some(view(residual)),
+                CubeOptionArgs::Some(batch_layout(residual)),
+            ),
+            None => (CubeOptionArgs::None, CubeOptionArgs::None),
+        };
+
+        let epilogue_config = epilogue.config();
+        let scale = |handle: &'a Option<TensorHandleRef<'a, R>>, granularity, per_row| {
+            match (handle, granularity) {
+                (Some(handle), Some(granularity)) => {
+                    // Channel scales of rhs are read with the output line size, others are
+                    // broadcast from a single element
+                    let line_size = match (granularity, per_row) {
+                        (ScaleGranularity::Channel, false) => line_sizes.out,
+                        _ => 1,
+                    };
+                    let layout = OperandScaleLayoutLaunch::from_handle(
+                        handle,
+                        granularity,
+                        per_row,
+                        line_size,
+                    );
+                    CubeOptionArgs::Some(ViewArg::new::<OperandScaleLayout>(
+                        handle.as_array_arg(line_size),
+                        layout,
+                    ))
+                }
+                _ => CubeOptionArgs::None,
+            }
+        };
+
+        TensorOutputLaunch::new(
+            view(out),
+            batch_layout(out),
+            bias,
+            residual,
+            residual_batch,
+            scale(&epilogue.lhs_scale, epilogue_config.lhs_scale, true),
+            scale(&epilogue.rhs_scale, epilogue_config.rhs_scale, false),
+            ScalarArg::new(epilogue.alpha),
+            ScalarArg::new(epilogue.beta),
+        )
     }
 }
 
@@ -316,6 +461,48 @@ impl MatmulArgs for TensorArgs {
     ) -> u32 {
         state.1.batch.to_source_pos(batch)
     }
+
+    fn view_bias<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        state.1.bias
+    }
+
+    fn view_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        state.1.residual
+    }
+
+    fn batch_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+        batch: u32,
+    ) -> u32 {
+        match state.1.residual_batch {
+            CubeOption::Some(layout) => layout.to_source_pos(batch),
+            CubeOption::None => batch,
+        }
+    }
+
+    fn view_lhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        state.1.lhs_scale
+    }
+
+    fn view_rhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        state.1.rhs_scale
+    }
+
+    fn alpha<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        state.1.alpha
+    }
+
+    fn beta<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        state.1.beta
+    }
 }
 
 #[derive(Clone)]
@@ -331,9 +518,9 @@ pub struct TensorMapInputs<Lhs: Numeric, Rhs: Numeric, EO: Numeric> {
     pub lhs: View<Line<Lhs>, Coords3d>,
     /// The rhs tensor.
     pub rhs: View<Line<Rhs>, Coords3d>,
-    /// The accumulator
+    /// The `c` tensor added to the output by the epilogue, scaled by `beta`
     pub acc: CubeOption<View<Line<EO>, Coords3d>>,
-    /// The accumulator batch layout
+    /// The `c` batch layout
     pub acc_batch: CubeOption<VirtualLayout<Coords1d, Coords1d>>,
 }
 
@@ -341,13 +528,14 @@ impl<Lhs: Numeric, Rhs: Numeric, EO: Numeric, A: Routine<Blueprint = TilingBluep
     ConcreteInputsFactory<A> for TensorMapInputs<Lhs, Rhs, EO>
 {
     fn create<'a, R: Runtime>(
-        _client: &ComputeClient<R>,
+        client: &ComputeClient<R>,
         lhs_handle: &'a MatmulInputHandleRef<'a, R>,
         rhs_handle: &'a MatmulInputHandleRef<'a, R>,
+        epilogue: &'a MatmulEpilogueHandleRef<'a, R>,
         blueprint: &A::Blueprint,
         problem: &MatmulProblem,
         line_sizes: &MatmulLineSizes,
-        _config: A::Config,
+        config: A::Config,
         dtypes: &MatmulElems,
     ) -> Self::RuntimeArg<'a, R> {
         let lhs = lhs_handle.data();
@@ -534,11 +722,31 @@ impl<Lhs: Numeric, Rhs: Numeric, EO: Numeric, A: Routine<Blueprint = TilingBluep
             ViewArg::new_tensor_map_tiled::<SimpleTmaGlobalLayout>(buffer, layout)
         };
 
+        // `c` is only read by the epilogue, so it doesn't need a tensor map
+        let (acc, acc_batch) = match epilogue.scaled_c() {
+            Some(c) => {
+                let layout = GlobalLayoutLaunch::from_handle(
+                    c,
+                    line_sizes.out,
+                    config.out_global_layout_config(),
+                );
+                let batch = BatchLayoutLaunch::from_handle(client, c, problem);
+                (
+                    CubeOptionArgs::Some(ViewArg::new::<GlobalLayout>(
+                        c.as_array_arg(line_sizes.out),
+                        layout,
+                    )),
+                    CubeOptionArgs::Some(VirtualLayoutLaunch::new::<BatchLayout>(batch)),
+                )
+            }
+            None => (CubeOptionArgs::None, CubeOptionArgs::None),
+        };
+
         TensorMapInputsLaunch::new(
             view(lhs, &lhs_shape, lhs_transposed),
             view(rhs, &rhs_shape, rhs_transposed),
-            CubeOptionArgs::None,
-            CubeOptionArgs::None,
+            acc,
+            acc_batch,
         )
     }
 }
@@ -614,4 +822,46 @@ impl MatmulArgs for TensorMapArgs {
     ) -> u32 {
         state.1.batch.to_source_pos(batch)
     }
+
+    fn view_bias<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        state.1.bias
+    }
+
+    fn view_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<EO>, Coords3d>> {
+        state.1.residual
+    }
+
+    fn batch_residual<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+        batch: u32,
+    ) -> u32 {
+        match state.1.residual_batch {
+            CubeOption::Some(layout) => layout.to_source_pos(batch),
+            CubeOption::None => batch,
+        }
+    }
+
+    fn view_lhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        state.1.lhs_scale
+    }
+
+    fn view_rhs_scale<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(
+        state: &Self::State<Lhs, Rhs, EO>,
+    ) -> CubeOption<View<Line<f32>, Coords3d>> {
+        state.1.rhs_scale
+    }
+
+    fn alpha<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        state.1.alpha
+    }
+
+    fn beta<Lhs: Numeric, Rhs: Numeric, EO: Numeric>(state: &Self::State<Lhs, Rhs, EO>) -> f32 {
+        state.1.beta
+    }
 }
@@ -1,8 +1,12 @@
-use cubecl::{Runtime, client::ComputeClient, prelude::TensorHandleRef};
+use cubecl::{
+    Runtime,
+    client::ComputeClient,
+    prelude::{CubeElement, TensorHandleRef},
+};
 
 use cubecl::std::tensor::TensorHandle;
 
-use crate::launch::handle::{MatmulInputHandle, MatmulInputHandleRef};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandle, MatmulInputHandleRef};
 use crate::{
     definition::{MatmulElems, MatmulSetupError},
     launch::Strategy,
@@ -43,5 +47,146 @@ pub fn launch_ref<R: Runtime>(
     out: &TensorHandleRef<R>,
     dtypes: &mut MatmulElems,
 ) -> Result<(), MatmulSetupError> {
-    strategy.launch_ref(client, lhs, rhs, out, dtypes)
+    launch_ref_with_epilogue(
+        strategy,
+        client,
+        lhs,
+        rhs,
+        out,
+        &MatmulEpilogueHandleRef::none(),
+        dtypes,
+    )
+}
+
+#[allow(clippy::result_large_err)]
+/// Launches a matrix multiplication kernel, fusing the given epilogue before storing the output.
+///
+/// The output is computed as `activation(alpha * (lhs @ rhs) + beta * c + bias) + residual`.
+///
+/// # Notes
+///
+/// Not supported by the naive strategy.
+pub fn launch_ref_with_epilogue<R: Runtime>(
+    strategy: &Strategy,
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<R>,
+    rhs: &MatmulInputHandleRef<R>,
+    out: &TensorHandleRef<R>,
+    epilogue: &MatmulEpilogueHandleRef<R>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    strategy.launch_ref(client, lhs, rhs, out, epilogue, dtypes)
+}
+
+#[allow(clippy::result_large_err)]
+/// Launches a grouped matrix multiplication kernel, as used by mixture-of-experts layers.
+///
+/// The rows of `lhs` (`[total_rows, k]`) and `out` (`[total_rows, n]`) are split into groups,
+/// with group `g` covering rows `group_offsets[g - 1]..group_offsets[g]` and multiplied with
+/// `rhs[g]` (`[num_groups, k, n]`). `group_offsets` is a `u32` tensor of the cumulative group
+/// sizes.
+///
+/// # Notes
+///
+/// Not supported by the naive and TMA strategies. The offsets aren't validated, use
+/// [check_group_offsets] when debugging.
+pub fn launch_ref_grouped<R: Runtime>(
+    strategy: &Strategy,
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<R>,
+    rhs: &MatmulInputHandleRef<R>,
+    group_offsets: &TensorHandleRef<R>,
+    out: &TensorHandleRef<R>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    strategy.launch_ref_grouped(client, lhs, rhs, group_offsets, out, dtypes)
+}
+
+#[allow(clippy::result_large_err)]
+/// Checks that the offsets of a grouped matmul are non-decreasing and at most `total_rows`.
+///
+/// This reads the offsets back to the host, which synchronizes with the device, so it is meant
+/// as a debug check and isn't done by [launch_ref_grouped].
+pub fn check_group_offsets<R: Runtime>(
+    client: &ComputeClient<R>,
+    group_offsets: &TensorHandleRef<R>,
+    total_rows: usize,
+) -> Result<(), MatmulSetupError> {
+    if group_offsets.shape.len() != 1 || group_offsets.elem_size != size_of::<u32>() {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects u32 group offsets of shape [num_groups]",
+        )));
+    }
+
+    let offsets = client.read_one_tensor(group_offsets.handle.copy_descriptor(
+        group_offsets.shape,
+        group_offsets.strides,
+        group_offsets.elem_size,
+    ));
+    let offsets = u32::from_bytes(&offsets);
+    let mut group_start = 0;
+    for &group_end in offsets {
+        if group_end < group_start || group_end as usize > total_rows {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul expects non-decreasing group offsets of at most the number of rows",
+            )));
+        }
+        group_start = group_end;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+/// Launches a matrix multiplication kernel where `rhs` is block quantized (i.e. MX or NVFP4
+/// formats), applying the block scales in the tile matmul rather than when reading `rhs`.
+///
+/// # Notes
+///
+/// Only supported by the simple unit strategy. Scales may be floats, or `ue8m0`/`ue4m3` bytes as
+/// used by MX and NVFP4, which are decoded to `f32` by the rhs reader when staged.
+pub fn launch_ref_block_scaled<R: Runtime>(
+    strategy: &Strategy,
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<R>,
+    rhs: &MatmulInputHandleRef<R>,
+    out: &TensorHandleRef<R>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    strategy.launch_ref_block_scaled(client, lhs, rhs, out, dtypes)
+}
+
+#[allow(clippy::result_large_err)]
+/// Launches a matrix multiplication kernel where `rhs` is 2:4 structured sparse along k, given
+/// as its kept values and their metadata (see [MatmulInputHandle::Sparse24]).
+///
+/// # Notes
+///
+/// Only supported by the simple unit strategy, with a row major `rhs`.
+pub fn launch_ref_sparse_24<R: Runtime>(
+    strategy: &Strategy,
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<R>,
+    rhs: &MatmulInputHandleRef<R>,
+    out: &TensorHandleRef<R>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    strategy.launch_ref_sparse_24(client, lhs, rhs, out, dtypes)
+}
+
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
+/// Launches a matrix multiplication kernel accumulating into the output, computing
+/// `out = alpha * (lhs @ rhs) + beta * out`.
+pub fn launch_ref_accumulate<R: Runtime>(
+    strategy: &Strategy,
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<R>,
+    rhs: &MatmulInputHandleRef<R>,
+    out: &TensorHandleRef<R>,
+    alpha: f32,
+    beta: f32,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    let epilogue = MatmulEpilogueHandleRef::none().with_scaled_c(alpha, beta, *out);
+    launch_ref_with_epilogue(strategy, client, lhs, rhs, out, &epilogue, dtypes)
 }
@@ -7,6 +7,10 @@ use cubecl::{
 };
 use cubecl_common::quant::scheme::{QuantScheme, QuantStore, QuantValue};
 
+use crate::definition::{
+    Activation, EpilogueConfig, MatmulProblem, MatmulSetupError, MatrixLayout, ScaleGranularity,
+};
+
 use cubecl::std::tensor::{TensorHandle, into_contiguous_packed, into_contiguous_pitched};
 
 pub enum MatmulInputHandle<R: Runtime> {
@@ -17,6 +21,12 @@ pub enum MatmulInputHandle<R: Runtime> {
         shape: Vec<usize>,
         scheme: QuantScheme,
     },
+    /// 2:4 structured sparse operand, see [MatmulInputHandleRef::Sparse24]
+    Sparse24 {
+        values: TensorHandle<R>,
+        metadata: TensorHandle<R>,
+        shape: Vec<usize>,
+    },
 }
 
 impl<R: Runtime> MatmulInputHandle<R> {
@@ -38,6 +48,16 @@ impl<R: Runtime> MatmulInputHandle<R> {
                 shape,
                 scheme,
             },
+            MatmulInputHandle::Sparse24 {
+                values,
+                metadata,
+                shape,
+            } => MatmulInputHandleRef::Sparse24 {
+                values: values.as_ref(),
+                values_dtype: values.dtype,
+                metadata: metadata.as_ref(),
+                shape,
+            },
         }
     }
 
@@ -59,6 +79,16 @@ impl<R: Runtime> MatmulInputHandle<R> {
                 shape: shape.to_vec(),
                 scheme: **scheme,
             },
+            MatmulInputHandleRef::Sparse24 {
+                values,
+                values_dtype,
+                metadata,
+                shape,
+            } => MatmulInputHandle::Sparse24 {
+                values: TensorHandle::from_ref(values, *values_dtype),
+                metadata: TensorHandle::from_ref(metadata, u32::as_type_native_unchecked()),
+                shape: shape.to_vec(),
+            },
         }
     }
 
@@ -66,6 +96,7 @@ impl<R: Runtime> MatmulInputHandle<R> {
         match self {
             MatmulInputHandle::Normal(handle) => handle,
             MatmulInputHandle::Quantized { data, .. } => data,
+            MatmulInputHandle::Sparse24 { values, .. } => values,
         }
     }
 
@@ -86,6 +117,17 @@ impl<R: Runtime> MatmulInputHandle<R> {
                 }
                 shape.swap(dim0, dim1);
             }
+            MatmulInputHandle::Sparse24 {
+                values,
+                metadata,
+                shape,
+            } => {
+                values.shape.swap(dim0, dim1);
+                values.strides.swap(dim0, dim1);
+                metadata.shape.swap(dim0, dim1);
+                metadata.strides.swap(dim0, dim1);
+                shape.swap(dim0, dim1);
+            }
         }
     }
 }
@@ -105,6 +147,15 @@ impl<R: Runtime> Clone for MatmulInputHandle<R> {
                 shape: shape.clone(),
                 scheme: *scheme,
             },
+            Self::Sparse24 {
+                values,
+                metadata,
+                shape,
+            } => Self::Sparse24 {
+                values: values.clone(),
+                metadata: metadata.clone(),
+                shape: shape.clone(),
+            },
         }
     }
 }
@@ -121,6 +172,18 @@ pub enum MatmulInputHandleRef<'a, R: Runtime> {
         shape: &'a [usize],
         scheme: &'a QuantScheme,
     },
+    /// Operand pruned with 2:4 structured sparsity along the reduction dimension, so at most two
+    /// of every four consecutive elements along k are non-zero.
+    Sparse24 {
+        /// The kept elements, with the reduction dimension halved
+        values: TensorHandleRef<'a, R>,
+        values_dtype: StorageType,
+        /// `u32` tensor with one element per group of four along k, holding the positions of
+        /// the two kept elements within the group in its two lowest 2-bit fields
+        metadata: TensorHandleRef<'a, R>,
+        /// Dense shape
+        shape: &'a [usize],
+    },
 }
 
 impl<'a, R: Runtime> Clone for MatmulInputHandleRef<'a, R> {
@@ -154,38 +217,74 @@ impl<'a, R: Runtime> MatmulInputHandleRef<'a, R> {
         }
     }
 
+    /// Create a 2:4 sparse operand from its kept values and their positions
+    pub fn sparse_24(
+        values: TensorHandleRef<'a, R>,
+        metadata: TensorHandleRef<'a, R>,
+        shape: &'a [usize],
+        values_dtype: StorageType,
+    ) -> Self {
+        Self::Sparse24 {
+            values,
+            values_dtype,
+            metadata,
+            shape,
+        }
+    }
+
     pub fn data(&self) -> &TensorHandleRef<'a, R> {
         match self {
             MatmulInputHandleRef::Normal(handle, ..) => handle,
             MatmulInputHandleRef::Quantized { data, .. } => data,
+            MatmulInputHandleRef::Sparse24 { values, .. } => values,
         }
     }
 
+    /// Whether the matrices of the data must be made contiguous before being read by a tiled
+    /// matmul, which is the case when neither of their two last dims is contiguous.
+    ///
+    /// Batch dims are read through their strides, so they may be permuted or broadcast with a
+    /// stride of zero without requiring a copy.
+    pub fn needs_contiguous(&self) -> bool {
+        let data = self.data();
+        MatrixLayout::try_from_shape_and_strides(data.shape, data.strides).is_none()
+    }
+
     pub fn data_mut(&mut self) -> &mut TensorHandleRef<'a, R> {
         match self {
             MatmulInputHandleRef::Normal(handle, ..) => handle,
             MatmulInputHandleRef::Quantized { data, .. } => data,
+            MatmulInputHandleRef::Sparse24 { values, .. } => values,
         }
     }
 
     pub fn scale(&self) -> Option<&TensorHandleRef<'a, R>> {
         match self {
-            MatmulInputHandleRef::Normal(..) => None,
+            MatmulInputHandleRef::Normal(..) | MatmulInputHandleRef::Sparse24 { .. } => None,
             MatmulInputHandleRef::Quantized { scale, .. } => Some(scale),
         }
     }
 
     pub fn scheme(&self) -> Option<&QuantScheme> {
         match self {
-            MatmulInputHandleRef::Normal(..) => None,
+            MatmulInputHandleRef::Normal(..) | MatmulInputHandleRef::Sparse24 { .. } => None,
             MatmulInputHandleRef::Quantized { scheme, .. } => Some(scheme),
         }
     }
 
+    /// The metadata of a 2:4 sparse operand
+    pub fn sparse_metadata(&self) -> Option<&TensorHandleRef<'a, R>> {
+        match self {
+            MatmulInputHandleRef::Normal(..) | MatmulInputHandleRef::Quantized { .. } => None,
+            MatmulInputHandleRef::Sparse24 { metadata, .. } => Some(metadata),
+        }
+    }
+
     pub fn shape(&self) -> &[usize] {
         match self {
             MatmulInputHandleRef::Normal(handle, ..) => handle.shape,
             MatmulInputHandleRef::Quantized { shape, .. } => shape,
+            MatmulInputHandleRef::Sparse24 { shape, .. } => shape,
         }
     }
 
@@ -238,8 +337,178 @@ impl<'a, R: Runtime> MatmulInputHandleRef<'a, R> {
                     scheme: **scheme,
                 }
             }
+            MatmulInputHandleRef::Sparse24 {
+                values,
+                values_dtype,
+                metadata,
+                shape,
+            } => MatmulInputHandle::Sparse24 {
+                values: into_contiguous_pitched(client, values, *values_dtype)?,
+                metadata: into_contiguous_pitched(
+                    client,
+                    metadata,
+                    u32::as_type_native_unchecked(),
+                )?,
+                shape: shape.to_vec(),
+            },
         };
 
         Ok(val)
     }
 }
+
+#[derive(Debug)]
+/// Tensors and activation fused after the matmul, see [EpilogueConfig].
+pub struct MatmulEpilogueHandleRef<'a, R: Runtime> {
+    pub activation: Activation,
+    /// Scale of `lhs @ rhs`
+    pub alpha: f32,
+    /// Scale of `c`
+    pub beta: f32,
+    /// Tensor with the same shape as the output, scaled by `beta` and added to `alpha * (lhs @ rhs)`.
+    /// May be the output itself to accumulate into it. Not read when `beta` is zero.
+    pub c: Option<TensorHandleRef<'a, R>>,
+    /// Bias of shape `[n]`, broadcast to every row of the output
+    pub bias: Option<TensorHandleRef<'a, R>>,
+    /// Residual with the same shape as the output
+    pub residual: Option<TensorHandleRef<'a, R>>,
+    /// `f32` scales of lhs, with a single element or one per row of the output
+    pub lhs_scale: Option<TensorHandleRef<'a, R>>,
+    /// `f32` scales of rhs, with a single element or one per column of the output
+    pub rhs_scale: Option<TensorHandleRef<'a, R>>,
+}
+
+impl<'a, R: Runtime> Clone for MatmulEpilogueHandleRef<'a, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, R: Runtime> Copy for MatmulEpilogueHandleRef<'a, R> {}
+
+impl<'a, R: Runtime> Default for MatmulEpilogueHandleRef<'a, R> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<'a, R: Runtime> MatmulEpilogueHandleRef<'a, R> {
+    /// No epilogue, the output is plain `lhs @ rhs`
+    pub fn none() -> Self {
+        Self {
+            activation: Activation::None,
+            alpha: 1.0,
+            beta: 0.0,
+            c: None,
+            bias: None,
+            residual: None,
+            lhs_scale: None,
+            rhs_scale: None,
+        }
+    }
+
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    /// Compute `alpha * (lhs @ rhs) + beta * c`
+    pub fn with_scaled_c(mut self, alpha: f32, beta: f32, c: TensorHandleRef<'a, R>) -> Self {
+        self.alpha = alpha;
+        self.beta = beta;
+        self.c = Some(c);
+        self
+    }
+
+    pub fn with_bias(mut self, bias: TensorHandleRef<'a, R>) -> Self {
+        self.bias = Some(bias);
+        self
+    }
+
+    pub fn with_residual(mut self, residual: TensorHandleRef<'a, R>) -> Self {
+        self.residual = Some(residual);
+        self
+    }
+
+    /// Scale `lhs @ rhs` by the scales of the operands, as used to dequantize fp8 inputs.
+    ///
+    /// Each scale tensor holds either a single element for the whole operand, or one element
+    /// per row of lhs or per column of rhs.
+    pub fn with_scales(
+        mut self,
+        lhs_scale: Option<TensorHandleRef<'a, R>>,
+        rhs_scale: Option<TensorHandleRef<'a, R>>,
+    ) -> Self {
+        self.lhs_scale = lhs_scale;
+        self.rhs_scale = rhs_scale;
+        self
+    }
+
+    /// The `c` tensor actually read by the epilogue.
+    ///
+    /// Following BLAS, `c` is ignored when `beta` is zero, so that NaN or infinite values in an
+    /// uninitialized `c` (or output, when accumulating into it) don't leak into the result.
+    pub fn scaled_c(&self) -> Option<&TensorHandleRef<'a, R>> {
+        self.c.as_ref().filter(|_| self.beta != 0.0)
+    }
+
+    /// Check that each scale tensor holds `f32` values with a shape of `[1]`, or `[m]` for lhs
+    /// and `[n]` for rhs.
+    #[allow(clippy::result_large_err)]
+    pub fn check_scales(&self, problem: &MatmulProblem) -> Result<(), MatmulSetupError> {
+        let check = |scale: &Option<TensorHandleRef<'a, R>>,
+                     channels: usize,
+                     message: &'static str| match scale {
+            Some(scale)
+                if scale.elem_size != size_of::<f32>()
+                    || (scale.shape != [1] && scale.shape != [channels]) =>
+            {
+                Err(MatmulSetupError::InvalidConfig(Box::new(message)))
+            }
+            _ => Ok(()),
+        };
+
+        check(
+            &self.lhs_scale,
+            problem.m,
+            "Lhs scales must be f32 with a shape of [1] or [m]",
+        )?;
+        check(
+            &self.rhs_scale,
+            problem.n,
+            "Rhs scales must be f32 with a shape of [1] or [n]",
+        )
+    }
+
+    /// Check that the bias has a shape of `[n]`, so that it is never partially read.
+    #[allow(clippy::result_large_err)]
+    pub fn check_bias(&self, problem: &MatmulProblem) -> Result<(), MatmulSetupError> {
+        match &self.bias {
+            Some(bias) if bias.shape != [problem.n] => {
+                Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                    "Bias must have a shape of [n] = [{}], got {:?}",
+                    problem.n, bias.shape
+                ))))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn config(&self) -> EpilogueConfig {
+        // Shapes are validated by `check_scales`
+        let granularity = |scale: &TensorHandleRef<'a, R>| match scale.shape {
+            [1] => ScaleGranularity::Tensor,
+            _ => ScaleGranularity::Channel,
+        };
+
+        EpilogueConfig {
+            activation: self.activation,
+            has_alpha: self.alpha != 1.0,
+            has_c: self.scaled_c().is_some(),
+            has_bias: self.bias.is_some(),
+            has_residual: self.residual.is_some(),
+            lhs_scale: self.lhs_scale.as_ref().map(granularity),
+            rhs_scale: self.rhs_scale.as_ref().map(granularity),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::components::batch::{
+    BlockScaledBatchConfig, BlockScaledBatchMatmulFamily, GlobalPartitionMatmul,
+    PartitionedBatchMatmulFamily,
+};
+use crate::components::global::memory::{GlobalLayoutLaunch, GlobalScaleLayout};
+use crate::components::global::read::ScaleEncoding;
+use crate::components::global::single_stage::block_scaled::BlockScaledGlobalMatmulFamily;
+use crate::definition::MatmulProblem;
+use crate::definition::{AvailableLineSizes, MatmulElems, TilingBlueprint, select_stage_dtypes};
+use crate::definition::{MatmulAvailabilityError, MatmulSetupError};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
+use crate::launch::{
+    ConcreteInputsFactory, ConcreteOutputFactory, InputArg, OutputArg, TensorArgs,
+};
+use crate::routines::{BlueprintStrategy, DeviceSettings, Routine};
+use cubecl::features::TypeUsage;
+use cubecl::prelude::*;
+use cubecl::std::tensor::launch::ViewArg;
+use cubecl_common::quant::scheme::{QuantLevel, QuantParam};
+
+/// Launch a matrix multiplication kernel where `rhs` is block quantized, as with MX and NVFP4
+/// formats.
+///
+/// The scales of the blocks of `rhs` overlapping a stage are decoded to `f32` by the rhs reader and
+/// staged along with its values, then applied by the tile matmul per block along k in accumulator
+/// precision instead of dequantizing `rhs` on read.
+///
+/// - `lhs` must not be quantized
+/// - `rhs` must be quantized per block. Scales may be floats, or the `ue8m0` scales of MX formats
+///   and the `ue4m3` scales of NVFP4, read as bytes
+/// - blocks along k must either contain whole stages or be contained in a single stage
+#[allow(clippy::result_large_err)]
+pub fn launch_ref<R: Runtime, A, GMM, S>(
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+    out: &TensorHandleRef<'_, R>,
+    blueprint_strategy: &BlueprintStrategy<A>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError>
+where
+    A: Routine<Blueprint = TilingBlueprint, BatchMatmul = PartitionedBatchMatmulFamily<GMM, S>>,
+    GMM: BlockScaledGlobalMatmulFamily,
+    S: GlobalPartitionMatmul,
+{
+    if !matches!(lhs, MatmulInputHandleRef::Normal(..)) {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Block scaled matmul doesn't support quantized or sparse lhs",
+        )));
+    }
+
+    let lhs_owned;
+    let lhs = if lhs.needs_contiguous() {
+        lhs_owned = lhs.into_contiguous(client)?;
+        &lhs_owned.as_ref()
+    } else {
+        lhs
+    };
+
+    let rhs_owned;
+    let rhs = if rhs.needs_contiguous() {
+        rhs_owned = rhs.into_contiguous(client)?;
+        &rhs_owned.as_ref()
+    } else {
+        rhs
+    };
+
+    let MatmulInputHandleRef::Quantized {
+        data,
+        data_dtype,
+        scale,
+        scale_dtype,
+        shape,
+        scheme,
+    } = rhs
+    else {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Block scaled matmul requires a quantized rhs",
+        )));
+    };
+
+    let block_k = match scheme.level {
+        QuantLevel::Block(block_size) => {
+            let [block_row, _] = block_size.as_dim();
+            block_row as usize
+        }
+        QuantLevel::Tensor => {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Block scaled matmul requires rhs to be quantized per block",
+            )));
+        }
+    };
+
+    // Byte encoded scales are decoded by the reader from their bits
+    let scale_encoding = ScaleEncoding::from_param(scheme.param);
+    let scale_dtype = match scale_encoding {
+        ScaleEncoding::Float => *scale_dtype,
+        ScaleEncoding::Ue8m0 | ScaleEncoding::Ue4m3 => u8::as_type_native_unchecked(),
+    };
+
+    let problem = MatmulProblem::from_shapes_and_strides(
+        lhs.shape().to_vec(),
+        rhs.shape().to_vec(),
+        out.shape.to_vec(),
+        lhs.data().strides.to_vec(),
+        rhs.data().strides.to_vec(),
+        out.strides.to_vec(),
+        dtypes.as_global_elems(),
+    )
+    .with_rhs_block_scales(block_k);
+    problem.check_batch_broadcast()?;
+
+    if !client
+        .properties()
+        .features
+        .type_usage(*dtypes.lhs_global)
+        .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.rhs_global)
+            .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.acc_global)
+            .contains(TypeUsage::Conversion)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::TypesUnavailable {
+                lhs: *dtypes.lhs_global,
+                rhs: *dtypes.rhs_global,
+                output: *dtypes.acc_global,
+            },
+        ));
+    }
+
+    // Scales are applied to the rhs fragments, so they are multiplied in accumulator precision
+    dtypes.rhs_register.dtype = dtypes.acc_register.dtype;
+
+    let mut line_sizes = AvailableLineSizes::from_type_sizes(
+        client,
+        lhs.data().elem_size,
+        data.elem_size,
+        out.elem_size,
+    )
+    .filter_lhs_with_tensor(&problem.lhs_strides, &problem.lhs_shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&problem.rhs_strides, &problem.rhs_shape, problem.rhs_layout)
+    .filter_out_with_tensor(&problem.out_strides, &problem.out_shape)
+    .pick_max()?;
+
+    // Same as for dequantized inputs, see `launch_tiling`
+    line_sizes.rhs = 1;
+    let mut view_line_sizes = line_sizes;
+    view_line_sizes.rhs *= scheme.num_quants() as u8;
+
+    let plane_dim = match A::select_plane_dim(client) {
+        // Default to a common plane size when the GPU doesn't report it, see `launch_tiling`.
+        0 => 32,
+        plane_dim => plane_dim,
+    };
+
+    let device_settings = DeviceSettings {
+        client: client.clone(),
+        plane_dim,
+        line_sizes: view_line_sizes,
+    };
+    let launch_info = A::prepare(&problem, &device_settings, blueprint_strategy)?;
+
+    let config = A::expand_config(
+        client,
+        &problem,
+        &launch_info.blueprint,
+        &view_line_sizes,
+        dtypes,
+    )?;
+    let block_scaled_config =
+        BlockScaledBatchConfig::new(config.global_config, scale_encoding).validate(&problem)?;
+
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
+
+    // Rhs values are read without their block scales, by dequantizing them with a single unit
+    // scale. The block scales are given to the kernel separately.
+    let unit_scale = client.create_from_slice(f32::as_bytes(&[1.0]));
+    let unit_scale =
+        unsafe { TensorHandleRef::from_raw_parts(&unit_scale, &[1], &[1], size_of::<f32>()) };
+    let unit_scheme = scheme
+        .with_level(QuantLevel::Tensor)
+        .with_param(QuantParam::F32);
+    let rhs_values = MatmulInputHandleRef::quantized(
+        *data,
+        unit_scale,
+        shape,
+        &unit_scheme,
+        *data_dtype,
+        f32::as_type_native_unchecked(),
+    );
+
+    let (_, scales_layout) = GlobalLayoutLaunch::from_quantized_handle(
+        client,
+        data,
+        scale,
+        shape,
+        &problem,
+        **scheme,
+        line_sizes.rhs,
+        block_scaled_config.rhs_global_layout_config(),
+    );
+    let rhs_scales = ViewArg::new::<GlobalScaleLayout>(scale.as_array_arg(1), scales_layout);
+
+    let epilogue = MatmulEpilogueHandleRef::none();
+    let input = <InputArg<TensorArgs> as ConcreteInputsFactory<A>>::create(
+        client,
+        lhs,
+        &rhs_values,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+    let output = <OutputArg<TensorArgs> as ConcreteOutputFactory<A>>::create(
+        client,
+        out,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+
+    let result = unsafe {
+        BlockScaledBatchMatmulFamily::<GMM>::launch_unchecked::<TensorArgs, R>(
+            client,
+            block_scaled_config.cube_dim(),
+            block_scaled_config.cube_count(&problem),
+            input,
+            output,
+            rhs_scales,
+            block_scaled_config,
+            dtypes,
+            scale_dtype,
+        )
+    };
+
+    result.map_err(MatmulSetupError::Launch)
+}
@@ -0,0 +1,240 @@
+use crate::components::batch::{
+    GlobalPartitionMatmul, GroupedBatchConfig, GroupedBatchMatmulFamily,
+    PartitionedBatchMatmulFamily,
+};
+use crate::components::global::GlobalMatmulFamily;
+use crate::definition::MatmulProblem;
+use crate::definition::{AvailableLineSizes, MatmulElems, TilingBlueprint, select_stage_dtypes};
+use crate::definition::{MatmulAvailabilityError, MatmulSetupError};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
+use crate::launch::{
+    ConcreteInputsFactory, ConcreteOutputFactory, InputArg, OutputArg, TensorArgs,
+};
+use crate::routines::{BlueprintStrategy, DeviceSettings, Routine};
+use cubecl::features::TypeUsage;
+use cubecl::prelude::*;
+
+/// Launch a grouped matrix multiplication kernel, where the rows of `lhs` and `out` are split
+/// into groups that are each multiplied with their own batch of `rhs`.
+///
+/// - `lhs` has shape `[total_rows, k]`
+/// - `rhs` has shape `[num_groups, k, n]`
+/// - `group_offsets` is a `u32` tensor of shape `[num_groups]` holding the end row of each
+///   group, so group `g` covers rows `group_offsets[g - 1]..group_offsets[g]`
+/// - `out` has shape `[total_rows, n]`
+///
+/// Groups may be empty and don't need to be aligned to the tile size, but the offsets must be
+/// non-decreasing and at most `total_rows`. They aren't read back at launch, so they can be
+/// computed on the device. Invalid offsets give an unspecified result, but are clamped so that
+/// no group reaches outside of `lhs` and `out`. See
+/// [check_group_offsets](crate::launch::check_group_offsets) to validate them.
+#[allow(clippy::result_large_err, clippy::too_many_arguments)]
+pub fn launch_ref<R: Runtime, A, GMM, S>(
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+    group_offsets: &TensorHandleRef<'_, R>,
+    out: &TensorHandleRef<'_, R>,
+    blueprint_strategy: &BlueprintStrategy<A>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError>
+where
+    A: Routine<Blueprint = TilingBlueprint, BatchMatmul = PartitionedBatchMatmulFamily<GMM, S>>,
+    GMM: GlobalMatmulFamily,
+    S: GlobalPartitionMatmul,
+{
+    let (lhs_handle, lhs_dtype) = match lhs {
+        MatmulInputHandleRef::Normal(handle, dtype) => (handle, *dtype),
+        MatmulInputHandleRef::Quantized { .. } | MatmulInputHandleRef::Sparse24 { .. } => {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul doesn't support quantized or sparse inputs",
+            )));
+        }
+    };
+    let rhs = match rhs {
+        MatmulInputHandleRef::Normal(..) => rhs,
+        MatmulInputHandleRef::Quantized { .. } | MatmulInputHandleRef::Sparse24 { .. } => {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul doesn't support quantized or sparse inputs",
+            )));
+        }
+    };
+
+    if lhs_handle.shape.len() != 2
+        || rhs.shape().len() != 3
+        || out.shape.len() != 2
+        || group_offsets.shape.len() != 1
+    {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects lhs [total_rows, k], rhs [num_groups, k, n], \
+             group offsets [num_groups] and out [total_rows, n]",
+        )));
+    }
+
+    let num_groups = rhs.shape()[0];
+    if group_offsets.shape[0] != num_groups {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects one offset per rhs group",
+        )));
+    }
+    if group_offsets.elem_size != size_of::<u32>() {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects u32 group offsets",
+        )));
+    }
+
+    let total_rows = lhs_handle.shape[0];
+    if out.shape[0] != total_rows {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects lhs and out to have the same number of rows",
+        )));
+    }
+    if rhs.shape()[1] != lhs_handle.shape[1] {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects lhs and rhs to have the same k",
+        )));
+    }
+    if rhs.shape()[2] != out.shape[1] {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Grouped matmul expects rhs and out to have the same n",
+        )));
+    }
+
+    let rhs_owned;
+    let rhs = if rhs.needs_contiguous() {
+        rhs_owned = rhs.into_contiguous(client)?;
+        &rhs_owned.as_ref()
+    } else {
+        rhs
+    };
+
+    // Lhs and out are seen as a single batch broadcast over all groups, so the batch index
+    // selects the rhs of the group.
+    let lhs_shape = [1, lhs_handle.shape[0], lhs_handle.shape[1]];
+    let lhs_strides = [0, lhs_handle.strides[0], lhs_handle.strides[1]];
+    let lhs_handle = unsafe {
+        TensorHandleRef::from_raw_parts(
+            lhs_handle.handle,
+            &lhs_strides,
+            &lhs_shape,
+            lhs_handle.elem_size,
+        )
+    };
+    let lhs = MatmulInputHandleRef::Normal(lhs_handle, lhs_dtype);
+
+    let out_shape = [1, out.shape[0], out.shape[1]];
+    let out_strides = [0, out.strides[0], out.strides[1]];
+    let out = unsafe {
+        TensorHandleRef::from_raw_parts(out.handle, &out_strides, &out_shape, out.elem_size)
+    };
+
+    let problem = MatmulProblem::from_shapes_and_strides(
+        lhs.shape().to_vec(),
+        rhs.shape().to_vec(),
+        vec![num_groups, out_shape[1], out_shape[2]],
+        lhs.data().strides.to_vec(),
+        rhs.data().strides.to_vec(),
+        out.strides.to_vec(),
+        dtypes.as_global_elems(),
+    )
+    .with_groups(num_groups);
+
+    if !client
+        .properties()
+        .features
+        .type_usage(*dtypes.lhs_global)
+        .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.rhs_global)
+            .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.acc_global)
+            .contains(TypeUsage::Conversion)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::TypesUnavailable {
+                lhs: *dtypes.lhs_global,
+                rhs: *dtypes.rhs_global,
+                output: *dtypes.acc_global,
+            },
+        ));
+    }
+
+    let line_sizes = AvailableLineSizes::from_type_sizes(
+        client,
+        lhs.data().elem_size,
+        rhs.data().elem_size,
+        out.elem_size,
+    )
+    .filter_lhs_with_tensor(&problem.lhs_strides, &problem.lhs_shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&problem.rhs_strides, &problem.rhs_shape, problem.rhs_layout)
+    .filter_out_with_tensor(&problem.out_strides, &problem.out_shape)
+    .pick_max()?;
+
+    let plane_dim = match A::select_plane_dim(client) {
+        // Default to a common plane size when the GPU doesn't report it, see `launch_tiling`.
+        0 => 32,
+        plane_dim => plane_dim,
+    };
+
+    let device_settings = DeviceSettings {
+        client: client.clone(),
+        plane_dim,
+        line_sizes,
+    };
+    let launch_info = A::prepare(&problem, &device_settings, blueprint_strategy)?;
+
+    let config = A::expand_config(
+        client,
+        &problem,
+        &launch_info.blueprint,
+        &line_sizes,
+        dtypes,
+    )?;
+    let grouped_config = GroupedBatchConfig::new(config.global_config)
+        .validate(&problem, &client.properties().hardware.max_cube_count)?;
+
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
+
+    let epilogue = MatmulEpilogueHandleRef::none();
+    let input = <InputArg<TensorArgs> as ConcreteInputsFactory<A>>::create(
+        client,
+        &lhs,
+        rhs,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+    let output = <OutputArg<TensorArgs> as ConcreteOutputFactory<A>>::create(
+        client,
+        &out,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+
+    let result = unsafe {
+        GroupedBatchMatmulFamily::<GMM>::launch_unchecked::<TensorArgs, R>(
+            client,
+            grouped_config.cube_dim(),
+            grouped_config.cube_count(&problem, &client.properties().hardware.max_cube_count),
+            input,
+            output,
+            group_offsets.as_tensor_arg(1),
+            grouped_config,
+            dtypes,
+        )
+    };
+
+    result.map_err(MatmulSetupError::Launch)
+}
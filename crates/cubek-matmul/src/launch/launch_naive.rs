@@ -11,7 +11,7 @@ use crate::definition::{MatmulAvailabilityError, MatmulElems, MatmulProblem, Mat
 
 use crate::components::batch::BatchMatmulFamily;
 use crate::launch::InputArg;
-use crate::launch::handle::{MatmulInputHandle, MatmulInputHandleRef};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandle, MatmulInputHandleRef};
 use crate::launch::{ConcreteInputsFactory, ConcreteOutputFactory, OutputArg, TensorArgs};
 use crate::routines::Routine as _;
 use crate::routines::naive::NaiveRoutine;
@@ -119,10 +119,12 @@ pub fn launch_ref<R: Runtime>(
     let cube_count_plan =
         simple_cube_count(lhs_shape, rhs_shape, out_shape, cube_dim_x, cube_dim_y)?;
 
+    let epilogue = MatmulEpilogueHandleRef::none();
     let input = <InputArg<TensorArgs> as ConcreteInputsFactory<NaiveRoutine>>::create(
         client,
         &lhs,
         &rhs,
+        &epilogue,
         &blueprint,
         &problem,
         &line_sizes,
@@ -132,6 +134,7 @@ pub fn launch_ref<R: Runtime>(
     let output = <OutputArg<TensorArgs> as ConcreteOutputFactory<NaiveRoutine>>::create(
         client,
         out,
+        &epilogue,
         &blueprint,
         &problem,
         &line_sizes,
@@ -145,7 +148,7 @@ pub fn launch_ref<R: Runtime>(
         cube_count_plan.resolve(),
         input,
         output,
-        cube_count_plan.as_args(),
+        cube_count_plan.as_args(None),
         config,
         dtypes,
     )
@@ -0,0 +1,219 @@
+use crate::components::batch::{
+    GlobalPartitionMatmul, PartitionedBatchMatmulFamily, Sparse24BatchConfig,
+    Sparse24BatchMatmulFamily,
+};
+use crate::components::global::memory::{GlobalLayout, GlobalLayoutLaunch};
+use crate::components::global::single_stage::sparse_24::Sparse24GlobalMatmulFamily;
+use crate::definition::{AvailableLineSizes, MatmulElems, TilingBlueprint, select_stage_dtypes};
+use crate::definition::{MatmulAvailabilityError, MatmulSetupError};
+use crate::definition::{MatmulProblem, MatrixLayout};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
+use crate::launch::{
+    ConcreteInputsFactory, ConcreteOutputFactory, InputArg, OutputArg, TensorArgs,
+};
+use crate::routines::{BlueprintStrategy, DeviceSettings, Routine};
+use cubecl::features::TypeUsage;
+use cubecl::prelude::*;
+use cubecl::std::tensor::launch::ViewArg;
+
+/// Launch a matrix multiplication kernel where `rhs` is 2:4 structured sparse along k.
+///
+/// Only the kept values of `rhs` are read and staged, along with their metadata, and expanded by
+/// the tile matmul when filling its fragments.
+///
+/// - `lhs` must not be quantized or sparse
+/// - `rhs` must be sparse, with its values and metadata row major, of shapes `[.., k / 2, n]` and
+///   `[.., k / 4, n]`
+/// - k must be a multiple of 4
+#[allow(clippy::result_large_err)]
+pub fn launch_ref<R: Runtime, A, GMM, S>(
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+    out: &TensorHandleRef<'_, R>,
+    blueprint_strategy: &BlueprintStrategy<A>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError>
+where
+    A: Routine<Blueprint = TilingBlueprint, BatchMatmul = PartitionedBatchMatmulFamily<GMM, S>>,
+    GMM: Sparse24GlobalMatmulFamily,
+    S: GlobalPartitionMatmul,
+{
+    if !matches!(lhs, MatmulInputHandleRef::Normal(..)) {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "2:4 sparse matmul doesn't support quantized or sparse lhs",
+        )));
+    }
+
+    let MatmulInputHandleRef::Sparse24 {
+        values,
+        metadata,
+        shape,
+        ..
+    } = rhs
+    else {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "2:4 sparse matmul requires a sparse rhs",
+        )));
+    };
+
+    // Each group of four rows keeps two values, with their positions in a single metadata row
+    let k = shape[shape.len() - 2];
+    if !k.is_multiple_of(4) {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+            "2:4 sparse matmul requires k to be a multiple of 4, got {k}"
+        ))));
+    }
+    let check_rows = |handle: &TensorHandleRef<'_, R>, name: &str, rows: usize| {
+        let mut expected = shape.to_vec();
+        expected[shape.len() - 2] = rows;
+        if handle.shape != expected {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Expected 2:4 sparse {name} of shape {expected:?}, got {:?}",
+                handle.shape
+            ))));
+        }
+        Ok(())
+    };
+    check_rows(values, "values", k / 2)?;
+    check_rows(metadata, "metadata", k / 4)?;
+
+    // Values and metadata are indexed along the same rows, so both must be row major
+    let is_row_major = |handle: &TensorHandleRef<'_, R>| {
+        MatrixLayout::try_from_shape_and_strides(handle.shape, handle.strides)
+            == Some(MatrixLayout::RowMajor)
+    };
+    if !is_row_major(values) || !is_row_major(metadata) {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "2:4 sparse matmul requires row major values and metadata",
+        )));
+    }
+
+    let lhs_owned;
+    let lhs = if lhs.needs_contiguous() {
+        lhs_owned = lhs.into_contiguous(client)?;
+        &lhs_owned.as_ref()
+    } else {
+        lhs
+    };
+
+    // The problem is that of the dense rhs, which has the same layout as the values
+    let problem = MatmulProblem::from_shapes_and_strides(
+        lhs.shape().to_vec(),
+        rhs.shape().to_vec(),
+        out.shape.to_vec(),
+        lhs.data().strides.to_vec(),
+        values.strides.to_vec(),
+        out.strides.to_vec(),
+        dtypes.as_global_elems(),
+    );
+    problem.check_batch_broadcast()?;
+
+    if !client
+        .properties()
+        .features
+        .type_usage(*dtypes.lhs_global)
+        .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.rhs_global)
+            .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.acc_global)
+            .contains(TypeUsage::Conversion)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::TypesUnavailable {
+                lhs: *dtypes.lhs_global,
+                rhs: *dtypes.rhs_global,
+                output: *dtypes.acc_global,
+            },
+        ));
+    }
+
+    let line_sizes = AvailableLineSizes::from_type_sizes(
+        client,
+        lhs.data().elem_size,
+        values.elem_size,
+        out.elem_size,
+    )
+    .filter_lhs_with_tensor(&problem.lhs_strides, &problem.lhs_shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(values.strides, values.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&problem.out_strides, &problem.out_shape)
+    .pick_max()?;
+
+    let plane_dim = match A::select_plane_dim(client) {
+        // Default to a common plane size when the GPU doesn't report it, see `launch_tiling`.
+        0 => 32,
+        plane_dim => plane_dim,
+    };
+
+    let device_settings = DeviceSettings {
+        client: client.clone(),
+        plane_dim,
+        line_sizes,
+    };
+    let launch_info = A::prepare(&problem, &device_settings, blueprint_strategy)?;
+
+    let config = A::expand_config(
+        client,
+        &problem,
+        &launch_info.blueprint,
+        &line_sizes,
+        dtypes,
+    )?;
+    let sparse_config = Sparse24BatchConfig::new(config.global_config);
+
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
+
+    // Metadata is read one element at a time, with the same bounds checks as the values
+    let metadata_layout = GlobalLayoutLaunch::from_handle_batched(
+        client,
+        metadata,
+        &problem,
+        1,
+        sparse_config.rhs_global_layout_config(),
+    );
+    let rhs_metadata = ViewArg::new::<GlobalLayout>(metadata.as_array_arg(1), metadata_layout);
+
+    let epilogue = MatmulEpilogueHandleRef::none();
+    let input = <InputArg<TensorArgs> as ConcreteInputsFactory<A>>::create(
+        client,
+        lhs,
+        rhs,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+    let output = <OutputArg<TensorArgs> as ConcreteOutputFactory<A>>::create(
+        client,
+        out,
+        &epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        dtypes,
+    );
+
+    let result = unsafe {
+        Sparse24BatchMatmulFamily::<GMM>::launch_unchecked::<TensorArgs, R>(
+            client,
+            sparse_config.cube_dim(),
+            sparse_config.cube_count(&problem),
+            input,
+            output,
+            rhs_metadata,
+            sparse_config,
+            dtypes,
+        )
+    };
+
+    result.map_err(MatmulSetupError::Launch)
+}
@@ -0,0 +1,271 @@
+use crate::components::batch::{
+    GlobalPartitionMatmul, PartitionedBatchMatmulFamily, SplitKArgsLaunch, SplitKBatchConfig,
+    SplitKBatchMatmulFamily, SplitKMode,
+};
+use crate::components::global::GlobalMatmulFamily;
+use crate::definition::{Activation, MatmulProblem};
+use crate::definition::{AvailableLineSizes, MatmulElems, TilingBlueprint, select_stage_dtypes};
+use crate::definition::{MatmulAvailabilityError, MatmulSetupError};
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
+use crate::launch::{
+    ConcreteInputsFactory, ConcreteOutputFactory, InputArg, OutputArg, TensorArgs,
+};
+use crate::routines::{BlueprintStrategy, DeviceSettings, Routine};
+use cubecl::features::TypeUsage;
+use cubecl::prelude::*;
+use cubecl::std::tensor::TensorHandle;
+use cubecl::tensor_line_size_parallel;
+
+/// Launch a matrix multiplication kernel where the k dimension is partitioned across cubes,
+/// which helps filling the GPU when `m` and `n` are small compared to `k`.
+///
+/// Partial results are accumulated in a workspace with the accumulator precision, then summed
+/// into `out` by a second kernel, which also applies `alpha`, `beta` and `c`. Other epilogues
+/// aren't supported. The global matmul of the routine must only read within the
+/// k range it is given, which is the case of single stage routines.
+#[allow(clippy::result_large_err)]
+pub fn launch_ref<R: Runtime, A, GMM, S>(
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+    out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
+    mode: SplitKMode,
+    blueprint_strategy: &BlueprintStrategy<A>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError>
+where
+    A: Routine<Blueprint = TilingBlueprint, BatchMatmul = PartitionedBatchMatmulFamily<GMM, S>>,
+    GMM: GlobalMatmulFamily,
+    S: GlobalPartitionMatmul,
+{
+    if !matches!(lhs, MatmulInputHandleRef::Normal(..))
+        || !matches!(rhs, MatmulInputHandleRef::Normal(..))
+    {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Split-K matmul doesn't support quantized or sparse inputs",
+        )));
+    }
+
+    let epilogue_config = epilogue.config();
+    if !matches!(epilogue_config.activation, Activation::None)
+        || epilogue_config.has_bias
+        || epilogue_config.has_residual
+        || epilogue_config.lhs_scale.is_some()
+        || epilogue_config.rhs_scale.is_some()
+    {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Split-K matmul only supports alpha, beta and c epilogues",
+        )));
+    }
+    let c = epilogue.scaled_c();
+    if let Some(c) = c
+        && c.shape != out.shape
+    {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Split-K matmul expects c to have the shape of the output",
+        )));
+    }
+
+    let lhs_owned;
+    let lhs = if lhs.needs_contiguous() {
+        lhs_owned = lhs.into_contiguous(client)?;
+        &lhs_owned.as_ref()
+    } else {
+        lhs
+    };
+
+    let rhs_owned;
+    let rhs = if rhs.needs_contiguous() {
+        rhs_owned = rhs.into_contiguous(client)?;
+        &rhs_owned.as_ref()
+    } else {
+        rhs
+    };
+
+    if !client
+        .properties()
+        .features
+        .type_usage(*dtypes.lhs_global)
+        .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.rhs_global)
+            .contains(TypeUsage::Conversion)
+        || !client
+            .properties()
+            .features
+            .type_usage(*dtypes.acc_global)
+            .contains(TypeUsage::Conversion)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::TypesUnavailable {
+                lhs: *dtypes.lhs_global,
+                rhs: *dtypes.rhs_global,
+                output: *dtypes.acc_global,
+            },
+        ));
+    }
+
+    // The matmul pass writes contiguous partials with the accumulator precision
+    let mut matmul_dtypes = dtypes.clone();
+    matmul_dtypes.acc_global = dtypes.acc_register;
+    let partials_elem_size = dtypes.acc_register.size();
+
+    let slab_shape = out.shape.to_vec();
+    let slab_strides = contiguous_strides(&slab_shape);
+
+    let problem = MatmulProblem::from_shapes_and_strides(
+        lhs.shape().to_vec(),
+        rhs.shape().to_vec(),
+        slab_shape.clone(),
+        lhs.data().strides.to_vec(),
+        rhs.data().strides.to_vec(),
+        slab_strides.clone(),
+        matmul_dtypes.as_global_elems(),
+    );
+    problem.check_batch_broadcast()?;
+
+    let line_sizes = AvailableLineSizes::from_type_sizes(
+        client,
+        lhs.data().elem_size,
+        rhs.data().elem_size,
+        partials_elem_size,
+    )
+    .filter_lhs_with_tensor(&problem.lhs_strides, &problem.lhs_shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&problem.rhs_strides, &problem.rhs_shape, problem.rhs_layout)
+    .filter_out_with_tensor(&problem.out_strides, &problem.out_shape)
+    .pick_max()?;
+
+    let plane_dim = match A::select_plane_dim(client) {
+        // Default to a common plane size when the GPU doesn't report it, see `launch_tiling`.
+        0 => 32,
+        plane_dim => plane_dim,
+    };
+
+    let device_settings = DeviceSettings {
+        client: client.clone(),
+        plane_dim,
+        line_sizes,
+    };
+    let launch_info = A::prepare(&problem, &device_settings, blueprint_strategy)?;
+
+    let config = A::expand_config(
+        client,
+        &problem,
+        &launch_info.blueprint,
+        &line_sizes,
+        &matmul_dtypes,
+    )?;
+    let split_k_config = SplitKBatchConfig::new(config.global_config, mode).validate(&problem)?;
+    let plan = split_k_config.plan(&problem);
+
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
+    select_stage_dtypes(&mut matmul_dtypes, A::can_cast_stage_element());
+
+    let slab_len: usize = slab_shape.iter().product();
+    let mut partials_shape = vec![plan.num_slabs as usize];
+    partials_shape.extend_from_slice(&slab_shape);
+    let partials = match plan.needs_zeroed_workspace {
+        true => TensorHandle::zeros(client, partials_shape, *dtypes.acc_register),
+        false => TensorHandle::empty(client, partials_shape, *dtypes.acc_register),
+    };
+
+    // The matmul sees the first slab as its output, and offsets the batch to reach the others
+    let slab = unsafe {
+        TensorHandleRef::from_raw_parts(
+            &partials.handle,
+            &slab_strides,
+            &slab_shape,
+            partials_elem_size,
+        )
+    };
+
+    // The epilogue is applied when summing the partials
+    let matmul_epilogue = MatmulEpilogueHandleRef::none();
+    let input = <InputArg<TensorArgs> as ConcreteInputsFactory<A>>::create(
+        client,
+        lhs,
+        rhs,
+        &matmul_epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        &matmul_dtypes,
+    );
+    let output = <OutputArg<TensorArgs> as ConcreteOutputFactory<A>>::create(
+        client,
+        &slab,
+        &matmul_epilogue,
+        &launch_info.blueprint,
+        &problem,
+        &line_sizes,
+        config,
+        &matmul_dtypes,
+    );
+
+    let args = SplitKArgsLaunch::new(
+        ScalarArg::new(plan.m_tiles),
+        ScalarArg::new(plan.n_tiles),
+        ScalarArg::new(plan.k_iters),
+        ScalarArg::new(plan.iters_per_cube),
+        ScalarArg::new(problem.num_batches() as u32 * plan.m_tiles * plan.n_tiles),
+        ScalarArg::new(slab_len as u32),
+    );
+
+    unsafe {
+        SplitKBatchMatmulFamily::<GMM>::launch_unchecked::<TensorArgs, R>(
+            client,
+            split_k_config.cube_dim(),
+            plan.cube_count,
+            input,
+            output,
+            args,
+            split_k_config,
+            &matmul_dtypes,
+        )
+    }
+    .map_err(MatmulSetupError::Launch)?;
+
+    let rank = out.shape.len();
+    let mut reduce_line_size = tensor_line_size_parallel(
+        R::supported_line_sizes().iter().copied(),
+        out.shape,
+        out.strides,
+        rank - 1,
+    );
+    if let Some(c) = c {
+        reduce_line_size = reduce_line_size.min(tensor_line_size_parallel(
+            R::supported_line_sizes().iter().copied(),
+            c.shape,
+            c.strides,
+            rank - 1,
+        ));
+    }
+
+    unsafe {
+        SplitKBatchMatmulFamily::<GMM>::launch_reduce(
+            client,
+            &partials.as_ref(),
+            c,
+            out,
+            plan.num_slabs,
+            epilogue.alpha,
+            epilogue.beta,
+            reduce_line_size,
+            *dtypes.acc_register,
+            *dtypes.acc_global,
+        )
+    }
+    .map_err(MatmulSetupError::Launch)
+}
+
+fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
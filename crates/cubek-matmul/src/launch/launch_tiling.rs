@@ -1,7 +1,10 @@
 use crate::definition::MatmulProblem;
-use crate::definition::{AvailableLineSizes, MatmulElems, TilingBlueprint};
+use crate::definition::{
+    AvailableLineSizes, MatmulElems, ScaleGranularity, TilingBlueprint, adjust_fp8_dtypes,
+    select_stage_dtypes,
+};
 use crate::definition::{MatmulAvailabilityError, MatmulSetupError};
-use crate::launch::handle::MatmulInputHandleRef;
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
 use crate::launch::launch_kernel_concrete;
 use crate::launch::{
     ConcreteInputsFactory, ConcreteOutputFactory, InputArg, MatmulArgs, OutputArg, TensorArgs,
@@ -10,7 +13,7 @@ use crate::launch::{
 use crate::routines::{BlueprintStrategy, Routine};
 use cubecl::features::TypeUsage;
 use cubecl::std::tensor::{MatrixBatchLayout, matrix_batch_layout};
-use cubecl::{Runtime, client::ComputeClient, frontend::TensorHandleRef};
+use cubecl::{Runtime, client::ComputeClient, frontend::TensorHandleRef, ir::StorageType};
 
 /// Launch a matrix multiplication kernel.
 ///
@@ -22,11 +25,12 @@ pub fn launch_ref<R: Runtime, A: Routine>(
     lhs: &MatmulInputHandleRef<'_, R>,
     rhs: &MatmulInputHandleRef<'_, R>,
     out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
     blueprint_strategy: &BlueprintStrategy<A>,
     dtypes: &mut MatmulElems,
 ) -> Result<(), MatmulSetupError> {
     let lhs_owned;
-    let lhs = if matrix_batch_layout(lhs.data().strides) == MatrixBatchLayout::HighlyPermuted {
+    let lhs = if lhs.needs_contiguous() {
         lhs_owned = lhs.into_contiguous(client)?;
         &lhs_owned.as_ref()
     } else {
@@ -34,18 +38,29 @@ pub fn launch_ref<R: Runtime, A: Routine>(
     };
 
     let rhs_owned;
-    let rhs = if matrix_batch_layout(rhs.data().strides) == MatrixBatchLayout::HighlyPermuted {
+    let rhs = if rhs.needs_contiguous() {
         rhs_owned = rhs.into_contiguous(client)?;
         &rhs_owned.as_ref()
     } else {
         rhs
     };
 
+    let folded = FoldedBatches::new(lhs, rhs, out, epilogue);
+    let folded_handles;
+    let (lhs, rhs, out) = match &folded {
+        Some(folded) => {
+            folded_handles = folded.handles(lhs, rhs, out);
+            (&folded_handles.0, &folded_handles.1, &folded_handles.2)
+        }
+        None => (lhs, rhs, out),
+    };
+
     launch_inner_ref::<R, TensorArgs, A>(
         client,
         lhs,
         rhs,
         out,
+        epilogue,
         blueprint_strategy,
         AvailableLineSizes::from_type_sizes(
             client,
@@ -68,6 +83,7 @@ pub fn launch_ref_tma<R: Runtime, A: Routine<Blueprint = TilingBlueprint>>(
     lhs: &MatmulInputHandleRef<'_, R>,
     rhs: &MatmulInputHandleRef<'_, R>,
     out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
     blueprint_strategy: &BlueprintStrategy<A>,
     dtypes: &mut MatmulElems,
 ) -> Result<(), MatmulSetupError> {
@@ -105,11 +121,31 @@ pub fn launch_ref_tma<R: Runtime, A: Routine<Blueprint = TilingBlueprint>>(
         }
     };
 
+    let folded = FoldedBatches::new(lhs, rhs, out, epilogue);
+    let folded_handles;
+    let (lhs, rhs, out) = match &folded {
+        Some(folded) => {
+            folded_handles = folded.handles(lhs, rhs, out);
+            (&folded_handles.0, &folded_handles.1, &folded_handles.2)
+        }
+        None => (lhs, rhs, out),
+    };
+
+    // Tensor maps combine all batches into a single dim, so they can't be broadcast
+    let num_batches = |shape: &[usize]| shape[..shape.len() - 2].iter().product::<usize>();
+    let out_batches = num_batches(out.shape);
+    if num_batches(lhs.shape()) != out_batches || num_batches(rhs.shape()) != out_batches {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "TMA matmul doesn't support broadcast batches",
+        )));
+    }
+
     launch_inner_ref::<R, TensorMapArgs, A>(
         client,
         lhs,
         rhs,
         out,
+        epilogue,
         blueprint_strategy,
         AvailableLineSizes::from_type_size_tma(client, out.elem_size),
         dtypes,
@@ -122,6 +158,7 @@ fn launch_inner_ref<R: Runtime, MA: MatmulArgs, A: Routine>(
     lhs: &MatmulInputHandleRef<'_, R>,
     rhs: &MatmulInputHandleRef<'_, R>,
     out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
     blueprint_strategy: &BlueprintStrategy<A>,
     line_sizes: AvailableLineSizes,
     dtypes: &mut MatmulElems,
@@ -138,7 +175,11 @@ where
         rhs.data().strides.to_vec(),
         out.strides.to_vec(),
         dtypes.as_global_elems(),
-    );
+    )
+    .with_epilogue(epilogue.config());
+    problem.check_batch_broadcast()?;
+    epilogue.check_scales(&problem)?;
+    epilogue.check_bias(&problem)?;
 
     if !client
         .properties()
@@ -165,11 +206,33 @@ where
         ));
     }
 
+    // Fp8 inputs are dequantized to f16 when the device has no fp8 matrix instructions. The
+    // stage types are chosen right away since they set the size of the stages.
+    adjust_fp8_dtypes(client, dtypes, A::requires_accelerator());
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
+
     let mut line_sizes = line_sizes
         .filter_lhs_with_tensor(&problem.lhs_strides, &problem.lhs_shape, problem.lhs_layout)
         .filter_rhs_with_tensor(&problem.rhs_strides, &problem.rhs_shape, problem.rhs_layout)
-        .filter_out_with_tensor(&problem.out_strides, &problem.out_shape)
-        .pick_max()?;
+        .filter_out_with_tensor(&problem.out_strides, &problem.out_shape);
+
+    // Epilogue tensors are read with the same line size as the output
+    if let Some(c) = epilogue.scaled_c() {
+        line_sizes = line_sizes.filter_out_with_tensor(c.strides, c.shape);
+    }
+    if let Some(bias) = &epilogue.bias {
+        line_sizes = line_sizes.filter_out_with_tensor(bias.strides, bias.shape);
+    }
+    if let Some(residual) = &epilogue.residual {
+        line_sizes = line_sizes.filter_out_with_tensor(residual.strides, residual.shape);
+    }
+    if let Some(ScaleGranularity::Channel) = epilogue.config().rhs_scale
+        && let Some(scale) = &epilogue.rhs_scale
+    {
+        line_sizes = line_sizes.filter_out_with_tensor(scale.strides, scale.shape);
+    }
+
+    let mut line_sizes = line_sizes.pick_max()?;
 
     // The large line size resulting from dequantizing ends up slower due to restrictions on
     // algorithms. Use this as a quick and dirty fix.
@@ -196,6 +259,7 @@ where
         lhs,
         rhs,
         out,
+        epilogue,
         problem,
         line_sizes,
         plane_dim,
@@ -203,3 +267,141 @@ where
         dtypes,
     )
 }
+
+/// Shapes and strides of a batched matmul whose rhs is shared by all batches, viewed as a single
+/// matmul with the batches of lhs and out folded into m.
+///
+/// The rhs stage is then reused across batches, and the tiling is selected for the actual number
+/// of rows rather than the rows of a single batch.
+struct FoldedBatches {
+    lhs_dtype: StorageType,
+    rhs_dtype: StorageType,
+    lhs_shape: [usize; 2],
+    lhs_strides: [usize; 2],
+    rhs_shape: [usize; 2],
+    rhs_strides: [usize; 2],
+    out_shape: [usize; 2],
+    out_strides: [usize; 2],
+}
+
+impl FoldedBatches {
+    /// Folds the batches into m when rhs is broadcast and the batches of both lhs and out
+    /// directly follow their rows in memory.
+    fn new<R: Runtime>(
+        lhs: &MatmulInputHandleRef<'_, R>,
+        rhs: &MatmulInputHandleRef<'_, R>,
+        out: &TensorHandleRef<'_, R>,
+        epilogue: &MatmulEpilogueHandleRef<'_, R>,
+    ) -> Option<Self> {
+        let (
+            MatmulInputHandleRef::Normal(lhs, lhs_dtype),
+            MatmulInputHandleRef::Normal(rhs, rhs_dtype),
+        ) = (lhs, rhs)
+        else {
+            return None;
+        };
+
+        // Epilogue inputs indexed by row would need to be folded as well
+        if epilogue.scaled_c().is_some()
+            || epilogue.residual.is_some()
+            || epilogue.lhs_scale.is_some()
+        {
+            return None;
+        }
+
+        let rhs_rank = rhs.shape.len();
+        if rhs.shape[..rhs_rank - 2].iter().any(|dim| *dim != 1) {
+            return None;
+        }
+
+        let lhs_rank = lhs.shape.len();
+        let (m, k) = (lhs.shape[lhs_rank - 2], lhs.shape[lhs_rank - 1]);
+        let n = rhs.shape[rhs_rank - 1];
+        let (lhs_rows, lhs_row_stride) = fold_rows(lhs.shape, lhs.strides)?;
+        let (out_rows, out_row_stride) = fold_rows(out.shape, out.strides)?;
+
+        // Nothing to fold with a single batch, and lhs must not be broadcast either
+        if out_rows == m || lhs_rows != out_rows {
+            return None;
+        }
+
+        Some(Self {
+            lhs_dtype: *lhs_dtype,
+            rhs_dtype: *rhs_dtype,
+            lhs_shape: [lhs_rows, k],
+            lhs_strides: [lhs_row_stride, 1],
+            rhs_shape: [k, n],
+            rhs_strides: [rhs.strides[rhs_rank - 2], rhs.strides[rhs_rank - 1]],
+            out_shape: [out_rows, n],
+            out_strides: [out_row_stride, 1],
+        })
+    }
+
+    /// Views the original handles with the folded shapes and strides
+    fn handles<'a, R: Runtime>(
+        &'a self,
+        lhs: &MatmulInputHandleRef<'a, R>,
+        rhs: &MatmulInputHandleRef<'a, R>,
+        out: &TensorHandleRef<'a, R>,
+    ) -> (
+        MatmulInputHandleRef<'a, R>,
+        MatmulInputHandleRef<'a, R>,
+        TensorHandleRef<'a, R>,
+    ) {
+        let (lhs, rhs) = (lhs.data(), rhs.data());
+
+        // SAFETY: the folded shapes and strides cover the same elements as the original ones
+        unsafe {
+            (
+                MatmulInputHandleRef::Normal(
+                    TensorHandleRef::from_raw_parts(
+                        lhs.handle,
+                        &self.lhs_strides,
+                        &self.lhs_shape,
+                        lhs.elem_size,
+                    ),
+                    self.lhs_dtype,
+                ),
+                MatmulInputHandleRef::Normal(
+                    TensorHandleRef::from_raw_parts(
+                        rhs.handle,
+                        &self.rhs_strides,
+                        &self.rhs_shape,
+                        rhs.elem_size,
+                    ),
+                    self.rhs_dtype,
+                ),
+                TensorHandleRef::from_raw_parts(
+                    out.handle,
+                    &self.out_strides,
+                    &self.out_shape,
+                    out.elem_size,
+                ),
+            )
+        }
+    }
+}
+
+/// Number of rows and row stride of a row major tensor whose batches are all viewed as rows,
+/// or `None` if the batches don't directly follow each other in memory.
+fn fold_rows(shape: &[usize], strides: &[usize]) -> Option<(usize, usize)> {
+    let rank = shape.len();
+    if strides[rank - 1] != 1 {
+        return None;
+    }
+
+    let row_stride = strides[rank - 2];
+    let mut rows = shape[rank - 2];
+    for dim in (0..rank - 2).rev() {
+        // Unit dims can have any stride
+        if shape[dim] == 1 {
+            continue;
+        }
+        if strides[dim] != rows * row_stride {
+            return None;
+        }
+        rows *= shape[dim];
+    }
+
+    Some((rows, row_stride))
+}
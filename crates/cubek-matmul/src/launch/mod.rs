@@ -1,4 +1,8 @@
+pub mod launch_block_scaled;
+pub mod launch_grouped;
 pub mod launch_naive;
+pub mod launch_sparse_24;
+pub mod launch_split_k;
 pub mod launch_tiling;
 
 mod args;
@@ -3,7 +3,8 @@ use crate::definition::MatmulElems;
 use crate::definition::MatmulLineSizes;
 use crate::definition::MatmulProblem;
 use crate::definition::MatmulSetupError;
-use crate::launch::handle::MatmulInputHandleRef;
+use crate::definition::select_stage_dtypes;
+use crate::launch::handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef};
 use crate::launch::{
     ConcreteInputsFactory, ConcreteOutputFactory, InputArg, InputRuntimeArg, MatmulArgs, OutputArg,
     OutputRuntimeArg,
@@ -22,6 +23,7 @@ pub fn launch_kernel_concrete<MA: MatmulArgs, R: Runtime, A: Routine>(
     lhs: &MatmulInputHandleRef<'_, R>,
     rhs: &MatmulInputHandleRef<'_, R>,
     out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
     problem: MatmulProblem,
     line_sizes: MatmulLineSizes,
     plane_dim: u32,
@@ -61,6 +63,7 @@ where
         client,
         lhs,
         rhs,
+        epilogue,
         &launch_info.blueprint,
         &problem,
         &line_sizes,
@@ -70,6 +73,7 @@ where
     let output = <OutputArg<MA> as ConcreteOutputFactory<A>>::create(
         client,
         out,
+        epilogue,
         &launch_info.blueprint,
         &problem,
         &line_sizes,
@@ -121,19 +125,13 @@ fn launch_kernel<'a, MA: MatmulArgs, R: Runtime, A: Routine>(
     config: A::Config,
     dtypes: &mut MatmulElems,
 ) -> Result<(), MatmulSetupError> {
-    // Prefer output type for stage because it's the same size at best, but often smaller.
-    // Having stage == global also enables things like TMA, and an f16 stage for output enables
-    // using `stmatrix` on the registers after casting.
-    if A::can_cast_stage_element() {
-        dtypes.lhs_stage.dtype = dtypes.lhs_global.dtype;
-        dtypes.rhs_stage.dtype = dtypes.rhs_global.dtype;
-        dtypes.acc_stage.dtype = dtypes.acc_global.dtype;
-    }
+    select_stage_dtypes(dtypes, A::can_cast_stage_element());
 
     let cube_count_plan = config.cube_count_plan(
         &problem,
         &client.properties().hardware.max_cube_count.clone(),
     );
+    let tile_counter = cube_count_plan.tile_counter(client);
 
     A::launch::<MA, R>(
         client,
@@ -141,7 +139,7 @@ fn launch_kernel<'a, MA: MatmulArgs, R: Runtime, A: Routine>(
         cube_count_plan.resolve(),
         input,
         output,
-        cube_count_plan.as_args(),
+        cube_count_plan.as_args(tile_counter.as_ref()),
         config,
         dtypes,
     )
@@ -4,6 +4,7 @@ use cubecl::{Runtime, client::ComputeClient, prelude::TensorHandleRef};
 
 use crate::{
     components::{
+        batch::SplitKMode,
         global::read::{
             async_full_cyclic, async_full_strided, async_partial_cyclic::AsyncPartialCyclicLoading,
             async_partial_strided::AsyncPartialStridedLoading, sync_full_strided,
@@ -12,10 +13,16 @@ use crate::{
         stage::{ColMajorTilingOrder, RowMajorTilingOrder},
         tile::{cmma::CmmaMatmul, io::Filled, mma::MmaMatmul},
     },
-    definition::{MatmulElems, MatmulSetupError},
-    launch::{handle::MatmulInputHandleRef, launch_naive, launch_tiling},
+    definition::{MatmulElems, MatmulKind, MatmulProblemSize, MatmulSetupError},
+    launch::{
+        handle::{MatmulEpilogueHandleRef, MatmulInputHandleRef},
+        launch_block_scaled, launch_grouped, launch_naive, launch_sparse_24, launch_split_k,
+        launch_tiling,
+        tune_key::is_k_dominant,
+    },
     routines::{
         BlueprintStrategy,
+        block_scaled_unit::BlockScaledUnitAlgorithm,
         double_buffering::{
             AsyncCyclicDoubleBufferingAlgorithm, AsyncStridedDoubleBufferingAlgorithm,
             CyclicDoubleBufferingAlgorithm, HybridDoubleBufferingAlgorithm,
@@ -25,6 +32,7 @@ use crate::{
         ordered_double_buffering::OrderedDoubleBufferingAlgorithm,
         simple::{SimpleAlgorithm, SimpleTmaAlgorithm},
         simple_unit::SimpleUnitAlgorithm,
+        sparse_24_unit::Sparse24UnitAlgorithm,
         specialized::SpecializedAlgorithm,
         vecmat::{DoubleVecMatAlgorithm, SimpleVecMatAlgorithm},
     },
@@ -33,7 +41,7 @@ use crate::{
 type Cmma = CmmaMatmul<Filled>;
 type Mma = MmaMatmul;
 
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default)]
 pub enum Strategy {
     SimpleCyclicCmma(BlueprintStrategy<SimpleAlgorithm<Cmma>>),
     SimpleCyclicMma(BlueprintStrategy<SimpleAlgorithm<Mma>>),
@@ -145,6 +153,9 @@ pub enum Strategy {
     DoubleUnit(BlueprintStrategy<DoubleUnitAlgorithm>),
     SimpleVecMat(BlueprintStrategy<SimpleVecMatAlgorithm>),
     DoubleVecMat(BlueprintStrategy<DoubleVecMatAlgorithm>),
+    SplitKCyclicCmma(BlueprintStrategy<SimpleAlgorithm<Cmma>>, SplitKMode),
+    SplitKCyclicMma(BlueprintStrategy<SimpleAlgorithm<Mma>>, SplitKMode),
+    SplitKUnit(BlueprintStrategy<SimpleUnitAlgorithm>, SplitKMode),
     Naive,
     #[default]
     Auto,
@@ -289,6 +300,18 @@ impl Display for Strategy {
             Strategy::DoubleVecMat(blueprint_strategy) => {
                 f.write_fmt(format_args!("matmul_double_vecmat{}", blueprint_strategy))
             }
+            Strategy::SplitKCyclicCmma(blueprint_strategy, mode) => f.write_fmt(format_args!(
+                "matmul_split_k_cyclic_cmma{}{}",
+                blueprint_strategy, mode
+            )),
+            Strategy::SplitKCyclicMma(blueprint_strategy, mode) => f.write_fmt(format_args!(
+                "matmul_split_k_cyclic_mma{}{}",
+                blueprint_strategy, mode
+            )),
+            Strategy::SplitKUnit(blueprint_strategy, mode) => f.write_fmt(format_args!(
+                "matmul_split_k_unit{}{}",
+                blueprint_strategy, mode
+            )),
             Strategy::Naive => f.write_str("matmul_naive"),
             Strategy::Auto => f.write_str("matmul_auto"),
         }
@@ -303,121 +326,316 @@ impl Strategy {
         lhs: &MatmulInputHandleRef<R>,
         rhs: &MatmulInputHandleRef<R>,
         out: &TensorHandleRef<R>,
+        epilogue: &MatmulEpilogueHandleRef<R>,
         dtypes: &mut MatmulElems,
     ) -> Result<(), MatmulSetupError> {
+        if matches!(lhs, MatmulInputHandleRef::Sparse24 { .. })
+            || matches!(rhs, MatmulInputHandleRef::Sparse24 { .. })
+        {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "2:4 sparse inputs are only supported by the sparse matmul",
+            )));
+        }
+
         match self {
             Strategy::SimpleCyclicCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleCyclicMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleStridedCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleStridedMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleTilewiseCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleTilewiseMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleAsyncStridedCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleAsyncStridedMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleAsyncCyclicCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleAsyncCyclicMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleTmaCmma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleTmaMma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleCyclicCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleCyclicMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleTilewiseCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleTilewiseMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleHybridCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleHybridMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleAsyncCyclicCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleAsyncCyclicMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleAsyncStridedCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleAsyncStridedMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleTmaCmma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleTmaMma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedCyclicCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedCyclicMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedStridedCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedStridedMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedTmaCmma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SpecializedTmaMma(selection) => {
-                launch_tiling::launch_ref_tma(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref_tma(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::OrderedDoubleCmma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::OrderedDoubleMma(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleUnit(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleUnit(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::SimpleVecMat(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
             }
             Strategy::DoubleVecMat(selection) => {
-                launch_tiling::launch_ref(client, lhs, rhs, out, selection, dtypes)
+                launch_tiling::launch_ref(client, lhs, rhs, out, epilogue, selection, dtypes)
+            }
+            Strategy::SplitKCyclicCmma(selection, mode) => launch_split_k::launch_ref(
+                client, lhs, rhs, out, epilogue, *mode, selection, dtypes,
+            ),
+            Strategy::SplitKCyclicMma(selection, mode) => launch_split_k::launch_ref(
+                client, lhs, rhs, out, epilogue, *mode, selection, dtypes,
+            ),
+            Strategy::SplitKUnit(selection, mode) => launch_split_k::launch_ref(
+                client, lhs, rhs, out, epilogue, *mode, selection, dtypes,
+            ),
+            Strategy::Naive => {
+                if !epilogue.config().is_identity() {
+                    return Err(MatmulSetupError::InvalidConfig(Box::new(
+                        "The naive matmul doesn't support fused epilogues",
+                    )));
+                }
+                launch_naive::launch_ref(client, lhs, rhs, out, dtypes)
+            }
+            Strategy::Auto => auto(client, lhs, rhs, out, epilogue, dtypes),
+        }
+    }
+
+    pub(crate) fn launch_ref_grouped<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        lhs: &MatmulInputHandleRef<R>,
+        rhs: &MatmulInputHandleRef<R>,
+        group_offsets: &TensorHandleRef<R>,
+        out: &TensorHandleRef<R>,
+        dtypes: &mut MatmulElems,
+    ) -> Result<(), MatmulSetupError> {
+        match self {
+            Strategy::SimpleCyclicCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleCyclicMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleStridedCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleStridedMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleTilewiseCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleTilewiseMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleAsyncStridedCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleAsyncStridedMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleAsyncCyclicCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleAsyncCyclicMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleCyclicCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleCyclicMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleTilewiseCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleTilewiseMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleHybridCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleHybridMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleAsyncCyclicCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleAsyncCyclicMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleAsyncStridedCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleAsyncStridedMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SpecializedCyclicCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SpecializedCyclicMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SpecializedStridedCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SpecializedStridedMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::OrderedDoubleCmma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::OrderedDoubleMma(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleUnit(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::DoubleUnit(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleVecMat(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
             }
-            Strategy::Naive => launch_naive::launch_ref(client, lhs, rhs, out, dtypes),
-            Strategy::Auto => auto(client, lhs, rhs, out, dtypes),
+            Strategy::DoubleVecMat(selection) => {
+                launch_grouped::launch_ref(client, lhs, rhs, group_offsets, out, selection, dtypes)
+            }
+            Strategy::SimpleTmaCmma(_)
+            | Strategy::SimpleTmaMma(_)
+            | Strategy::DoubleTmaCmma(_)
+            | Strategy::DoubleTmaMma(_)
+            | Strategy::SpecializedTmaCmma(_)
+            | Strategy::SpecializedTmaMma(_)
+            | Strategy::Naive => Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul requires bounds checked loads, unsupported by TMA and naive strategies",
+            ))),
+            Strategy::SplitKCyclicCmma(..)
+            | Strategy::SplitKCyclicMma(..)
+            | Strategy::SplitKUnit(..) => Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Grouped matmul doesn't support split-K strategies",
+            ))),
+            Strategy::Auto => auto_grouped(client, lhs, rhs, group_offsets, out, dtypes),
         }
     }
+
+    pub(crate) fn launch_ref_block_scaled<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        lhs: &MatmulInputHandleRef<R>,
+        rhs: &MatmulInputHandleRef<R>,
+        out: &TensorHandleRef<R>,
+        dtypes: &mut MatmulElems,
+    ) -> Result<(), MatmulSetupError> {
+        let selection: BlueprintStrategy<BlockScaledUnitAlgorithm> = match self {
+            Strategy::SimpleUnit(BlueprintStrategy::Forced(blueprint)) => {
+                BlueprintStrategy::Forced(blueprint.clone())
+            }
+            Strategy::SimpleUnit(BlueprintStrategy::Inferred(args)) => {
+                BlueprintStrategy::Inferred(args.clone())
+            }
+            Strategy::Auto => Default::default(),
+            _ => {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(
+                    "Block scaled matmul is only available with the simple unit strategy",
+                )));
+            }
+        };
+
+        launch_block_scaled::launch_ref(client, lhs, rhs, out, &selection, dtypes)
+    }
+
+    pub(crate) fn launch_ref_sparse_24<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        lhs: &MatmulInputHandleRef<R>,
+        rhs: &MatmulInputHandleRef<R>,
+        out: &TensorHandleRef<R>,
+        dtypes: &mut MatmulElems,
+    ) -> Result<(), MatmulSetupError> {
+        let selection: BlueprintStrategy<Sparse24UnitAlgorithm> = match self {
+            Strategy::SimpleUnit(BlueprintStrategy::Forced(blueprint)) => {
+                BlueprintStrategy::Forced(blueprint.clone())
+            }
+            Strategy::SimpleUnit(BlueprintStrategy::Inferred(args)) => {
+                BlueprintStrategy::Inferred(args.clone())
+            }
+            Strategy::Auto => Default::default(),
+            _ => {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(
+                    "2:4 sparse matmul is only available with the simple unit strategy",
+                )));
+            }
+        };
+
+        launch_sparse_24::launch_ref(client, lhs, rhs, out, &selection, dtypes)
+    }
 }
 
 fn auto<R: Runtime>(
@@ -425,20 +643,70 @@ fn auto<R: Runtime>(
     lhs: &MatmulInputHandleRef<'_, R>,
     rhs: &MatmulInputHandleRef<'_, R>,
     out: &TensorHandleRef<'_, R>,
+    epilogue: &MatmulEpilogueHandleRef<'_, R>,
     dtypes: &mut MatmulElems,
 ) -> Result<(), MatmulSetupError> {
-    if let Err(err) =
-        Strategy::SimpleCyclicCmma(Default::default()).launch_ref(client, lhs, rhs, out, dtypes)
-    {
-        match err {
-            MatmulSetupError::Unavailable(_) => {
-                Strategy::SimpleUnit(Default::default())
-                    .launch_ref(client, lhs, rhs, out, dtypes)
-                    .unwrap();
+    // Too few output tiles to fill the GPU, splitting k is tried first. The dtypes are only
+    // updated if it launches, since unsupported epilogues or layouts fall back to the others.
+    if prefers_split_k(lhs, rhs) {
+        let mut split_k_dtypes = dtypes.clone();
+        match Strategy::SplitKCyclicCmma(Default::default(), SplitKMode::from_device(client))
+            .launch_ref(client, lhs, rhs, out, epilogue, &mut split_k_dtypes)
+        {
+            Err(MatmulSetupError::Unavailable(_) | MatmulSetupError::InvalidConfig(_)) => {}
+            result => {
+                *dtypes = split_k_dtypes;
+                return result;
             }
-            _ => panic!("{err:?}"),
         }
     }
 
-    Ok(())
+    match Strategy::SimpleCyclicCmma(Default::default())
+        .launch_ref(client, lhs, rhs, out, epilogue, dtypes)
+    {
+        Err(MatmulSetupError::Unavailable(_)) => Strategy::SimpleUnit(Default::default())
+            .launch_ref(client, lhs, rhs, out, epilogue, dtypes),
+        result => result,
+    }
+}
+
+/// Whether the problem is a general matmul with a dominant k, see [is_k_dominant].
+fn prefers_split_k<R: Runtime>(
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+) -> bool {
+    let (lhs_shape, rhs_shape) = (lhs.shape(), rhs.shape());
+    let (lhs_rank, rhs_rank) = (lhs_shape.len(), rhs_shape.len());
+    let m = lhs_shape[lhs_rank - 2];
+    let k = lhs_shape[lhs_rank - 1];
+    let n = rhs_shape[rhs_rank - 1];
+
+    let kind = MatmulKind::from(MatmulProblemSize {
+        m: m as u32,
+        n: n as u32,
+        k: k as u32,
+    });
+    matches!(kind, MatmulKind::General) && is_k_dominant(m, n, k)
+}
+
+fn auto_grouped<R: Runtime>(
+    client: &ComputeClient<R>,
+    lhs: &MatmulInputHandleRef<'_, R>,
+    rhs: &MatmulInputHandleRef<'_, R>,
+    group_offsets: &TensorHandleRef<'_, R>,
+    out: &TensorHandleRef<'_, R>,
+    dtypes: &mut MatmulElems,
+) -> Result<(), MatmulSetupError> {
+    match Strategy::SimpleCyclicCmma(Default::default()).launch_ref_grouped(
+        client,
+        lhs,
+        rhs,
+        group_offsets,
+        out,
+        dtypes,
+    ) {
+        Err(MatmulSetupError::Unavailable(_)) => Strategy::SimpleUnit(Default::default())
+            .launch_ref_grouped(client, lhs, rhs, group_offsets, out, dtypes),
+        result => result,
+    }
 }
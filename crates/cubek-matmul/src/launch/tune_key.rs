@@ -49,6 +49,9 @@ pub enum MatmulGlobalScale {
 pub struct MatmulAutotuneAnalysis {
     pub scale_global: MatmulGlobalScale,
     pub kind: MatmulKind,
+    /// Whether k is much larger than m and n, so that too few output tiles are available to
+    /// fill the GPU without splitting k.
+    pub k_dominant: bool,
 }
 
 impl MatmulGlobalScale {
@@ -63,6 +66,19 @@ impl MatmulGlobalScale {
     }
 }
 
+/// Minimum ratio between k and the largest of m and n for k to be considered dominant.
+const K_DOMINANCE_RATIO: usize = 8;
+
+/// Whether k is large enough compared to m and n to benefit from being split across cubes.
+pub fn is_k_dominant(m: usize, n: usize, k: usize) -> bool {
+    k >= 1024 && k >= K_DOMINANCE_RATIO * m.max(n)
+}
+
+/// Whether it's a good idea to try and run split-K or stream-K matmul.
+pub fn should_tune_split_k(key: &MatmulAutotuneKey) -> bool {
+    matches!(key.analysis.kind, MatmulKind::General) && key.analysis.k_dominant
+}
+
 /// Whether it's a good idea to try and run double-buffered matmul.
 pub fn should_tune_double_buffering(fused: bool, key: &MatmulAutotuneKey) -> bool {
     matches!(key.analysis.kind, MatmulKind::General)
@@ -154,6 +170,7 @@ impl MatmulAutotuneKey {
         let analysis = MatmulAutotuneAnalysis {
             scale_global: MatmulGlobalScale::from_size(m, n, k),
             kind,
+            k_dominant: is_k_dominant(m, n, k),
         };
 
         Self::new(definition, analysis)
@@ -9,7 +9,7 @@ use std::fmt::{Debug, Display};
 
 /// Specifications for a matmul algorithm
 pub trait Routine: Sized {
-    type Strategy: Default + Display + Clone;
+    type Strategy: Default + Display + Debug + Clone;
     type Blueprint: Debug + Clone;
     type Config: BatchConfig;
 
@@ -65,6 +65,9 @@ pub trait Routine: Sized {
 
     // Ideally put this elsewhere
     fn can_cast_stage_element() -> bool;
+
+    /// Whether the tile matmul runs on matrix accelerator instructions
+    fn requires_accelerator() -> bool;
 }
 
 pub struct LaunchInfo<B: Debug + Clone> {
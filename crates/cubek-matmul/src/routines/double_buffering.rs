@@ -124,6 +124,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 impl<TMM> base::Routine for AsyncCyclicDoubleBufferingAlgorithm<TMM>
@@ -179,6 +183,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 impl<TMM> Routine for TilewiseDoubleBufferingAlgorithm<TMM>
@@ -236,6 +244,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 impl<TMM> base::Routine for HybridDoubleBufferingAlgorithm<TMM>
@@ -292,6 +304,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 impl<TMM> base::Routine for TmaDoubleBufferingAlgorithm<TMM>
@@ -347,6 +363,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 impl<TMM> base::Routine for AsyncStridedDoubleBufferingAlgorithm<TMM>
@@ -402,4 +422,8 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
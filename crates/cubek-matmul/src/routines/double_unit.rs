@@ -79,4 +79,8 @@ impl Routine for DoubleUnitAlgorithm {
     fn can_cast_stage_element() -> bool {
         RegisterMatmul::<Filled>::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        RegisterMatmul::<Filled>::requires_accelerator()
+    }
 }
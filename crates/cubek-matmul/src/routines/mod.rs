@@ -1,11 +1,13 @@
 /// Naive non-cooperative matmul without tiling that can be very fast on small matrices.
 pub mod naive;
 
+pub mod block_scaled_unit;
 pub mod double_buffering;
 pub mod double_unit;
 pub mod ordered_double_buffering;
 pub mod simple;
 pub mod simple_unit;
+pub mod sparse_24_unit;
 pub mod specialized;
 pub mod vecmat;
 
@@ -11,7 +11,7 @@ use crate::{
 
 pub struct NaiveRoutine {}
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct NaiveStrategy {}
 
 impl Display for NaiveStrategy {
@@ -47,4 +47,8 @@ impl Routine for NaiveRoutine {
         // Irrelevant
         false
     }
+
+    fn requires_accelerator() -> bool {
+        false
+    }
 }
@@ -107,4 +107,8 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
@@ -1,5 +1,5 @@
 use crate::routines::Routine;
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
 
 pub enum BlueprintStrategy<A: Routine> {
     /// Use a predefined blueprint
@@ -36,6 +36,15 @@ impl<A: Routine> Display for BlueprintStrategy<A> {
     }
 }
 
+impl<A: Routine> Debug for BlueprintStrategy<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Forced(blueprint) => f.debug_tuple("Forced").field(blueprint).finish(),
+            Self::Inferred(strategy) => f.debug_tuple("Inferred").field(strategy).finish(),
+        }
+    }
+}
+
 impl<A: Routine> Clone for BlueprintStrategy<A> {
     fn clone(&self) -> Self {
         match self {
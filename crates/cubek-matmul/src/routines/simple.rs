@@ -124,6 +124,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 fn infer_blueprint_multi_rows<R: Runtime, TMM: TileMatmulFamily>(
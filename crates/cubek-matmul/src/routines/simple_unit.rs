@@ -108,4 +108,8 @@ where
     fn can_cast_stage_element() -> bool {
         RegisterMatmul::<Filled>::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        RegisterMatmul::<Filled>::requires_accelerator()
+    }
 }
@@ -0,0 +1,117 @@
+use cubecl::{Runtime, client::ComputeClient};
+
+use std::marker::PhantomData;
+
+use crate::{
+    components::{
+        batch::{BatchMatmulFamily, PartitionedBatchMatmulFamily, RowMajorGlobalPartitionMatmul},
+        global::{
+            UnitWriterFamily,
+            read::{FullLoadingStrategy, sync_full_cyclic::SyncFullCyclicLoading},
+            single_stage::sparse_24::Sparse24MatmulFamily,
+        },
+        stage::{
+            ColMajorTilingOrder, FilledStageFamily, RowMajorTilingOrder, Sparse24StageFamily,
+            StridedStageFamily, UnitMatmulFamily,
+        },
+        tile::{
+            TileMatmulFamily,
+            io::{Filled, Sparse24},
+            register::RegisterMatmul,
+        },
+    },
+    definition::{MatmulElems, MatmulProblem, MatmulSetupError, TilingBlueprint},
+    routines::{
+        BlueprintStrategy, DeviceSettings, LaunchInfo,
+        selector::{
+            PartitionScaling, StageScaling, TileSizeSelection, UnitTilingBlueprintOptions,
+            infer_blueprint_unit,
+        },
+        simple_unit::SimpleUnitSelectionArgs,
+    },
+};
+
+use super::Routine;
+
+/// Unit single stage matmul with 2:4 sparse rhs, whose kept values are expanded by the register
+/// tile matmul
+pub struct Sparse24UnitAlgorithm<
+    LL = SyncFullCyclicLoading<ColMajorTilingOrder>,
+    RL = SyncFullCyclicLoading<RowMajorTilingOrder>,
+> {
+    pub _ll: PhantomData<LL>,
+    pub _rl: PhantomData<RL>,
+}
+
+type TileMatmul = RegisterMatmul<Filled, Sparse24>;
+
+impl<LL, RL> Routine for Sparse24UnitAlgorithm<LL, RL>
+where
+    LL: FullLoadingStrategy,
+    RL: FullLoadingStrategy<SyncStrategy = LL::SyncStrategy>,
+{
+    type Strategy = SimpleUnitSelectionArgs;
+    type BatchMatmul = PartitionedBatchMatmulFamily<
+        Sparse24MatmulFamily<
+            UnitMatmulFamily<
+                TileMatmul,
+                StridedStageFamily,
+                FilledStageFamily,
+                Sparse24StageFamily,
+            >,
+            LL,
+            RL,
+            UnitWriterFamily,
+        >,
+        RowMajorGlobalPartitionMatmul,
+    >;
+    type Blueprint = TilingBlueprint;
+    type Config = <Self::BatchMatmul as BatchMatmulFamily>::Config;
+
+    fn prepare<R: Runtime>(
+        problem: &MatmulProblem,
+        device_settings: &DeviceSettings<R>,
+        strategy: &BlueprintStrategy<Self>,
+    ) -> Result<LaunchInfo<TilingBlueprint>, MatmulSetupError> {
+        match strategy {
+            BlueprintStrategy::Forced(blueprint) => Ok(LaunchInfo {
+                blueprint: blueprint.clone(),
+                dtypes: MatmulElems::from_globals(&problem.global_dtypes),
+            }),
+            BlueprintStrategy::Inferred(strategy) => Ok(infer_blueprint_unit(
+                &device_settings.client,
+                problem,
+                device_settings.plane_dim,
+                false,
+                &device_settings.line_sizes,
+                UnitTilingBlueprintOptions {
+                    tile: strategy.tile_size,
+                    stage: match strategy.tile_size {
+                        TileSizeSelection::MinTileSize => StageScaling::Enabled(2),
+                        TileSizeSelection::MaxTileSize => StageScaling::Disabled,
+                    },
+                    partition: match strategy.tile_size {
+                        TileSizeSelection::MinTileSize => PartitionScaling::Disabled,
+                        TileSizeSelection::MaxTileSize => PartitionScaling::Enabled,
+                    },
+                    swizzle: <TileMatmul as TileMatmulFamily>::should_swizzle(
+                        &device_settings.client,
+                    ),
+                },
+                &problem.global_dtypes,
+            )),
+        }
+    }
+
+    fn select_plane_dim<R: Runtime>(client: &ComputeClient<R>) -> u32 {
+        client.properties().hardware.plane_size_min
+    }
+
+    fn can_cast_stage_element() -> bool {
+        TileMatmul::can_cast_stage_element()
+    }
+
+    fn requires_accelerator() -> bool {
+        TileMatmul::requires_accelerator()
+    }
+}
@@ -41,7 +41,7 @@ pub struct SpecializedAlgorithm<TMM, L = AsyncPartialTmaLoading> {
     pub _phantom: PhantomData<(TMM, L)>,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct SpecializedStrategy {}
 
 impl Display for SpecializedStrategy {
@@ -109,6 +109,10 @@ where
     fn can_cast_stage_element() -> bool {
         TMM::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        TMM::requires_accelerator()
+    }
 }
 
 #[allow(unused, reason = "needs more tuning")]
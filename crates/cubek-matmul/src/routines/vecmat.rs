@@ -32,7 +32,7 @@ use crate::{
 
 pub struct SimpleVecMatAlgorithm {}
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct VecMatStrategy {}
 
 impl Display for VecMatStrategy {
@@ -95,6 +95,10 @@ impl Routine for SimpleVecMatAlgorithm {
     fn can_cast_stage_element() -> bool {
         PlaneVecMatInnerProduct::<Filled>::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        PlaneVecMatInnerProduct::<Filled>::requires_accelerator()
+    }
 }
 
 pub struct DoubleVecMatAlgorithm {}
@@ -148,6 +152,10 @@ impl Routine for DoubleVecMatAlgorithm {
     fn can_cast_stage_element() -> bool {
         PlaneVecMatInnerProduct::<Filled>::can_cast_stage_element()
     }
+
+    fn requires_accelerator() -> bool {
+        PlaneVecMatInnerProduct::<Filled>::requires_accelerator()
+    }
 }
 
 fn infer_blueprint_vecmat<R: Runtime>(
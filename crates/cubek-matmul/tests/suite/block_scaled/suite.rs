@@ -0,0 +1,306 @@
+use crate::suite::assert_result_matches;
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+use cubecl::prelude::{StorageType, e2m1x2, e4m3, ue8m0};
+use cubecl_common::quant::scheme::{
+    QuantLevel, QuantMode, QuantParam, QuantScheme, QuantStore, QuantValue,
+};
+
+use cubecl::std::tensor::TensorHandle;
+use cubek_matmul::definition::MatmulElems;
+use cubek_matmul::definition::MatmulGlobalElems;
+use cubek_matmul::launch::{MatmulInputHandleRef, Strategy, launch_ref, launch_ref_block_scaled};
+use cubek_test_utils::{
+    Distribution, HostData, HostDataType, StrideSpec, TestInput, current_test_mode,
+};
+
+type TestRuntime = cubecl::TestRuntime;
+
+struct BlockScaledTestCase {
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    /// Number of consecutive rows of rhs sharing the same scale
+    pub block_k: usize,
+    pub format: BlockFormat,
+    pub elems: MatmulGlobalElems,
+}
+
+#[derive(Clone, Copy)]
+/// Encoding of the values and scales of rhs
+enum BlockFormat {
+    /// `i8` values with `f32` scales
+    Int8,
+    /// `e2m1` values with `ue8m0` scales, as in MXFP4
+    Mxfp4,
+    /// `e2m1` values with `ue4m3` scales, as in NVFP4
+    Nvfp4,
+}
+
+#[test]
+pub fn test_small_blocks() {
+    let case = BlockScaledTestCase {
+        m: 32,
+        n: 32,
+        k: 64,
+        block_k: 4,
+        format: BlockFormat::Int8,
+        elems: elems(),
+    };
+
+    test_block_scaled(case);
+}
+
+#[test]
+pub fn test_blocks_of_32() {
+    let case = BlockScaledTestCase {
+        m: 33,
+        n: 40,
+        k: 128,
+        block_k: 32,
+        format: BlockFormat::Int8,
+        elems: elems(),
+    };
+
+    test_block_scaled(case);
+}
+
+#[test]
+pub fn test_single_block() {
+    let case = BlockScaledTestCase {
+        m: 16,
+        n: 24,
+        k: 64,
+        block_k: 64,
+        format: BlockFormat::Int8,
+        elems: elems(),
+    };
+
+    test_block_scaled(case);
+}
+
+#[test]
+pub fn test_mxfp4() {
+    let case = BlockScaledTestCase {
+        m: 33,
+        n: 40,
+        k: 128,
+        block_k: 32,
+        format: BlockFormat::Mxfp4,
+        elems: elems(),
+    };
+
+    test_block_scaled(case);
+}
+
+#[test]
+pub fn test_nvfp4() {
+    let case = BlockScaledTestCase {
+        m: 16,
+        n: 24,
+        k: 64,
+        block_k: 16,
+        format: BlockFormat::Nvfp4,
+        elems: elems(),
+    };
+
+    test_block_scaled(case);
+}
+
+/// Values of the `e2m1` codes, the fourth bit being the sign
+const E2M1_VALUES: [f32; 8] = [0., 0.5, 1., 1.5, 2., 3., 4., 6.];
+
+fn decode_e2m1(code: u8) -> f32 {
+    let value = E2M1_VALUES[(code & 0x7) as usize];
+    if code & 0x8 != 0 { -value } else { value }
+}
+
+/// Scales of the blocks of a fp4 format, along with their encoding and type
+fn fp4_scales(format: BlockFormat, count: usize) -> (Vec<u8>, QuantParam, StorageType) {
+    match format {
+        BlockFormat::Mxfp4 => (
+            (0..count).map(|i| 124 + (i * 5 % 7) as u8).collect(),
+            QuantParam::UE8M0,
+            ue8m0::as_type_native_unchecked(),
+        ),
+        // Also covers subnormal scales, with a zero exponent
+        BlockFormat::Nvfp4 => (
+            (0..count)
+                .map(|i| (((i * 3) % 10) << 3) | ((i * 5) % 8))
+                .map(|bits| bits as u8)
+                .collect(),
+            QuantParam::UE4M3,
+            e4m3::as_type_native_unchecked(),
+        ),
+        BlockFormat::Int8 => unreachable!("Int8 blocks have f32 scales"),
+    }
+}
+
+fn decode_ue8m0(bits: u8) -> f32 {
+    2f32.powi(bits as i32 - 127)
+}
+
+fn decode_ue4m3(bits: u8) -> f32 {
+    let (exponent, mantissa) = ((bits >> 3) & 0xF, (bits & 0x7) as f32 / 8.);
+    match exponent {
+        0 => mantissa * 2f32.powi(-6),
+        _ => (1. + mantissa) * 2f32.powi(exponent as i32 - 7),
+    }
+}
+
+/// Compares the block scaled matmul with the regular matmul on the dequantized rhs.
+fn test_block_scaled(case: BlockScaledTestCase) {
+    let client = TestRuntime::client(&Default::default());
+    let (m, n, k, block_k) = (case.m, case.n, case.k, case.block_k);
+    let num_blocks = k.div_ceil(block_k);
+
+    let lhs = TestInput::random(
+        client.clone(),
+        vec![m, k],
+        *case.elems.lhs,
+        1234,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let num_scales = num_blocks * n;
+    let (rhs_codes, rhs_scales, values, scales, param) = match case.format {
+        BlockFormat::Int8 => {
+            let codes: Vec<f32> = (0..k * n).map(|i| ((i * 7 + 3) % 15) as f32 - 7.).collect();
+            let scales: Vec<f32> = (0..num_scales)
+                .map(|i| 0.125 * ((i * 5) % 7 + 1) as f32)
+                .collect();
+
+            let rhs_codes = TestInput::custom(
+                client.clone(),
+                vec![k, n],
+                i8::as_type_native_unchecked(),
+                StrideSpec::RowMajor,
+                codes.clone(),
+            )
+            .generate_without_host_data();
+            let rhs_scales = TestInput::custom(
+                client.clone(),
+                vec![num_blocks, n],
+                f32::as_type_native_unchecked(),
+                StrideSpec::RowMajor,
+                scales.clone(),
+            )
+            .generate_without_host_data();
+
+            (rhs_codes, rhs_scales, codes, scales, QuantParam::F32)
+        }
+        BlockFormat::Mxfp4 | BlockFormat::Nvfp4 => {
+            let codes: Vec<u8> = (0..k * n).map(|i| ((i * 7 + 3) % 16) as u8).collect();
+            // Two codes per byte, the first one in the low bits
+            let packed: Vec<u8> = codes
+                .chunks(2)
+                .map(|pair| pair[0] | (pair[1] << 4))
+                .collect();
+
+            let (scale_bits, param, scale_dtype) = fp4_scales(case.format, num_scales);
+            let scales = scale_bits
+                .iter()
+                .map(|bits| match param {
+                    QuantParam::UE8M0 => decode_ue8m0(*bits),
+                    _ => decode_ue4m3(*bits),
+                })
+                .collect();
+            let rhs_scales = TensorHandle::new_contiguous(
+                vec![num_blocks, n],
+                client.create_from_slice(&scale_bits),
+                scale_dtype,
+            );
+            let rhs_codes = TensorHandle::new_contiguous(
+                vec![k, n / 2],
+                client.create_from_slice(&packed),
+                e2m1x2::as_type_native_unchecked(),
+            );
+
+            let values = codes.iter().map(|code| decode_e2m1(*code)).collect();
+            (rhs_codes, rhs_scales, values, scales, param)
+        }
+    };
+    let dequantized = (0..k * n)
+        .map(|i| {
+            let (row, col) = (i / n, i % n);
+            values[i] * scales[(row / block_k) * n + col]
+        })
+        .collect();
+
+    let rhs_dequantized = TestInput::custom(
+        client.clone(),
+        vec![k, n],
+        *case.elems.rhs,
+        StrideSpec::RowMajor,
+        dequantized,
+    )
+    .generate_without_host_data();
+
+    let shape = [k, n];
+    let value = match case.format {
+        BlockFormat::Int8 => QuantValue::Q8S,
+        BlockFormat::Mxfp4 | BlockFormat::Nvfp4 => QuantValue::E2M1,
+    };
+    let scheme = QuantScheme::default()
+        .with_level(QuantLevel::block([block_k as u8, 1]))
+        .with_mode(QuantMode::Symmetric)
+        .with_value(value)
+        .with_store(QuantStore::Native)
+        .with_param(param);
+
+    let out = TestInput::zeros(
+        client.clone(),
+        vec![m, n],
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let expected = TestInput::zeros(
+        client.clone(),
+        vec![m, n],
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let mut dtypes = MatmulElems::from_globals(&case.elems);
+
+    let result = launch_ref_block_scaled(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::quantized(
+            rhs_codes.as_ref(),
+            rhs_scales.as_ref(),
+            &shape,
+            &scheme,
+            rhs_codes.dtype,
+            rhs_scales.dtype,
+        ),
+        &out.as_ref(),
+        &mut dtypes,
+    );
+    if let Err(err) = result {
+        // Low precision formats aren't available on every runtime
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Can't launch the test: {err}");
+        }
+        return;
+    }
+
+    launch_ref(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::Normal(rhs_dequantized.as_ref(), *case.elems.rhs),
+        &expected.as_ref(),
+        &mut MatmulElems::from_globals(&case.elems),
+    )
+    .unwrap();
+
+    let expected = HostData::from_tensor_handle(&client, &expected, HostDataType::F32);
+
+    assert_result_matches(&expected, &client, &out, dtypes);
+}
@@ -0,0 +1,211 @@
+use crate::suite::assert_result;
+use cubecl::Runtime;
+use cubecl::client::ComputeClient;
+use cubecl::frontend::CubePrimitive;
+use cubecl::ir::StorageType;
+use cubecl::std::tensor::TensorHandle;
+
+use cubek_matmul::definition::{MatmulElems, MatmulGlobalElems, MatmulProblem};
+use cubek_matmul::launch::{MatmulInputHandleRef, Strategy, launch_ref};
+use cubek_test_utils::{Distribution, HostData, StrideSpec, TestInput};
+
+type TestRuntime = cubecl::TestRuntime;
+
+/// How the batch dims of an input are laid out in memory
+enum BatchLayout {
+    /// Contiguous batches
+    Contiguous,
+    /// First batch dim generated with a size of one, then expanded with a stride of zero
+    Expanded(usize),
+    /// Two first batch dims generated in the opposite order, then swapped back
+    Swapped,
+}
+
+struct BroadcastTestCase {
+    pub lhs_shape: Vec<usize>,
+    pub rhs_shape: Vec<usize>,
+    pub out_shape: Vec<usize>,
+    pub lhs_layout: BatchLayout,
+    pub rhs_layout: BatchLayout,
+    pub elems: MatmulGlobalElems,
+}
+
+#[test]
+pub fn test_rhs_unit_batch() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![3, 40, 32],
+        rhs_shape: vec![1, 32, 24],
+        out_shape: vec![3, 40, 24],
+        lhs_layout: BatchLayout::Contiguous,
+        rhs_layout: BatchLayout::Contiguous,
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+#[test]
+pub fn test_rhs_without_batch() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![2, 3, 17, 32],
+        rhs_shape: vec![32, 16],
+        out_shape: vec![2, 3, 17, 16],
+        lhs_layout: BatchLayout::Contiguous,
+        rhs_layout: BatchLayout::Contiguous,
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+#[test]
+pub fn test_lhs_unit_batch() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![1, 16, 32],
+        rhs_shape: vec![4, 32, 24],
+        out_shape: vec![4, 16, 24],
+        lhs_layout: BatchLayout::Contiguous,
+        rhs_layout: BatchLayout::Contiguous,
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+#[test]
+pub fn test_both_broadcast() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![2, 1, 16, 32],
+        rhs_shape: vec![1, 3, 32, 16],
+        out_shape: vec![2, 3, 16, 16],
+        lhs_layout: BatchLayout::Contiguous,
+        rhs_layout: BatchLayout::Contiguous,
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+#[test]
+pub fn test_zero_batch_stride() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![3, 16, 32],
+        rhs_shape: vec![3, 32, 16],
+        out_shape: vec![3, 16, 16],
+        lhs_layout: BatchLayout::Contiguous,
+        rhs_layout: BatchLayout::Expanded(3),
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+#[test]
+pub fn test_permuted_batches() {
+    let case = BroadcastTestCase {
+        lhs_shape: vec![2, 3, 16, 32],
+        rhs_shape: vec![2, 3, 32, 16],
+        out_shape: vec![2, 3, 16, 16],
+        lhs_layout: BatchLayout::Swapped,
+        rhs_layout: BatchLayout::Contiguous,
+        elems: elems(),
+    };
+
+    test_broadcast(case);
+}
+
+/// Generates an input with the given batch layout, returning it along with its host data
+fn generate_input(
+    client: &ComputeClient<TestRuntime>,
+    shape: &[usize],
+    layout: &BatchLayout,
+    dtype: StorageType,
+    seed: u64,
+) -> (TensorHandle<TestRuntime>, HostData) {
+    let mut generated_shape = shape.to_vec();
+    match layout {
+        BatchLayout::Contiguous => {}
+        BatchLayout::Expanded(_) => generated_shape[0] = 1,
+        BatchLayout::Swapped => generated_shape.swap(0, 1),
+    }
+
+    let (mut handle, mut data) = TestInput::random(
+        client.clone(),
+        generated_shape,
+        dtype,
+        seed,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    match layout {
+        BatchLayout::Contiguous => {}
+        BatchLayout::Expanded(size) => {
+            handle.shape[0] = *size;
+            handle.strides[0] = 0;
+            data.shape[0] = *size;
+            data.strides[0] = 0;
+        }
+        BatchLayout::Swapped => {
+            handle.shape.swap(0, 1);
+            handle.strides.swap(0, 1);
+            data.shape.swap(0, 1);
+            data.strides.swap(0, 1);
+        }
+    }
+
+    (handle, data)
+}
+
+/// Compares a matmul with broadcast or strided batches with the reference.
+fn test_broadcast(case: BroadcastTestCase) {
+    let client = TestRuntime::client(&Default::default());
+
+    let (lhs, lhs_data) = generate_input(
+        &client,
+        &case.lhs_shape,
+        &case.lhs_layout,
+        *case.elems.lhs,
+        1234,
+    );
+    let (rhs, rhs_data) = generate_input(
+        &client,
+        &case.rhs_shape,
+        &case.rhs_layout,
+        *case.elems.rhs,
+        5678,
+    );
+
+    let out = TestInput::zeros(
+        client.clone(),
+        case.out_shape.clone(),
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let problem = MatmulProblem::from_shapes_and_strides(
+        lhs.shape.clone(),
+        rhs.shape.clone(),
+        out.shape.clone(),
+        lhs.strides.clone(),
+        rhs.strides.clone(),
+        out.strides.clone(),
+        case.elems.clone(),
+    );
+
+    let mut dtypes = MatmulElems::from_globals(&case.elems);
+
+    launch_ref(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::Normal(rhs.as_ref(), *case.elems.rhs),
+        &out.as_ref(),
+        &mut dtypes,
+    )
+    .unwrap();
+
+    assert_result(&lhs_data, &rhs_data, &problem, &client, &out, dtypes);
+}
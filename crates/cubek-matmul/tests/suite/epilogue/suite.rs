@@ -0,0 +1,550 @@
+use crate::suite::{EpilogueReference, assert_result_with_epilogue};
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+
+use crate::suite::layout_to_stride_spec;
+use cubek_matmul::components::batch::SplitKMode;
+use cubek_matmul::definition::{Activation, MatmulElems, MatmulProblem};
+use cubek_matmul::definition::{MatmulGlobalElems, MatrixLayout};
+use cubek_matmul::launch::{
+    MatmulEpilogueHandleRef, MatmulInputHandleRef, Strategy, launch_ref_with_epilogue,
+};
+use cubek_test_utils::{Distribution, StrideSpec, TestInput, current_test_mode};
+
+type TestRuntime = cubecl::TestRuntime;
+
+#[derive(PartialEq, Eq)]
+enum CInput {
+    None,
+    /// Separate tensor
+    Tensor,
+    /// Accumulate into the output
+    Output,
+    /// Accumulate into an output full of NaN, with a `beta` of zero
+    NanOutput,
+}
+
+struct EpilogueTestCase {
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub batch: usize,
+    pub activation: Activation,
+    pub alpha: f32,
+    pub beta: f32,
+    pub c: CInput,
+    pub bias: bool,
+    pub residual: bool,
+    pub elems: MatmulGlobalElems,
+}
+
+impl EpilogueTestCase {
+    fn problem(&self) -> MatmulProblem {
+        MatmulProblem::from_parameters(
+            self.m,
+            self.n,
+            self.k,
+            vec![self.batch],
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            self.elems.clone(),
+        )
+    }
+}
+
+#[test]
+pub fn test_bias() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::None,
+        bias: true,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_bias_relu() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Relu,
+        bias: true,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_gelu() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Gelu,
+        bias: false,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_silu_residual() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Silu,
+        bias: false,
+        residual: true,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_bias_gelu_residual_batched() {
+    let case = EpilogueTestCase {
+        m: 64,
+        n: 32,
+        k: 48,
+        batch: 3,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Gelu,
+        bias: true,
+        residual: true,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_bias_relu_residual_out_of_bounds() {
+    let case = EpilogueTestCase {
+        m: 33,
+        n: 17,
+        k: 19,
+        batch: 2,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Relu,
+        bias: true,
+        residual: true,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_bias_silu_residual_double_buffering() {
+    let case = EpilogueTestCase {
+        m: 64,
+        n: 64,
+        k: 64,
+        batch: 1,
+        alpha: 1.0,
+        beta: 0.0,
+        c: CInput::None,
+        activation: Activation::Silu,
+        bias: true,
+        residual: true,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::DoubleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_alpha_beta_c() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        alpha: 0.5,
+        beta: 2.0,
+        c: CInput::Tensor,
+        activation: Activation::None,
+        bias: false,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_accumulate_into_output() {
+    let case = EpilogueTestCase {
+        m: 33,
+        n: 17,
+        k: 19,
+        batch: 2,
+        alpha: 1.0,
+        beta: 1.0,
+        c: CInput::Output,
+        activation: Activation::None,
+        bias: false,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_zero_beta_ignores_output() {
+    let case = EpilogueTestCase {
+        m: 33,
+        n: 17,
+        k: 19,
+        batch: 2,
+        alpha: 2.0,
+        beta: 0.0,
+        c: CInput::NanOutput,
+        activation: Activation::None,
+        bias: true,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_alpha_beta_c_split_k() {
+    let case = EpilogueTestCase {
+        m: 33,
+        n: 17,
+        k: 1000,
+        batch: 2,
+        alpha: 0.5,
+        beta: 2.0,
+        c: CInput::Tensor,
+        activation: Activation::None,
+        bias: false,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::SplitK { num_splits: 4 }),
+    );
+}
+
+#[test]
+pub fn test_accumulate_into_output_stream_k() {
+    let case = EpilogueTestCase {
+        m: 32,
+        n: 32,
+        k: 512,
+        batch: 1,
+        alpha: 1.0,
+        beta: 1.0,
+        c: CInput::Output,
+        activation: Activation::None,
+        bias: false,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::StreamK { num_cubes: 5 }),
+    );
+}
+
+#[test]
+pub fn test_alpha_beta_output_bias_relu_double_buffering() {
+    let case = EpilogueTestCase {
+        m: 64,
+        n: 64,
+        k: 64,
+        batch: 1,
+        alpha: -1.5,
+        beta: 0.25,
+        c: CInput::Output,
+        activation: Activation::Relu,
+        bias: true,
+        residual: false,
+        elems: elems(),
+    };
+
+    test_epilogue(case, Strategy::DoubleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_bias_gelu_residual_cyclic_cmma() {
+    test_epilogue(
+        plane_case(64, 64, 64),
+        Strategy::SimpleCyclicCmma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_cyclic_mma() {
+    test_epilogue(
+        plane_case(64, 64, 64),
+        Strategy::SimpleCyclicMma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_cyclic_cmma_out_of_bounds() {
+    test_epilogue(
+        plane_case(33, 17, 40),
+        Strategy::SimpleCyclicCmma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_double_cyclic_cmma() {
+    test_epilogue(
+        plane_case(64, 64, 128),
+        Strategy::DoubleCyclicCmma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_double_tilewise_mma() {
+    test_epilogue(
+        plane_case(64, 64, 128),
+        Strategy::DoubleTilewiseMma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_ordered_double_cmma() {
+    test_epilogue(
+        plane_case(64, 64, 128),
+        Strategy::OrderedDoubleCmma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_specialized_cyclic_cmma() {
+    test_epilogue(
+        plane_case(64, 64, 128),
+        Strategy::SpecializedCyclicCmma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_gelu_residual_specialized_strided_mma() {
+    test_epilogue(
+        plane_case(64, 64, 128),
+        Strategy::SpecializedStridedMma(Default::default()),
+    );
+}
+
+#[test]
+pub fn test_bias_of_wrong_length_rejected() {
+    let client = TestRuntime::client(&Default::default());
+    let case = plane_case(32, 32, 32);
+    let problem = case.problem();
+
+    let zeros = |shape: Vec<usize>, dtype| {
+        TestInput::zeros(client.clone(), shape, dtype, StrideSpec::RowMajor)
+            .generate_without_host_data()
+    };
+    let lhs = zeros(problem.lhs_shape.clone(), *problem.global_dtypes.lhs);
+    let rhs = zeros(problem.rhs_shape.clone(), *problem.global_dtypes.rhs);
+    let out = zeros(problem.out_shape.clone(), *problem.global_dtypes.out);
+    let bias = zeros(vec![case.n - 1], *problem.global_dtypes.out);
+
+    let result = launch_ref_with_epilogue(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *problem.global_dtypes.lhs),
+        &MatmulInputHandleRef::Normal(rhs.as_ref(), *problem.global_dtypes.rhs),
+        &out.as_ref(),
+        &MatmulEpilogueHandleRef::none().with_bias(bias.as_ref()),
+        &mut MatmulElems::from_globals(&problem.global_dtypes),
+    );
+
+    assert!(result.is_err());
+}
+
+/// A case using every part of the epilogue, for the algorithms writing with a plane.
+fn plane_case(m: usize, n: usize, k: usize) -> EpilogueTestCase {
+    EpilogueTestCase {
+        m,
+        n,
+        k,
+        batch: 2,
+        alpha: 0.5,
+        beta: 1.5,
+        c: CInput::Tensor,
+        activation: Activation::Gelu,
+        bias: true,
+        residual: true,
+        elems: elems(),
+    }
+}
+
+fn test_epilogue(case: EpilogueTestCase, strategy: Strategy) {
+    let client = TestRuntime::client(&Default::default());
+    let problem = case.problem();
+
+    let (lhs, lhs_data) = TestInput::random(
+        client.clone(),
+        problem.lhs_shape.clone(),
+        *problem.global_dtypes.lhs,
+        1234,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.lhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let (rhs, rhs_data) = TestInput::random(
+        client.clone(),
+        problem.rhs_shape.clone(),
+        *problem.global_dtypes.rhs,
+        5678,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.rhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let (bias, bias_data) = TestInput::random(
+        client.clone(),
+        vec![case.n],
+        *problem.global_dtypes.out,
+        91011,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    let (residual, residual_data) = TestInput::random(
+        client.clone(),
+        problem.out_shape.clone(),
+        *problem.global_dtypes.out,
+        121314,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    let (c, c_data) = TestInput::random(
+        client.clone(),
+        problem.out_shape.clone(),
+        *problem.global_dtypes.out,
+        151617,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    let out_input = match case.c {
+        CInput::NanOutput => TestInput::custom(
+            client.clone(),
+            problem.out_shape.clone(),
+            *problem.global_dtypes.out,
+            StrideSpec::RowMajor,
+            vec![f32::NAN; problem.out_shape.iter().product()],
+        ),
+        _ => TestInput::random(
+            client.clone(),
+            problem.out_shape.clone(),
+            *problem.global_dtypes.out,
+            181920,
+            Distribution::Uniform(-1., 1.),
+            StrideSpec::RowMajor,
+        ),
+    };
+    let (out, out_data) = out_input.generate_with_f32_host_data();
+
+    let lhs_handle = MatmulInputHandleRef::Normal(lhs.as_ref(), *problem.global_dtypes.lhs);
+    let rhs_handle = MatmulInputHandleRef::Normal(rhs.as_ref(), *problem.global_dtypes.rhs);
+    let out_handle = out.as_ref();
+
+    let mut epilogue = MatmulEpilogueHandleRef::none().with_activation(case.activation);
+    match case.c {
+        CInput::None => {}
+        CInput::Tensor => {
+            epilogue = epilogue.with_scaled_c(case.alpha, case.beta, c.as_ref());
+        }
+        CInput::Output | CInput::NanOutput => {
+            epilogue = epilogue.with_scaled_c(case.alpha, case.beta, out_handle);
+        }
+    }
+    if case.bias {
+        epilogue = epilogue.with_bias(bias.as_ref());
+    }
+    if case.residual {
+        epilogue = epilogue.with_residual(residual.as_ref());
+    }
+
+    let mut all_elems = MatmulElems::from_globals(&problem.global_dtypes.clone());
+
+    if let Err(err) = launch_ref_with_epilogue(
+        &strategy,
+        &client,
+        &lhs_handle,
+        &rhs_handle,
+        &out_handle,
+        &epilogue,
+        &mut all_elems,
+    ) {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Can't launch the test: {err}");
+        }
+        return;
+    }
+
+    let (alpha, beta, c_data) = match case.c {
+        CInput::None => (1.0, 0.0, None),
+        CInput::Tensor => (case.alpha, case.beta, Some(&c_data)),
+        CInput::Output => (case.alpha, case.beta, Some(&out_data)),
+        // `c` must be ignored, otherwise `0 * NaN` would still be NaN
+        CInput::NanOutput => (case.alpha, 0.0, None),
+    };
+    let reference = EpilogueReference {
+        activation: case.activation,
+        alpha,
+        beta,
+        c: c_data,
+        bias: case.bias.then_some(&bias_data),
+        residual: case.residual.then_some(&residual_data),
+        lhs_scale: None,
+        rhs_scale: None,
+    };
+
+    assert_result_with_epilogue(
+        &lhs_data, &rhs_data, &reference, &problem, &client, &out, all_elems,
+    );
+}
@@ -0,0 +1,27 @@
+mod e4m3_ty {
+    use cubek_matmul::definition::MatmulElemType;
+
+    fn elems() -> MatmulGlobalElems {
+        MatmulGlobalElems {
+            lhs: MatmulElemType::new(cubecl::prelude::e4m3::as_type_native_unchecked(), false),
+            rhs: MatmulElemType::new(cubecl::prelude::e4m3::as_type_native_unchecked(), false),
+            out: MatmulElemType::new(half::bf16::as_type_native_unchecked(), false),
+        }
+    }
+
+    include!("suite.rs");
+}
+
+mod e5m2_ty {
+    use cubek_matmul::definition::MatmulElemType;
+
+    fn elems() -> MatmulGlobalElems {
+        MatmulGlobalElems {
+            lhs: MatmulElemType::new(cubecl::prelude::e5m2::as_type_native_unchecked(), false),
+            rhs: MatmulElemType::new(cubecl::prelude::e5m2::as_type_native_unchecked(), false),
+            out: MatmulElemType::new(half::bf16::as_type_native_unchecked(), false),
+        }
+    }
+
+    include!("suite.rs");
+}
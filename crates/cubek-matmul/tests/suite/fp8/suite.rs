@@ -0,0 +1,234 @@
+use crate::suite::{EpilogueReference, assert_result_with_epilogue};
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+
+use crate::suite::layout_to_stride_spec;
+use cubek_matmul::definition::{Activation, MatmulElems, MatmulProblem};
+use cubek_matmul::definition::{MatmulGlobalElems, MatrixLayout};
+use cubek_matmul::launch::{
+    MatmulEpilogueHandleRef, MatmulInputHandleRef, Strategy, launch_ref_with_epilogue,
+};
+use cubek_test_utils::{Distribution, StrideSpec, TestInput};
+
+type TestRuntime = cubecl::TestRuntime;
+
+#[derive(Clone, Copy)]
+enum Scale {
+    None,
+    /// A single scale for the whole operand
+    Tensor,
+    /// One scale per row of lhs or per column of rhs
+    Channel,
+    /// One scale too many for a channel scale, which must be rejected
+    Mismatched,
+}
+
+struct Fp8TestCase {
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub batch: usize,
+    pub lhs_scale: Scale,
+    pub rhs_scale: Scale,
+    pub elems: MatmulGlobalElems,
+}
+
+impl Fp8TestCase {
+    fn problem(&self) -> MatmulProblem {
+        MatmulProblem::from_parameters(
+            self.m,
+            self.n,
+            self.k,
+            vec![self.batch],
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            self.elems.clone(),
+        )
+    }
+}
+
+#[test]
+pub fn test_fp8_unscaled() {
+    let case = Fp8TestCase {
+        m: 32,
+        n: 32,
+        k: 32,
+        batch: 1,
+        lhs_scale: Scale::None,
+        rhs_scale: Scale::None,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_fp8_tensor_scales() {
+    let case = Fp8TestCase {
+        m: 32,
+        n: 32,
+        k: 64,
+        batch: 2,
+        lhs_scale: Scale::Tensor,
+        rhs_scale: Scale::Tensor,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_fp8_channel_scales() {
+    let case = Fp8TestCase {
+        m: 64,
+        n: 32,
+        k: 48,
+        batch: 1,
+        lhs_scale: Scale::Channel,
+        rhs_scale: Scale::Channel,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_fp8_mixed_scales_out_of_bounds() {
+    let case = Fp8TestCase {
+        m: 33,
+        n: 17,
+        k: 19,
+        batch: 1,
+        lhs_scale: Scale::Channel,
+        rhs_scale: Scale::Tensor,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_fp8_channel_scales_cmma() {
+    let case = Fp8TestCase {
+        m: 64,
+        n: 64,
+        k: 64,
+        batch: 1,
+        lhs_scale: Scale::Channel,
+        rhs_scale: Scale::Channel,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleCyclicCmma(Default::default()));
+}
+
+#[test]
+pub fn test_fp8_mismatched_scales_rejected() {
+    let case = Fp8TestCase {
+        m: 16,
+        n: 24,
+        k: 32,
+        batch: 1,
+        lhs_scale: Scale::Tensor,
+        rhs_scale: Scale::Mismatched,
+        elems: elems(),
+    };
+
+    test_fp8(case, Strategy::SimpleUnit(Default::default()));
+}
+
+fn test_fp8(case: Fp8TestCase, strategy: Strategy) {
+    let client = TestRuntime::client(&Default::default());
+    let problem = case.problem();
+
+    let (lhs, lhs_data) = TestInput::random(
+        client.clone(),
+        problem.lhs_shape.clone(),
+        *problem.global_dtypes.lhs,
+        1234,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.lhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let (rhs, rhs_data) = TestInput::random(
+        client.clone(),
+        problem.rhs_shape.clone(),
+        *problem.global_dtypes.rhs,
+        5678,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.rhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let scale = |scale: Scale, channels: usize, seed: u64| {
+        let len = match scale {
+            Scale::None | Scale::Tensor => 1,
+            Scale::Channel => channels,
+            Scale::Mismatched => channels + 1,
+        };
+        TestInput::random(
+            client.clone(),
+            vec![len],
+            f32::as_type_native_unchecked(),
+            seed,
+            Distribution::Uniform(0.5, 2.),
+            StrideSpec::RowMajor,
+        )
+        .generate_with_f32_host_data()
+    };
+    let (lhs_scale, lhs_scale_data) = scale(case.lhs_scale, case.m, 91011);
+    let (rhs_scale, rhs_scale_data) = scale(case.rhs_scale, case.n, 121314);
+
+    let out = TestInput::zeros(
+        client.clone(),
+        problem.out_shape.clone(),
+        *problem.global_dtypes.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let lhs_handle = MatmulInputHandleRef::Normal(lhs.as_ref(), *problem.global_dtypes.lhs);
+    let rhs_handle = MatmulInputHandleRef::Normal(rhs.as_ref(), *problem.global_dtypes.rhs);
+
+    let enabled = |scale: Scale| !matches!(scale, Scale::None);
+    let epilogue = MatmulEpilogueHandleRef::none().with_scales(
+        enabled(case.lhs_scale).then_some(lhs_scale.as_ref()),
+        enabled(case.rhs_scale).then_some(rhs_scale.as_ref()),
+    );
+
+    let mut all_elems = MatmulElems::from_globals(&problem.global_dtypes.clone());
+
+    let result = launch_ref_with_epilogue(
+        &strategy,
+        &client,
+        &lhs_handle,
+        &rhs_handle,
+        &out.as_ref(),
+        &epilogue,
+        &mut all_elems,
+    );
+
+    let mismatched = |scale: Scale| matches!(scale, Scale::Mismatched);
+    if mismatched(case.lhs_scale) || mismatched(case.rhs_scale) {
+        assert!(result.is_err());
+        return;
+    }
+    result.unwrap();
+
+    let reference = EpilogueReference {
+        activation: Activation::None,
+        alpha: 1.0,
+        beta: 0.0,
+        c: None,
+        bias: None,
+        residual: None,
+        lhs_scale: enabled(case.lhs_scale).then_some(&lhs_scale_data),
+        rhs_scale: enabled(case.rhs_scale).then_some(&rhs_scale_data),
+    };
+
+    assert_result_with_epilogue(
+        &lhs_data, &rhs_data, &reference, &problem, &client, &out, all_elems,
+    );
+}
@@ -0,0 +1,233 @@
+use crate::suite::assert_result_matches;
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+
+use cubek_matmul::definition::MatmulElems;
+use cubek_matmul::definition::MatmulGlobalElems;
+use cubek_matmul::launch::{
+    MatmulInputHandleRef, Strategy, check_group_offsets, launch_ref, launch_ref_grouped,
+};
+use cubek_test_utils::{Distribution, HostData, HostDataType, HostDataVec, StrideSpec, TestInput};
+
+type TestRuntime = cubecl::TestRuntime;
+
+struct GroupedTestCase {
+    /// Number of rows of each group, in order
+    pub group_sizes: Vec<usize>,
+    pub n: usize,
+    pub k: usize,
+    pub elems: MatmulGlobalElems,
+}
+
+#[test]
+pub fn test_aligned_groups() {
+    let case = GroupedTestCase {
+        group_sizes: vec![32, 32, 64],
+        n: 32,
+        k: 32,
+        elems: elems(),
+    };
+
+    test_grouped(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_unaligned_groups() {
+    let case = GroupedTestCase {
+        group_sizes: vec![5, 37, 16, 1],
+        n: 40,
+        k: 24,
+        elems: elems(),
+    };
+
+    test_grouped(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_empty_groups() {
+    let case = GroupedTestCase {
+        group_sizes: vec![0, 19, 0, 0, 33, 0],
+        n: 17,
+        k: 19,
+        elems: elems(),
+    };
+
+    test_grouped(case, Strategy::SimpleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_unaligned_groups_double_buffering() {
+    let case = GroupedTestCase {
+        group_sizes: vec![70, 3, 41],
+        n: 64,
+        k: 64,
+        elems: elems(),
+    };
+
+    test_grouped(case, Strategy::DoubleUnit(Default::default()));
+}
+
+#[test]
+pub fn test_decreasing_offsets_rejected() {
+    test_invalid_offsets(vec![16., 8., 24.], 24);
+}
+
+#[test]
+pub fn test_offsets_past_rows_rejected() {
+    test_invalid_offsets(vec![8., 16., 32.], 24);
+}
+
+/// Checks that offsets reaching outside of lhs and out are rejected by the debug check.
+fn test_invalid_offsets(group_offsets: Vec<f32>, total_rows: usize) {
+    let client = TestRuntime::client(&Default::default());
+    let num_groups = group_offsets.len();
+
+    let group_offsets = TestInput::custom(
+        client.clone(),
+        vec![num_groups],
+        u32::as_type_native_unchecked(),
+        StrideSpec::RowMajor,
+        group_offsets,
+    )
+    .generate_without_host_data();
+
+    let result = check_group_offsets(&client, &group_offsets.as_ref(), total_rows);
+
+    assert!(result.is_err());
+}
+
+/// Compares the grouped matmul with looping the regular matmul over each group.
+fn test_grouped(case: GroupedTestCase, strategy: Strategy) {
+    let client = TestRuntime::client(&Default::default());
+    let num_groups = case.group_sizes.len();
+    let total_rows: usize = case.group_sizes.iter().sum();
+    let (k, n) = (case.k, case.n);
+
+    let (lhs, lhs_data) = TestInput::random(
+        client.clone(),
+        vec![total_rows, k],
+        *case.elems.lhs,
+        1234,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    let (rhs, rhs_data) = TestInput::random(
+        client.clone(),
+        vec![num_groups, k, n],
+        *case.elems.rhs,
+        5678,
+        Distribution::Uniform(-1., 1.),
+        StrideSpec::RowMajor,
+    )
+    .generate_with_f32_host_data();
+
+    let group_offsets = case
+        .group_sizes
+        .iter()
+        .scan(0, |end, size| {
+            *end += size;
+            Some(*end as f32)
+        })
+        .collect();
+    let group_offsets = TestInput::custom(
+        client.clone(),
+        vec![num_groups],
+        u32::as_type_native_unchecked(),
+        StrideSpec::RowMajor,
+        group_offsets,
+    )
+    .generate_without_host_data();
+
+    let out = TestInput::zeros(
+        client.clone(),
+        vec![total_rows, n],
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let mut dtypes = MatmulElems::from_globals(&case.elems);
+
+    launch_ref_grouped(
+        &strategy,
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::Normal(rhs.as_ref(), *case.elems.rhs),
+        &group_offsets.as_ref(),
+        &out.as_ref(),
+        &mut dtypes,
+    )
+    .unwrap();
+
+    let mut expected = vec![0.; total_rows * n];
+    let mut row_start = 0;
+
+    for (group, &rows) in case.group_sizes.iter().enumerate() {
+        if rows == 0 {
+            continue;
+        }
+
+        let group_lhs = (row_start..row_start + rows)
+            .flat_map(|row| (0..k).map(move |col| (row, col)))
+            .map(|(row, col)| lhs_data.get_f32(&[row, col]))
+            .collect();
+        let group_lhs = TestInput::custom(
+            client.clone(),
+            vec![rows, k],
+            *case.elems.lhs,
+            StrideSpec::RowMajor,
+            group_lhs,
+        )
+        .generate_without_host_data();
+
+        let group_rhs = (0..k)
+            .flat_map(|row| (0..n).map(move |col| (row, col)))
+            .map(|(row, col)| rhs_data.get_f32(&[group, row, col]))
+            .collect();
+        let group_rhs = TestInput::custom(
+            client.clone(),
+            vec![k, n],
+            *case.elems.rhs,
+            StrideSpec::RowMajor,
+            group_rhs,
+        )
+        .generate_without_host_data();
+
+        let group_out = TestInput::zeros(
+            client.clone(),
+            vec![rows, n],
+            *case.elems.out,
+            StrideSpec::RowMajor,
+        )
+        .generate_without_host_data();
+
+        launch_ref(
+            &strategy,
+            &client,
+            &MatmulInputHandleRef::Normal(group_lhs.as_ref(), *case.elems.lhs),
+            &MatmulInputHandleRef::Normal(group_rhs.as_ref(), *case.elems.rhs),
+            &group_out.as_ref(),
+            &mut MatmulElems::from_globals(&case.elems),
+        )
+        .unwrap();
+
+        let group_out = HostData::from_tensor_handle(&client, &group_out, HostDataType::F32);
+        for row in 0..rows {
+            for col in 0..n {
+                expected[(row_start + row) * n + col] = group_out.get_f32(&[row, col]);
+            }
+        }
+
+        row_start += rows;
+    }
+
+    let expected = HostData {
+        data: HostDataVec::F32(expected),
+        shape: vec![total_rows, n],
+        strides: vec![n, 1],
+    };
+
+    assert_result_matches(&expected, &client, &out, dtypes);
+}
@@ -11,6 +11,7 @@ use cubek_matmul::components::batch::{BatchConfig, BatchMatmulFamily};
 use cubek_matmul::definition::MatmulElems;
 use cubek_matmul::definition::{MatmulProblem, TilingBlueprint};
 use cubek_matmul::launch::ConcreteInputsFactory;
+use cubek_matmul::launch::MatmulEpilogueHandleRef;
 use cubek_matmul::launch::MatmulInputHandleRef;
 use cubek_matmul::launch::TensorArgs;
 use cubek_matmul::launch::TensorInputs;
@@ -140,9 +141,11 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
         return false;
     }
 
+    let epilogue = MatmulEpilogueHandleRef::none();
     let output = <TensorOutput<_> as ConcreteOutputFactory<A>>::create(
         client,
         &out,
+        &epilogue,
         &selection,
         problem,
         &line_sizes,
@@ -154,6 +157,7 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
         problem,
         &client.properties().hardware.max_cube_count.clone(),
     );
+    let tile_counter = cube_count_plan.tile_counter(client);
 
     match input_representation {
         InputRepresentation::Normal => {
@@ -161,6 +165,7 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
                 client,
                 &lhs,
                 &rhs,
+                &epilogue,
                 &selection,
                 problem,
                 &line_sizes,
@@ -175,7 +180,7 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
                     cube_count_plan.resolve(),
                     inputs,
                     output,
-                    cube_count_plan.as_args(),
+                    cube_count_plan.as_args(tile_counter.as_ref()),
                     config,
                     dtypes,
                 )
@@ -186,6 +191,7 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
                 client,
                 &lhs,
                 &rhs,
+                &epilogue,
                 &selection,
                 problem,
                 &line_sizes,
@@ -200,7 +206,7 @@ pub fn launch_matmul_algorithm<A: Routine<Blueprint = TilingBlueprint>>(
                     cube_count_plan.resolve(),
                     inputs,
                     output,
-                    cube_count_plan.as_args(),
+                    cube_count_plan.as_args(tile_counter.as_ref()),
                     config,
                     dtypes,
                 )
@@ -166,3 +166,43 @@ mod swizzlerow_spread {
 
     include!("partition_buffering.rs");
 }
+
+#[cfg(feature = "matmul_tests_hypercube")]
+mod row_persistent_stride {
+    use super::*;
+    use cubek_matmul::definition::{
+        CubeCountPlanBlueprint, GlobalOrder, GlobalOrderBlueprint, HypercubeBlueprint,
+        TileScheduler,
+    };
+
+    fn hypercube_selection(tiling_scheme: &TilingScheme) -> HypercubeBlueprint {
+        HypercubeBlueprint::builder(tiling_scheme)
+            .global_order(GlobalOrderBlueprint::Fixed(GlobalOrder::RowMajor))
+            .cube_count_plan(CubeCountPlanBlueprint::Persistent {
+                scheduler: TileScheduler::StaticStride,
+            })
+            .build()
+    }
+
+    include!("partition_buffering.rs");
+}
+
+#[cfg(feature = "matmul_tests_hypercube")]
+mod hilbert_persistent_atomic {
+    use super::*;
+    use cubek_matmul::definition::{
+        CubeCountPlanBlueprint, GlobalOrder, GlobalOrderBlueprint, HypercubeBlueprint,
+        TileScheduler,
+    };
+
+    fn hypercube_selection(tiling_scheme: &TilingScheme) -> HypercubeBlueprint {
+        HypercubeBlueprint::builder(tiling_scheme)
+            .global_order(GlobalOrderBlueprint::Fixed(GlobalOrder::Hilbert(2)))
+            .cube_count_plan(CubeCountPlanBlueprint::Persistent {
+                scheduler: TileScheduler::AtomicCounter,
+            })
+            .build()
+    }
+
+    include!("partition_buffering.rs");
+}
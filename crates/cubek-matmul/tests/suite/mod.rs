@@ -1,13 +1,22 @@
 #![allow(missing_docs)]
 
+pub mod block_scaled;
+pub mod broadcast;
+pub mod epilogue;
+pub mod fp8;
+pub mod grouped;
 pub mod layered;
 pub mod naive;
+pub mod sparse_24;
+pub mod split_k;
 
 mod reference;
 
 use cubek_matmul::definition::MatrixLayout;
 use cubek_test_utils::StrideSpec;
-pub use reference::assert_result;
+pub use reference::{
+    EpilogueReference, assert_result, assert_result_matches, assert_result_with_epilogue,
+};
 
 pub(crate) fn layout_to_stride_spec(layout: MatrixLayout) -> StrideSpec {
     match layout {
@@ -1,7 +1,7 @@
 use cubecl::TestRuntime;
 use cubecl::std::tensor::TensorHandle;
 use cubecl::{CubeElement, client::ComputeClient};
-use cubek_matmul::definition::MatmulElems;
+use cubek_matmul::definition::{Activation, MatmulElems};
 use cubek_matmul::definition::{MatmulIdent, MatmulProblem, MatrixLayout};
 use cubek_test_utils::{HostData, HostDataType, HostDataVec, StrideSpec, assert_equals_approx};
 
@@ -24,6 +24,58 @@ pub fn assert_result(
     }
 }
 
+/// Host data of a fused epilogue, computing
+/// `activation(alpha * (lhs @ rhs) + beta * c + bias) + residual`
+pub struct EpilogueReference<'a> {
+    pub activation: Activation,
+    pub alpha: f32,
+    pub beta: f32,
+    pub c: Option<&'a HostData>,
+    pub bias: Option<&'a HostData>,
+    pub residual: Option<&'a HostData>,
+    /// Scales of lhs, with a single element or one per row
+    pub lhs_scale: Option<&'a HostData>,
+    /// Scales of rhs, with a single element or one per column
+    pub rhs_scale: Option<&'a HostData>,
+}
+
+pub fn assert_result_with_epilogue(
+    lhs: &HostData,
+    rhs: &HostData,
+    epilogue: &EpilogueReference,
+    problem: &MatmulProblem,
+    client: &ComputeClient<TestRuntime>,
+    out: &TensorHandle<TestRuntime>,
+    dtypes: MatmulElems,
+) {
+    let epsilon = matmul_epsilon(&dtypes, 100.);
+
+    let mut expected = matmul_cpu_reference(lhs, rhs, problem);
+    epilogue_cpu_reference(&mut expected, epilogue);
+
+    let actual = HostData::from_tensor_handle(client, out, HostDataType::F32);
+
+    if let Err(e) = assert_equals_approx(&actual, &expected, epsilon) {
+        panic!("{}", e);
+    }
+}
+
+/// Compares the output of a matmul with expected host data computed by other means
+pub fn assert_result_matches(
+    expected: &HostData,
+    client: &ComputeClient<TestRuntime>,
+    out: &TensorHandle<TestRuntime>,
+    dtypes: MatmulElems,
+) {
+    let epsilon = matmul_epsilon(&dtypes, 100.);
+
+    let actual = HostData::from_tensor_handle(client, out, HostDataType::F32);
+
+    if let Err(e) = assert_equals_approx(&actual, expected, epsilon) {
+        panic!("{}", e);
+    }
+}
+
 fn matmul_epsilon(elems: &MatmulElems, safety_factor: f32) -> f32 {
     let total_eps = elems
         .lhs_global
@@ -57,8 +109,10 @@ fn matmul_cpu_reference(lhs: &HostData, rhs: &HostData, problem: &MatmulProblem)
     let mut out = vec![0.0; num_batches * m * n];
 
     let mut batch_index = vec![0usize; rank - 2];
-    let mut lhs_index = vec![0usize; rank];
-    let mut rhs_index = vec![0usize; rank];
+    let lhs_rank = lhs.shape.len();
+    let rhs_rank = rhs.shape.len();
+    let mut lhs_index = vec![0usize; lhs_rank];
+    let mut rhs_index = vec![0usize; rhs_rank];
     let mut out_index = vec![0usize; rank];
 
     // Iterate over all batches (cartesian product)
@@ -70,23 +124,23 @@ fn matmul_cpu_reference(lhs: &HostData, rhs: &HostData, problem: &MatmulProblem)
             t /= out_shape[d];
         }
 
-        // copy batch dims into indices
-        lhs_index[..rank - 2].copy_from_slice(&batch_index);
-        rhs_index[..rank - 2].copy_from_slice(&batch_index);
+        // copy batch dims into indices, broadcasting inputs from the right
+        broadcast_batch_index(&batch_index, &lhs.shape, &mut lhs_index);
+        broadcast_batch_index(&batch_index, &rhs.shape, &mut rhs_index);
         out_index[..rank - 2].copy_from_slice(&batch_index);
 
         for i in 0..m {
             out_index[rank - 2] = i;
-            lhs_index[rank - 2] = i;
+            lhs_index[lhs_rank - 2] = i;
 
             for j in 0..n {
                 out_index[rank - 1] = j;
 
                 let mut sum = 0.0;
                 for kk in 0..k {
-                    lhs_index[rank - 1] = kk;
-                    rhs_index[rank - 2] = kk;
-                    rhs_index[rank - 1] = j;
+                    lhs_index[lhs_rank - 1] = kk;
+                    rhs_index[rhs_rank - 2] = kk;
+                    rhs_index[rhs_rank - 1] = j;
 
                     sum += lhs.get_f32(&lhs_index) * rhs.get_f32(&rhs_index);
                 }
@@ -104,3 +158,80 @@ fn matmul_cpu_reference(lhs: &HostData, rhs: &HostData, problem: &MatmulProblem)
         strides,
     }
 }
+
+/// Writes the batch index of an input with the given shape, where batch dims are aligned from
+/// the right with the output and unit dims are broadcast.
+fn broadcast_batch_index(out_batch_index: &[usize], shape: &[usize], index: &mut [usize]) {
+    let num_batch_dims = shape.len() - 2;
+    let offset = out_batch_index.len() - num_batch_dims;
+
+    for dim in 0..num_batch_dims {
+        index[dim] = match shape[dim] {
+            1 => 0,
+            _ => out_batch_index[offset + dim],
+        };
+    }
+}
+
+/// Applies the epilogue in place on a row-major output
+fn epilogue_cpu_reference(out: &mut HostData, epilogue: &EpilogueReference) {
+    let shape = out.shape.clone();
+    let rank = shape.len();
+    let mut index = vec![0usize; rank];
+
+    let HostDataVec::F32(data) = &mut out.data else {
+        unreachable!("Reference output is always f32")
+    };
+
+    for (flat, value) in data.iter_mut().enumerate() {
+        let mut t = flat;
+        for d in (0..rank).rev() {
+            index[d] = t % shape[d];
+            t /= shape[d];
+        }
+
+        let mut x = epilogue.alpha * *value;
+        if let Some(scale) = epilogue.lhs_scale {
+            x *= scale_at(scale, index[rank - 2]);
+        }
+        if let Some(scale) = epilogue.rhs_scale {
+            x *= scale_at(scale, index[rank - 1]);
+        }
+        if let Some(c) = epilogue.c {
+            x += epilogue.beta * c.get_f32(&index);
+        }
+        if let Some(bias) = epilogue.bias {
+            x += bias.get_f32(&index[rank - 1..]);
+        }
+        x = match epilogue.activation {
+            Activation::None => x,
+            Activation::Relu => x.max(0.0),
+            Activation::Gelu => 0.5 * x * (1.0 + erf(x * core::f32::consts::FRAC_1_SQRT_2)),
+            Activation::Silu => x / (1.0 + (-x).exp()),
+        };
+        if let Some(residual) = epilogue.residual {
+            x += residual.get_f32(&index);
+        }
+        *value = x;
+    }
+}
+
+/// Scale of the given channel from 1D scales, or the single scale of the tensor
+fn scale_at(scale: &HostData, channel: usize) -> f32 {
+    match scale.shape[0] {
+        1 => scale.get_f32(&[0]),
+        _ => scale.get_f32(&[channel]),
+    }
+}
+
+/// Abramowitz and Stegun approximation of erf, max error of 1.5e-7
+fn erf(x: f32) -> f32 {
+    let sign = x.signum();
+    let x = x.abs() as f64;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp()) as f32
+}
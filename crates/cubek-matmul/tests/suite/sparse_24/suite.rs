@@ -0,0 +1,200 @@
+use crate::suite::assert_result_matches;
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+
+use cubek_matmul::definition::MatmulElems;
+use cubek_matmul::definition::MatmulGlobalElems;
+use cubek_matmul::launch::{MatmulInputHandleRef, Strategy, launch_ref, launch_ref_sparse_24};
+use cubek_test_utils::{HostData, HostDataType, StrideSpec, TestInput, compress_sparse_24};
+
+type TestRuntime = cubecl::TestRuntime;
+
+struct Sparse24TestCase {
+    pub batches: usize,
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub elems: MatmulGlobalElems,
+}
+
+#[test]
+pub fn test_aligned() {
+    let case = Sparse24TestCase {
+        batches: 1,
+        m: 32,
+        n: 32,
+        k: 64,
+        elems: elems(),
+    };
+
+    test_sparse_24(case);
+}
+
+#[test]
+pub fn test_unaligned() {
+    let case = Sparse24TestCase {
+        batches: 1,
+        m: 33,
+        n: 40,
+        k: 100,
+        elems: elems(),
+    };
+
+    test_sparse_24(case);
+}
+
+#[test]
+pub fn test_batched() {
+    let case = Sparse24TestCase {
+        batches: 3,
+        m: 16,
+        n: 24,
+        k: 32,
+        elems: elems(),
+    };
+
+    test_sparse_24(case);
+}
+
+#[test]
+pub fn test_k_not_multiple_of_4_rejected() {
+    test_invalid_shapes(34, 17, 8);
+}
+
+#[test]
+pub fn test_mismatched_values_rejected() {
+    test_invalid_shapes(32, 32, 8);
+}
+
+#[test]
+pub fn test_mismatched_metadata_rejected() {
+    test_invalid_shapes(32, 16, 16);
+}
+
+/// Launches the sparse matmul with values and metadata of the given number of rows, which must be
+/// rejected for a dense rhs with `k` rows.
+fn test_invalid_shapes(k: usize, values_rows: usize, metadata_rows: usize) {
+    let client = TestRuntime::client(&Default::default());
+    let elems = elems();
+    let (m, n) = (16, 16);
+
+    let input = |shape: Vec<usize>, dtype| {
+        TestInput::zeros(client.clone(), shape, dtype, StrideSpec::RowMajor)
+            .generate_without_host_data()
+    };
+    let lhs = input(vec![m, k], *elems.lhs);
+    let rhs_values = input(vec![values_rows, n], *elems.rhs);
+    let rhs_metadata = input(vec![metadata_rows, n], u32::as_type_native_unchecked());
+    let out = input(vec![m, n], *elems.out);
+    let rhs_shape = [k, n];
+
+    let result = launch_ref_sparse_24(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *elems.lhs),
+        &MatmulInputHandleRef::sparse_24(
+            rhs_values.as_ref(),
+            rhs_metadata.as_ref(),
+            &rhs_shape,
+            rhs_values.dtype,
+        ),
+        &out.as_ref(),
+        &mut MatmulElems::from_globals(&elems),
+    );
+
+    assert!(result.is_err());
+}
+
+/// Compares the sparse matmul with the regular matmul on the pruned dense rhs.
+fn test_sparse_24(case: Sparse24TestCase) {
+    let client = TestRuntime::client(&Default::default());
+    let (batches, m, n, k) = (case.batches, case.m, case.n, case.k);
+
+    let lhs = TestInput::custom(
+        client.clone(),
+        vec![batches, m, k],
+        *case.elems.lhs,
+        StrideSpec::RowMajor,
+        (0..batches * m * k)
+            .map(|i| ((i * 3 + 1) % 11) as f32 / 8. - 0.5)
+            .collect(),
+    )
+    .generate_without_host_data();
+
+    let rhs: Vec<f32> = (0..batches * k * n)
+        .map(|i| ((i * 7 + 3) % 15) as f32 / 8. - 0.875)
+        .collect();
+    let rhs_shape = [batches, k, n];
+    let sparse = compress_sparse_24(&rhs, &rhs_shape);
+
+    let rhs_values = TestInput::custom(
+        client.clone(),
+        vec![batches, k / 2, n],
+        *case.elems.rhs,
+        StrideSpec::RowMajor,
+        sparse.values,
+    )
+    .generate_without_host_data();
+    let rhs_metadata = TestInput::custom(
+        client.clone(),
+        vec![batches, k / 4, n],
+        u32::as_type_native_unchecked(),
+        StrideSpec::RowMajor,
+        sparse.metadata,
+    )
+    .generate_without_host_data();
+    let rhs_dense = TestInput::custom(
+        client.clone(),
+        vec![batches, k, n],
+        *case.elems.rhs,
+        StrideSpec::RowMajor,
+        sparse.dense,
+    )
+    .generate_without_host_data();
+
+    let out = TestInput::zeros(
+        client.clone(),
+        vec![batches, m, n],
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+    let expected = TestInput::zeros(
+        client.clone(),
+        vec![batches, m, n],
+        *case.elems.out,
+        StrideSpec::RowMajor,
+    )
+    .generate_without_host_data();
+
+    let mut dtypes = MatmulElems::from_globals(&case.elems);
+
+    launch_ref_sparse_24(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::sparse_24(
+            rhs_values.as_ref(),
+            rhs_metadata.as_ref(),
+            &rhs_shape,
+            rhs_values.dtype,
+        ),
+        &out.as_ref(),
+        &mut dtypes,
+    )
+    .unwrap();
+
+    launch_ref(
+        &Strategy::SimpleUnit(Default::default()),
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *case.elems.lhs),
+        &MatmulInputHandleRef::Normal(rhs_dense.as_ref(), *case.elems.rhs),
+        &expected.as_ref(),
+        &mut MatmulElems::from_globals(&case.elems),
+    )
+    .unwrap();
+
+    let expected = HostData::from_tensor_handle(&client, &expected, HostDataType::F32);
+
+    assert_result_matches(&expected, &client, &out, dtypes);
+}
@@ -0,0 +1,260 @@
+use crate::suite::assert_result;
+use cubecl::Runtime;
+use cubecl::frontend::CubePrimitive;
+
+use crate::suite::layout_to_stride_spec;
+use cubek_matmul::components::batch::SplitKMode;
+use cubek_matmul::definition::{MatmulElems, MatmulProblem};
+use cubek_matmul::definition::{MatmulGlobalElems, MatrixLayout};
+use cubek_matmul::launch::{MatmulInputHandleRef, Strategy, launch_ref};
+use cubek_test_utils::{Distribution, TestInput, current_test_mode};
+
+type TestRuntime = cubecl::TestRuntime;
+
+struct SplitKTestCase {
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub batch: usize,
+    pub elems: MatmulGlobalElems,
+}
+
+impl SplitKTestCase {
+    fn problem(&self) -> MatmulProblem {
+        MatmulProblem::from_parameters(
+            self.m,
+            self.n,
+            self.k,
+            vec![self.batch],
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            MatrixLayout::RowMajor,
+            self.elems.clone(),
+        )
+    }
+}
+
+#[test]
+pub fn test_split_k_aligned() {
+    let case = SplitKTestCase {
+        m: 32,
+        n: 32,
+        k: 1024,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::SplitK { num_splits: 4 }),
+    );
+}
+
+#[test]
+pub fn test_split_k_unaligned() {
+    let case = SplitKTestCase {
+        m: 33,
+        n: 17,
+        k: 1000,
+        batch: 2,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::SplitK { num_splits: 3 }),
+    );
+}
+
+#[test]
+pub fn test_split_k_more_splits_than_iterations() {
+    let case = SplitKTestCase {
+        m: 16,
+        n: 16,
+        k: 40,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::SplitK { num_splits: 64 }),
+    );
+}
+
+#[test]
+pub fn test_stream_k_aligned() {
+    let case = SplitKTestCase {
+        m: 64,
+        n: 64,
+        k: 1024,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::StreamK { num_cubes: 7 }),
+    );
+}
+
+#[test]
+pub fn test_stream_k_unaligned() {
+    let case = SplitKTestCase {
+        m: 45,
+        n: 30,
+        k: 777,
+        batch: 3,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::StreamK { num_cubes: 5 }),
+    );
+}
+
+#[test]
+pub fn test_stream_k_more_cubes_than_iterations() {
+    let case = SplitKTestCase {
+        m: 16,
+        n: 16,
+        k: 24,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKUnit(Default::default(), SplitKMode::StreamK { num_cubes: 128 }),
+    );
+}
+
+#[test]
+pub fn test_split_k_cyclic_cmma() {
+    let case = SplitKTestCase {
+        m: 64,
+        n: 64,
+        k: 1024,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKCyclicCmma(Default::default(), SplitKMode::SplitK { num_splits: 4 }),
+    );
+}
+
+#[test]
+pub fn test_stream_k_cyclic_cmma_unaligned() {
+    let case = SplitKTestCase {
+        m: 45,
+        n: 30,
+        k: 777,
+        batch: 2,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKCyclicCmma(Default::default(), SplitKMode::StreamK { num_cubes: 5 }),
+    );
+}
+
+#[test]
+pub fn test_split_k_cyclic_mma() {
+    let case = SplitKTestCase {
+        m: 64,
+        n: 64,
+        k: 1024,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKCyclicMma(Default::default(), SplitKMode::SplitK { num_splits: 4 }),
+    );
+}
+
+#[test]
+pub fn test_stream_k_cyclic_mma_unaligned() {
+    let case = SplitKTestCase {
+        m: 33,
+        n: 17,
+        k: 1000,
+        batch: 2,
+        elems: elems(),
+    };
+
+    test_split_k(
+        case,
+        Strategy::SplitKCyclicMma(Default::default(), SplitKMode::StreamK { num_cubes: 7 }),
+    );
+}
+
+/// K-dominant problems are split by the auto strategy.
+#[test]
+pub fn test_auto_k_dominant() {
+    let case = SplitKTestCase {
+        m: 32,
+        n: 32,
+        k: 2048,
+        batch: 1,
+        elems: elems(),
+    };
+
+    test_split_k(case, Strategy::Auto);
+}
+
+fn test_split_k(case: SplitKTestCase, strategy: Strategy) {
+    let client = TestRuntime::client(&Default::default());
+    let problem = case.problem();
+
+    let (lhs, lhs_data) = TestInput::random(
+        client.clone(),
+        problem.lhs_shape.clone(),
+        *problem.global_dtypes.lhs,
+        1234,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.lhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let (rhs, rhs_data) = TestInput::random(
+        client.clone(),
+        problem.rhs_shape.clone(),
+        *problem.global_dtypes.rhs,
+        5678,
+        Distribution::Uniform(-1., 1.),
+        layout_to_stride_spec(problem.rhs_layout),
+    )
+    .generate_with_f32_host_data();
+
+    let out = TestInput::zeros(
+        client.clone(),
+        problem.out_shape.clone(),
+        *problem.global_dtypes.out,
+        layout_to_stride_spec(problem.out_layout),
+    )
+    .generate_without_host_data();
+
+    let mut dtypes = MatmulElems::from_globals(&case.elems);
+
+    if let Err(err) = launch_ref(
+        &strategy,
+        &client,
+        &MatmulInputHandleRef::Normal(lhs.as_ref(), *problem.global_dtypes.lhs),
+        &MatmulInputHandleRef::Normal(rhs.as_ref(), *problem.global_dtypes.rhs),
+        &out.as_ref(),
+        &mut dtypes,
+    ) {
+        if current_test_mode().should_fail_on_test_compilation_fail() {
+            panic!("Can't launch the test: {err}");
+        }
+        return;
+    }
+
+    assert_result(&lhs_data, &rhs_data, &problem, &client, &out, dtypes);
+}
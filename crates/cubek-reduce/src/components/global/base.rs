@@ -31,6 +31,20 @@ pub fn idle_check<P: ReducePrecision, Out: Numeric>(
             input.line_size(),
         );
 
+        idle_check_count(reduce_count, reduce_index_start, idle_mode)
+    } else {
+        CubeOption::new_None()
+    }
+}
+
+/// Same as [`idle_check`] for a known number of reductions.
+#[cube]
+pub(crate) fn idle_check_count(
+    reduce_count: u32,
+    reduce_index_start: u32,
+    #[comptime] idle_mode: IdleMode,
+) -> CubeOption<bool> {
+    if comptime![idle_mode.is_enabled()] {
         match comptime!(idle_mode) {
             IdleMode::None => CubeOption::new_None(),
             IdleMode::Mask => CubeOption::new_Some(reduce_index_start >= reduce_count),
@@ -100,7 +100,7 @@ impl GlobalFullCubeReduce {
         }
     }
 
-    fn worker_pos(#[comptime] blueprint: CubeBlueprint) -> u32 {
+    pub(crate) fn worker_pos(#[comptime] blueprint: CubeBlueprint) -> u32 {
         match comptime!(blueprint.use_planes) {
             true => UNIT_POS_Y,
             false => UNIT_POS,
@@ -108,7 +108,7 @@ impl GlobalFullCubeReduce {
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn reduce_shared<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
+    pub(crate) fn reduce_shared<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
         input: &VirtualTensor<P::EI>,
         output: &mut VirtualTensor<Out, ReadWrite>,
         reduce_axis: u32,
@@ -131,6 +131,18 @@ impl GlobalFullCubeReduce {
             line_mode,
         );
         let reader = CubeReader::<P>::new(reader);
+
+        Self::reduce_reader::<P, I>(&reader, inst, input_line_size, blueprint)
+    }
+
+    /// Reduce the vector of the `reader` with all units of the cube, writing one accumulator per
+    /// worker into the returned shared accumulator.
+    pub(crate) fn reduce_reader<P: ReducePrecision, I: ReduceInstruction<P>>(
+        reader: &CubeReader<P>,
+        inst: &I,
+        #[comptime] input_line_size: u32,
+        #[comptime] blueprint: CubeBlueprint,
+    ) -> I::SharedAccumulator {
         let mut accumulator = I::null_accumulator(inst, input_line_size);
 
         for i in 0..reader.length() {
@@ -143,10 +155,7 @@ impl GlobalFullCubeReduce {
         let accumulator_plane = match comptime!(blueprint.use_planes) {
             true => {
                 // Sync at the plane level.
-                let (item, coordinate) = I::read_accumulator(inst, &accumulator);
-                let mut accumulator_plane = I::null_accumulator(inst, input_line_size);
-                reduce_inplace::<P, I>(inst, &mut accumulator_plane, item, coordinate, true);
-                accumulator_plane
+                I::plane_fuse_accumulator(inst, &accumulator, input_line_size)
             }
             false => accumulator,
         };
@@ -158,6 +167,8 @@ impl GlobalFullCubeReduce {
             accumulator_size,
             input_line_size,
             requirements.coordinates,
+            requirements.moments,
+            requirements.exp_sums,
         );
 
         I::SharedAccumulator::write(&mut accumulator_shared, worker_pos, accumulator_plane);
@@ -169,17 +180,18 @@ impl GlobalFullCubeReduce {
 }
 
 #[cube]
-fn reduce_scan<P: ReducePrecision, I: ReduceInstruction<P>>(
+pub(crate) fn reduce_scan<P: ReducePrecision, I: ReduceInstruction<P>>(
     inst: &I,
     accumulator: &mut I::SharedAccumulator,
     result: &mut I::AccumulatorItem,
     #[comptime] size: u32,
 ) {
-    for i in 0..size {
-        let item = I::SharedAccumulator::read(accumulator, i);
-        let (item, coordinate) = I::read_accumulator(inst, &item);
-        reduce_inplace::<P, I>(inst, result, item, coordinate, false);
+    for i in 1..size {
+        fuse_accumulator_inplace::<P, I>(inst, accumulator, 0, i);
     }
+
+    let tmp = I::SharedAccumulator::read(accumulator, 0);
+    I::assign_accumulator(inst, result, &tmp);
 }
 
 /// Use all units within a cube to fuse the first `size` elements of `accumulator` inplace like this with some padding if `size` is not a power of 2.
@@ -207,7 +219,7 @@ fn reduce_scan<P: ReducePrecision, I: ReduceInstruction<P>>(
 /// There is no out-of-bound check, so it is the responsibility of the caller to ensure that `size` is at most the length
 /// of the shared memory and that there are at least `size` units within each cube.
 #[cube]
-fn reduce_tree<P: ReducePrecision, I: ReduceInstruction<P>>(
+pub(crate) fn reduce_tree<P: ReducePrecision, I: ReduceInstruction<P>>(
     inst: &I,
     accumulator: &mut I::SharedAccumulator,
     result: &mut I::AccumulatorItem,
@@ -244,6 +256,5 @@ fn reduce_tree<P: ReducePrecision, I: ReduceInstruction<P>>(
     sync_cube();
 
     let tmp = I::SharedAccumulator::read(accumulator, 0);
-    let (item, coordinate) = I::read_accumulator(inst, &tmp);
-    reduce_inplace::<P, I>(inst, result, item, coordinate, false);
+    I::assign_accumulator(inst, result, &tmp);
 }
@@ -71,7 +71,7 @@ impl GlobalFullPlaneReduce {
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn reduce_single<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
+    pub(crate) fn reduce_single<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
         input: &VirtualTensor<P::EI>,
         output: &mut VirtualTensor<Out, ReadWrite>,
         reduce_axis: u32,
@@ -95,6 +95,18 @@ impl GlobalFullPlaneReduce {
         );
         let reader = PlaneReader::<P>::new(reader);
 
+        Self::reduce_reader::<P, I>(&reader, inst, input_line_size, blueprint)
+    }
+
+    /// Reduce the vector of the `reader` with all units of the plane.
+    ///
+    /// Unless the units work independently, every unit ends up with the same accumulator.
+    pub(crate) fn reduce_reader<P: ReducePrecision, I: ReduceInstruction<P>>(
+        reader: &PlaneReader<P>,
+        inst: &I,
+        #[comptime] input_line_size: u32,
+        #[comptime] blueprint: PlaneReduceBlueprint,
+    ) -> I::AccumulatorItem {
         let mut accumulator = I::null_accumulator(inst, input_line_size);
 
         for i in 0..reader.length() {
@@ -109,12 +121,7 @@ impl GlobalFullPlaneReduce {
         }
 
         match blueprint.independent {
-            true => {
-                let (item, coordinate) = I::read_accumulator(inst, &accumulator);
-                let mut result = I::null_accumulator(inst, input_line_size);
-                reduce_inplace::<P, I>(inst, &mut result, item, coordinate, true);
-                result
-            }
+            true => I::plane_fuse_accumulator(inst, &accumulator, input_line_size),
             false => accumulator,
         }
     }
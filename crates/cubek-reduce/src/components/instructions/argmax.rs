@@ -1,6 +1,6 @@
 use super::{
     ArgAccumulator, ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction,
-    lowest_coordinate_matching,
+    lowest_coordinate_matching, plane_fuse_read_back,
 };
 use crate::{components::instructions::ReduceRequirements, components::precision::ReducePrecision};
 use cubecl::prelude::*;
@@ -43,7 +43,11 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
     type Config = ();
 
     fn requirements(_this: &Self) -> ReduceRequirements {
-        ReduceRequirements { coordinates: true }
+        ReduceRequirements {
+            coordinates: true,
+            moments: false,
+            exp_sums: false,
+        }
     }
 
     fn from_config(_config: Self::Config) -> Self {
@@ -80,6 +84,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
         )
     }
 
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+    }
+
     fn reduce(
         _this: &Self,
         accumulator: &Self::AccumulatorItem,
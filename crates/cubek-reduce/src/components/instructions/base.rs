@@ -10,6 +10,14 @@ pub trait ReduceFamily: Send + Sync + 'static + std::fmt::Debug {
 pub struct ReduceRequirements {
     #[cube(comptime)]
     pub coordinates: bool,
+    /// Whether the accumulator tracks the count and the sum of squared differences to the mean,
+    /// on top of its elements.
+    #[cube(comptime)]
+    pub moments: bool,
+    /// Whether the accumulator tracks a sum of exponentials rescaled to its elements, which are
+    /// running maxima.
+    #[cube(comptime)]
+    pub exp_sums: bool,
 }
 
 /// An instruction for a reduce algorithm that works with [`Line`].
@@ -61,6 +69,16 @@ pub trait ReduceInstruction<P: ReducePrecision>:
         accumulator: &Self::AccumulatorItem,
     ) -> (Line<P::EI>, ReduceCoordinate);
 
+    /// Fuse the accumulators of all units of the plane, every unit getting the fused accumulator.
+    ///
+    /// See [`plane_fuse_read_back`] for the implementation of instructions whose accumulator can
+    /// be read back as a single item.
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem;
+
     /// If `use_planes` is `true`, reduce all the `item` and `coordinate` within the `accumulator`.
     /// Else, reduce the given `item` and `coordinate` into the accumulator.
     fn reduce(
@@ -108,6 +126,8 @@ pub trait SharedAccumulator: CubeType + Send + Sync + 'static {
         #[comptime] length: u32,
         #[comptime] line_size: u32,
         #[comptime] _coordinate: bool,
+        #[comptime] _moments: bool,
+        #[comptime] _exp_sums: bool,
     ) -> Self;
 
     fn read(accumulator: &Self, index: u32) -> Self::Item;
@@ -123,6 +143,8 @@ impl<In: Numeric> SharedAccumulator for SharedMemory<Line<In>> {
         #[comptime] length: u32,
         #[comptime] line_size: u32,
         #[comptime] _coordinate: bool,
+        #[comptime] _moments: bool,
+        #[comptime] _exp_sums: bool,
     ) -> Self {
         SharedMemory::new_lined(length, line_size)
     }
@@ -151,6 +173,8 @@ impl<In: Numeric> SharedAccumulator for ArgAccumulator<In> {
         #[comptime] length: u32,
         #[comptime] line_size: u32,
         #[comptime] _coordinate: bool,
+        #[comptime] _moments: bool,
+        #[comptime] _exp_sums: bool,
     ) -> Self {
         ArgAccumulator::<In> {
             elements: SharedMemory::new_lined(length, line_size),
@@ -180,6 +204,20 @@ pub fn reduce_inplace<P: ReducePrecision, R: ReduceInstruction<P>>(
     R::assign_accumulator(inst, accumulator, reduction);
 }
 
+/// Fuse the accumulators of all units of the plane by reading each one back as a single item and
+/// reducing these items with plane instructions.
+#[cube]
+pub fn plane_fuse_read_back<P: ReducePrecision, R: ReduceInstruction<P>>(
+    inst: &R,
+    accumulator: &R::AccumulatorItem,
+    #[comptime] line_size: u32,
+) -> R::AccumulatorItem {
+    let (item, coordinate) = R::read_accumulator(inst, accumulator);
+    let mut fused = R::null_accumulator(inst, line_size);
+    reduce_inplace::<P, R>(inst, &mut fused, item, coordinate, true);
+    fused
+}
+
 #[cube]
 pub fn reduce_shared_inplace<P: ReducePrecision, R: ReduceInstruction<P>>(
     inst: &R,
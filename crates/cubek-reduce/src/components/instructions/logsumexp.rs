@@ -0,0 +1,202 @@
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+    plane_fuse_read_back,
+};
+use crate::components::precision::ReducePrecision;
+use cubecl::prelude::*;
+
+/// Compute `log(sum(exp(x)))` without overflowing, using an online `(max, sum)` accumulator.
+///
+/// The sum of exponentials is always relative to the running maximum, so it is rescaled every
+/// time a larger item is found. Partial accumulators are merged by rescaling both sums to the
+/// larger of their maxima.
+///
+/// An item of `-inf` is ignored, and a slice of only `-inf` gives `-inf`.
+#[derive(Debug, CubeType, Clone)]
+pub struct LogSumExp;
+
+impl ReduceFamily for LogSumExp {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = ();
+}
+
+#[cube]
+impl LogSumExp {
+    /// Merge two `(max, sum)` accumulators.
+    pub fn merge<N: Numeric>(
+        lhs: (Line<N>, Line<N>),
+        rhs: (Line<N>, Line<N>),
+    ) -> (Line<N>, Line<N>) {
+        let lhs_larger = lhs.0.greater_than(rhs.0);
+        let max = select_many(lhs_larger, lhs.0, rhs.0);
+        let min = select_many(lhs_larger, rhs.0, lhs.0);
+        // A single exponential is required, since the larger side is already relative to `max`.
+        let scale = Self::exp(min - max);
+        let sum = select_many(lhs_larger, lhs.1 + rhs.1 * scale, lhs.1 * scale + rhs.1);
+
+        (max, sum)
+    }
+
+    pub fn exp<N: Numeric>(value: Line<N>) -> Line<N> {
+        Line::cast_from(Exp::exp(Line::<f32>::cast_from(value)))
+    }
+
+    pub fn output<N: Numeric, Out: Numeric>(max: Line<N>, sum: Line<N>) -> Line<Out> {
+        Line::cast_from(Line::<f32>::cast_from(max) + Log::ln(Line::<f32>::cast_from(sum)))
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for LogSumExp {
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = LogSumExpAccumulator<P::EA>;
+    type Config = ();
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements {
+            coordinates: false,
+            moments: false,
+            exp_sums: true,
+        }
+    }
+
+    fn from_config(_config: Self::Config) -> Self {
+        LogSumExp {}
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::min_value())
+    }
+
+    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(P::EA::min_value()),
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+        )
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn read_accumulator(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+    ) -> (Line<P::EI>, ReduceCoordinate) {
+        // The log-sum-exp of log-sum-exps is the log-sum-exp of all the items.
+        (
+            Self::output::<P::EA, P::EI>(accumulator.0, accumulator.1),
+            ReduceCoordinate::new_NotRequired(),
+        )
+    }
+
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let line_size = item.size();
+        let lowest = Line::empty(line_size).fill(P::EA::min_value());
+
+        // Infinite items are clamped to the null input, which doesn't count in the sum.
+        let item = Line::<P::EA>::cast_from(item);
+        let item = select_many(item.greater_than(lowest), item, lowest);
+        let present = select_many(
+            item.equal(lowest),
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+            Line::empty(line_size).fill(P::EA::from_int(1)),
+        );
+
+        let batch = if use_planes {
+            let max = plane_max(item);
+            (max, plane_sum(Self::exp(item - max) * present))
+        } else {
+            (item, present)
+        };
+
+        Self::merge::<P::EA>((accumulator.0, accumulator.1), batch)
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        Self::merge::<P::EA>(lhs, rhs)
+    }
+
+    fn merge_line<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut merged = (Line::new(accumulator.0[0]), Line::new(accumulator.1[0]));
+        #[unroll]
+        for k in 1..accumulator.0.size() {
+            merged = Self::merge::<P::EA>(
+                merged,
+                (Line::new(accumulator.0[k]), Line::new(accumulator.1[k])),
+            );
+        }
+
+        let output = Self::output::<P::EA, Out>(merged.0, merged.1);
+        output[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Self::output::<P::EA, Out>(accumulator.0, accumulator.1)
+    }
+}
+
+/// A pair of shared memories used for [`LogSumExp`].
+#[derive(CubeType)]
+pub struct LogSumExpAccumulator<N: Numeric> {
+    pub maxes: SharedMemory<Line<N>>,
+    pub sums: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<In: Numeric> SharedAccumulator for LogSumExpAccumulator<In> {
+    type Item = (Line<In>, Line<In>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+        #[comptime] _moments: bool,
+        #[comptime] _exp_sums: bool,
+    ) -> Self {
+        LogSumExpAccumulator::<In> {
+            maxes: SharedMemory::new_lined(length, line_size),
+            sums: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.maxes[index], accumulator.sums[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.maxes[index] = item.0;
+        accumulator.sums[index] = item.1;
+    }
+}
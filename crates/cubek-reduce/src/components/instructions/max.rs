@@ -1,4 +1,7 @@
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction};
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ScanFamily, ScanInstruction,
+    plane_fuse_read_back,
+};
 use crate::{components::instructions::ReduceRequirements, components::precision::ReducePrecision};
 use cubecl::prelude::*;
 
@@ -19,7 +22,11 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
     type Config = ();
 
     fn requirements(_this: &Self) -> ReduceRequirements {
-        ReduceRequirements { coordinates: false }
+        ReduceRequirements {
+            coordinates: false,
+            moments: false,
+            exp_sums: false,
+        }
     }
 
     fn from_config(_config: Self::Config) -> Self {
@@ -52,6 +59,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
         )
     }
 
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+    }
+
     fn reduce(
         _this: &Self,
         accumulator: &Self::AccumulatorItem,
@@ -102,3 +117,25 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
         Line::cast_from(accumulator)
     }
 }
+
+impl ScanFamily for Max {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = ();
+}
+
+#[cube]
+impl<P: ReducePrecision> ScanInstruction<P> for Max {
+    type Config = ();
+
+    fn from_config(_config: Self::Config) -> Self {
+        Max {}
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Line<P::EA> {
+        Line::empty(line_size).fill(P::EA::min_value())
+    }
+
+    fn combine(_this: &Self, lhs: Line<P::EA>, rhs: Line<P::EA>) -> Line<P::EA> {
+        select_many(lhs.greater_than(rhs), lhs, rhs)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction};
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, plane_fuse_read_back};
 use crate::{components::instructions::ReduceRequirements, components::precision::ReducePrecision};
 use cubecl::prelude::*;
 
@@ -19,7 +19,11 @@ impl<P: ReducePrecision> ReduceInstruction<P> for MaxAbs {
     type Config = ();
 
     fn requirements(_this: &Self) -> ReduceRequirements {
-        ReduceRequirements { coordinates: false }
+        ReduceRequirements {
+            coordinates: false,
+            moments: false,
+            exp_sums: false,
+        }
     }
 
     fn from_config(_config: Self::Config) -> Self {
@@ -42,6 +46,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for MaxAbs {
         *destination = *source;
     }
 
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+    }
+
     fn reduce(
         _this: &Self,
         accumulator: &Self::AccumulatorItem,
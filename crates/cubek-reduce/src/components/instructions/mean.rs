@@ -1,4 +1,7 @@
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, Sum};
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, Sum,
+    plane_fuse_read_back,
+};
 use crate::components::precision::ReducePrecision;
 use cubecl::prelude::*;
 
@@ -27,7 +30,11 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Mean {
     type Config = ();
 
     fn requirements(_this: &Self) -> ReduceRequirements {
-        ReduceRequirements { coordinates: false }
+        ReduceRequirements {
+            coordinates: false,
+            moments: false,
+            exp_sums: false,
+        }
     }
     fn from_config(_config: Self::Config) -> Self {
         Mean { sum: Sum {} }
@@ -59,6 +66,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Mean {
         )
     }
 
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+    }
+
     fn reduce(
         this: &Self,
         accumulator: &Self::AccumulatorItem,
@@ -1,6 +1,6 @@
 use super::{
-    ArgMax, ArgMin, Max, MaxAbs, Mean, Min, Prod, ReduceCoordinate, ReduceFamily,
-    ReduceInstruction, ReduceRequirements, SharedAccumulator, Sum,
+    ArgMax, ArgMin, LogSumExp, Max, MaxAbs, Mean, Min, Prod, ReduceCoordinate, ReduceFamily,
+    ReduceInstruction, ReduceRequirements, SharedAccumulator, Sum, Variance, plane_fuse_read_back,
 };
 use crate::{ReduceDtypes, components::precision::ReducePrecision};
 use cubecl::{
@@ -20,6 +20,8 @@ pub(crate) enum ReduceOperation {
     ArgMin(ArgMin),
     Max(Max),
     Min(Min),
+    Variance(Variance),
+    LogSumExp(LogSumExp),
 }
 
 #[derive_cube_comptime]
@@ -32,6 +34,17 @@ pub enum ReduceOperationConfig {
     ArgMin,
     Max,
     Min,
+    /// Variance with `correction` subtracted from the number of items, `1` being Bessel's
+    /// correction.
+    Var {
+        correction: u32,
+    },
+    /// Standard deviation with `correction` subtracted from the number of items.
+    Std {
+        correction: u32,
+    },
+    /// `log(sum(exp(x)))`, computed without overflow.
+    LogSumExp,
 }
 
 impl ReduceOperationConfig {
@@ -41,6 +54,16 @@ impl ReduceOperationConfig {
             ReduceOperationConfig::Sum
             | ReduceOperationConfig::Prod
             | ReduceOperationConfig::Mean => {}
+            ReduceOperationConfig::Var { .. } | ReduceOperationConfig::Std { .. } => {
+                if !matches!(input, ElemType::Float(_)) {
+                    panic!("Can't compute the variance of integers");
+                }
+            }
+            ReduceOperationConfig::LogSumExp => {
+                if !matches!(input, ElemType::Float(_)) {
+                    panic!("Can't compute the log-sum-exp of integers");
+                }
+            }
             // No benefit to mixed precision accumulation.
             ReduceOperationConfig::MaxAbs
             | ReduceOperationConfig::Max
@@ -113,12 +136,18 @@ impl ReduceFamily for ReduceOperation {
 pub struct DynamicAccumulator<N: Numeric> {
     pub elements: SharedMemory<Line<N>>,
     pub args: CubeOption<SharedMemory<Line<u32>>>,
+    pub count: CubeOption<SharedMemory<Line<N>>>,
+    pub m2: CubeOption<SharedMemory<Line<N>>>,
+    pub exp_sums: CubeOption<SharedMemory<Line<N>>>,
 }
 
 #[derive(CubeType)]
 pub struct DynamicAccumulatorItem<N: Numeric> {
     pub elements: Line<N>,
     pub args: CubeOption<Line<u32>>,
+    pub count: CubeOption<Line<N>>,
+    pub m2: CubeOption<Line<N>>,
+    pub exp_sums: CubeOption<Line<N>>,
 }
 
 #[cube]
@@ -129,6 +158,8 @@ impl<In: Numeric> SharedAccumulator for DynamicAccumulator<In> {
         #[comptime] length: u32,
         #[comptime] line_size: u32,
         #[comptime] coordinate: bool,
+        #[comptime] moments: bool,
+        #[comptime] exp_sums: bool,
     ) -> Self {
         let elements = SharedMemory::new_lined(length, line_size);
         let args = if comptime![coordinate] {
@@ -137,8 +168,32 @@ impl<In: Numeric> SharedAccumulator for DynamicAccumulator<In> {
         } else {
             CubeOption::new_None()
         };
+        let count = if comptime![moments] {
+            let count = SharedMemory::new_lined(length, line_size);
+            CubeOption::new_Some(count)
+        } else {
+            CubeOption::new_None()
+        };
+        let m2 = if comptime![moments] {
+            let m2 = SharedMemory::new_lined(length, line_size);
+            CubeOption::new_Some(m2)
+        } else {
+            CubeOption::new_None()
+        };
+        let exp_sums = if comptime![exp_sums] {
+            let exp_sums = SharedMemory::new_lined(length, line_size);
+            CubeOption::new_Some(exp_sums)
+        } else {
+            CubeOption::new_None()
+        };
 
-        DynamicAccumulator::<In> { elements, args }
+        DynamicAccumulator::<In> {
+            elements,
+            args,
+            count,
+            m2,
+            exp_sums,
+        }
     }
 
     fn read(accumulator: &Self, index: u32) -> Self::Item {
@@ -147,8 +202,26 @@ impl<In: Numeric> SharedAccumulator for DynamicAccumulator<In> {
             CubeOption::Some(args) => CubeOption::new_Some(args[index]),
             CubeOption::None => CubeOption::new_None(),
         };
+        let count = match accumulator.count {
+            CubeOption::Some(count) => CubeOption::new_Some(count[index]),
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let m2 = match accumulator.m2 {
+            CubeOption::Some(m2) => CubeOption::new_Some(m2[index]),
+            CubeOption::None => CubeOption::new_None(),
+        };
+        let exp_sums = match accumulator.exp_sums {
+            CubeOption::Some(exp_sums) => CubeOption::new_Some(exp_sums[index]),
+            CubeOption::None => CubeOption::new_None(),
+        };
 
-        DynamicAccumulatorItem::<In> { elements, args }
+        DynamicAccumulatorItem::<In> {
+            elements,
+            args,
+            count,
+            m2,
+            exp_sums,
+        }
     }
 
     fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
@@ -161,6 +234,30 @@ impl<In: Numeric> SharedAccumulator for DynamicAccumulator<In> {
             }
             CubeOption::None => {}
         };
+
+        let count = &mut accumulator.count;
+        match count {
+            CubeOption::Some(count) => {
+                count[index] = item.count.unwrap();
+            }
+            CubeOption::None => {}
+        };
+
+        let m2 = &mut accumulator.m2;
+        match m2 {
+            CubeOption::Some(m2) => {
+                m2[index] = item.m2.unwrap();
+            }
+            CubeOption::None => {}
+        };
+
+        let exp_sums = &mut accumulator.exp_sums;
+        match exp_sums {
+            CubeOption::Some(exp_sums) => {
+                exp_sums[index] = item.exp_sums.unwrap();
+            }
+            CubeOption::None => {}
+        };
     }
 }
 
@@ -180,9 +277,37 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             ReduceOperation::ArgMin(..) => comptime![true],
             ReduceOperation::Max(..) => comptime![false],
             ReduceOperation::Min(..) => comptime![false],
+            ReduceOperation::Variance(..) => comptime![true],
+            ReduceOperation::LogSumExp(..) => comptime![false],
+        };
+        let moments = match this {
+            ReduceOperation::Sum(..) => comptime![false],
+            ReduceOperation::Prod(..) => comptime![false],
+            ReduceOperation::Mean(..) => comptime![false],
+            ReduceOperation::MaxAbs(..) => comptime![false],
+            ReduceOperation::ArgMax(..) => comptime![false],
+            ReduceOperation::ArgMin(..) => comptime![false],
+            ReduceOperation::Max(..) => comptime![false],
+            ReduceOperation::Min(..) => comptime![false],
+            ReduceOperation::Variance(..) => comptime![true],
+            ReduceOperation::LogSumExp(..) => comptime![false],
+        };
+        let exp_sums = match this {
+            ReduceOperation::Sum(..) => comptime![false],
+            ReduceOperation::Prod(..) => comptime![false],
+            ReduceOperation::Mean(..) => comptime![false],
+            ReduceOperation::MaxAbs(..) => comptime![false],
+            ReduceOperation::ArgMax(..) => comptime![false],
+            ReduceOperation::ArgMin(..) => comptime![false],
+            ReduceOperation::Max(..) => comptime![false],
+            ReduceOperation::Min(..) => comptime![false],
+            ReduceOperation::Variance(..) => comptime![false],
+            ReduceOperation::LogSumExp(..) => comptime![true],
         };
         ReduceRequirements {
             coordinates: comptime! {coordinates},
+            moments: comptime! {moments},
+            exp_sums: comptime! {exp_sums},
         }
     }
 
@@ -196,6 +321,17 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             ReduceOperationConfig::ArgMin => ReduceOperation::new_ArgMin(ArgMin {}),
             ReduceOperationConfig::Max => ReduceOperation::new_Max(Max {}),
             ReduceOperationConfig::Min => ReduceOperation::new_Min(Min {}),
+            ReduceOperationConfig::Var { correction } => ReduceOperation::new_Variance(Variance {
+                correction,
+                std: false,
+                mean: false,
+            }),
+            ReduceOperationConfig::Std { correction } => ReduceOperation::new_Variance(Variance {
+                correction,
+                std: true,
+                mean: false,
+            }),
+            ReduceOperationConfig::LogSumExp => ReduceOperation::new_LogSumExp(LogSumExp {}),
         }
     }
 
@@ -219,6 +355,12 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             }
             ReduceOperation::Max(max) => <Max as ReduceInstruction<P>>::null_input(max, line_size),
             ReduceOperation::Min(min) => <Min as ReduceInstruction<P>>::null_input(min, line_size),
+            ReduceOperation::Variance(variance) => {
+                <Variance as ReduceInstruction<P>>::null_input(variance, line_size)
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                <LogSumExp as ReduceInstruction<P>>::null_input(logsumexp, line_size)
+            }
         }
     }
 
@@ -230,6 +372,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Mean(sum) => {
@@ -238,6 +383,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Prod(sum) => {
@@ -246,6 +394,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::MaxAbs(maxabs) => {
@@ -255,6 +406,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMax(argmax) => {
@@ -264,6 +418,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMin(argmin) => {
@@ -273,6 +430,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Max(max) => {
@@ -281,6 +441,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Min(min) => {
@@ -289,6 +452,33 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::Variance(variance) => {
+                let (count, mean, m2) =
+                    <Variance as ReduceInstruction<P>>::null_accumulator(variance, line_size);
+
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: mean,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_Some(count),
+                    m2: CubeOption::new_Some(m2),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                let (max, sum) =
+                    <LogSumExp as ReduceInstruction<P>>::null_accumulator(logsumexp, line_size);
+
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: max,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_Some(sum),
                 }
             }
         }
@@ -325,6 +515,77 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             ReduceOperation::Min(min) => {
                 <Min as ReduceInstruction<P>>::read_accumulator(min, &accumulator.elements)
             }
+            ReduceOperation::Variance(variance) => {
+                <Variance as ReduceInstruction<P>>::read_accumulator(
+                    variance,
+                    &(
+                        accumulator.count.unwrap(),
+                        accumulator.elements,
+                        accumulator.m2.unwrap(),
+                    ),
+                )
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                <LogSumExp as ReduceInstruction<P>>::read_accumulator(
+                    logsumexp,
+                    &(accumulator.elements, accumulator.exp_sums.unwrap()),
+                )
+            }
+        }
+    }
+
+    fn plane_fuse_accumulator(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        match this {
+            ReduceOperation::Sum(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::Prod(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::Mean(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::MaxAbs(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::ArgMax(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::ArgMin(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::Max(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::Min(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
+            ReduceOperation::Variance(variance) => {
+                let (count, mean, m2) = <Variance as ReduceInstruction<P>>::plane_fuse_accumulator(
+                    variance,
+                    &(
+                        accumulator.count.unwrap(),
+                        accumulator.elements,
+                        accumulator.m2.unwrap(),
+                    ),
+                    line_size,
+                );
+
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: mean,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_Some(count),
+                    m2: CubeOption::new_Some(m2),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::LogSumExp(..) => {
+                plane_fuse_read_back::<P, Self>(this, accumulator, line_size)
+            }
         }
     }
 
@@ -339,6 +600,21 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             CubeOption::Some(val) => *val = source.args.unwrap(),
             CubeOption::None => {}
         }
+        let count = &mut destination.count;
+        match count {
+            CubeOption::Some(val) => *val = source.count.unwrap(),
+            CubeOption::None => {}
+        }
+        let m2 = &mut destination.m2;
+        match m2 {
+            CubeOption::Some(val) => *val = source.m2.unwrap(),
+            CubeOption::None => {}
+        }
+        let exp_sums = &mut destination.exp_sums;
+        match exp_sums {
+            CubeOption::Some(val) => *val = source.exp_sums.unwrap(),
+            CubeOption::None => {}
+        }
     }
 
     fn reduce(
@@ -360,6 +636,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Prod(sum) => {
@@ -373,6 +652,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Mean(sum) => {
@@ -386,6 +668,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::MaxAbs(maxabs) => {
@@ -399,6 +684,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMax(argmax) => {
@@ -413,6 +701,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMin(argmin) => {
@@ -427,6 +718,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Max(max) => {
@@ -440,6 +734,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Min(min) => {
@@ -453,6 +750,45 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::Variance(variance) => {
+                let (count, mean, m2) = <Variance as ReduceInstruction<P>>::reduce(
+                    variance,
+                    &(
+                        accumulator.count.unwrap(),
+                        accumulator.elements,
+                        accumulator.m2.unwrap(),
+                    ),
+                    item,
+                    coordinate,
+                    use_planes,
+                );
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: mean,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_Some(count),
+                    m2: CubeOption::new_Some(m2),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                let (max, sum) = <LogSumExp as ReduceInstruction<P>>::reduce(
+                    logsumexp,
+                    &(accumulator.elements, accumulator.exp_sums.unwrap()),
+                    item,
+                    coordinate,
+                    use_planes,
+                );
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: max,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_Some(sum),
                 }
             }
         }
@@ -473,6 +809,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Prod(prod) => {
@@ -484,6 +823,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Mean(mean) => {
@@ -495,6 +837,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::MaxAbs(maxabs) => {
@@ -506,6 +851,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMax(argmax) => {
@@ -517,6 +865,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::ArgMin(argmin) => {
@@ -528,6 +879,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_Some(args),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Max(max) => {
@@ -539,6 +893,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
                 }
             }
             ReduceOperation::Min(min) => {
@@ -550,6 +907,37 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
                     args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::Variance(variance) => {
+                let (count, mean, m2) = <Variance as ReduceInstruction<P>>::fuse_accumulators(
+                    variance,
+                    (lhs.count.unwrap(), lhs.elements, lhs.m2.unwrap()),
+                    (rhs.count.unwrap(), rhs.elements, rhs.m2.unwrap()),
+                );
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: mean,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_Some(count),
+                    m2: CubeOption::new_Some(m2),
+                    exp_sums: CubeOption::new_None(),
+                }
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                let (max, sum) = <LogSumExp as ReduceInstruction<P>>::fuse_accumulators(
+                    logsumexp,
+                    (lhs.elements, lhs.exp_sums.unwrap()),
+                    (rhs.elements, rhs.exp_sums.unwrap()),
+                );
+                DynamicAccumulatorItem::<P::EA> {
+                    elements: max,
+                    args: CubeOption::new_None(),
+                    count: CubeOption::new_None(),
+                    m2: CubeOption::new_None(),
+                    exp_sums: CubeOption::new_Some(sum),
                 }
             }
         }
@@ -603,6 +991,24 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
                 accumulator.elements,
                 shape_axis_reduce,
             ),
+            ReduceOperation::Variance(variance) => {
+                <Variance as ReduceInstruction<P>>::merge_line::<Out>(
+                    variance,
+                    (
+                        accumulator.count.unwrap(),
+                        accumulator.elements,
+                        accumulator.m2.unwrap(),
+                    ),
+                    shape_axis_reduce,
+                )
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                <LogSumExp as ReduceInstruction<P>>::merge_line::<Out>(
+                    logsumexp,
+                    (accumulator.elements, accumulator.exp_sums.unwrap()),
+                    shape_axis_reduce,
+                )
+            }
         }
     }
 
@@ -656,6 +1062,24 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceOperation {
             ReduceOperation::Min(min) => <Min as ReduceInstruction<P>>::to_output_perpendicular::<
                 Out,
             >(min, accumulator.elements, shape_axis_reduce),
+            ReduceOperation::Variance(variance) => {
+                <Variance as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
+                    variance,
+                    (
+                        accumulator.count.unwrap(),
+                        accumulator.elements,
+                        accumulator.m2.unwrap(),
+                    ),
+                    shape_axis_reduce,
+                )
+            }
+            ReduceOperation::LogSumExp(logsumexp) => {
+                <LogSumExp as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
+                    logsumexp,
+                    (accumulator.elements, accumulator.exp_sums.unwrap()),
+                    shape_axis_reduce,
+                )
+            }
         }
     }
 }
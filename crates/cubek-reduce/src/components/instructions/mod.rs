@@ -1,23 +1,29 @@
 mod argmax;
 mod argmin;
 mod base;
+mod logsumexp;
 mod max;
 mod maxabs;
 mod mean;
 mod min;
 mod mixed;
 mod prod;
+mod scan;
 mod sum;
 mod utils;
+mod variance;
 
 pub use argmax::*;
 pub use argmin::*;
 pub use base::*;
+pub use logsumexp::*;
 pub use max::*;
 pub use maxabs::*;
 pub use mean::*;
 pub use min::*;
 pub use mixed::*;
 pub use prod::*;
+pub use scan::*;
 pub use sum::*;
 pub(crate) use utils::*;
+pub use variance::*;
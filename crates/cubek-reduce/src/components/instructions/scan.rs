@@ -0,0 +1,28 @@
+use crate::components::precision::ReducePrecision;
+use cubecl::prelude::*;
+
+pub trait ScanFamily: Send + Sync + 'static + std::fmt::Debug {
+    type Instruction<P: ReducePrecision>: ScanInstruction<P, Config = Self::Config>;
+    type Config: CubeComptime + Send + Sync;
+}
+
+/// An instruction for a scan algorithm that works with [`Line`].
+///
+/// This is the counterpart of [`ReduceInstruction`](super::ReduceInstruction) for a scan, which
+/// only needs to combine partial results. The operation must be associative, but doesn't need to
+/// be commutative since partial results are always combined in the order of the axis.
+#[cube]
+pub trait ScanInstruction<P: ReducePrecision>:
+    Send + Sync + 'static + std::fmt::Debug + CubeType
+{
+    type Config: CubeComptime + Send + Sync;
+
+    fn from_config(#[comptime] config: Self::Config) -> Self;
+
+    /// An item such that `Self::combine(Self::identity(), item)` is guaranteed to return `item`
+    /// unchanged, which an exclusive scan writes first.
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Line<P::EA>;
+
+    /// Combine two partial results, where `lhs` comes before `rhs` along the axis.
+    fn combine(this: &Self, lhs: Line<P::EA>, rhs: Line<P::EA>) -> Line<P::EA>;
+}
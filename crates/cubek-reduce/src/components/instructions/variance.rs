@@ -0,0 +1,281 @@
+use super::{
+    ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction, ReduceRequirements,
+    SharedAccumulator,
+};
+use crate::components::precision::ReducePrecision;
+use cubecl::prelude::*;
+
+/// Compute the variance, or the standard deviation, using Welford's online algorithm.
+///
+/// The accumulator is a `(count, mean, M2)` triple where `M2` is the sum of squared differences
+/// to the mean. Unlike computing `E[x²] - E[x]²` in two passes, this doesn't suffer from
+/// cancellation when the mean is large compared to the spread, which matters for f16 and bf16.
+/// Partial accumulators are merged with the parallel formula of Chan et al.
+///
+/// Out-of-bound items are recognized by their coordinate set to `u32::MAX`, which is why
+/// coordinates are required.
+#[derive(Debug, CubeType, Clone)]
+pub struct Variance {
+    /// Subtracted from the count when dividing `M2`, `1` giving the unbiased (Bessel) estimator.
+    #[cube(comptime)]
+    pub correction: u32,
+    /// Output the standard deviation instead of the variance.
+    #[cube(comptime)]
+    pub std: bool,
+    /// Output the mean instead of the variance, ignoring `correction` and `std`.
+    #[cube(comptime)]
+    pub mean: bool,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct VarianceConfig {
+    pub correction: u32,
+    pub std: bool,
+    pub mean: bool,
+}
+
+impl ReduceFamily for Variance {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = VarianceConfig;
+}
+
+#[cube]
+impl Variance {
+    /// Merge two `(count, mean, M2)` accumulators.
+    pub fn merge<N: Numeric>(
+        lhs: (Line<N>, Line<N>, Line<N>),
+        rhs: (Line<N>, Line<N>, Line<N>),
+    ) -> (Line<N>, Line<N>, Line<N>) {
+        let line_size = lhs.0.size();
+        let count = lhs.0 + rhs.0;
+        // Avoid dividing by zero when both sides are empty, in which case `delta` is zero.
+        let divisor = select_many(
+            count.equal(Line::empty(line_size).fill(N::from_int(0))),
+            Line::empty(line_size).fill(N::from_int(1)),
+            count,
+        );
+
+        let delta = rhs.1 - lhs.1;
+        let mean = lhs.1 + delta * rhs.0 / divisor;
+        let m2 = lhs.2 + rhs.2 + delta * delta * lhs.0 * rhs.0 / divisor;
+
+        (count, mean, m2)
+    }
+
+    fn output<N: Numeric, Out: Numeric>(
+        this: &Self,
+        count: Line<N>,
+        mean: Line<N>,
+        m2: Line<N>,
+    ) -> Line<Out> {
+        let correction = comptime![this.correction as i64];
+        let variance = m2 / (count - Line::empty(count.size()).fill(N::from_int(correction)));
+
+        if comptime![this.mean] {
+            Line::cast_from(mean)
+        } else if comptime![this.std] {
+            Line::cast_from(Sqrt::sqrt(Line::<f32>::cast_from(variance)))
+        } else {
+            Line::cast_from(variance)
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Variance {
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = WelfordAccumulator<P::EA>;
+    type Config = VarianceConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements {
+            coordinates: true,
+            moments: true,
+            exp_sums: false,
+        }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Variance {
+            correction: config.correction,
+            std: config.std,
+            mean: config.mean,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        let zero = Line::empty(line_size).fill(P::EA::from_int(0));
+        (zero, zero, zero)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+        destination.2 = source.2;
+    }
+
+    fn read_accumulator(
+        _this: &Self,
+        _accumulator: &Self::AccumulatorItem,
+    ) -> (Line<P::EI>, ReduceCoordinate) {
+        comptime! {panic!("A Welford accumulator can't be read back as a single item")};
+        #[allow(unreachable_code)]
+        (
+            Line::new(P::EI::from_int(0)),
+            ReduceCoordinate::new_NotRequired(),
+        )
+    }
+
+    fn plane_fuse_accumulator(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        #[comptime] line_size: u32,
+    ) -> Self::AccumulatorItem {
+        let zero = Line::empty(line_size).fill(P::EA::from_int(0));
+        let count = plane_sum(accumulator.0);
+        let divisor = select_many(
+            count.equal(zero),
+            Line::empty(line_size).fill(P::EA::from_int(1)),
+            count,
+        );
+        let mean = plane_sum(accumulator.0 * accumulator.1) / divisor;
+        // Empty accumulators have a count of zero and don't contribute to the spread.
+        let delta = accumulator.1 - mean;
+        let m2 = plane_sum(accumulator.2 + accumulator.0 * delta * delta);
+
+        (count, mean, m2)
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let line_size = item.size();
+        let coordinate = match coordinate {
+            ReduceCoordinate::Required(val) => val,
+            ReduceCoordinate::NotRequired => {
+                comptime! {panic!("Coordinates are required for Variance")};
+                #[allow(unreachable_code)]
+                Line::new(0)
+            }
+        };
+
+        let zero = Line::empty(line_size).fill(P::EA::from_int(0));
+        let count = select_many(
+            coordinate.equal(Line::empty(line_size).fill(u32::MAX)),
+            zero,
+            Line::empty(line_size).fill(P::EA::from_int(1)),
+        );
+        let item = Line::cast_from(item) * count;
+
+        let batch = if use_planes {
+            let count = plane_sum(count);
+            let divisor = select_many(
+                count.equal(zero),
+                Line::empty(line_size).fill(P::EA::from_int(1)),
+                count,
+            );
+            let mean = plane_sum(item) / divisor;
+            let difference = (item - mean) * count;
+            (count, mean, plane_sum(difference * difference))
+        } else {
+            (count, item, zero)
+        };
+
+        Self::merge::<P::EA>((accumulator.0, accumulator.1, accumulator.2), batch)
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        Self::merge::<P::EA>(lhs, rhs)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut merged = (
+            Line::new(accumulator.0[0]),
+            Line::new(accumulator.1[0]),
+            Line::new(accumulator.2[0]),
+        );
+        #[unroll]
+        for k in 1..accumulator.0.size() {
+            merged = Self::merge::<P::EA>(
+                merged,
+                (
+                    Line::new(accumulator.0[k]),
+                    Line::new(accumulator.1[k]),
+                    Line::new(accumulator.2[k]),
+                ),
+            );
+        }
+
+        let output = Self::output::<P::EA, Out>(this, merged.0, merged.1, merged.2);
+        output[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Self::output::<P::EA, Out>(this, accumulator.0, accumulator.1, accumulator.2)
+    }
+}
+
+/// A triple of shared memories used for [`Variance`].
+#[derive(CubeType)]
+pub struct WelfordAccumulator<N: Numeric> {
+    pub counts: SharedMemory<Line<N>>,
+    pub means: SharedMemory<Line<N>>,
+    pub m2s: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<In: Numeric> SharedAccumulator for WelfordAccumulator<In> {
+    type Item = (Line<In>, Line<In>, Line<In>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+        #[comptime] _moments: bool,
+        #[comptime] _exp_sums: bool,
+    ) -> Self {
+        WelfordAccumulator::<In> {
+            counts: SharedMemory::new_lined(length, line_size),
+            means: SharedMemory::new_lined(length, line_size),
+            m2s: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (
+            accumulator.counts[index],
+            accumulator.means[index],
+            accumulator.m2s[index],
+        )
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.counts[index] = item.0;
+        accumulator.means[index] = item.1;
+        accumulator.m2s[index] = item.2;
+    }
+}
@@ -52,6 +52,40 @@ impl<P: ReducePrecision> Reader<P> {
             }
         }
     }
+
+    /// Create a reader for the vector, or the vectors in perpendicular mode, starting at the
+    /// element `batch_offset` of `input` instead of locating it from an output index.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_at<I: ReduceInstruction<P>>(
+        input: &VirtualTensor<P::EI>,
+        inst: &I,
+        reduce_axis: u32,
+        batch_offset: u32,
+        idle: CubeOption<bool>,
+        #[comptime] bound_checks: BoundChecks,
+        #[comptime] line_mode: LineMode,
+    ) -> Reader<P> {
+        match line_mode {
+            LineMode::Parallel => Reader::<P>::new_Parallel(ParallelReader::<P>::new_at::<I>(
+                input,
+                inst,
+                reduce_axis,
+                batch_offset,
+                idle,
+                bound_checks,
+            )),
+            LineMode::Perpendicular => {
+                Reader::<P>::new_Perpendicular(PerpendicularReader::<P>::new_at::<I>(
+                    input,
+                    inst,
+                    reduce_axis,
+                    batch_offset,
+                    idle,
+                    bound_checks,
+                ))
+            }
+        }
+    }
 }
 
 #[cube]
@@ -1,4 +1,7 @@
-use crate::{BoundChecks, ReduceInstruction, ReducePrecision};
+use crate::{
+    BoundChecks, ReduceInstruction, ReducePrecision,
+    components::instructions::{ReduceCoordinate, ReduceCoordinateExpand},
+};
 use cubecl::{
     prelude::*,
     std::{
@@ -73,4 +76,26 @@ impl<P: ReducePrecision> ReaderBoundChecks<P> {
             },
         }
     }
+
+    /// Set the coordinates of out-of-bound reads to `u32::MAX`, so that instructions can tell
+    /// them apart from real items.
+    pub fn coordinate(&self, pos: u32, coordinate: ReduceCoordinate) -> ReduceCoordinate {
+        match coordinate {
+            ReduceCoordinate::NotRequired => ReduceCoordinate::new_NotRequired(),
+            ReduceCoordinate::Required(coordinate) => match self {
+                ReaderBoundChecks::NotRequired => ReduceCoordinate::new_Required(coordinate),
+                ReaderBoundChecks::Required(checks) => match comptime!(checks.bound_checks) {
+                    BoundChecks::None => ReduceCoordinate::new_Required(coordinate),
+                    BoundChecks::Mask | BoundChecks::Branch => {
+                        let padding = Line::empty(coordinate.size()).fill(u32::MAX);
+                        ReduceCoordinate::new_Required(select(
+                            pos < checks.pos_max,
+                            coordinate,
+                            padding,
+                        ))
+                    }
+                },
+            },
+        }
+    }
 }
@@ -40,14 +40,26 @@ impl<P: ReducePrecision> ParallelReader<P> {
         idle: CubeOption<bool>,
         #[comptime] bound_checks: BoundChecks,
     ) -> ParallelReader<P> {
-        let line_size = input.line_size();
-
         let mut batch_offset = 0;
         for axis in 0..input.rank() {
             let coordinate = output.coordinate(reduce_index, axis);
             batch_offset += coordinate * input.stride(axis);
         }
-        batch_offset /= line_size;
+
+        ParallelReader::<P>::new_at::<I>(input, inst, reduce_axis, batch_offset, idle, bound_checks)
+    }
+
+    /// Create a reader for the vector starting at the element `batch_offset` of `input`.
+    pub fn new_at<I: ReduceInstruction<P>>(
+        input: &VirtualTensor<P::EI>,
+        inst: &I,
+        reduce_axis: u32,
+        batch_offset: u32,
+        idle: CubeOption<bool>,
+        #[comptime] bound_checks: BoundChecks,
+    ) -> ParallelReader<P> {
+        let line_size = input.line_size();
+        let batch_offset = batch_offset / line_size;
 
         let requirements = I::requirements(inst);
 
@@ -94,6 +106,8 @@ impl<P: ReducePrecision> ParallelReader<P> {
             LineMode::Parallel,
         );
 
+        let coordinate = self.bound_checks.coordinate(pos, coordinate);
+
         (item, coordinate)
     }
 
@@ -112,6 +126,8 @@ impl<P: ReducePrecision> ParallelReader<P> {
             LineMode::Parallel,
         );
 
+        let coordinate = self.bound_checks.coordinate(pos, coordinate);
+
         (item, coordinate)
     }
 
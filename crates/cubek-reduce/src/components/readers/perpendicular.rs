@@ -41,15 +41,35 @@ impl<P: ReducePrecision> PerpendicularReader<P> {
         idle: CubeOption<bool>,
         #[comptime] bound_checks: BoundChecks,
     ) -> PerpendicularReader<P> {
-        let line_size = input.line_size();
-        let output_index = reduce_index * line_size;
+        let output_index = reduce_index * input.line_size();
 
         let mut batch_offset = 0;
         for axis in 0..input.rank() {
             let coordinate = output.coordinate(output_index, axis);
             batch_offset += coordinate * input.stride(axis);
         }
-        batch_offset /= line_size;
+
+        PerpendicularReader::<P>::new_at::<I>(
+            input,
+            inst,
+            reduce_axis,
+            batch_offset,
+            idle,
+            bound_checks,
+        )
+    }
+
+    /// Create a reader for the vectors starting at the element `batch_offset` of `input`.
+    pub fn new_at<I: ReduceInstruction<P>>(
+        input: &VirtualTensor<P::EI>,
+        inst: &I,
+        reduce_axis: u32,
+        batch_offset: u32,
+        idle: CubeOption<bool>,
+        #[comptime] bound_checks: BoundChecks,
+    ) -> PerpendicularReader<P> {
+        let line_size = input.line_size();
+        let batch_offset = batch_offset / line_size;
 
         let requirements = I::requirements(inst);
         let vector_offset_stride = input.stride(reduce_axis) / line_size;
@@ -97,6 +117,8 @@ impl<P: ReducePrecision> PerpendicularReader<P> {
             LineMode::Perpendicular,
         );
 
+        let coordinate = self.bound_checks.coordinate(pos, coordinate);
+
         (item, coordinate)
     }
 
@@ -117,6 +139,8 @@ impl<P: ReducePrecision> PerpendicularReader<P> {
             LineMode::Perpendicular,
         );
 
+        let coordinate = self.bound_checks.coordinate(pos, coordinate);
+
         (item, coordinate)
     }
 
@@ -0,0 +1,301 @@
+use crate::{
+    ReduceError,
+    components::instructions::ReduceOperationConfig,
+    launch::{ReduceDtypes, ReduceStrategy, launch_reduce},
+};
+use cubecl::{prelude::*, std::tensor::TensorHandle};
+use std::cmp::Reverse;
+
+/// Launch the reduction of multiple axes. This function assumes that the axes are valid, sorted
+/// and distinct, and that the output shape matches. See the entrypoint `reduce_axes` in `lib.rs`.
+///
+/// Adjacent axes that are contiguous-collapsible are merged into a single axis and reduced by
+/// one kernel. When the axes can't all be merged, they are reduced one group at a time, starting
+/// with the group shrinking the tensor the most, through intermediate buffers in the
+/// accumulation type.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn launch_reduce_axes<Run: Runtime>(
+    client: &ComputeClient<Run>,
+    input: TensorHandleRef<Run>,
+    output: TensorHandleRef<Run>,
+    axes: &[usize],
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+    inst: ReduceOperationConfig,
+) -> Result<(), ReduceError> {
+    // Reducing an axis of size 1 doesn't change anything, unless it is the only one.
+    let mut remaining: Vec<usize> = axes
+        .iter()
+        .copied()
+        .filter(|axis| input.shape[*axis] != 1)
+        .collect();
+    if remaining.is_empty() {
+        remaining.push(axes[0]);
+    }
+
+    if collapsible_groups(input.shape, input.strides, &remaining).len() > 1
+        && matches!(
+            inst,
+            ReduceOperationConfig::ArgMax
+                | ReduceOperationConfig::ArgMin
+                | ReduceOperationConfig::Var { .. }
+                | ReduceOperationConfig::Std { .. }
+        )
+    {
+        return Err(ReduceError::Validation {
+            details: "ArgMax, ArgMin, Var and Std over multiple axes require the axes to be contiguous-collapsible.",
+        });
+    }
+
+    let mut intermediate: Option<TensorHandle<Run>> = None;
+
+    loop {
+        let source = match &intermediate {
+            Some(tensor) => tensor.as_ref(),
+            None => unsafe {
+                TensorHandleRef::from_raw_parts(
+                    input.handle,
+                    input.strides,
+                    input.shape,
+                    input.elem_size,
+                )
+            },
+        };
+        let source_dtype = match &intermediate {
+            Some(_) => dtypes.accumulation,
+            None => dtypes.input,
+        };
+
+        let groups = collapsible_groups(source.shape, source.strides, &remaining);
+        let group = groups
+            .into_iter()
+            .max_by_key(|group| {
+                group
+                    .iter()
+                    .map(|axis| source.shape[*axis])
+                    .product::<usize>()
+            })
+            .expect("At least one axis to reduce");
+        remaining.retain(|axis| !group.contains(axis));
+
+        if remaining.is_empty() {
+            let dtypes = ReduceDtypes {
+                input: source_dtype,
+                ..dtypes
+            };
+            return launch_reduce_group(client, &source, &output, &group, strategy, dtypes, inst);
+        }
+
+        let mut shape = source.shape.to_vec();
+        for axis in group.iter() {
+            shape[*axis] = 1;
+        }
+        let next = TensorHandle::empty(client, shape, dtypes.accumulation);
+        let pass_dtypes = ReduceDtypes {
+            input: source_dtype,
+            output: dtypes.accumulation,
+            accumulation: dtypes.accumulation,
+        };
+        launch_reduce_group(
+            client,
+            &source,
+            &next.as_ref(),
+            &group,
+            strategy.clone(),
+            pass_dtypes,
+            inst,
+        )?;
+
+        intermediate = Some(next);
+    }
+}
+
+/// Minimum number of elements reduced into each partial of a full reduction.
+const FULL_REDUCE_MIN_PARTIAL_SIZE: usize = 4096;
+/// Maximum number of partials of a full reduction, since they are combined by a single cube.
+const FULL_REDUCE_MAX_PARTIALS: usize = 1024;
+
+/// Launch the reduction of all the axes of `input` into the single element of `output`. This
+/// function assumes that the output shape matches. See the entrypoint `reduce_full` in `lib.rs`.
+///
+/// When the operation can be applied to its own results and the input is contiguous up to a
+/// permutation of its axes, the input is seen as a single axis split into equal chunks. A first
+/// kernel reduces the chunks into partials of the accumulation type, spreading them over many
+/// cubes, then a second one combines the partials into `output`. Otherwise, this falls back to
+/// reducing all the axes with `launch_reduce_axes`.
+pub(crate) fn launch_reduce_full<Run: Runtime>(
+    client: &ComputeClient<Run>,
+    input: TensorHandleRef<Run>,
+    output: TensorHandleRef<Run>,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+    inst: ReduceOperationConfig,
+) -> Result<(), ReduceError> {
+    // The partials of these can't be combined by the same operation.
+    let combinable = !matches!(
+        inst,
+        ReduceOperationConfig::ArgMax
+            | ReduceOperationConfig::ArgMin
+            | ReduceOperationConfig::Var { .. }
+            | ReduceOperationConfig::Std { .. }
+    );
+    let length = input.shape.iter().product::<usize>();
+    let stride = contiguous_stride(input.shape, input.strides).filter(|_| combinable);
+
+    let (Some(stride), Some(num_partials)) = (stride, num_partials(length)) else {
+        let axes = (0..input.shape.len()).collect::<Vec<_>>();
+        return launch_reduce_axes(client, input, output, &axes, strategy, dtypes, inst);
+    };
+
+    let chunk_size = length / num_partials;
+    let chunks_shape = [num_partials, chunk_size];
+    let chunks_strides = [chunk_size * stride, stride];
+    let chunks = unsafe {
+        TensorHandleRef::from_raw_parts(
+            input.handle,
+            &chunks_strides,
+            &chunks_shape,
+            input.elem_size,
+        )
+    };
+
+    let partials = TensorHandle::empty(client, vec![num_partials, 1], dtypes.accumulation);
+    let partial_dtypes = ReduceDtypes {
+        output: dtypes.accumulation,
+        ..dtypes
+    };
+    launch_reduce::<Run>(
+        client,
+        chunks,
+        partials.as_ref(),
+        1,
+        strategy.clone(),
+        partial_dtypes,
+        inst,
+    )?;
+
+    let output = unsafe {
+        TensorHandleRef::from_raw_parts(output.handle, &[1, 1], &[1, 1], output.elem_size)
+    };
+    let combine_dtypes = ReduceDtypes {
+        input: dtypes.accumulation,
+        ..dtypes
+    };
+    launch_reduce::<Run>(
+        client,
+        partials.as_ref(),
+        output,
+        0,
+        strategy,
+        combine_dtypes,
+        inst,
+    )
+}
+
+/// The stride of the innermost axis when all the axes of size greater than 1 can be seen as a
+/// single one, once ordered by decreasing stride.
+fn contiguous_stride(shape: &[usize], strides: &[usize]) -> Option<usize> {
+    let mut dims = shape
+        .iter()
+        .zip(strides.iter())
+        .filter(|(shape, _)| **shape != 1)
+        .map(|(shape, stride)| (*shape, *stride))
+        .collect::<Vec<_>>();
+    dims.sort_by_key(|(_, stride)| Reverse(*stride));
+
+    let (shape, strides): (Vec<usize>, Vec<usize>) = dims.into_iter().unzip();
+    let axes = (0..shape.len()).collect::<Vec<_>>();
+    match collapsible_groups(&shape, &strides, &axes).len() {
+        1 => strides.last().copied(),
+        _ => None,
+    }
+}
+
+/// The number of equal chunks to split `length` elements into for a full reduction, or `None`
+/// if it isn't worth more than one.
+fn num_partials(length: usize) -> Option<usize> {
+    let max = (length / FULL_REDUCE_MIN_PARTIAL_SIZE).min(FULL_REDUCE_MAX_PARTIALS);
+    (2..=max)
+        .rev()
+        .find(|num_partials| length % num_partials == 0)
+}
+
+/// Reduce the adjacent and contiguous-collapsible `group` of axes with a single kernel, by
+/// merging them into the axis `group[0]`.
+fn launch_reduce_group<Run: Runtime>(
+    client: &ComputeClient<Run>,
+    input: &TensorHandleRef<Run>,
+    output: &TensorHandleRef<Run>,
+    group: &[usize],
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+    inst: ReduceOperationConfig,
+) -> Result<(), ReduceError> {
+    let (input_shape, input_strides) = collapse(input.shape, input.strides, group);
+    let (output_shape, output_strides) = collapse(output.shape, output.strides, group);
+
+    let (input, output) = unsafe {
+        (
+            TensorHandleRef::from_raw_parts(
+                input.handle,
+                &input_strides,
+                &input_shape,
+                input.elem_size,
+            ),
+            TensorHandleRef::from_raw_parts(
+                output.handle,
+                &output_strides,
+                &output_shape,
+                output.elem_size,
+            ),
+        )
+    };
+
+    launch_reduce::<Run>(
+        client,
+        input,
+        output,
+        group[0] as u32,
+        strategy,
+        dtypes,
+        inst,
+    )
+}
+
+/// Split the sorted `axes` into groups of adjacent axes that can be seen as a single axis, that
+/// is where each axis steps over exactly one full span of the next one.
+///
+/// Broadcast axes, with a stride of 0, are collapsible with each other.
+fn collapsible_groups(shape: &[usize], strides: &[usize], axes: &[usize]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+
+    for &axis in axes {
+        match groups.last_mut() {
+            Some(group)
+                if group[group.len() - 1] + 1 == axis
+                    && strides[axis - 1] == strides[axis] * shape[axis] =>
+            {
+                group.push(axis)
+            }
+            _ => groups.push(vec![axis]),
+        }
+    }
+
+    groups
+}
+
+/// Merge the adjacent axes of `group` into one, keeping the stride of the innermost.
+fn collapse(shape: &[usize], strides: &[usize], group: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let first = group[0];
+    let last = group[group.len() - 1];
+
+    let mut collapsed_shape = shape[..first].to_vec();
+    collapsed_shape.push(shape[first..=last].iter().product());
+    collapsed_shape.extend_from_slice(&shape[last + 1..]);
+
+    let mut collapsed_strides = strides[..first].to_vec();
+    collapsed_strides.push(strides[last]);
+    collapsed_strides.extend_from_slice(&strides[last + 1..]);
+
+    (collapsed_shape, collapsed_strides)
+}
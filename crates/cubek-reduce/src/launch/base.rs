@@ -13,7 +13,7 @@ use crate::{
         cube::CubeRoutine, plane::PlaneRoutine, unit::UnitRoutine,
     },
 };
-use cubecl::{prelude::*, std::tensor::r#virtual::VirtualTensor};
+use cubecl::{ir::ElemType, prelude::*, std::tensor::r#virtual::VirtualTensor};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ReduceDtypes {
@@ -75,6 +75,13 @@ pub(crate) fn launch_reduce<Run: Runtime>(
         }
     };
 
+    if matches!(
+        inst,
+        ReduceOperationConfig::Var { .. } | ReduceOperationConfig::Std { .. }
+    ) {
+        validate_variance(dtypes)?;
+    }
+
     unsafe {
         reduce_kernel::launch_unchecked::<TensorArgs, Run>(
             client,
@@ -93,6 +100,17 @@ pub(crate) fn launch_reduce<Run: Runtime>(
     }
 }
 
+/// The Welford formulas require a float accumulation type.
+fn validate_variance(dtypes: ReduceDtypes) -> Result<(), ReduceError> {
+    if !matches!(dtypes.accumulation.elem_type(), ElemType::Float(_)) {
+        return Err(ReduceError::Validation {
+            details: "Var and Std require a float accumulation type.",
+        });
+    }
+
+    Ok(())
+}
+
 #[cube(launch_unchecked)]
 pub fn reduce_kernel<In: Numeric, Out: Numeric, Acc: Numeric, RA: ReduceArgs>(
     input: &RA::Input<In>,
@@ -1,9 +1,11 @@
 pub mod tune_key;
 
+mod axes;
 mod base;
 mod strategy;
 mod utils;
 
+pub(crate) use axes::*;
 pub use base::*;
 pub use strategy::*;
 pub use utils::*;
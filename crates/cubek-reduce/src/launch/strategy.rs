@@ -2,6 +2,7 @@ use crate::routines::{
     BlueprintStrategy, cube::CubeRoutine, plane::PlaneRoutine, unit::UnitRoutine,
 };
 use cubecl::{features::Plane, prelude::*};
+use std::fmt::Display;
 
 #[derive(Debug, Clone)]
 pub struct ReduceStrategy {
@@ -26,6 +27,25 @@ pub struct LineSizeStrategy {
     pub parallel_output_vectorization: bool,
 }
 
+// Display implementations are used to save names when autotuning. Forced blueprints aren't part
+// of the name, so strategies only differing by their blueprint share the same name.
+
+impl Display for ReduceStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let routine = match self.routine {
+            RoutineStrategy::Unit(_) => "unit",
+            RoutineStrategy::Plane(_) => "plane",
+            RoutineStrategy::Cube(_) => "cube",
+        };
+        write!(f, "reduce_{routine}")?;
+
+        if self.line_size.parallel_output_vectorization {
+            f.write_str("_parallel_output")?;
+        }
+        Ok(())
+    }
+}
+
 pub(crate) fn support_plane<R: Runtime>(client: &ComputeClient<R>) -> bool {
     client.properties().features.plane.contains(Plane::Ops)
 }
@@ -7,6 +7,10 @@
 //!
 //! This crate provides a main entrypoint as the [`reduce`] function which allows to automatically
 //! perform a reduction for a given instruction implementing the [`ReduceInstruction`] trait and a given [`ReduceStrategy`].
+//! The [`reduce_axes`] and [`reduce_full`] functions extend it to several axes and to all of them.
+//! The [`softmax`] and [`log_softmax`] functions fuse a log-sum-exp reduction with the normalization of the input.
+//! The [`top_k`] and [`argsort`] functions select and order the items of an axis.
+//! The [`scan`] function accumulates a [`ScanInstruction`] along an axis, such as in [`cumsum`].
 //! It also provides implementation of the [`ReduceInstruction`] trait for common operations in the [`instructions`] module.
 //! Finally, it provides many reusable primitives to perform different general reduction algorithms in the [`primitives`] module.
 
@@ -17,17 +21,24 @@ pub mod routines;
 mod error;
 
 pub use crate::launch::ReduceStrategy;
-use crate::{components::instructions::ReduceOperationConfig, launch::launch_reduce};
+use crate::{
+    components::instructions::ReduceOperationConfig,
+    launch::{launch_reduce, launch_reduce_axes, launch_reduce_full},
+};
 pub use components::{
     args::init_tensors,
     config::*,
-    instructions::{ReduceFamily, ReduceInstruction},
+    instructions::{ReduceFamily, ReduceInstruction, ScanFamily, ScanInstruction},
     precision::ReducePrecision,
 };
 use cubecl::prelude::*;
 pub use error::*;
 pub use launch::{ReduceDtypes, reduce_kernel};
+pub use routines::scan::{cummax, cummin, cumprod, cumsum, scan};
 pub use routines::shared_sum::shared_sum;
+pub use routines::softmax::{log_softmax, softmax};
+pub use routines::top_k::{TopKConfig, argsort, top_k};
+pub use routines::var_mean::var_mean;
 
 /// Reduce the given `axis` of the `input` tensor using the instruction `Inst` and write the result into `output`.
 ///
@@ -92,7 +103,7 @@ pub fn reduce<R: Runtime>(
     dtypes: ReduceDtypes,
 ) -> Result<(), ReduceError> {
     validate_axis(input.shape.len(), axis)?;
-    valid_output_shape(input.shape, output.shape, axis)?;
+    valid_output_shape(input.shape, output.shape, &[axis])?;
 
     launch_reduce::<R>(
         client,
@@ -105,22 +116,94 @@ pub fn reduce<R: Runtime>(
     )
 }
 
+/// Reduce all the given `axes` of the `input` tensor at once and write the result into `output`.
+///
+/// Adjacent axes that are contiguous in memory, such as the spatial axes of a `NCHW` tensor, are
+/// collapsed into a single axis and reduced by one kernel. Otherwise, the reduction is split into
+/// several launches through intermediate buffers of the accumulation type.
+///
+/// For `ArgMax`, `ArgMin`, `Var` and `Std`, the axes must be collapsible into one. The index
+/// returned by `ArgMax` and `ArgMin` is then the row-major index within the reduced axes.
+///
+/// Return an error if `axes` is empty, contains duplicates or an axis larger than the `input` rank,
+/// or if the shape of `output` is invalid.
+/// The shape of `output` must be the same as input except with a value of 1 for all the given `axes`.
+pub fn reduce_axes<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axes: &[usize],
+    strategy: ReduceStrategy,
+    operation: ReduceOperationConfig,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    let mut axes = axes.to_vec();
+    axes.sort_unstable();
+
+    if axes.is_empty() {
+        return Err(ReduceError::Validation {
+            details: "At least one axis must be reduced.",
+        });
+    }
+    if axes.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(ReduceError::Validation {
+            details: "The reduced axes must be distinct.",
+        });
+    }
+    for axis in axes.iter() {
+        validate_axis(input.shape.len(), *axis)?;
+    }
+    valid_output_shape(input.shape, output.shape, &axes)?;
+
+    launch_reduce_axes::<R>(client, input, output, &axes, strategy, dtypes, operation)
+}
+
+/// Reduce all the elements of the `input` tensor into the single element of `output`.
+///
+/// Unlike [`shared_sum`], this supports every [`ReduceOperationConfig`] and overwrites `output`.
+/// The shape of `output` must have the same rank as input with a value of 1 for every axis.
+///
+/// Large inputs that are contiguous, up to a permutation of their axes, are reduced by many cubes
+/// into partial results, which are then combined by a second kernel. This isn't done for
+/// `ArgMax`, `ArgMin`, `Var` and `Std`, whose partial results can't be combined by the same
+/// operation.
+pub fn reduce_full<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    strategy: ReduceStrategy,
+    operation: ReduceOperationConfig,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    if input.shape.is_empty() {
+        return Err(ReduceError::Validation {
+            details: "At least one axis must be reduced.",
+        });
+    }
+    let axes = (0..input.shape.len()).collect::<Vec<_>>();
+    valid_output_shape(input.shape, output.shape, &axes)?;
+
+    launch_reduce_full::<R>(client, input, output, strategy, dtypes, operation)
+}
+
 // Check that the given axis is less than the rank of the input.
 fn validate_axis(rank: usize, axis: usize) -> Result<(), ReduceError> {
-    if axis > rank {
+    if axis >= rank {
         return Err(ReduceError::InvalidAxis { axis, rank });
     }
     Ok(())
 }
 
-// Check that the output shape match the input shape with the given axis set to 1.
+// Check that the output shape match the input shape with the given axes set to 1.
 fn valid_output_shape(
     input_shape: &[usize],
     output_shape: &[usize],
-    axis: usize,
+    axes: &[usize],
 ) -> Result<(), ReduceError> {
     let mut expected_shape = input_shape.to_vec();
-    expected_shape[axis] = 1;
+    for axis in axes {
+        expected_shape[*axis] = 1;
+    }
     if output_shape != expected_shape {
         return Err(ReduceError::MismatchShape {
             expected_shape,
@@ -1,8 +1,12 @@
 pub mod cube;
 pub mod plane;
 pub mod reduce_dim;
+pub mod scan;
 pub mod shared_sum;
+pub mod softmax;
+pub mod top_k;
 pub mod unit;
+pub mod var_mean;
 
 mod base;
 mod blueprint;
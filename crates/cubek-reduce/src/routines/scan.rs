@@ -0,0 +1,695 @@
+use crate::{
+    LineMode, ReduceDtypes, ReduceError, ReducePrecision, ReduceStrategy,
+    components::{
+        args::{ReduceArgs, TensorArgs, init_tensors},
+        global::{idle_check_count, reduce_count},
+        instructions::{Max, Min, Prod, ScanFamily, ScanInstruction, Sum},
+    },
+    launch::{RoutineStrategy, generate_line_size},
+    routines::{
+        GlobalReduceBlueprint, ReduceBlueprint, ReduceLineSettings, ReduceProblem, Routine,
+        cube::CubeRoutine, plane::PlaneRoutine, unit::UnitRoutine,
+    },
+    validate_axis,
+};
+use cubecl::{
+    calculate_cube_count_elemwise,
+    prelude::*,
+    std::{
+        CubeOption, CubeOptionExpand,
+        tensor::{
+            TensorHandle, View, is_contiguous,
+            layout::{Coords1d, plain::PlainLayout},
+            r#virtual::VirtualTensor,
+        },
+    },
+};
+
+/// The largest number of items along the axis scanned by a single unit, plane or cube.
+pub const SCAN_BLOCK_SIZE: usize = 4096;
+
+/// Scan the given `axis` of `input` with the instruction `I` and write the result into `output`.
+///
+/// Each position of `output` holds the combination of the items of `input` up to that position,
+/// including its own item or, when `exclusive` is set, excluding it. An exclusive scan thus
+/// starts with the identity of `I`.
+///
+/// Each block of at most [`SCAN_BLOCK_SIZE`] items is scanned by a unit, a plane or a cube
+/// depending on the routine of the `strategy`, one chunk at a time. Longer axes take multiple
+/// passes: the totals of the blocks are scanned recursively, then combined with every item of
+/// their following blocks.
+///
+/// Both `input` and `output` must be contiguous and have the same shape.
+#[allow(clippy::too_many_arguments)]
+pub fn scan<R: Runtime, I: ScanFamily>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    exclusive: bool,
+    config: I::Config,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if input.shape != output.shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape: input.shape.to_vec(),
+            output_shape: output.shape.to_vec(),
+        });
+    }
+    if !is_contiguous(input.shape, input.strides) || !is_contiguous(output.shape, output.strides) {
+        return Err(ReduceError::Validation {
+            details: "Scan requires contiguous input and output tensors.",
+        });
+    }
+
+    let vector_size = input.shape[axis];
+    let block_size = vector_size.min(SCAN_BLOCK_SIZE);
+    let num_blocks = vector_size.div_ceil(block_size);
+    let problem = ReduceProblem {
+        vector_size: block_size as u32,
+        vector_count: (input.shape.iter().product::<usize>() / vector_size * num_blocks) as u32,
+        axis: axis as u32,
+        dtypes,
+    };
+    let line_mode = match input.strides[axis] {
+        1 => LineMode::Parallel,
+        _ => LineMode::Perpendicular,
+    };
+    let (line_size, _) = generate_line_size::<R>(
+        client,
+        &input,
+        &output,
+        axis,
+        dtypes.input,
+        line_mode,
+        &strategy.line_size,
+    );
+    // In parallel mode, each block has a single total.
+    let line_size_totals = match line_mode {
+        LineMode::Parallel => 1,
+        LineMode::Perpendicular => line_size,
+    };
+    let settings = ReduceLineSettings {
+        line_mode,
+        line_size_input: line_size,
+        line_size_output: 1,
+    };
+
+    let (blueprint, settings) = match strategy.routine.clone() {
+        RoutineStrategy::Unit(strategy) => {
+            UnitRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Plane(strategy) => {
+            PlaneRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Cube(strategy) => {
+            CubeRoutine.prepare(client, problem, settings, strategy)?
+        }
+    };
+
+    let mut totals_shape = input.shape.to_vec();
+    totals_shape[axis] = num_blocks;
+    let totals = TensorHandle::empty(client, totals_shape.clone(), dtypes.accumulation);
+
+    // With multiple blocks, the scanned blocks stay in the accumulation precision until their
+    // carry is applied, so that the output is only rounded once.
+    let partial = match num_blocks {
+        1 => None,
+        _ => Some(TensorHandle::empty(
+            client,
+            input.shape.to_vec(),
+            dtypes.accumulation,
+        )),
+    };
+    let (scanned, scanned_dtype) = match &partial {
+        Some(partial) => (partial.as_ref(), dtypes.accumulation),
+        None => (output, dtypes.output),
+    };
+
+    unsafe {
+        scan_kernel::launch_unchecked::<TensorArgs, I, R>(
+            client,
+            settings.cube_count,
+            settings.cube_dim,
+            input.as_tensor_arg(line_size),
+            scanned.as_tensor_arg(line_size),
+            totals.as_ref().as_tensor_arg(line_size_totals),
+            ScalarArg::new(axis as u32),
+            ScalarArg::new(block_size as u32),
+            blueprint,
+            exclusive,
+            config.clone(),
+            dtypes.input,
+            scanned_dtype,
+            dtypes.accumulation,
+        )
+        .map_err(ReduceError::Launch)?;
+    }
+
+    let Some(partial) = partial else {
+        return Ok(());
+    };
+
+    // The exclusive scan of the totals gives what precedes each block.
+    let carries = TensorHandle::empty(client, totals_shape, dtypes.accumulation);
+    scan::<R, I>(
+        client,
+        totals.as_ref(),
+        carries.as_ref(),
+        axis,
+        true,
+        config.clone(),
+        strategy,
+        ReduceDtypes {
+            input: dtypes.accumulation,
+            output: dtypes.accumulation,
+            accumulation: dtypes.accumulation,
+        },
+    )?;
+
+    let num_lines = output.shape.iter().product::<usize>() / line_size as usize;
+    let cube_dim = CubeDim::new(client, num_lines);
+    let cube_count = calculate_cube_count_elemwise(client, num_lines, cube_dim);
+
+    unsafe {
+        propagate_kernel::launch_unchecked::<I, R>(
+            client,
+            cube_count,
+            cube_dim,
+            partial.as_ref().as_tensor_arg(line_size),
+            output.as_tensor_arg(line_size),
+            carries.as_ref().as_tensor_arg(line_size_totals),
+            ScalarArg::new(axis as u32),
+            ScalarArg::new(block_size as u32),
+            line_mode,
+            config,
+            dtypes.output,
+            dtypes.accumulation,
+        )
+        .map_err(ReduceError::Launch)
+    }
+}
+
+/// The cumulative sum of the given `axis`, see [`scan`].
+pub fn cumsum<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    exclusive: bool,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    scan::<R, Sum>(client, input, output, axis, exclusive, (), strategy, dtypes)
+}
+
+/// The cumulative product of the given `axis`, see [`scan`].
+pub fn cumprod<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    exclusive: bool,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    scan::<R, Prod>(client, input, output, axis, exclusive, (), strategy, dtypes)
+}
+
+/// The cumulative maximum of the given `axis`, see [`scan`].
+pub fn cummax<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    exclusive: bool,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    scan::<R, Max>(client, input, output, axis, exclusive, (), strategy, dtypes)
+}
+
+/// The cumulative minimum of the given `axis`, see [`scan`].
+pub fn cummin<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    exclusive: bool,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    scan::<R, Min>(client, input, output, axis, exclusive, (), strategy, dtypes)
+}
+
+#[cube(launch_unchecked)]
+#[allow(clippy::too_many_arguments)]
+fn scan_kernel<In: Numeric, Out: Numeric, Acc: Numeric, RA: ReduceArgs, I: ScanFamily>(
+    input: &RA::Input<In>,
+    output: &mut RA::Output<Out>,
+    totals: &mut Tensor<Line<Acc>>,
+    axis_scan: u32,
+    block_size: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] exclusive: bool,
+    #[comptime] config: I::Config,
+    #[define(In)] _input_dtype: StorageType,
+    #[define(Out)] _output_dtype: StorageType,
+    #[define(Acc)] _acc_dtype: StorageType,
+) {
+    let (input, mut output) = init_tensors::<RA, In, Out>(input, output);
+    let inst = &I::Instruction::<(In, Acc)>::from_config(config);
+    scan_inner::<(In, Acc), Out, I::Instruction<(In, Acc)>>(
+        &input,
+        &mut output,
+        totals,
+        axis_scan,
+        block_size,
+        inst,
+        blueprint,
+        exclusive,
+    );
+}
+
+#[cube]
+#[allow(clippy::too_many_arguments)]
+fn scan_inner<P: ReducePrecision, Out: Numeric, I: ScanInstruction<P>>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    totals: &mut Tensor<Line<P::EA>>,
+    axis_scan: u32,
+    block_size: u32,
+    inst: &I,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] exclusive: bool,
+) {
+    let line_size = input.line_size();
+    let line_mode = comptime!(blueprint.line_mode);
+    let vector_size = input.shape(axis_scan);
+    let num_blocks = vector_size.div_ceil(block_size);
+    let work_count =
+        reduce_count(input.len() * line_size / vector_size, line_mode, line_size) * num_blocks;
+
+    let input_view = input.view(PlainLayout::new(input.len()));
+    let mut output_view = output.view_mut(PlainLayout::new(output.len()));
+    let mut carry = I::identity(inst, line_size);
+
+    match comptime!(blueprint.global) {
+        GlobalReduceBlueprint::Unit(unit) => {
+            let work_index = ABSOLUTE_POS;
+            let idle = idle_check_count(work_count, work_index, unit.unit_idle);
+            let block = ScanBlock::new(input, axis_scan, work_index, block_size, idle, line_mode);
+
+            for pos in 0..block.length {
+                let item = read_item::<P, I>(inst, &input_view, &block, pos, line_size);
+                let (scanned, total) = scan_line::<P, I>(inst, item, exclusive, line_mode);
+
+                output_view.write(
+                    block.offset + pos * block.stride,
+                    Line::cast_from(I::combine(inst, carry, scanned)),
+                );
+                carry = I::combine(inst, carry, total);
+            }
+
+            write_total::<P::EA>(totals, &block, carry, idle, line_mode);
+        }
+        GlobalReduceBlueprint::Plane(plane) => {
+            let work_index = CUBE_POS * CUBE_DIM_Y + UNIT_POS_Y;
+            let idle = idle_check_count(work_count, work_index, plane.plane_idle);
+            let block = ScanBlock::new(input, axis_scan, work_index, block_size, idle, line_mode);
+
+            for chunk in 0..block.length.div_ceil(CUBE_DIM_X) {
+                let pos = chunk * CUBE_DIM_X + UNIT_POS_X;
+                let item = read_item::<P, I>(inst, &input_view, &block, pos, line_size);
+                let (scanned, total) = scan_line::<P, I>(inst, item, exclusive, line_mode);
+                let (prefix, chunk_total) = plane_scan::<P, I>(inst, total);
+
+                if pos < block.length {
+                    let value = I::combine(inst, prefix, scanned);
+                    output_view.write(
+                        block.offset + pos * block.stride,
+                        Line::cast_from(I::combine(inst, carry, value)),
+                    );
+                }
+                carry = I::combine(inst, carry, chunk_total);
+            }
+
+            if UNIT_POS_X == 0 {
+                write_total::<P::EA>(totals, &block, carry, idle, line_mode);
+            }
+        }
+        GlobalReduceBlueprint::Cube(cube) => {
+            let work_index = CUBE_POS;
+            let idle = idle_check_count(work_count, work_index, cube.cube_idle);
+            let block = ScanBlock::new(input, axis_scan, work_index, block_size, idle, line_mode);
+            let mut shared =
+                SharedMemory::<Line<P::EA>>::new_lined(cube.num_shared_accumulators, line_size);
+
+            for chunk in 0..block.length.div_ceil(CUBE_DIM) {
+                let pos = chunk * CUBE_DIM + UNIT_POS;
+                let item = read_item::<P, I>(inst, &input_view, &block, pos, line_size);
+                let (scanned, total) = scan_line::<P, I>(inst, item, exclusive, line_mode);
+                let (prefix, chunk_total) = if comptime!(cube.use_planes) {
+                    cube_scan_planes::<P, I>(inst, &mut shared, total)
+                } else {
+                    cube_scan_shared::<P, I>(inst, &mut shared, total)
+                };
+
+                if pos < block.length {
+                    let value = I::combine(inst, prefix, scanned);
+                    output_view.write(
+                        block.offset + pos * block.stride,
+                        Line::cast_from(I::combine(inst, carry, value)),
+                    );
+                }
+                carry = I::combine(inst, carry, chunk_total);
+            }
+
+            if UNIT_POS == 0 {
+                write_total::<P::EA>(totals, &block, carry, idle, line_mode);
+            }
+        }
+    }
+}
+
+/// The lines of the block scanned at `work_index`, which covers `line_size` adjacent vectors in
+/// perpendicular mode.
+///
+/// Blocks are indexed by vector first, in row-major order over the axes other than `axis_scan`,
+/// then by position along the axis.
+#[derive(CubeType)]
+struct ScanBlock {
+    /// The offset of the first line of the block.
+    offset: u32,
+    /// The distance between consecutive lines of the block.
+    stride: u32,
+    /// The number of lines in the block, which is zero when idle.
+    length: u32,
+    /// The offset of the total of the block, in elements.
+    total_offset: u32,
+}
+
+#[cube]
+impl ScanBlock {
+    fn new<N: Numeric>(
+        input: &VirtualTensor<N>,
+        axis_scan: u32,
+        work_index: u32,
+        block_size: u32,
+        idle: CubeOption<bool>,
+        #[comptime] line_mode: LineMode,
+    ) -> ScanBlock {
+        let line_size = input.line_size();
+        let vector_size = input.shape(axis_scan);
+        let num_blocks = vector_size.div_ceil(block_size);
+        let block_index = work_index % num_blocks;
+        let vector_index = match comptime!(line_mode) {
+            LineMode::Parallel => work_index / num_blocks,
+            LineMode::Perpendicular => (work_index / num_blocks) * line_size,
+        };
+
+        // All tensors are contiguous, so the input and the totals only differ by the size of
+        // the scanned axis.
+        let inner = input.stride(axis_scan);
+        let outer = vector_index / inner;
+        let within = vector_index % inner;
+        let start = block_index * block_size;
+        let remaining = vector_size - start;
+        let block_length = select(remaining < block_size, remaining, block_size);
+        let block_length = active_count(block_length, idle);
+
+        let offset = (outer * vector_size + start) * inner + within;
+        let total_offset = (outer * num_blocks + block_index) * inner + within;
+
+        match comptime!(line_mode) {
+            LineMode::Parallel => ScanBlock {
+                offset: offset / line_size,
+                stride: 1,
+                length: block_length / line_size,
+                total_offset,
+            },
+            LineMode::Perpendicular => ScanBlock {
+                offset: offset / line_size,
+                stride: inner / line_size,
+                length: block_length,
+                total_offset,
+            },
+        }
+    }
+}
+
+/// Read the line at `pos` in the block, or the identity when out of bounds.
+#[cube]
+fn read_item<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    view: &View<Line<P::EI>, Coords1d>,
+    block: &ScanBlock,
+    pos: u32,
+    #[comptime] line_size: u32,
+) -> Line<P::EA> {
+    let in_bounds = pos < block.length;
+    let offset = (block.offset + pos * block.stride) * u32::cast_from(in_bounds);
+    select(
+        in_bounds,
+        Line::cast_from(view[offset]),
+        I::identity(inst, line_size),
+    )
+}
+
+/// Scan the lanes of `item`, which are consecutive items of the vector in parallel mode and
+/// items of different vectors in perpendicular mode.
+///
+/// Return the scanned line and the combination of all the items of the line, broadcast to every
+/// lane in parallel mode.
+#[cube]
+fn scan_line<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    item: Line<P::EA>,
+    #[comptime] exclusive: bool,
+    #[comptime] line_mode: LineMode,
+) -> (Line<P::EA>, Line<P::EA>) {
+    let line_size = item.size();
+
+    match comptime!(line_mode) {
+        LineMode::Parallel => {
+            let mut scanned = I::identity(inst, line_size);
+            let mut running = I::identity(inst, 1);
+
+            #[unroll]
+            for k in 0..line_size {
+                let next = I::combine(inst, running, Line::new(item[k]));
+                scanned[k] = if comptime!(exclusive) {
+                    running[0]
+                } else {
+                    next[0]
+                };
+                running = next;
+            }
+
+            (scanned, Line::empty(line_size).fill(running[0]))
+        }
+        LineMode::Perpendicular => {
+            let scanned = if comptime!(exclusive) {
+                I::identity(inst, line_size)
+            } else {
+                item
+            };
+            (scanned, item)
+        }
+    }
+}
+
+/// Inclusive scan of `value` over the units of the plane, using the Hillis-Steele algorithm.
+#[cube]
+fn plane_inclusive_scan<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    value: Line<P::EA>,
+) -> Line<P::EA> {
+    let mut value = value;
+    let mut offset = 1;
+    while offset < CUBE_DIM_X {
+        let has_other = UNIT_POS_X >= offset;
+        let other = plane_broadcast(value, UNIT_POS_X - offset * u32::cast_from(has_other));
+        value = select(has_other, I::combine(inst, other, value), value);
+        offset *= 2;
+    }
+    value
+}
+
+/// Exclusive scan of `total` over the units of the plane, returned with the combination of all
+/// the totals of the plane.
+#[cube]
+fn plane_scan<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    total: Line<P::EA>,
+) -> (Line<P::EA>, Line<P::EA>) {
+    let inclusive = plane_inclusive_scan::<P, I>(inst, total);
+    let has_previous = UNIT_POS_X > 0;
+    let previous = plane_broadcast(inclusive, UNIT_POS_X - u32::cast_from(has_previous));
+    let exclusive = select(has_previous, previous, I::identity(inst, total.size()));
+
+    (exclusive, plane_broadcast(inclusive, CUBE_DIM_X - 1))
+}
+
+/// Exclusive scan of `total` over the units of the cube, returned with the combination of all
+/// the totals of the cube.
+///
+/// Each plane is scanned with plane instructions, and the totals of the planes are shared
+/// through `shared`, which holds one line per plane.
+#[cube]
+fn cube_scan_planes<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    shared: &mut SharedMemory<Line<P::EA>>,
+    total: Line<P::EA>,
+) -> (Line<P::EA>, Line<P::EA>) {
+    let line_size = total.size();
+    let (exclusive, plane_total) = plane_scan::<P, I>(inst, total);
+
+    if UNIT_POS_X == 0 {
+        shared[UNIT_POS_Y] = plane_total;
+    }
+    sync_cube();
+
+    let mut plane_prefix = I::identity(inst, line_size);
+    let mut cube_total = I::identity(inst, line_size);
+    for plane in 0..CUBE_DIM_Y {
+        let plane_total = shared[plane];
+        plane_prefix = select(
+            plane < UNIT_POS_Y,
+            I::combine(inst, plane_prefix, plane_total),
+            plane_prefix,
+        );
+        cube_total = I::combine(inst, cube_total, plane_total);
+    }
+    sync_cube();
+
+    (I::combine(inst, plane_prefix, exclusive), cube_total)
+}
+
+/// Exclusive scan of `total` over the units of the cube, returned with the combination of all
+/// the totals of the cube.
+///
+/// This uses the Hillis-Steele algorithm in `shared`, which holds one line per unit.
+#[cube]
+fn cube_scan_shared<P: ReducePrecision, I: ScanInstruction<P>>(
+    inst: &I,
+    shared: &mut SharedMemory<Line<P::EA>>,
+    total: Line<P::EA>,
+) -> (Line<P::EA>, Line<P::EA>) {
+    shared[UNIT_POS] = total;
+    sync_cube();
+
+    let mut offset = 1;
+    while offset < CUBE_DIM {
+        let has_other = UNIT_POS >= offset;
+        let other = shared[UNIT_POS - offset * u32::cast_from(has_other)];
+        let current = shared[UNIT_POS];
+        let value = select(has_other, I::combine(inst, other, current), current);
+        sync_cube();
+        shared[UNIT_POS] = value;
+        sync_cube();
+        offset *= 2;
+    }
+
+    let has_previous = UNIT_POS > 0;
+    let exclusive = select(
+        has_previous,
+        shared[UNIT_POS - u32::cast_from(has_previous)],
+        I::identity(inst, total.size()),
+    );
+    let cube_total = shared[CUBE_DIM - 1];
+    sync_cube();
+
+    (exclusive, cube_total)
+}
+
+/// The number of items to scan, which is zero when idle.
+#[cube]
+fn active_count(length: u32, idle: CubeOption<bool>) -> u32 {
+    match idle {
+        CubeOption::Some(idle) => length * u32::cast_from(!idle),
+        CubeOption::None => length,
+    }
+}
+
+/// Write the combination of all the items of the block, which is only used when the axis is
+/// split into multiple blocks.
+#[cube]
+fn write_total<N: Numeric>(
+    totals: &mut Tensor<Line<N>>,
+    block: &ScanBlock,
+    total: Line<N>,
+    idle: CubeOption<bool>,
+    #[comptime] line_mode: LineMode,
+) {
+    let is_idle = match idle {
+        CubeOption::Some(idle) => idle,
+        CubeOption::None => false,
+    };
+
+    if !is_idle {
+        match comptime!(line_mode) {
+            LineMode::Parallel => {
+                totals[block.total_offset] = Line::new(total[0]);
+            }
+            LineMode::Perpendicular => {
+                totals[block.total_offset / totals.line_size()] = total;
+            }
+        }
+    }
+}
+
+#[cube(launch_unchecked)]
+#[allow(clippy::too_many_arguments)]
+fn propagate_kernel<Out: Numeric, Acc: Numeric, I: ScanFamily>(
+    partial: &Tensor<Line<Acc>>,
+    output: &mut Tensor<Line<Out>>,
+    carries: &Tensor<Line<Acc>>,
+    axis_scan: u32,
+    block_size: u32,
+    #[comptime] line_mode: LineMode,
+    #[comptime] config: I::Config,
+    #[define(Out)] _output_dtype: StorageType,
+    #[define(Acc)] _acc_dtype: StorageType,
+) {
+    let inst = &I::Instruction::<(Out, Acc)>::from_config(config);
+    propagate_inner::<(Out, Acc), I::Instruction<(Out, Acc)>>(
+        partial, output, carries, axis_scan, block_size, inst, line_mode,
+    );
+}
+
+/// Combine every line of `partial` with the carry of its block, which is the combination of all
+/// the items of the previous blocks, and write it into `output`.
+#[cube]
+fn propagate_inner<P: ReducePrecision, I: ScanInstruction<P>>(
+    partial: &Tensor<Line<P::EA>>,
+    output: &mut Tensor<Line<P::EI>>,
+    carries: &Tensor<Line<P::EA>>,
+    axis_scan: u32,
+    block_size: u32,
+    inst: &I,
+    #[comptime] line_mode: LineMode,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let line_size = output.line_size();
+    let element = ABSOLUTE_POS * line_size;
+    let inner = output.stride(axis_scan);
+    let vector_size = output.shape(axis_scan);
+    let num_blocks = vector_size.div_ceil(block_size);
+
+    let outer = element / (inner * vector_size);
+    let position = (element / inner) % vector_size;
+    let within = element % inner;
+    let carry_offset = (outer * num_blocks + position / block_size) * inner + within;
+
+    let carry = match comptime!(line_mode) {
+        LineMode::Parallel => Line::empty(line_size).fill(carries[carry_offset][0]),
+        LineMode::Perpendicular => carries[carry_offset / line_size],
+    };
+    output[ABSOLUTE_POS] = Line::cast_from(I::combine(inst, carry, partial[ABSOLUTE_POS]));
+}
@@ -0,0 +1,383 @@
+use crate::{
+    LineMode, ReduceDtypes, ReduceError, ReducePrecision, ReduceStrategy,
+    components::{
+        args::{ReduceArgs, TensorArgs, init_tensors},
+        global::{
+            cube::{GlobalFullCubeReduce, reduce_scan, reduce_tree},
+            idle_check_count,
+            plane::GlobalFullPlaneReduce,
+            reduce_count,
+        },
+        instructions::{LogSumExp, ReduceInstruction},
+        readers::{Reader, cube::CubeReader, plane::PlaneReader},
+    },
+    launch::{RoutineStrategy, generate_line_size},
+    routines::{
+        GlobalReduceBlueprint, ReduceBlueprint, ReduceLineSettings, ReduceProblem, Routine,
+        cube::CubeRoutine, plane::PlaneRoutine,
+    },
+    validate_axis,
+};
+use cubecl::{
+    ir::ElemType,
+    prelude::*,
+    std::{
+        CubeOption, CubeOptionExpand,
+        tensor::{is_contiguous, layout::plain::PlainLayout, r#virtual::VirtualTensor},
+    },
+};
+
+/// Compute the softmax of the given `axis` of `input` and write it into `output`.
+///
+/// A single kernel reads each vector twice: once to compute its [`LogSumExp`] with the plane or
+/// cube routine, and once to write the normalized values. The `strategy` must use the
+/// [`PlaneRoutine`] or the [`CubeRoutine`].
+///
+/// Both `input` and `output` must be contiguous and have the same shape.
+pub fn softmax<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    launch_softmax(client, input, output, axis, strategy, dtypes, false)
+}
+
+/// Compute the logarithm of the softmax of the given `axis` of `input` and write it into `output`.
+///
+/// This is `x - log(sum(exp(x)))`, which stays finite where the softmax underflows to zero.
+/// See [`softmax`] for the requirements on the arguments.
+pub fn log_softmax<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    launch_softmax(client, input, output, axis, strategy, dtypes, true)
+}
+
+fn launch_softmax<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+    log: bool,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if input.shape != output.shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape: input.shape.to_vec(),
+            output_shape: output.shape.to_vec(),
+        });
+    }
+    if !is_contiguous(input.shape, input.strides) || !is_contiguous(output.shape, output.strides) {
+        return Err(ReduceError::Validation {
+            details: "Softmax requires contiguous input and output tensors.",
+        });
+    }
+    if !matches!(dtypes.accumulation.elem_type(), ElemType::Float(_)) {
+        return Err(ReduceError::Validation {
+            details: "Softmax requires a float accumulation type.",
+        });
+    }
+
+    let vector_size = input.shape[axis];
+    let problem = ReduceProblem {
+        vector_size: vector_size as u32,
+        vector_count: (input.shape.iter().product::<usize>() / vector_size) as u32,
+        axis: axis as u32,
+        dtypes,
+    };
+    let line_mode = match input.strides[axis] {
+        1 => LineMode::Parallel,
+        _ => LineMode::Perpendicular,
+    };
+    let (line_size, _) = generate_line_size::<R>(
+        client,
+        &input,
+        &input,
+        axis,
+        dtypes.input,
+        line_mode,
+        &strategy.line_size,
+    );
+    // Each plane or cube writes the full vectors it reduced, never a line of reduced values.
+    let settings = ReduceLineSettings {
+        line_mode,
+        line_size_input: line_size,
+        line_size_output: 1,
+    };
+
+    let (blueprint, settings) = match strategy.routine {
+        RoutineStrategy::Unit(_) => {
+            return Err(ReduceError::Validation {
+                details: "Softmax requires the plane or cube routine.",
+            });
+        }
+        RoutineStrategy::Plane(strategy) => {
+            PlaneRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Cube(strategy) => {
+            CubeRoutine.prepare(client, problem, settings, strategy)?
+        }
+    };
+
+    unsafe {
+        softmax_kernel::launch_unchecked::<TensorArgs, R>(
+            client,
+            settings.cube_count,
+            settings.cube_dim,
+            input.as_tensor_arg(line_size),
+            output.as_tensor_arg(line_size),
+            ScalarArg::new(axis as u32),
+            blueprint,
+            log,
+            dtypes.input,
+            dtypes.output,
+            dtypes.accumulation,
+        )
+        .map_err(ReduceError::Launch)
+    }
+}
+
+#[cube(launch_unchecked)]
+fn softmax_kernel<In: Numeric, Out: Numeric, Acc: Numeric, RA: ReduceArgs>(
+    input: &RA::Input<In>,
+    output: &mut RA::Output<Out>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] log: bool,
+    #[define(In)] _input_dtype: StorageType,
+    #[define(Out)] _output_dtype: StorageType,
+    #[define(Acc)] _acc_dtype: StorageType,
+) {
+    let (input, mut output) = init_tensors::<RA, In, Out>(input, output);
+    softmax_inner::<(In, Acc), Out>(&input, &mut output, axis_reduce, blueprint, log);
+}
+
+#[cube]
+fn softmax_inner<P: ReducePrecision, Out: Numeric>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] log: bool,
+) {
+    let inst = &LogSumExp {};
+    let line_size = input.line_size();
+    let line_mode = comptime!(blueprint.line_mode);
+    let vector_size = input.shape(axis_reduce);
+    let reduce_count = reduce_count(input.len() * line_size / vector_size, line_mode, line_size);
+
+    match comptime!(blueprint.global) {
+        GlobalReduceBlueprint::Plane(plane) => {
+            let reduce_index = CUBE_POS * CUBE_DIM_Y + UNIT_POS_Y;
+            let idle = idle_check_count(reduce_count, reduce_index, plane.plane_idle);
+            let batch_offset = batch_offset(input, axis_reduce, reduce_index, line_mode);
+
+            let reader = Reader::<P>::new_at::<LogSumExp>(
+                input,
+                inst,
+                axis_reduce,
+                batch_offset,
+                idle,
+                plane.bound_checks,
+                line_mode,
+            );
+            let reader = PlaneReader::<P>::new(reader);
+            let accumulator = GlobalFullPlaneReduce::reduce_reader::<P, LogSumExp>(
+                &reader, inst, line_size, plane,
+            );
+
+            let log_sum_exp =
+                log_sum_exp::<P>(inst, accumulator, vector_size, line_size, line_mode);
+            normalize::<P, Out>(
+                input,
+                output,
+                axis_reduce,
+                batch_offset,
+                log_sum_exp,
+                idle,
+                UNIT_POS_X,
+                CUBE_DIM_X,
+                line_mode,
+                log,
+            );
+        }
+        GlobalReduceBlueprint::Cube(cube) => {
+            let reduce_index = CUBE_POS;
+            let idle = idle_check_count(reduce_count, reduce_index, cube.cube_idle);
+            let batch_offset = batch_offset(input, axis_reduce, reduce_index, line_mode);
+
+            let reader = Reader::<P>::new_at::<LogSumExp>(
+                input,
+                inst,
+                axis_reduce,
+                batch_offset,
+                idle,
+                cube.bound_checks,
+                line_mode,
+            );
+            let reader = CubeReader::<P>::new(reader);
+            let mut accumulator_shared =
+                GlobalFullCubeReduce::reduce_reader::<P, LogSumExp>(&reader, inst, line_size, cube);
+
+            // Every unit needs the statistics of the vector, not only the first worker.
+            let worker_pos = GlobalFullCubeReduce::worker_pos(cube);
+            let mut accumulator =
+                <LogSumExp as ReduceInstruction<P>>::null_accumulator(inst, line_size);
+            if comptime!(cube.use_planes) {
+                if worker_pos == 0 {
+                    reduce_scan::<P, LogSumExp>(
+                        inst,
+                        &mut accumulator_shared,
+                        &mut accumulator,
+                        cube.num_shared_accumulators,
+                    );
+                }
+                sync_cube();
+                <LogSumExp as ReduceInstruction<P>>::assign_accumulator(
+                    inst,
+                    &mut accumulator,
+                    &(accumulator_shared.maxes[0], accumulator_shared.sums[0]),
+                );
+            } else {
+                reduce_tree::<P, LogSumExp>(
+                    inst,
+                    &mut accumulator_shared,
+                    &mut accumulator,
+                    worker_pos,
+                    cube.num_shared_accumulators,
+                );
+            }
+
+            let log_sum_exp =
+                log_sum_exp::<P>(inst, accumulator, vector_size, line_size, line_mode);
+            normalize::<P, Out>(
+                input,
+                output,
+                axis_reduce,
+                batch_offset,
+                log_sum_exp,
+                idle,
+                UNIT_POS,
+                CUBE_DIM,
+                line_mode,
+                log,
+            );
+        }
+        GlobalReduceBlueprint::Unit(_) => {
+            comptime! {panic!("Softmax requires the plane or cube routine")};
+        }
+    }
+}
+
+/// The offset of the first element of the vector reduced at `reduce_index`, which is the first
+/// of `line_size` adjacent vectors in perpendicular mode.
+///
+/// Vectors are indexed in row-major order over the axes other than `reduce_axis`.
+#[cube]
+fn batch_offset<N: Numeric>(
+    input: &VirtualTensor<N>,
+    reduce_axis: u32,
+    reduce_index: u32,
+    #[comptime] line_mode: LineMode,
+) -> u32 {
+    let vector_index = match comptime!(line_mode) {
+        LineMode::Parallel => reduce_index,
+        LineMode::Perpendicular => reduce_index * input.line_size(),
+    };
+
+    let rank = input.rank();
+    let mut remainder = vector_index;
+    let mut offset = 0;
+    for i in 0..rank {
+        let axis = rank - 1 - i;
+        if axis != reduce_axis {
+            let shape = input.shape(axis);
+            offset += (remainder % shape) * input.stride(axis);
+            remainder /= shape;
+        }
+    }
+
+    offset
+}
+
+/// Broadcast the log-sum-exp of the vector, or of each vector in perpendicular mode, to a line.
+#[cube]
+fn log_sum_exp<P: ReducePrecision>(
+    inst: &LogSumExp,
+    accumulator: (Line<P::EA>, Line<P::EA>),
+    vector_size: u32,
+    #[comptime] line_size: u32,
+    #[comptime] line_mode: LineMode,
+) -> Line<P::EA> {
+    match comptime!(line_mode) {
+        LineMode::Parallel => {
+            let value = <LogSumExp as ReduceInstruction<P>>::merge_line::<P::EA>(
+                inst,
+                accumulator,
+                vector_size,
+            );
+            Line::empty(line_size).fill(value)
+        }
+        LineMode::Perpendicular => <LogSumExp as ReduceInstruction<P>>::to_output_perpendicular::<
+            P::EA,
+        >(inst, accumulator, vector_size),
+    }
+}
+
+/// Read the vector again and write `exp(x - log_sum_exp)`, or `x - log_sum_exp` when `log` is
+/// set, at the same offsets in `output`.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+fn normalize<P: ReducePrecision, Out: Numeric>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    reduce_axis: u32,
+    batch_offset: u32,
+    log_sum_exp: Line<P::EA>,
+    idle: CubeOption<bool>,
+    unit_pos: u32,
+    num_units: u32,
+    #[comptime] line_mode: LineMode,
+    #[comptime] log: bool,
+) {
+    let line_size = input.line_size();
+    let length = match comptime!(line_mode) {
+        LineMode::Parallel => input.shape(reduce_axis) / line_size,
+        LineMode::Perpendicular => input.shape(reduce_axis),
+    };
+    let stride = match comptime!(line_mode) {
+        LineMode::Parallel => 1,
+        LineMode::Perpendicular => input.stride(reduce_axis) / line_size,
+    };
+    let length = match idle {
+        CubeOption::Some(idle) => length * u32::cast_from(!idle),
+        CubeOption::None => length,
+    };
+
+    let input_view = input.view(PlainLayout::new(input.len()));
+    let mut output_view = output.view_mut(PlainLayout::new(output.len()));
+    let batch_offset = batch_offset / line_size;
+
+    for i in 0..length.div_ceil(num_units) {
+        let pos = i * num_units + unit_pos;
+        if pos < length {
+            let offset = batch_offset + pos * stride;
+            let shifted = Line::<P::EA>::cast_from(input_view[offset]) - log_sum_exp;
+            let value = if comptime!(log) {
+                shifted
+            } else {
+                LogSumExp::exp::<P::EA>(shifted)
+            };
+            output_view.write(offset, Line::cast_from(value));
+        }
+    }
+}
@@ -0,0 +1,737 @@
+use crate::{
+    BoundChecks, LineMode, ReduceDtypes, ReduceError, ReducePrecision, ReduceStrategy,
+    components::{
+        args::{ReduceArgs, TensorArgs, init_tensors},
+        global::{idle_check_count, reduce_count},
+        instructions::{ArgMax, ReduceCoordinate, ReduceCoordinateExpand},
+        readers::{Reader, plane::PlaneReader, unit::UnitReader},
+    },
+    launch::{RoutineStrategy, generate_line_size},
+    routines::{
+        GlobalReduceBlueprint, ReduceBlueprint, ReduceLineSettings, ReduceProblem, Routine,
+        plane::PlaneRoutine, unit::UnitRoutine,
+    },
+    validate_axis,
+};
+use cubecl::{
+    calculate_cube_count_elemwise,
+    prelude::*,
+    std::{
+        CubeOption, CubeOptionExpand,
+        tensor::{is_contiguous, layout::plain::PlainLayout, r#virtual::VirtualTensor},
+    },
+};
+
+/// The largest `k` supported by [`top_k`].
+pub const MAX_TOP_K: u32 = 64;
+
+/// Which items [`top_k`] keeps and in which order it writes them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TopKConfig {
+    /// The number of items kept for each vector, from 1 to [`MAX_TOP_K`].
+    pub k: u32,
+    /// Keep the largest items when set, otherwise the smallest.
+    pub largest: bool,
+    /// Write the items from the best to the worst when set, otherwise in the order of their
+    /// indices along the axis.
+    pub sorted: bool,
+}
+
+/// Select the `k` largest or smallest items of the given `axis` of `input`, writing them into
+/// `values` and their indices along `axis` into `indices`.
+///
+/// Each unit keeps a sorted list of its `k` best items, reading the vector with the same readers
+/// as [`reduce`](crate::reduce). With the [`PlaneRoutine`], the lists of a plane are then merged
+/// using plane instructions. The `strategy` must use the [`UnitRoutine`] or the [`PlaneRoutine`].
+///
+/// Equal items are ranked by increasing index, like [`ArgMax`].
+///
+/// The `input` and both outputs must be contiguous. The outputs have the shape of `input` except
+/// for a size of `k` along `axis`, and `indices` must hold `u32`.
+#[allow(clippy::too_many_arguments)]
+pub fn top_k<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    values: TensorHandleRef<R>,
+    indices: TensorHandleRef<R>,
+    axis: usize,
+    config: TopKConfig,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    let vector_size = input.shape[axis];
+    if config.k == 0 || config.k > MAX_TOP_K {
+        return Err(ReduceError::Validation {
+            details: "Top-k requires k to be between 1 and 64.",
+        });
+    }
+    if config.k as usize > vector_size {
+        return Err(ReduceError::Validation {
+            details: "Top-k requires k to be at most the size of the axis.",
+        });
+    }
+
+    validate_outputs(&input, &values, &indices, axis, config.k as usize)?;
+
+    let problem = ReduceProblem {
+        vector_size: vector_size as u32,
+        vector_count: (input.shape.iter().product::<usize>() / vector_size) as u32,
+        axis: axis as u32,
+        dtypes,
+    };
+    let line_mode = match input.strides[axis] {
+        1 => LineMode::Parallel,
+        _ => LineMode::Perpendicular,
+    };
+    let (line_size, _) = generate_line_size::<R>(
+        client,
+        &input,
+        &values,
+        axis,
+        dtypes.input,
+        line_mode,
+        &strategy.line_size,
+    );
+    // In parallel mode, the lanes of a vector are merged before being written one item at a time.
+    let line_size_output = match line_mode {
+        LineMode::Parallel => 1,
+        LineMode::Perpendicular => line_size,
+    };
+    let settings = ReduceLineSettings {
+        line_mode,
+        line_size_input: line_size,
+        line_size_output: 1,
+    };
+
+    let (blueprint, settings) = match strategy.routine {
+        RoutineStrategy::Unit(strategy) => {
+            UnitRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Plane(strategy) => {
+            PlaneRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Cube(_) => {
+            return Err(ReduceError::Validation {
+                details: "Top-k requires the unit or plane routine.",
+            });
+        }
+    };
+
+    unsafe {
+        top_k_kernel::launch_unchecked::<TensorArgs, R>(
+            client,
+            settings.cube_count,
+            settings.cube_dim,
+            input.as_tensor_arg(line_size),
+            values.as_tensor_arg(line_size_output),
+            indices.as_tensor_arg(line_size_output),
+            ScalarArg::new(axis as u32),
+            blueprint,
+            config,
+            dtypes.input,
+            dtypes.output,
+            dtypes.accumulation,
+        )
+        .map_err(ReduceError::Launch)
+    }
+}
+
+/// Sort the given `axis` of `input`, writing the sorted items into `values` and their indices
+/// along `axis` into `indices`.
+///
+/// Axes of at most [`MAX_TOP_K`] items are sorted by a [`top_k`] keeping every item, using the
+/// `strategy`. Longer axes are sorted in place in `values` and `indices` by a bitonic sorting
+/// network, with one launch per pass, and items are compared in the output precision. The
+/// `strategy` is unused in that case.
+///
+/// Equal items are ranked by increasing index. See [`top_k`] for the requirements on the
+/// arguments.
+#[allow(clippy::too_many_arguments)]
+pub fn argsort<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    values: TensorHandleRef<R>,
+    indices: TensorHandleRef<R>,
+    axis: usize,
+    descending: bool,
+    strategy: ReduceStrategy,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    let vector_size = input.shape[axis];
+    if vector_size <= MAX_TOP_K as usize {
+        let config = TopKConfig {
+            k: vector_size as u32,
+            largest: descending,
+            sorted: true,
+        };
+        return top_k(
+            client, input, values, indices, axis, config, strategy, dtypes,
+        );
+    }
+    validate_outputs(&input, &values, &indices, axis, vector_size)?;
+
+    let num_elems = input.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::new(client, num_elems);
+    let cube_count = calculate_cube_count_elemwise(client, num_elems, cube_dim);
+
+    unsafe {
+        argsort_init_kernel::launch_unchecked::<R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            values.as_tensor_arg(1),
+            indices.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            dtypes.input,
+            dtypes.output,
+        )
+        .map_err(ReduceError::Launch)?;
+    }
+
+    // The axis is padded to a power of two, with one unit per pair of items in each pass.
+    let padded_size = vector_size.next_power_of_two();
+    let num_pairs = num_elems / vector_size * padded_size / 2;
+    let cube_dim = CubeDim::new(client, num_pairs);
+    let cube_count = calculate_cube_count_elemwise(client, num_pairs, cube_dim);
+
+    // Each stage merges sorted blocks into blocks twice as large, flipping the first pass.
+    let mut block_size = 2;
+    while block_size <= padded_size {
+        let mut distance = block_size / 2;
+        let mut flip = true;
+        while distance >= 1 {
+            unsafe {
+                bitonic_pass_kernel::launch_unchecked::<R>(
+                    client,
+                    cube_count.clone(),
+                    cube_dim,
+                    values.as_tensor_arg(1),
+                    indices.as_tensor_arg(1),
+                    ScalarArg::new(axis as u32),
+                    ScalarArg::new(padded_size as u32),
+                    ScalarArg::new(distance as u32),
+                    flip,
+                    descending,
+                    dtypes.output,
+                )
+                .map_err(ReduceError::Launch)?;
+            }
+            flip = false;
+            distance /= 2;
+        }
+        block_size *= 2;
+    }
+
+    Ok(())
+}
+
+/// Check that `values` and `indices` have the shape of `input` except for a size of `k` along
+/// `axis`, that `indices` holds `u32` and that all tensors are contiguous.
+fn validate_outputs<R: Runtime>(
+    input: &TensorHandleRef<R>,
+    values: &TensorHandleRef<R>,
+    indices: &TensorHandleRef<R>,
+    axis: usize,
+    k: usize,
+) -> Result<(), ReduceError> {
+    let mut expected_shape = input.shape.to_vec();
+    expected_shape[axis] = k;
+    for output in [values, indices] {
+        if output.shape != expected_shape {
+            return Err(ReduceError::MismatchShape {
+                expected_shape,
+                output_shape: output.shape.to_vec(),
+            });
+        }
+    }
+    if indices.elem_size != size_of::<u32>() {
+        return Err(ReduceError::Validation {
+            details: "Top-k requires u32 indices.",
+        });
+    }
+    if !is_contiguous(input.shape, input.strides)
+        || !is_contiguous(values.shape, values.strides)
+        || !is_contiguous(indices.shape, indices.strides)
+    {
+        return Err(ReduceError::Validation {
+            details: "Top-k requires contiguous input and output tensors.",
+        });
+    }
+
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn top_k_kernel<In: Numeric, Out: Numeric, Acc: Numeric, RA: ReduceArgs>(
+    input: &RA::Input<In>,
+    values: &mut RA::Output<Out>,
+    indices: &mut Tensor<Line<u32>>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] config: TopKConfig,
+    #[define(In)] _input_dtype: StorageType,
+    #[define(Out)] _output_dtype: StorageType,
+    #[define(Acc)] _acc_dtype: StorageType,
+) {
+    let (input, mut values) = init_tensors::<RA, In, Out>(input, values);
+    top_k_inner::<(In, Acc), Out>(&input, &mut values, indices, axis_reduce, blueprint, config);
+}
+
+#[cube]
+fn top_k_inner<P: ReducePrecision, Out: Numeric>(
+    input: &VirtualTensor<P::EI>,
+    values: &mut VirtualTensor<Out, ReadWrite>,
+    indices: &mut Tensor<Line<u32>>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] config: TopKConfig,
+) {
+    // The readers of `ArgMax` give the coordinate of each item, and `u32::MAX` for padding.
+    let inst = &ArgMax {};
+    let line_size = input.line_size();
+    let line_mode = comptime!(blueprint.line_mode);
+    let vector_size = input.shape(axis_reduce);
+    let reduce_count = reduce_count(input.len() * line_size / vector_size, line_mode, line_size);
+
+    match comptime!(blueprint.global) {
+        GlobalReduceBlueprint::Unit(unit) => {
+            let reduce_index = ABSOLUTE_POS;
+            let idle = idle_check_count(reduce_count, reduce_index, unit.unit_idle);
+            let (input_offset, output_offset) =
+                vector_offsets(input, axis_reduce, reduce_index, config.k, line_mode);
+
+            let reader = Reader::<P>::new_at::<ArgMax>(
+                input,
+                inst,
+                axis_reduce,
+                input_offset,
+                idle,
+                comptime!(BoundChecks::None),
+                line_mode,
+            );
+            let reader = UnitReader::<P>::new(reader);
+
+            let mut list = TopKList::<P::EA>::new(config.k, line_size, config.largest);
+            for i in 0..active_count(reader.length(), idle) {
+                let (item, coordinate) = reader.read(i);
+                list.insert(
+                    Line::cast_from(item),
+                    coordinates(coordinate),
+                    config.k,
+                    config.largest,
+                    false,
+                );
+            }
+
+            let list = match comptime!(line_mode) {
+                LineMode::Parallel => list.merge_lanes(config.k, line_size, config.largest),
+                LineMode::Perpendicular => list,
+            };
+            write::<P::EA, Out>(
+                list,
+                values,
+                indices,
+                input.stride(axis_reduce),
+                output_offset,
+                idle,
+                config,
+            );
+        }
+        GlobalReduceBlueprint::Plane(plane) => {
+            let reduce_index = CUBE_POS * CUBE_DIM_Y + UNIT_POS_Y;
+            let idle = idle_check_count(reduce_count, reduce_index, plane.plane_idle);
+            let (input_offset, output_offset) =
+                vector_offsets(input, axis_reduce, reduce_index, config.k, line_mode);
+
+            let reader = Reader::<P>::new_at::<ArgMax>(
+                input,
+                inst,
+                axis_reduce,
+                input_offset,
+                idle,
+                plane.bound_checks,
+                line_mode,
+            );
+            let reader = PlaneReader::<P>::new(reader);
+
+            let mut list = TopKList::<P::EA>::new(config.k, line_size, config.largest);
+            for i in 0..reader.length() {
+                let (item, coordinate) = reader.read(i);
+                list.insert(
+                    Line::cast_from(item),
+                    coordinates(coordinate),
+                    config.k,
+                    config.largest,
+                    false,
+                );
+            }
+
+            let mut list = match comptime!(line_mode) {
+                LineMode::Parallel => list.merge_lanes(config.k, line_size, config.largest),
+                LineMode::Perpendicular => list,
+            };
+            let list = list.merge_plane(config.k, config.largest);
+            if UNIT_POS_X == 0 {
+                write::<P::EA, Out>(
+                    list,
+                    values,
+                    indices,
+                    input.stride(axis_reduce),
+                    output_offset,
+                    idle,
+                    config,
+                );
+            }
+        }
+        GlobalReduceBlueprint::Cube(_) => {
+            comptime! {panic!("Top-k requires the unit or plane routine")};
+        }
+    }
+}
+
+/// The `k` best items seen in each lane, sorted from the best to the worst.
+///
+/// Empty slots hold the `u32::MAX` index, which ranks after every item.
+#[derive(CubeType)]
+struct TopKList<N: Numeric> {
+    values: Array<Line<N>>,
+    indices: Array<Line<u32>>,
+}
+
+#[cube]
+impl<N: Numeric> TopKList<N> {
+    fn new(
+        #[comptime] k: u32,
+        #[comptime] line_size: u32,
+        #[comptime] largest: bool,
+    ) -> TopKList<N> {
+        let mut values = Array::vectorized(k, line_size);
+        let mut indices = Array::vectorized(k, line_size);
+        let null = if comptime!(largest) {
+            N::min_value()
+        } else {
+            N::max_value()
+        };
+
+        #[unroll]
+        for j in 0..k {
+            values[j] = Line::empty(line_size).fill(null);
+            indices[j] = Line::empty(line_size).fill(u32::MAX);
+        }
+
+        TopKList::<N> { values, indices }
+    }
+
+    /// Insert an item in each lane, dropping the worst one.
+    ///
+    /// The item moves down the list, swapping places with every item it ranks before.
+    fn insert(
+        &mut self,
+        value: Line<N>,
+        index: Line<u32>,
+        #[comptime] k: u32,
+        #[comptime] largest: bool,
+        #[comptime] by_index: bool,
+    ) {
+        let mut value = value;
+        let mut index = index;
+
+        #[unroll]
+        for j in 0..k {
+            let current_value = self.values[j];
+            let current_index = self.indices[j];
+            let before = ranks_before::<N>(
+                value,
+                index,
+                current_value,
+                current_index,
+                largest,
+                by_index,
+            );
+            self.values[j] = select_many(before, value, current_value);
+            self.indices[j] = select_many(before, index, current_index);
+            value = select_many(before, current_value, value);
+            index = select_many(before, current_index, index);
+        }
+    }
+
+    /// Merge the lists of all lanes, which hold parts of the same vector in parallel mode.
+    fn merge_lanes(
+        &self,
+        #[comptime] k: u32,
+        #[comptime] line_size: u32,
+        #[comptime] largest: bool,
+    ) -> TopKList<N> {
+        let mut merged = TopKList::<N>::new(k, 1, largest);
+
+        #[unroll]
+        for lane in 0..line_size {
+            for j in 0..k {
+                merged.insert(
+                    Line::new(self.values[j][lane]),
+                    Line::new(self.indices[j][lane]),
+                    k,
+                    largest,
+                    false,
+                );
+            }
+        }
+
+        merged
+    }
+
+    /// Merge the lists of all units of the plane, which all get the result.
+    ///
+    /// Each round selects the best head among the units, which the unit holding it then drops.
+    fn merge_plane(&mut self, #[comptime] k: u32, #[comptime] largest: bool) -> TopKList<N> {
+        let line_size = self.values[0].size();
+        let mut merged = TopKList::<N>::new(k, line_size, largest);
+        let padding = Line::empty(line_size).fill(u32::MAX);
+
+        for j in 0..k {
+            let value = self.values[0];
+            let index = self.indices[0];
+            let valid = index.less_than(padding);
+
+            // Empty heads take the value of the worst valid head, so that they never win even
+            // against infinite items.
+            let best = if comptime!(largest) {
+                let worst = plane_min(select_many(
+                    valid,
+                    value,
+                    Line::empty(line_size).fill(N::max_value()),
+                ));
+                plane_max(select_many(valid, value, worst))
+            } else {
+                let worst = plane_max(select_many(
+                    valid,
+                    value,
+                    Line::empty(line_size).fill(N::min_value()),
+                ));
+                plane_min(select_many(valid, value, worst))
+            };
+            let is_best = select_many(valid, value.equal(best), valid);
+            let chosen = plane_min(select_many(is_best, index, padding));
+
+            merged.values[j] = best;
+            merged.indices[j] = chosen;
+
+            // The unit holding the chosen item shifts its list.
+            let pop = index.equal(chosen);
+            #[unroll]
+            for i in 1..k {
+                self.values[i - 1] = select_many(pop, self.values[i], self.values[i - 1]);
+                self.indices[i - 1] = select_many(pop, self.indices[i], self.indices[i - 1]);
+            }
+            self.indices[k - 1] = select_many(pop, padding, self.indices[k - 1]);
+        }
+
+        merged
+    }
+
+    /// Sort the list by increasing index.
+    fn sort_by_index(&self, #[comptime] k: u32, #[comptime] largest: bool) -> TopKList<N> {
+        let line_size = self.values[0].size();
+        let mut sorted = TopKList::<N>::new(k, line_size, largest);
+
+        for j in 0..k {
+            sorted.insert(self.values[j], self.indices[j], k, largest, true);
+        }
+
+        sorted
+    }
+}
+
+/// Whether the item `(value, index)` ranks before the item `(other_value, other_index)` in each
+/// lane. Equal values are ranked by increasing index, and padding ranks after every item.
+#[cube]
+fn ranks_before<N: Numeric>(
+    value: Line<N>,
+    index: Line<u32>,
+    other_value: Line<N>,
+    other_index: Line<u32>,
+    #[comptime] largest: bool,
+    #[comptime] by_index: bool,
+) -> Line<bool> {
+    let by_index_order = index.less_than(other_index);
+
+    if comptime!(by_index) {
+        by_index_order
+    } else {
+        let by_value_order = if comptime!(largest) {
+            value.greater_than(other_value)
+        } else {
+            value.less_than(other_value)
+        };
+        let order = select_many(value.equal(other_value), by_index_order, by_value_order);
+
+        let padding = Line::empty(index.size()).fill(u32::MAX);
+        let any_padding = select_many(
+            index.equal(padding),
+            index.equal(padding),
+            other_index.equal(padding),
+        );
+        select_many(any_padding, by_index_order, order)
+    }
+}
+
+#[cube]
+fn coordinates(coordinate: ReduceCoordinate) -> Line<u32> {
+    match coordinate {
+        ReduceCoordinate::Required(val) => val,
+        ReduceCoordinate::NotRequired => {
+            comptime! {panic!("Coordinates are required for top-k")};
+            #[allow(unreachable_code)]
+            Line::new(0)
+        }
+    }
+}
+
+/// The number of lines to read, which is zero when idle.
+#[cube]
+fn active_count(length: u32, idle: CubeOption<bool>) -> u32 {
+    match idle {
+        CubeOption::Some(idle) => length * u32::cast_from(!idle),
+        CubeOption::None => length,
+    }
+}
+
+/// The offsets of the first element of the vector reduced at `reduce_index` in the input and in
+/// the outputs, which is the first of `line_size` adjacent vectors in perpendicular mode.
+///
+/// Since all tensors are contiguous, they only differ by the size of the reduced axis.
+#[cube]
+fn vector_offsets<N: Numeric>(
+    input: &VirtualTensor<N>,
+    reduce_axis: u32,
+    reduce_index: u32,
+    #[comptime] k: u32,
+    #[comptime] line_mode: LineMode,
+) -> (u32, u32) {
+    let vector_index = match comptime!(line_mode) {
+        LineMode::Parallel => reduce_index,
+        LineMode::Perpendicular => reduce_index * input.line_size(),
+    };
+
+    let inner = input.stride(reduce_axis);
+    let outer = vector_index / inner;
+    let within = vector_index % inner;
+
+    (
+        outer * input.shape(reduce_axis) * inner + within,
+        outer * k * inner + within,
+    )
+}
+
+/// Write the list at `output_offset`, in rank order or in index order depending on `config`.
+#[cube]
+fn write<N: Numeric, Out: Numeric>(
+    list: TopKList<N>,
+    values: &mut VirtualTensor<Out, ReadWrite>,
+    indices: &mut Tensor<Line<u32>>,
+    stride: u32,
+    output_offset: u32,
+    idle: CubeOption<bool>,
+    #[comptime] config: TopKConfig,
+) {
+    let list = if comptime!(config.sorted) {
+        list
+    } else {
+        list.sort_by_index(config.k, config.largest)
+    };
+
+    let line_size = indices.line_size();
+    let mut values_view = values.view_mut(PlainLayout::new(values.len()));
+    let output_offset = output_offset / line_size;
+    let stride = stride / line_size;
+
+    for j in 0..active_count(config.k, idle) {
+        let offset = output_offset + j * stride;
+        values_view.write(offset, Line::cast_from(list.values[j]));
+        indices[offset] = list.indices[j];
+    }
+}
+
+/// Copy `input` into `values`, and the position of each item along `axis` into `indices`.
+#[cube(launch_unchecked)]
+fn argsort_init_kernel<In: Numeric, Out: Numeric>(
+    input: &Tensor<In>,
+    values: &mut Tensor<Out>,
+    indices: &mut Tensor<u32>,
+    axis: u32,
+    #[define(In)] _input_dtype: StorageType,
+    #[define(Out)] _output_dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    values[ABSOLUTE_POS] = Out::cast_from(input[ABSOLUTE_POS]);
+    indices[ABSOLUTE_POS] = (ABSOLUTE_POS / input.stride(axis)) % input.shape(axis);
+}
+
+/// One pass of a bitonic sorting network over every vector of `axis`, padded to `padded_size`
+/// items. Each unit compares a pair of items and puts the best one first.
+///
+/// Pairs are `distance` items apart, or mirror each other in blocks of `2 * distance` items when
+/// `flip` is set, so that every comparison goes in the same direction. Pairs reaching past the
+/// end of the axis are skipped, since padding ranks after every item and never moves.
+#[cube(launch_unchecked)]
+#[allow(clippy::too_many_arguments)]
+fn bitonic_pass_kernel<N: Numeric>(
+    values: &mut Tensor<N>,
+    indices: &mut Tensor<u32>,
+    axis: u32,
+    padded_size: u32,
+    distance: u32,
+    #[comptime] flip: bool,
+    #[comptime] largest: bool,
+    #[define(N)] _dtype: StorageType,
+) {
+    let vector_size = values.shape(axis);
+    let pairs_per_vector = padded_size / 2;
+    if ABSOLUTE_POS >= values.len() / vector_size * pairs_per_vector {
+        terminate!();
+    }
+
+    let vector_index = ABSOLUTE_POS / pairs_per_vector;
+    let pair = ABSOLUTE_POS % pairs_per_vector;
+    let block_start = (pair / distance) * 2 * distance;
+    let within = pair % distance;
+    let first = block_start + within;
+    let second = if comptime!(flip) {
+        block_start + 2 * distance - 1 - within
+    } else {
+        first + distance
+    };
+
+    if second < vector_size {
+        // Tensors are contiguous, so vectors are laid out as in `vector_offsets`.
+        let inner = values.stride(axis);
+        let start = (vector_index / inner) * vector_size * inner + vector_index % inner;
+        let first_offset = start + first * inner;
+        let second_offset = start + second * inner;
+
+        let first_value = values[first_offset];
+        let second_value = values[second_offset];
+        let first_index = indices[first_offset];
+        let second_index = indices[second_offset];
+
+        let better = if comptime!(largest) {
+            second_value > first_value
+        } else {
+            second_value < first_value
+        };
+        let tie = second_value == first_value && second_index < first_index;
+
+        if better || tie {
+            values[first_offset] = second_value;
+            values[second_offset] = first_value;
+            indices[first_offset] = second_index;
+            indices[second_offset] = first_index;
+        }
+    }
+}
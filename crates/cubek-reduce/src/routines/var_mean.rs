@@ -0,0 +1,294 @@
+use crate::{
+    LineMode, ReduceDtypes, ReduceError, ReducePrecision, ReduceStrategy,
+    components::{
+        args::{ReduceArgs, TensorArgs, init_tensors},
+        global::{
+            cube::{GlobalFullCubeReduce, reduce_scan, reduce_tree},
+            idle_check,
+            plane::GlobalFullPlaneReduce,
+            unit::GlobalFullUnitReduce,
+        },
+        instructions::{ReduceInstruction, Variance},
+        writer::Writer,
+    },
+    launch::{RoutineStrategy, generate_line_size},
+    routines::{
+        GlobalReduceBlueprint, ReduceBlueprint, ReduceLineSettings, ReduceProblem, Routine,
+        cube::CubeRoutine, plane::PlaneRoutine, unit::UnitRoutine,
+    },
+    valid_output_shape, validate_axis,
+};
+use cubecl::{ir::ElemType, prelude::*, std::tensor::r#virtual::VirtualTensor};
+
+/// Compute both the variance and the mean of the given `axis` of `input`, writing them into
+/// `var` and `mean`.
+///
+/// The variance is computed with Welford's algorithm, `correction` being subtracted from the
+/// number of items (`1` for the unbiased estimator). Both outputs must have the shape of `input`
+/// with a value of 1 for the given `axis`, and the same strides.
+///
+/// A single kernel reads `input` once and writes both outputs from the same Welford accumulators.
+#[allow(clippy::too_many_arguments)]
+pub fn var_mean<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorHandleRef<R>,
+    var: TensorHandleRef<R>,
+    mean: TensorHandleRef<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+    correction: u32,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, var.shape, &[axis])?;
+    valid_output_shape(input.shape, mean.shape, &[axis])?;
+    if var.strides != mean.strides {
+        return Err(ReduceError::Validation {
+            details: "The variance and the mean must have the same strides.",
+        });
+    }
+    if !matches!(dtypes.accumulation.elem_type(), ElemType::Float(_)) {
+        return Err(ReduceError::Validation {
+            details: "Var and Std require a float accumulation type.",
+        });
+    }
+
+    let problem = ReduceProblem {
+        vector_size: input.shape[axis] as u32,
+        vector_count: var.shape.iter().map(|i| *i as u32).product(),
+        axis: axis as u32,
+        dtypes,
+    };
+    let line_mode = match input.strides[axis] {
+        1 => LineMode::Parallel,
+        _ => LineMode::Perpendicular,
+    };
+    let (line_size_input, line_size_output) = generate_line_size::<R>(
+        client,
+        &input,
+        &var,
+        axis,
+        dtypes.input,
+        line_mode,
+        &strategy.line_size,
+    );
+    let settings = ReduceLineSettings {
+        line_mode,
+        line_size_input,
+        line_size_output,
+    };
+
+    let (blueprint, settings) = match strategy.routine {
+        RoutineStrategy::Unit(strategy) => {
+            UnitRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Plane(strategy) => {
+            PlaneRoutine.prepare(client, problem, settings, strategy)?
+        }
+        RoutineStrategy::Cube(strategy) => {
+            CubeRoutine.prepare(client, problem, settings, strategy)?
+        }
+    };
+
+    unsafe {
+        var_mean_kernel::launch_unchecked::<TensorArgs, R>(
+            client,
+            settings.cube_count,
+            settings.cube_dim,
+            input.as_tensor_arg(settings.line.line_size_input),
+            var.as_tensor_arg(settings.line.line_size_output),
+            mean.as_tensor_arg(settings.line.line_size_output),
+            ScalarArg::new(axis as u32),
+            blueprint,
+            correction,
+            dtypes.input,
+            dtypes.output,
+            dtypes.accumulation,
+        )
+        .map_err(ReduceError::Launch)
+    }
+}
+
+#[cube(launch_unchecked)]
+#[allow(clippy::too_many_arguments)]
+fn var_mean_kernel<In: Numeric, Out: Numeric, Acc: Numeric, RA: ReduceArgs>(
+    input: &RA::Input<In>,
+    var: &mut RA::Output<Out>,
+    mean: &mut RA::Output<Out>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] correction: u32,
+    #[define(In)] _input_dtype: StorageType,
+    #[define(Out)] _output_dtype: StorageType,
+    #[define(Acc)] _acc_dtype: StorageType,
+) {
+    let (input_tensor, mut var) = init_tensors::<RA, In, Out>(input, var);
+    let (_, mut mean) = init_tensors::<RA, In, Out>(input, mean);
+    var_mean_inner::<(In, Acc), Out>(
+        &input_tensor,
+        &mut var,
+        &mut mean,
+        axis_reduce,
+        blueprint,
+        correction,
+    );
+}
+
+/// Reduce each vector once like [`reduce_kernel`](crate::reduce_kernel) and write its
+/// accumulator twice, as the variance into `var` and as the mean into `mean`.
+#[cube]
+fn var_mean_inner<P: ReducePrecision, Out: Numeric>(
+    input: &VirtualTensor<P::EI>,
+    var: &mut VirtualTensor<Out, ReadWrite>,
+    mean: &mut VirtualTensor<Out, ReadWrite>,
+    axis_reduce: u32,
+    #[comptime] blueprint: ReduceBlueprint,
+    #[comptime] correction: u32,
+) {
+    let var_inst = &Variance {
+        correction,
+        std: false,
+        mean: false,
+    };
+    let mean_inst = &Variance {
+        correction,
+        std: false,
+        mean: true,
+    };
+    let line_mode = comptime!(blueprint.line_mode);
+
+    match comptime!(blueprint.global) {
+        GlobalReduceBlueprint::Unit(unit) => {
+            let write_index = ABSOLUTE_POS;
+            let mut var_writer =
+                Writer::<Out>::new::<P>(input, var, axis_reduce, write_index, line_mode);
+            let mut mean_writer =
+                Writer::<Out>::new::<P>(input, mean, axis_reduce, write_index, line_mode);
+
+            let write_count = var_writer.write_count();
+            let reduce_index_start = write_index * write_count;
+            let idle =
+                idle_check::<P, Out>(input, var, reduce_index_start, line_mode, unit.unit_idle);
+
+            for b in 0..write_count {
+                let accumulator = GlobalFullUnitReduce::reduce_single::<P, Out, Variance>(
+                    input,
+                    var,
+                    axis_reduce,
+                    reduce_index_start + b,
+                    var_inst,
+                    idle,
+                    line_mode,
+                );
+                var_writer.write::<P, Variance>(b, accumulator, var_inst);
+                mean_writer.write::<P, Variance>(b, accumulator, mean_inst);
+            }
+
+            var_writer.commit();
+            mean_writer.commit();
+        }
+        GlobalReduceBlueprint::Plane(plane) => {
+            let write_index = CUBE_POS * CUBE_DIM_Y + UNIT_POS_Y;
+            let mut var_writer =
+                Writer::<Out>::new::<P>(input, var, axis_reduce, write_index, line_mode);
+            let mut mean_writer =
+                Writer::<Out>::new::<P>(input, mean, axis_reduce, write_index, line_mode);
+
+            let write_count = var_writer.write_count();
+            let reduce_index_start = write_index * write_count;
+            let idle =
+                idle_check::<P, Out>(input, var, reduce_index_start, line_mode, plane.plane_idle);
+
+            for b in 0..write_count {
+                let accumulator = GlobalFullPlaneReduce::reduce_single::<P, Out, Variance>(
+                    input,
+                    var,
+                    axis_reduce,
+                    reduce_index_start + b,
+                    var_inst,
+                    idle,
+                    line_mode,
+                    plane,
+                );
+
+                if UNIT_POS_X == 0 {
+                    var_writer.write::<P, Variance>(b, accumulator, var_inst);
+                    mean_writer.write::<P, Variance>(b, accumulator, mean_inst);
+                }
+            }
+
+            let commit_required = var_writer.commit_required();
+
+            #[allow(clippy::collapsible_if)]
+            if comptime!(commit_required) {
+                if UNIT_POS_X == 0u32 {
+                    var_writer.commit();
+                    mean_writer.commit();
+                }
+            }
+        }
+        GlobalReduceBlueprint::Cube(cube) => {
+            let write_index = CUBE_POS;
+            let input_line_size = input.line_size();
+            let worker_pos = GlobalFullCubeReduce::worker_pos(cube);
+            let mut var_writer =
+                Writer::<Out>::new::<P>(input, var, axis_reduce, write_index, line_mode);
+            let mut mean_writer =
+                Writer::<Out>::new::<P>(input, mean, axis_reduce, write_index, line_mode);
+
+            let write_count = var_writer.write_count();
+            let reduce_index_start = write_index * write_count;
+            let idle =
+                idle_check::<P, Out>(input, var, reduce_index_start, line_mode, cube.cube_idle);
+
+            for b in 0..write_count {
+                let mut accumulator_shared = GlobalFullCubeReduce::reduce_shared::<P, Out, Variance>(
+                    input,
+                    var,
+                    axis_reduce,
+                    reduce_index_start + b,
+                    var_inst,
+                    idle,
+                    line_mode,
+                    cube,
+                );
+                let mut accumulator =
+                    <Variance as ReduceInstruction<P>>::null_accumulator(var_inst, input_line_size);
+
+                if comptime!(cube.use_planes) {
+                    if worker_pos == 0 {
+                        reduce_scan::<P, Variance>(
+                            var_inst,
+                            &mut accumulator_shared,
+                            &mut accumulator,
+                            cube.num_shared_accumulators,
+                        );
+                    }
+                } else {
+                    reduce_tree::<P, Variance>(
+                        var_inst,
+                        &mut accumulator_shared,
+                        &mut accumulator,
+                        worker_pos,
+                        cube.num_shared_accumulators,
+                    );
+                }
+
+                if worker_pos == 0 {
+                    var_writer.write::<P, Variance>(b, accumulator, var_inst);
+                    mean_writer.write::<P, Variance>(b, accumulator, mean_inst);
+                }
+            }
+
+            let commit_required = var_writer.commit_required();
+
+            #[allow(clippy::collapsible_if)]
+            if comptime!(commit_required) {
+                if worker_pos == 0u32 {
+                    var_writer.commit();
+                    mean_writer.commit();
+                }
+            }
+        }
+    }
+}
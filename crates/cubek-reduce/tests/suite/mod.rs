@@ -1,3 +1,4 @@
+mod reduce_axes;
 pub mod test_case;
 
 macro_rules! testgen_reduce {
@@ -390,6 +391,22 @@ mod reduce_dim {
         );
     }
 
+    mod parallel_matrix_long {
+        testgen_reduce!(
+            shape: vec![2, 8704],
+            strides: vec![8704, 1],
+            axis: Some(1),
+        );
+    }
+
+    mod perpendicular_matrix_long {
+        testgen_reduce!(
+            shape: vec![8704, 4],
+            strides: vec![4, 1],
+            axis: Some(0),
+        );
+    }
+
     mod parallel_matrix_large_odd_batch {
         testgen_reduce!(
             shape: vec![33, 1024],
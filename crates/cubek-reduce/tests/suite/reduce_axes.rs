@@ -0,0 +1,344 @@
+use cubecl::TestRuntime;
+use cubecl::prelude::*;
+use cubek_reduce::components::instructions::ReduceOperationConfig;
+use cubek_reduce::launch::{LineSizeStrategy, RoutineStrategy};
+use cubek_reduce::routines::{BlueprintStrategy, unit::UnitStrategy};
+use cubek_reduce::{ReduceDtypes, ReduceError, ReduceStrategy, reduce_axes, reduce_full};
+
+use crate::suite::test_case::assert_approx_equal;
+
+#[derive(Debug)]
+struct AxesTestCase {
+    shape: Vec<usize>,
+    stride: Vec<usize>,
+    axes: Vec<usize>,
+}
+
+impl AxesTestCase {
+    fn input_values(&self) -> Vec<f32> {
+        let size = self
+            .shape
+            .iter()
+            .zip(self.stride.iter())
+            .map(|(shape, stride)| (shape - 1) * stride)
+            .sum::<usize>()
+            + 1;
+        // Distinct values for the first 97 elements, so arg reductions have no ties.
+        (0..size)
+            .map(|i| ((i * 7) % 97) as f32 / 16.0 - 3.0)
+            .collect()
+    }
+
+    fn output_shape(&self) -> Vec<usize> {
+        let mut shape = self.shape.clone();
+        for axis in self.axes.iter() {
+            shape[*axis] = 1;
+        }
+        shape
+    }
+
+    /// All the input values reduced into each output element, along with the row-major index of
+    /// each value within the reduced axes.
+    fn cpu_groups(&self, input: &[f32]) -> Vec<Vec<(f32, u32)>> {
+        let output_shape = self.output_shape();
+        let num_outputs = output_shape.iter().product::<usize>();
+        let mut groups = vec![Vec::new(); num_outputs];
+
+        for index in 0..self.shape.iter().product::<usize>() {
+            let mut remainder = index;
+            let mut coordinate = vec![0; self.shape.len()];
+            for axis in (0..self.shape.len()).rev() {
+                coordinate[axis] = remainder % self.shape[axis];
+                remainder /= self.shape[axis];
+            }
+
+            let offset: usize = coordinate
+                .iter()
+                .zip(self.stride.iter())
+                .map(|(c, s)| c * s)
+                .sum();
+            let output_index = coordinate
+                .iter()
+                .zip(output_shape.iter())
+                .fold(0, |acc, (c, shape)| acc * shape + c % shape);
+            let reduced_index = self
+                .axes
+                .iter()
+                .fold(0, |acc, axis| acc * self.shape[*axis] + coordinate[*axis]);
+
+            groups[output_index].push((input[offset], reduced_index as u32));
+        }
+
+        groups
+    }
+
+    fn run(&self, config: ReduceOperationConfig) -> Result<(), ReduceError> {
+        let input_values = self.input_values();
+        let groups = self.cpu_groups(&input_values);
+
+        match config {
+            ReduceOperationConfig::ArgMax | ReduceOperationConfig::ArgMin => {
+                let expected = groups
+                    .iter()
+                    .map(|group| {
+                        let mut best = group[0];
+                        for item in group.iter() {
+                            let better = match config {
+                                ReduceOperationConfig::ArgMax => item.0 > best.0,
+                                _ => item.0 < best.0,
+                            };
+                            if better {
+                                best = *item;
+                            }
+                        }
+                        best.1
+                    })
+                    .collect::<Vec<_>>();
+                self.launch::<u32>(input_values, expected, config)
+            }
+            _ => {
+                let expected = groups
+                    .iter()
+                    .map(|group| {
+                        let values = group.iter().map(|(value, _)| *value);
+                        match config {
+                            ReduceOperationConfig::Sum => values.sum(),
+                            ReduceOperationConfig::Mean => values.sum::<f32>() / group.len() as f32,
+                            ReduceOperationConfig::Max => values.fold(f32::MIN, f32::max),
+                            ReduceOperationConfig::Min => values.fold(f32::MAX, f32::min),
+                            ReduceOperationConfig::MaxAbs => {
+                                values.map(f32::abs).fold(0.0, f32::max)
+                            }
+                            ReduceOperationConfig::Var { correction } => {
+                                let count = group.len() as f32;
+                                let mean = values.clone().sum::<f32>() / count;
+                                values.map(|value| (value - mean).powi(2)).sum::<f32>()
+                                    / (count - correction as f32)
+                            }
+                            _ => unreachable!(),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                self.launch::<f32>(input_values, expected, config)
+            }
+        }
+    }
+
+    fn launch<O: Numeric + CubeElement>(
+        &self,
+        input_values: Vec<f32>,
+        expected: Vec<O>,
+        config: ReduceOperationConfig,
+    ) -> Result<(), ReduceError> {
+        let client = TestRuntime::client(&Default::default());
+
+        let input_handle = client.create_from_slice(f32::as_bytes(&input_values));
+        let output_handle =
+            client.create_from_slice(O::as_bytes(&vec![O::from_int(0); expected.len()]));
+
+        let output_shape = self.output_shape();
+        let output_stride = output_shape
+            .iter()
+            .rev()
+            .scan(1, |stride, shape| {
+                let current = *stride;
+                *stride *= shape;
+                Some(current)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>();
+
+        let input = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<f32>(),
+            )
+        };
+        let output = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<O>(),
+            )
+        };
+
+        let strategy = ReduceStrategy {
+            routine: RoutineStrategy::Unit(BlueprintStrategy::Inferred(UnitStrategy)),
+            line_size: LineSizeStrategy {
+                parallel_output_vectorization: false,
+            },
+        };
+        let dtypes = ReduceDtypes {
+            input: f32::as_type_native_unchecked(),
+            output: O::as_type_native_unchecked(),
+            accumulation: f32::as_type_native_unchecked(),
+        };
+
+        if self.axes.len() == self.shape.len() {
+            reduce_full::<TestRuntime>(&client, input, output, strategy, config, dtypes)?;
+        } else {
+            reduce_axes::<TestRuntime>(
+                &client, input, output, &self.axes, strategy, config, dtypes,
+            )?;
+        }
+
+        let bytes = client.read_one(output_handle);
+        let output_values = O::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected, false);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sum_spatial_axes_collapsed() {
+    // Global pooling of a `NCHW` tensor.
+    AxesTestCase {
+        shape: vec![2, 3, 4, 5],
+        stride: vec![60, 20, 5, 1],
+        axes: vec![2, 3],
+    }
+    .run(ReduceOperationConfig::Sum)
+    .unwrap();
+}
+
+#[test]
+fn test_mean_outer_axes_collapsed() {
+    AxesTestCase {
+        shape: vec![4, 6, 8],
+        stride: vec![48, 8, 1],
+        axes: vec![0, 1],
+    }
+    .run(ReduceOperationConfig::Mean)
+    .unwrap();
+}
+
+#[test]
+fn test_mean_non_adjacent_axes() {
+    AxesTestCase {
+        shape: vec![4, 3, 6, 5],
+        stride: vec![90, 30, 5, 1],
+        axes: vec![0, 2],
+    }
+    .run(ReduceOperationConfig::Mean)
+    .unwrap();
+}
+
+#[test]
+fn test_max_transposed_axes() {
+    // Adjacent axes whose strides can't be merged.
+    AxesTestCase {
+        shape: vec![3, 8, 6],
+        stride: vec![48, 1, 8],
+        axes: vec![1, 2],
+    }
+    .run(ReduceOperationConfig::Max)
+    .unwrap();
+}
+
+#[test]
+fn test_var_spatial_axes_collapsed() {
+    AxesTestCase {
+        shape: vec![2, 3, 4, 5],
+        stride: vec![60, 20, 5, 1],
+        axes: vec![2, 3],
+    }
+    .run(ReduceOperationConfig::Var { correction: 1 })
+    .unwrap();
+}
+
+#[test]
+fn test_var_non_adjacent_axes_is_rejected() {
+    let result = AxesTestCase {
+        shape: vec![4, 3, 6, 5],
+        stride: vec![90, 30, 5, 1],
+        axes: vec![0, 2],
+    }
+    .run(ReduceOperationConfig::Var { correction: 1 });
+
+    assert!(matches!(result, Err(ReduceError::Validation { .. })));
+}
+
+#[test]
+fn test_argmax_collapsed_axes() {
+    AxesTestCase {
+        shape: vec![3, 4, 8],
+        stride: vec![32, 8, 1],
+        axes: vec![1, 2],
+    }
+    .run(ReduceOperationConfig::ArgMax)
+    .unwrap();
+}
+
+#[test]
+fn test_argmin_non_adjacent_axes_is_rejected() {
+    let result = AxesTestCase {
+        shape: vec![3, 4, 8],
+        stride: vec![32, 8, 1],
+        axes: vec![0, 2],
+    }
+    .run(ReduceOperationConfig::ArgMin);
+
+    assert!(matches!(result, Err(ReduceError::Validation { .. })));
+}
+
+#[test]
+fn test_full_sum() {
+    AxesTestCase {
+        shape: vec![4, 8, 16],
+        stride: vec![128, 16, 1],
+        axes: vec![0, 1, 2],
+    }
+    .run(ReduceOperationConfig::Sum)
+    .unwrap();
+}
+
+#[test]
+fn test_full_maxabs_transposed() {
+    AxesTestCase {
+        shape: vec![16, 12],
+        stride: vec![1, 16],
+        axes: vec![0, 1],
+    }
+    .run(ReduceOperationConfig::MaxAbs)
+    .unwrap();
+}
+
+#[test]
+fn test_full_argmin() {
+    AxesTestCase {
+        shape: vec![6, 10],
+        stride: vec![10, 1],
+        axes: vec![0, 1],
+    }
+    .run(ReduceOperationConfig::ArgMin)
+    .unwrap();
+}
+
+#[test]
+fn test_full_mean_partials() {
+    // Large enough to be reduced into partials by many cubes.
+    AxesTestCase {
+        shape: vec![64, 1024],
+        stride: vec![1024, 1],
+        axes: vec![0, 1],
+    }
+    .run(ReduceOperationConfig::Mean)
+    .unwrap();
+}
+
+#[test]
+fn test_full_max_partials_transposed() {
+    AxesTestCase {
+        shape: vec![256, 300],
+        stride: vec![1, 256],
+        axes: vec![0, 1],
+    }
+    .run(ReduceOperationConfig::Max)
+    .unwrap();
+}
@@ -25,6 +25,76 @@ pub fn test_prod() {
     test_case().test_prod();
 }
 
+#[test]
+pub fn test_var() {
+    test_case().test_var();
+}
+
+#[test]
+pub fn test_std() {
+    test_case().test_std();
+}
+
+#[test]
+pub fn test_var_mean() {
+    test_case().test_var_mean();
+}
+
+#[test]
+pub fn test_logsumexp() {
+    test_case().test_logsumexp();
+}
+
+#[test]
+pub fn test_softmax() {
+    test_case().test_softmax();
+}
+
+#[test]
+pub fn test_log_softmax() {
+    test_case().test_log_softmax();
+}
+
+#[test]
+pub fn test_top_k() {
+    test_case().test_top_k();
+}
+
+#[test]
+pub fn test_top_k_smallest() {
+    test_case().test_top_k_smallest();
+}
+
+#[test]
+pub fn test_top_k_unsorted() {
+    test_case().test_top_k_unsorted();
+}
+
+#[test]
+pub fn test_top_k_max_k() {
+    test_case().test_top_k_max_k();
+}
+
+#[test]
+pub fn test_argsort() {
+    test_case().test_argsort();
+}
+
+#[test]
+pub fn test_cumsum() {
+    test_case().test_cumsum();
+}
+
+#[test]
+pub fn test_cumsum_exclusive() {
+    test_case().test_cumsum_exclusive();
+}
+
+#[test]
+pub fn test_cummax() {
+    test_case().test_cummax();
+}
+
 fn test_case() -> TestCase<TestDType> {
     TestCase::<TestDType> {
         shape: test_shape(),
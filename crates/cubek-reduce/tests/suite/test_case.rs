@@ -1,10 +1,16 @@
 use std::marker::PhantomData;
 
 use cubecl::TestRuntime;
+use cubecl::client::ComputeClient;
 use cubecl::prelude::*;
+use cubecl::server::Handle;
 use cubek_reduce::components::instructions::ReduceOperationConfig;
 use cubek_reduce::launch::RoutineStrategy;
-use cubek_reduce::{ReduceDtypes, ReduceError, ReducePrecision, launch::ReduceStrategy, reduce};
+use cubek_reduce::routines::top_k::MAX_TOP_K;
+use cubek_reduce::{
+    ReduceDtypes, ReduceError, ReducePrecision, TopKConfig, argsort, cummax, cumsum,
+    launch::ReduceStrategy, log_softmax, reduce, softmax, top_k, var_mean,
+};
 use rand::{
     SeedableRng,
     distr::{Distribution, Uniform},
@@ -154,6 +160,341 @@ where
         expected
     }
 
+    pub fn test_logsumexp(&self) {
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => input_values
+                .iter()
+                .map(|v| *v + P::EI::new((self.shape[axis] as f32).ln()))
+                .collect(),
+            _ => self.cpu_logsumexp(&input_values),
+        };
+        self.run_reduce_test::<P::EI>(
+            input_values,
+            expected_values,
+            ReduceOperationConfig::LogSumExp,
+        )
+    }
+
+    fn cpu_logsumexp<F: Float>(&self, values: &[F]) -> Vec<F> {
+        self.cpu_logsumexp_f64(values)
+            .into_iter()
+            .map(|value| F::new(value as f32))
+            .collect()
+    }
+
+    fn cpu_logsumexp_f64<F: Float>(&self, values: &[F]) -> Vec<f64> {
+        let mut maxes = vec![f64::MIN; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                maxes[output_index] = maxes[output_index].max(value.to_f64().unwrap());
+            }
+        }
+
+        let mut sums = vec![0.0; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                sums[output_index] += (value.to_f64().unwrap() - maxes[output_index]).exp();
+            }
+        }
+
+        maxes
+            .into_iter()
+            .zip(sums)
+            .map(|(max, sum)| max + sum.ln())
+            .collect()
+    }
+
+    pub fn test_softmax(&self) {
+        self.test_softmax_inner(false);
+    }
+
+    pub fn test_log_softmax(&self) {
+        self.test_softmax_inner(true);
+    }
+
+    fn test_softmax_inner(&self, log: bool) {
+        // The fused softmax only runs on contiguous tensors with the plane or cube routines.
+        if matches!(self.strategy.routine, RoutineStrategy::Unit(_)) || !self.is_contiguous() {
+            return;
+        }
+
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let log_sum_exps = self.cpu_logsumexp_f64(&input_values);
+        let expected_values = input_values
+            .iter()
+            .enumerate()
+            .map(|(input_index, value)| {
+                let output_index = self.to_output_index(input_index).unwrap();
+                let shifted = value.to_f64().unwrap() - log_sum_exps[output_index];
+                match log {
+                    true => P::EI::new(shifted as f32),
+                    false => P::EI::new(shifted.exp() as f32),
+                }
+            })
+            .collect();
+        self.run_softmax_test(input_values, expected_values, log)
+    }
+
+    fn is_contiguous(&self) -> bool {
+        let mut expected_stride = 1;
+        for (shape, stride) in self.shape.iter().zip(self.stride.iter()).rev() {
+            if *stride != expected_stride {
+                return false;
+            }
+            expected_stride *= shape;
+        }
+        true
+    }
+
+    pub fn test_top_k(&self) {
+        self.test_top_k_inner(true, true, 8);
+    }
+
+    pub fn test_top_k_smallest(&self) {
+        self.test_top_k_inner(false, true, 8);
+    }
+
+    pub fn test_top_k_unsorted(&self) {
+        self.test_top_k_inner(true, false, 8);
+    }
+
+    pub fn test_top_k_max_k(&self) {
+        self.test_top_k_inner(true, true, MAX_TOP_K as usize);
+    }
+
+    fn test_top_k_inner(&self, largest: bool, sorted: bool, max_k: usize) {
+        // Top-k only runs on contiguous tensors with the unit or plane routines.
+        if matches!(self.strategy.routine, RoutineStrategy::Cube(_)) || !self.is_contiguous() {
+            return;
+        }
+
+        let config = TopKConfig {
+            k: self.shape[self.axis.unwrap()].min(max_k) as u32,
+            largest,
+            sorted,
+        };
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let (expected_values, expected_indices) = self.cpu_top_k(&input_values, config);
+        self.run_top_k_test(
+            input_values,
+            expected_values,
+            expected_indices,
+            config,
+            false,
+        )
+    }
+
+    pub fn test_argsort(&self) {
+        // Short axes need the unit or plane routine, while long ones are sorted without it.
+        if matches!(self.strategy.routine, RoutineStrategy::Cube(_)) || !self.is_contiguous() {
+            return;
+        }
+        let vector_size = self.shape[self.axis.unwrap()];
+
+        let config = TopKConfig {
+            k: vector_size as u32,
+            largest: true,
+            sorted: true,
+        };
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let (expected_values, expected_indices) = self.cpu_top_k(&input_values, config);
+        self.run_top_k_test(
+            input_values,
+            expected_values,
+            expected_indices,
+            config,
+            true,
+        )
+    }
+
+    fn cpu_top_k<F: Float>(&self, values: &[F], config: TopKConfig) -> (Vec<F>, Vec<u32>) {
+        let axis = self.axis.unwrap();
+        let k = config.k as usize;
+        let output_shape = self.top_k_output_shape(k);
+        let output_stride = Self::contiguous_stride(&output_shape);
+
+        let mut vectors = vec![(Vec::new(), Vec::new()); self.num_output_values()];
+        for (input_index, &value) in values.iter().enumerate() {
+            if let Some(coordinate) = self.to_input_coordinate(input_index) {
+                let output_index = self.to_output_index(input_index).unwrap();
+                let (items, vector_coordinate) = &mut vectors[output_index];
+                items.push((value, coordinate[axis] as u32));
+                *vector_coordinate = coordinate;
+            }
+        }
+
+        let mut expected_values = vec![F::from_int(0); self.num_output_values() * k];
+        let mut expected_indices = vec![0; self.num_output_values() * k];
+        for (mut items, mut coordinate) in vectors {
+            // Items are pushed by increasing index, so the stable sort ranks ties by index.
+            items.sort_by(|(lhs, _), (rhs, _)| match config.largest {
+                true => rhs.partial_cmp(lhs).unwrap(),
+                false => lhs.partial_cmp(rhs).unwrap(),
+            });
+            items.truncate(k);
+            if !config.sorted {
+                items.sort_by_key(|(_, index)| *index);
+            }
+
+            for (j, (value, index)) in items.into_iter().enumerate() {
+                coordinate[axis] = j;
+                let offset = coordinate
+                    .iter()
+                    .zip(output_stride.iter())
+                    .map(|(c, s)| c * s)
+                    .sum::<usize>();
+                expected_values[offset] = value;
+                expected_indices[offset] = index;
+            }
+        }
+
+        (expected_values, expected_indices)
+    }
+
+    fn top_k_output_shape(&self, k: usize) -> Vec<usize> {
+        let mut shape = self.shape.clone();
+        shape[self.axis.unwrap()] = k;
+        shape
+    }
+
+    fn contiguous_stride(shape: &[usize]) -> Vec<usize> {
+        let mut stride = vec![1; shape.len()];
+        for axis in (0..shape.len().saturating_sub(1)).rev() {
+            stride[axis] = stride[axis + 1] * shape[axis + 1];
+        }
+        stride
+    }
+
+    pub fn test_cumsum(&self) {
+        self.test_scan(ScanOperation::Sum, false);
+    }
+
+    pub fn test_cumsum_exclusive(&self) {
+        self.test_scan(ScanOperation::Sum, true);
+    }
+
+    pub fn test_cummax(&self) {
+        self.test_scan(ScanOperation::Max, false);
+    }
+
+    fn test_scan(&self, operation: ScanOperation, exclusive: bool) {
+        // The scan only runs on contiguous tensors.
+        if !self.is_contiguous() {
+            return;
+        }
+
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let expected_values = self.cpu_scan(&input_values, operation, exclusive);
+        self.run_scan_test(input_values, expected_values, operation, exclusive)
+    }
+
+    fn cpu_scan<F: Float>(
+        &self,
+        values: &[F],
+        operation: ScanOperation,
+        exclusive: bool,
+    ) -> Vec<F> {
+        let identity = match operation {
+            ScanOperation::Sum => 0.0,
+            ScanOperation::Max => F::min_value().to_f64().unwrap(),
+        };
+        let mut running = vec![identity; self.num_output_values()];
+
+        // Items of a vector are visited by increasing index along the axis.
+        values
+            .iter()
+            .enumerate()
+            .map(|(input_index, value)| {
+                let output_index = self.to_output_index(input_index).unwrap();
+                let previous = running[output_index];
+                let value = value.to_f64().unwrap();
+                running[output_index] = match operation {
+                    ScanOperation::Sum => previous + value,
+                    ScanOperation::Max => previous.max(value),
+                };
+                match exclusive {
+                    true => F::new(previous as f32),
+                    false => F::new(running[output_index] as f32),
+                }
+            })
+            .collect()
+    }
+
+    pub fn test_var(&self) {
+        self.test_variance(false);
+    }
+
+    pub fn test_std(&self) {
+        self.test_variance(true);
+    }
+
+    fn test_variance(&self, std: bool) {
+        let axis = self.axis.unwrap();
+        let correction = match self.shape[axis] {
+            1 => 0,
+            _ => 1,
+        };
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => {
+                vec![P::EI::new(0.0); self.num_output_values()]
+            }
+            _ => self.cpu_variance(&input_values, correction, std),
+        };
+        let config = match std {
+            true => ReduceOperationConfig::Std { correction },
+            false => ReduceOperationConfig::Var { correction },
+        };
+        self.run_reduce_test::<P::EI>(input_values, expected_values, config)
+    }
+
+    pub fn test_var_mean(&self) {
+        let axis = self.axis.unwrap();
+        let correction = match self.shape[axis] {
+            1 => 0,
+            _ => 1,
+        };
+        let input_values: Vec<P::EI> = self.random_input_values();
+        let (expected_var, expected_mean) = match self.stride[axis] {
+            0 => (
+                vec![P::EI::new(0.0); self.num_output_values()],
+                input_values.clone(),
+            ),
+            _ => (
+                self.cpu_variance(&input_values, correction, false),
+                self.cpu_mean(&input_values),
+            ),
+        };
+        self.run_var_mean_test(input_values, expected_var, expected_mean, correction)
+    }
+
+    fn cpu_variance<F: Float>(&self, values: &[F], correction: u32, std: bool) -> Vec<F> {
+        let count = self.shape[self.axis.unwrap()] as f64;
+        let mut means = vec![0.0; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                means[output_index] += value.to_f64().unwrap() / count;
+            }
+        }
+
+        let mut m2 = vec![0.0; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let difference = value.to_f64().unwrap() - means[output_index];
+                m2[output_index] += difference * difference;
+            }
+        }
+
+        m2.into_iter()
+            .map(|m2| {
+                let variance = m2 / (count - correction as f64);
+                let output = if std { variance.sqrt() } else { variance };
+                F::new(output as f32)
+            })
+            .collect()
+    }
+
     pub fn run_reduce_test<O>(
         &self,
         input_values: Vec<P::EI>,
@@ -221,26 +562,9 @@ where
                 accumulation: <P as ReducePrecision>::EA::as_type_native_unchecked(),
             },
         );
-        match result {
-            Ok(_) => {}
-            Err(e) => {
-                let is_ok = matches!(e, ReduceError::PlanesUnavailable)
-                    || matches!(e, ReduceError::ImprecisePlaneDim)
-                    || matches!(e, ReduceError::Validation { .. });
-
-                let test_mode = match is_ok {
-                    true => std::env::var("CUBEK_TEST_MODE").unwrap_or("skip".to_string()),
-                    false => "unexpected_error".to_string(),
-                };
-
-                match test_mode.as_str() {
-                    "skip" => {}
-                    "verbose" => println!("Skipping: {e:?}"),
-                    mode => panic!("TestMode='{mode}', the test didn't run:\n {e:?}"),
-                };
-
-                return;
-            }
+        if let Err(e) = result {
+            Self::skip_on_error(e);
+            return;
         }
 
         let bytes = client.read_one(output_handle);
@@ -253,6 +577,341 @@ where
         );
     }
 
+    /// Create the client, the input handle and a zeroed handle for each output, given as its
+    /// number of elements and the size of its elements.
+    ///
+    /// Return `None` for cube tests on CPU, which are skipped unless `CUBEK_TEST_FULL` is set.
+    fn create_handles<const N: usize>(
+        &self,
+        input_values: &[P::EI],
+        outputs: [(usize, usize); N],
+    ) -> Option<(ComputeClient<TestRuntime>, Handle, [Handle; N])> {
+        let client = TestRuntime::client(&Default::default());
+        if let RoutineStrategy::Cube(_blueprint) = &self.strategy.routine
+            && client.properties().hardware.num_cpu_cores.is_some()
+        {
+            let test_full = std::env::var("CUBEK_TEST_FULL").unwrap_or("0".to_string());
+
+            match test_full.as_str() {
+                "1" | "true" => {}
+                _ => {
+                    println!(
+                        "Skipping cube tests on CPU, because they are long to run and can stall the CI"
+                    );
+                    return None;
+                }
+            }
+        };
+
+        let input_handle = client.create_from_slice(<P::EI as CubeElement>::as_bytes(input_values));
+        let output_handles =
+            outputs.map(|(len, elem_size)| client.create_from_slice(&vec![0; len * elem_size]));
+
+        Some((client, input_handle, output_handles))
+    }
+
+    pub fn run_var_mean_test(
+        &self,
+        input_values: Vec<P::EI>,
+        expected_var: Vec<P::EI>,
+        expected_mean: Vec<P::EI>,
+        correction: u32,
+    ) {
+        let Some((client, input_handle, [var_handle, mean_handle])) = self.create_handles(
+            &input_values,
+            [
+                (expected_var.len(), size_of::<P::EI>()),
+                (expected_mean.len(), size_of::<P::EI>()),
+            ],
+        ) else {
+            return;
+        };
+
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let input = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let var = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &var_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let mean = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &mean_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let dtypes = ReduceDtypes {
+            input: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            output: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            accumulation: <P as ReducePrecision>::EA::as_type_native_unchecked(),
+        };
+
+        let result = var_mean::<TestRuntime>(
+            &client,
+            input,
+            var,
+            mean,
+            self.axis.unwrap(),
+            self.strategy.clone(),
+            correction,
+            dtypes,
+        );
+        if let Err(e) = result {
+            Self::skip_on_error(e);
+            return;
+        }
+
+        let bytes = client.read_one(var_handle);
+        assert_approx_equal(P::EI::from_bytes(&bytes), &expected_var, false);
+        let bytes = client.read_one(mean_handle);
+        assert_approx_equal(P::EI::from_bytes(&bytes), &expected_mean, false);
+    }
+
+    pub fn run_softmax_test(
+        &self,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<P::EI>,
+        log: bool,
+    ) {
+        let Some((client, input_handle, [output_handle])) =
+            self.create_handles(&input_values, [(expected_values.len(), size_of::<P::EI>())])
+        else {
+            return;
+        };
+
+        let input = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let output = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &output_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let dtypes = ReduceDtypes {
+            input: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            output: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            accumulation: <P as ReducePrecision>::EA::as_type_native_unchecked(),
+        };
+
+        let result = match log {
+            true => log_softmax::<TestRuntime>(
+                &client,
+                input,
+                output,
+                self.axis.unwrap(),
+                self.strategy.clone(),
+                dtypes,
+            ),
+            false => softmax::<TestRuntime>(
+                &client,
+                input,
+                output,
+                self.axis.unwrap(),
+                self.strategy.clone(),
+                dtypes,
+            ),
+        };
+        if let Err(e) = result {
+            Self::skip_on_error(e);
+            return;
+        }
+
+        let bytes = client.read_one(output_handle);
+        let output_values = P::EI::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values, false);
+    }
+
+    pub fn run_scan_test(
+        &self,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<P::EI>,
+        operation: ScanOperation,
+        exclusive: bool,
+    ) {
+        let Some((client, input_handle, [output_handle])) =
+            self.create_handles(&input_values, [(expected_values.len(), size_of::<P::EI>())])
+        else {
+            return;
+        };
+
+        let input = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let output = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &output_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let dtypes = ReduceDtypes {
+            input: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            output: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            accumulation: <P as ReducePrecision>::EA::as_type_native_unchecked(),
+        };
+
+        let result = match operation {
+            ScanOperation::Sum => cumsum::<TestRuntime>(
+                &client,
+                input,
+                output,
+                self.axis.unwrap(),
+                exclusive,
+                self.strategy.clone(),
+                dtypes,
+            ),
+            ScanOperation::Max => cummax::<TestRuntime>(
+                &client,
+                input,
+                output,
+                self.axis.unwrap(),
+                exclusive,
+                self.strategy.clone(),
+                dtypes,
+            ),
+        };
+        if let Err(e) = result {
+            Self::skip_on_error(e);
+            return;
+        }
+
+        let bytes = client.read_one(output_handle);
+        let output_values = P::EI::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values, false);
+    }
+
+    pub fn run_top_k_test(
+        &self,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<P::EI>,
+        expected_indices: Vec<u32>,
+        config: TopKConfig,
+        sort: bool,
+    ) {
+        let Some((client, input_handle, [values_handle, indices_handle])) = self.create_handles(
+            &input_values,
+            [
+                (expected_values.len(), size_of::<P::EI>()),
+                (expected_indices.len(), size_of::<u32>()),
+            ],
+        ) else {
+            return;
+        };
+        let output_shape = self.top_k_output_shape(config.k as usize);
+        let output_stride = Self::contiguous_stride(&output_shape);
+
+        let input = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let values = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &values_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<P::EI>(),
+            )
+        };
+        let indices = unsafe {
+            TensorHandleRef::from_raw_parts(
+                &indices_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<u32>(),
+            )
+        };
+        let dtypes = ReduceDtypes {
+            input: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            output: <P as ReducePrecision>::EI::as_type_native_unchecked(),
+            accumulation: <P as ReducePrecision>::EA::as_type_native_unchecked(),
+        };
+
+        let result = match sort {
+            true => argsort::<TestRuntime>(
+                &client,
+                input,
+                values,
+                indices,
+                self.axis.unwrap(),
+                config.largest,
+                self.strategy.clone(),
+                dtypes,
+            ),
+            false => top_k::<TestRuntime>(
+                &client,
+                input,
+                values,
+                indices,
+                self.axis.unwrap(),
+                config,
+                self.strategy.clone(),
+                dtypes,
+            ),
+        };
+        if let Err(e) = result {
+            Self::skip_on_error(e);
+            return;
+        }
+
+        let bytes = client.read_one(values_handle);
+        let output_values = P::EI::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values, false);
+
+        let bytes = client.read_one(indices_handle);
+        let output_indices = u32::from_bytes(&bytes);
+        assert_eq!(output_indices, &expected_indices);
+    }
+
+    fn skip_on_error(e: ReduceError) {
+        let is_ok = matches!(e, ReduceError::PlanesUnavailable)
+            || matches!(e, ReduceError::ImprecisePlaneDim)
+            || matches!(e, ReduceError::Validation { .. });
+
+        let test_mode = match is_ok {
+            true => std::env::var("CUBEK_TEST_MODE").unwrap_or("skip".to_string()),
+            false => "unexpected_error".to_string(),
+        };
+
+        match test_mode.as_str() {
+            "skip" => {}
+            "verbose" => println!("Skipping: {e:?}"),
+            mode => panic!("TestMode='{mode}', the test didn't run:\n {e:?}"),
+        };
+    }
+
     fn num_output_values(&self) -> usize {
         self.shape.iter().product::<usize>() / self.shape[self.axis.unwrap()]
     }
@@ -343,6 +1002,13 @@ where
     }
 }
 
+/// The scan operations covered by the tests.
+#[derive(Clone, Copy, Debug)]
+pub enum ScanOperation {
+    Sum,
+    Max,
+}
+
 pub fn assert_approx_equal<N: Numeric>(actual: &[N], expected: &[N], only_relative: bool) {
     for (i, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
         let a = a.to_f32().unwrap();
@@ -5,9 +5,11 @@ mod custom;
 mod eye;
 mod host_data;
 mod random;
+mod sparse;
 mod strides;
 mod zeros;
 
 pub use base::*;
 pub use host_data::*;
+pub use sparse::{Sparse24Data, compress_sparse_24};
 pub use strides::StrideSpec;
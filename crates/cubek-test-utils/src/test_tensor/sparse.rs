@@ -0,0 +1,65 @@
+/// A row major matrix pruned to 2:4 structured sparsity along its rows, see [compress_sparse_24].
+pub struct Sparse24Data {
+    /// Kept values, with half the rows of the dense matrix
+    pub values: Vec<f32>,
+    /// Positions of the kept values within their group of four rows, with a quarter of the rows
+    /// of the dense matrix. Each position holds `first | second << 2`.
+    ///
+    /// Stored as `f32` so it can be given to [TestInput::custom](crate::TestInput::custom) with a
+    /// `u32` dtype.
+    pub metadata: Vec<f32>,
+    /// The dense matrix after pruning, to compute reference results
+    pub dense: Vec<f32>,
+}
+
+/// Prunes a row major matrix (or batch of matrices) of the given shape to 2:4 structured sparsity
+/// along its rows, keeping the two values of largest magnitude in each group of four consecutive
+/// rows of each column.
+///
+/// The number of rows must be a multiple of 4.
+pub fn compress_sparse_24(data: &[f32], shape: &[usize]) -> Sparse24Data {
+    let rank = shape.len();
+    let rows = shape[rank - 2];
+    let cols = shape[rank - 1];
+    let batches = shape[..rank - 2].iter().product::<usize>();
+
+    assert!(rows.is_multiple_of(4), "Rows must be a multiple of 4");
+    assert_eq!(data.len(), batches * rows * cols);
+
+    let groups = rows / 4;
+    let mut values = vec![0.; batches * groups * 2 * cols];
+    let mut metadata = vec![0.; batches * groups * cols];
+    let mut dense = vec![0.; data.len()];
+
+    for batch in 0..batches {
+        for group in 0..groups {
+            for col in 0..cols {
+                let dense_index = |pos: usize| (batch * rows + group * 4 + pos) * cols + col;
+
+                let mut positions = [0, 1, 2, 3];
+                positions.sort_by(|a, b| {
+                    data[dense_index(*b)]
+                        .abs()
+                        .total_cmp(&data[dense_index(*a)].abs())
+                });
+                let (first, second) = match positions[0] < positions[1] {
+                    true => (positions[0], positions[1]),
+                    false => (positions[1], positions[0]),
+                };
+
+                for (i, pos) in [first, second].into_iter().enumerate() {
+                    let value = data[dense_index(pos)];
+                    values[(batch * groups * 2 + group * 2 + i) * cols + col] = value;
+                    dense[dense_index(pos)] = value;
+                }
+                metadata[(batch * groups + group) * cols + col] = (first | second << 2) as f32;
+            }
+        }
+    }
+
+    Sparse24Data {
+        values,
+        metadata,
+        dense,
+    }
+}
@@ -1,6 +1,8 @@
 use cubecl::frontend::CubePrimitive;
 use cubecl::{Runtime, TestRuntime};
-use cubek_test_utils::{HostData, HostDataType, StrideSpec, TestInput, assert_equals_approx};
+use cubek_test_utils::{
+    HostData, HostDataType, StrideSpec, TestInput, assert_equals_approx, compress_sparse_24,
+};
 
 #[test]
 fn eye_handle_row_major() {
@@ -140,3 +142,16 @@ fn custom_handle_row_major_col_major() {
 
     assert_equals_approx(&col_major, &row_major, 0.001).unwrap();
 }
+
+#[test]
+fn compress_sparse_24_keeps_largest_magnitudes() {
+    // Shape [4, 2], a single group of four rows per column
+    let data = [1., -8., 4., 2., -3., 0., 2., 5.];
+
+    let sparse = compress_sparse_24(&data, &[4, 2]);
+
+    // Column 0 keeps rows 1 and 2, column 1 keeps rows 0 and 3
+    assert_eq!(sparse.values, [4., -8., -3., 5.]);
+    assert_eq!(sparse.metadata, [(1 | 2 << 2) as f32, (3 << 2) as f32]);
+    assert_eq!(sparse.dense, [0., -8., 4., 0., -3., 0., 0., 5.]);
+}
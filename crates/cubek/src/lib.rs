@@ -17,3 +17,6 @@ pub use cubek_convolution as convolution;
 pub use cubek_attention as attention;
 
 pub use cubecl;
+
+#[cfg(feature = "std")]
+pub mod tune_cache;
@@ -0,0 +1,655 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::File,
+    hash::Hash,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    time::Duration,
+};
+
+use cubecl::prelude::*;
+use cubecl::std::tensor::TensorHandle;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+/// Version of the tune cache file format, bumped whenever the format itself changes.
+pub const TUNE_CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Version of the kernels the tune results were produced with. Keys and strategies are only
+/// meaningful for the version of the kernels that generated them.
+const KERNELS_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg(feature = "matmul")]
+/// Tune cache of matmul results
+pub type MatmulTuneCache = TuneCache<cubek_matmul::launch::MatmulAutotuneKey>;
+
+#[cfg(feature = "convolution")]
+/// Tune cache of convolution results
+pub type ConvTuneCache = TuneCache<cubek_convolution::ConvAutotuneKey>;
+
+#[cfg(feature = "reduce")]
+/// Tune cache of reduce results
+pub type ReduceTuneCache = TuneCache<cubek_reduce::launch::tune_key::ReduceAutotuneKey>;
+
+/// Winning strategy per autotune key, which can be exported to a file after tuning on a
+/// reference device, then imported at startup on identical devices to skip tuning.
+///
+/// Strategies are stored by their [`Debug`] representation, which covers every field of the
+/// strategy, unlike the autotune names, which are shared by all forced blueprints of a routine.
+/// The cache is used when launching through [`select`](Self::select), or the launch functions of
+/// the operation caches such as `MatmulTuneCache::launch_ref`.
+#[derive(Debug, Clone)]
+pub struct TuneCache<K> {
+    device: String,
+    entries: HashMap<K, String>,
+}
+
+/// How to handle keys present in both caches when merging.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the strategy already in the cache.
+    #[default]
+    KeepExisting,
+    /// Replace the strategy with the imported one.
+    Overwrite,
+    /// Fail if the strategies differ.
+    Strict,
+}
+
+#[derive(Error, Debug)]
+/// Errors when exporting or importing a tune cache
+pub enum TuneCacheError {
+    #[error("Failed to access the tune cache file\nCaused by:\n  {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid tune cache file\nCaused by:\n  {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("Unsupported tune cache format version {found}, expected {expected}")]
+    FormatVersion { expected: u32, found: u32 },
+    #[error("Tune cache was generated with kernels version {found}, expected {expected}")]
+    KernelsVersion { expected: String, found: String },
+    #[error("Tune cache was generated on device {found:?}, expected {expected:?}")]
+    DeviceMismatch { expected: String, found: String },
+    #[error("Conflicting strategies for key {key}: {existing} and {imported}")]
+    Conflict {
+        key: String,
+        existing: String,
+        imported: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "K: Serialize", deserialize = "K: DeserializeOwned"))]
+struct TuneCacheFile<K> {
+    format_version: u32,
+    kernels_version: String,
+    device: String,
+    entries: Vec<TuneCacheEntry<K>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "K: Serialize", deserialize = "K: DeserializeOwned"))]
+struct TuneCacheEntry<K> {
+    key: K,
+    strategy: String,
+}
+
+impl<K: Eq + Hash + Clone + Debug + Serialize + DeserializeOwned> TuneCache<K> {
+    /// Create an empty cache for the given device.
+    ///
+    /// The device name is matched exactly when importing, so it should identify the device model
+    /// and anything else that impacts the tuning results, such as the driver version.
+    pub fn new(device: impl Into<String>) -> Self {
+        Self {
+            device: device.into(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The device the results are valid for.
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    /// Record the winning strategy for a key, returning the previous one if any.
+    pub fn insert(&mut self, key: K, strategy: &impl Debug) -> Option<String> {
+        self.entries.insert(key, strategy_key(strategy))
+    }
+
+    /// The [`Debug`] representation of the winning strategy for a key.
+    pub fn get(&self, key: &K) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Iterate over the keys and their winning strategy.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, strategy)| (key, strategy.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pick the strategy to launch for `key` among `candidates`.
+    ///
+    /// A cached strategy for `key` is picked as is when it is one of the candidates, without
+    /// tuning. Otherwise `tune` gives the index of the fastest candidate, which is recorded for
+    /// `key`, or `None` if no candidate can be launched.
+    ///
+    /// # Panics
+    ///
+    /// If two candidates are the same strategy.
+    pub fn select<'a, S: Debug>(
+        &mut self,
+        key: K,
+        candidates: &'a [S],
+        tune: impl FnOnce(&'a [S]) -> Option<usize>,
+    ) -> Option<&'a S> {
+        let candidate_keys: Vec<String> = candidates.iter().map(strategy_key).collect();
+        for (index, candidate_key) in candidate_keys.iter().enumerate() {
+            assert!(
+                !candidate_keys[..index].contains(candidate_key),
+                "Strategy {candidate_key} is a candidate more than once"
+            );
+        }
+
+        if let Some(index) = self.get(&key).and_then(|cached| {
+            candidate_keys
+                .iter()
+                .position(|candidate_key| candidate_key == cached)
+        }) {
+            return Some(&candidates[index]);
+        }
+
+        let fastest = &candidates[tune(candidates)?];
+        self.insert(key, fastest);
+        Some(fastest)
+    }
+
+    /// Merge the results of another cache into this one.
+    ///
+    /// Nothing is merged if an error is returned.
+    pub fn merge(
+        &mut self,
+        other: TuneCache<K>,
+        policy: MergePolicy,
+    ) -> Result<(), TuneCacheError> {
+        if other.device != self.device {
+            return Err(TuneCacheError::DeviceMismatch {
+                expected: self.device.clone(),
+                found: other.device,
+            });
+        }
+
+        if policy == MergePolicy::Strict {
+            for (key, imported) in other.entries.iter() {
+                match self.entries.get(key) {
+                    Some(existing) if existing != imported => {
+                        return Err(TuneCacheError::Conflict {
+                            key: format!("{key:?}"),
+                            existing: existing.clone(),
+                            imported: imported.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (key, imported) in other.entries {
+            match policy {
+                MergePolicy::KeepExisting | MergePolicy::Strict => {
+                    self.entries.entry(key).or_insert(imported);
+                }
+                MergePolicy::Overwrite => {
+                    self.entries.insert(key, imported);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the cache in its versioned file format.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), TuneCacheError> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(key, strategy)| TuneCacheEntry {
+                key: key.clone(),
+                strategy: strategy.clone(),
+            })
+            .collect();
+        let file = TuneCacheFile {
+            format_version: TUNE_CACHE_FORMAT_VERSION,
+            kernels_version: KERNELS_VERSION.to_string(),
+            device: self.device.clone(),
+            entries,
+        };
+
+        serde_json::to_writer_pretty(writer, &file)?;
+        Ok(())
+    }
+
+    /// Read a cache written with [`to_writer`](Self::to_writer), failing if it was written with
+    /// a different format or kernels version.
+    pub fn from_reader<Rd: Read>(reader: Rd) -> Result<Self, TuneCacheError> {
+        let file: TuneCacheFile<K> = serde_json::from_reader(reader)?;
+
+        if file.format_version != TUNE_CACHE_FORMAT_VERSION {
+            return Err(TuneCacheError::FormatVersion {
+                expected: TUNE_CACHE_FORMAT_VERSION,
+                found: file.format_version,
+            });
+        }
+        if file.kernels_version != KERNELS_VERSION {
+            return Err(TuneCacheError::KernelsVersion {
+                expected: KERNELS_VERSION.to_string(),
+                found: file.kernels_version,
+            });
+        }
+
+        let entries = file
+            .entries
+            .into_iter()
+            .map(|entry| (entry.key, entry.strategy))
+            .collect();
+
+        Ok(Self {
+            device: file.device,
+            entries,
+        })
+    }
+
+    /// Export the cache to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TuneCacheError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a cache exported with [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TuneCacheError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Load a cache exported with [`save`](Self::save) and merge it into this one, failing if it
+    /// was generated on another device.
+    pub fn import(
+        &mut self,
+        path: impl AsRef<Path>,
+        policy: MergePolicy,
+    ) -> Result<(), TuneCacheError> {
+        let other = Self::load(path)?;
+        self.merge(other, policy)
+    }
+}
+
+/// Identifies a strategy in the cache.
+fn strategy_key(strategy: &impl Debug) -> String {
+    format!("{strategy:?}")
+}
+
+/// Number of profiled launches of each candidate when tuning, after a first unprofiled launch.
+const TUNE_LAUNCHES: u32 = 5;
+
+/// Index of the fastest of `candidates` on `client`, or `None` if none of them can be launched.
+///
+/// Each candidate is launched once to compile it, then profiled over a few launches with the
+/// timing method of the client, as for benchmarks. Since every candidate is launched several
+/// times, `launch` should write into scratch outputs rather than the outputs of the operation.
+pub fn fastest<R: Runtime, S, E>(
+    client: &ComputeClient<R>,
+    candidates: &[S],
+    mut launch: impl FnMut(&S) -> Result<(), E>,
+) -> Option<usize> {
+    let mut fastest: Option<(usize, Duration)> = None;
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        if launch(candidate).is_err() {
+            continue;
+        }
+        cubecl::future::block_on(client.sync());
+
+        let profiled = client.profile(
+            || {
+                for _ in 0..TUNE_LAUNCHES {
+                    let _ = launch(candidate);
+                }
+            },
+            "tune",
+        );
+        let Ok((_, duration)) = profiled else {
+            continue;
+        };
+        let elapsed = cubecl::future::block_on(duration.resolve()).duration();
+
+        if fastest.is_none_or(|(_, best)| elapsed < best) {
+            fastest = Some((index, elapsed));
+        }
+    }
+
+    fastest.map(|(index, _)| index)
+}
+
+#[cfg(feature = "matmul")]
+impl MatmulTuneCache {
+    /// Launch a matmul with the strategy selected for its autotune key among `candidates`, see
+    /// [`select`](Self::select). When none is cached, candidates are tuned on a scratch output
+    /// of the shape of `out`, which is only written by the selected strategy.
+    ///
+    /// # Panics
+    ///
+    /// If `candidates` is empty or contains the same strategy twice.
+    #[allow(clippy::result_large_err)]
+    pub fn launch_ref<R: Runtime>(
+        &mut self,
+        candidates: &[cubek_matmul::launch::Strategy],
+        client: &ComputeClient<R>,
+        lhs: &cubek_matmul::launch::MatmulInputHandleRef<'_, R>,
+        rhs: &cubek_matmul::launch::MatmulInputHandleRef<'_, R>,
+        out: &TensorHandleRef<'_, R>,
+        dtypes: &mut cubek_matmul::definition::MatmulElems,
+    ) -> Result<(), cubek_matmul::definition::MatmulSetupError> {
+        use cubek_matmul::launch::{MatmulAutotuneKey, launch_ref};
+
+        assert!(!candidates.is_empty(), "No matmul strategy to launch");
+
+        let key = MatmulAutotuneKey::generate(
+            client,
+            lhs.shape(),
+            rhs.shape(),
+            lhs.data().strides,
+            rhs.data().strides,
+            dtypes.lhs_global,
+            dtypes.rhs_global,
+            dtypes.acc_global,
+        );
+        let strategy = self
+            .select(key, candidates, |candidates| {
+                let scratch =
+                    TensorHandle::<R>::empty(client, out.shape.to_vec(), dtypes.acc_global.dtype);
+                fastest(client, candidates, |strategy| {
+                    launch_ref(
+                        strategy,
+                        client,
+                        lhs,
+                        rhs,
+                        &scratch.as_ref(),
+                        &mut dtypes.clone(),
+                    )
+                })
+            })
+            // Surface the error of the first strategy when none can be launched
+            .unwrap_or(&candidates[0]);
+
+        launch_ref(strategy, client, lhs, rhs, out, dtypes)
+    }
+}
+
+#[cfg(feature = "reduce")]
+impl ReduceTuneCache {
+    /// Reduce `axis` with the strategy selected for its autotune key among `candidates`, see
+    /// [`select`](Self::select). When none is cached, candidates are tuned on a scratch output
+    /// of the shape of `output`, which is only written by the selected strategy.
+    ///
+    /// # Panics
+    ///
+    /// If `candidates` is empty or contains the same strategy twice.
+    #[allow(clippy::too_many_arguments)]
+    pub fn reduce<R: Runtime>(
+        &mut self,
+        candidates: &[cubek_reduce::ReduceStrategy],
+        client: &ComputeClient<R>,
+        input: TensorHandleRef<R>,
+        output: TensorHandleRef<R>,
+        axis: usize,
+        operation: cubek_reduce::components::instructions::ReduceOperationConfig,
+        dtypes: cubek_reduce::ReduceDtypes,
+    ) -> Result<(), cubek_reduce::ReduceError> {
+        use cubek_reduce::{ReduceError, launch::tune_key::ReduceAutotuneKey, reduce};
+
+        assert!(!candidates.is_empty(), "No reduce strategy to launch");
+        let rank = input.shape.len();
+        if axis >= rank {
+            return Err(ReduceError::InvalidAxis { axis, rank });
+        }
+
+        let key = ReduceAutotuneKey::generate(
+            dtypes.input.elem_type(),
+            dtypes.output.elem_type(),
+            dtypes.accumulation.elem_type(),
+            input.shape,
+            input.strides[axis] == 1,
+            axis,
+        );
+        let strategy = self
+            .select(key, candidates, |candidates| {
+                let scratch =
+                    TensorHandle::<R>::empty(client, output.shape.to_vec(), dtypes.output);
+                fastest(client, candidates, |strategy| {
+                    reduce(
+                        client,
+                        input,
+                        scratch.as_ref(),
+                        axis,
+                        strategy.clone(),
+                        operation,
+                        dtypes,
+                    )
+                })
+            })
+            // Surface the error of the first strategy when none can be launched
+            .unwrap_or(&candidates[0]);
+
+        reduce(
+            client,
+            input,
+            output,
+            axis,
+            strategy.clone(),
+            operation,
+            dtypes,
+        )
+    }
+}
+
+#[cfg(feature = "convolution")]
+impl ConvTuneCache {
+    /// Launch a forward convolution with the strategy selected for its autotune key among
+    /// `candidates`, see [`select`](Self::select). When none is cached, candidates are tuned on a
+    /// scratch output of the shape of `out`, which is only written by the selected strategy.
+    ///
+    /// # Panics
+    ///
+    /// If `candidates` is empty or contains the same strategy twice.
+    #[allow(clippy::result_large_err, clippy::too_many_arguments)]
+    pub fn launch_forward<R: Runtime, const N_SPATIAL: usize>(
+        &mut self,
+        candidates: &[cubek_convolution::Strategy],
+        client: &ComputeClient<R>,
+        input: &cubek_matmul::launch::MatmulInputHandleRef<'_, R>,
+        weight: &cubek_matmul::launch::MatmulInputHandleRef<'_, R>,
+        bias: &Option<cubek_matmul::launch::MatmulInputHandleRef<'_, R>>,
+        out: &TensorHandleRef<'_, R>,
+        args: cubek_convolution::ConvolutionArgs<N_SPATIAL>,
+        dtypes: cubek_matmul::definition::MatmulElems,
+    ) -> Result<(), cubek_convolution::components::ConvSetupError> {
+        use cubek_convolution::{
+            ConvAutotuneKey, components::ConvolutionOperation, forward::launch_ref,
+        };
+
+        assert!(!candidates.is_empty(), "No convolution strategy to launch");
+
+        let key = ConvAutotuneKey::generate(
+            ConvolutionOperation::Forward,
+            input.shape(),
+            weight.shape(),
+            &args,
+            bias.is_some(),
+            dtypes.lhs_global,
+            dtypes.rhs_global,
+            dtypes.acc_global,
+        );
+        let strategy = self
+            .select(key, candidates, |candidates| {
+                let scratch =
+                    TensorHandle::<R>::empty(client, out.shape.to_vec(), dtypes.acc_global.dtype);
+                fastest(client, candidates, |strategy| {
+                    launch_ref(
+                        strategy,
+                        client,
+                        input,
+                        weight,
+                        bias,
+                        &scratch.as_ref(),
+                        args.clone(),
+                        dtypes.clone(),
+                    )
+                })
+            })
+            // Surface the error of the first strategy when none can be launched
+            .unwrap_or(&candidates[0]);
+
+        launch_ref(strategy, client, input, weight, bias, out, args, dtypes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+    struct Key {
+        m: usize,
+        n: usize,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Candidate {
+        A,
+        B,
+        C,
+        D,
+        Removed,
+    }
+    use Candidate::*;
+
+    fn cache(entries: &[(usize, Candidate)]) -> TuneCache<Key> {
+        let mut cache = TuneCache::new("device");
+        for (m, strategy) in entries {
+            cache.insert(Key { m: *m, n: 1 }, strategy);
+        }
+        cache
+    }
+
+    #[test]
+    fn roundtrip() {
+        let cache = cache(&[(1, A), (2, B)]);
+
+        let mut bytes = Vec::new();
+        cache.to_writer(&mut bytes).unwrap();
+        let loaded = TuneCache::<Key>::from_reader(bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.device(), "device");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(&Key { m: 1, n: 1 }), Some("A"));
+        assert_eq!(loaded.get(&Key { m: 2, n: 1 }), Some("B"));
+    }
+
+    #[test]
+    fn reject_other_format_version() {
+        let mut bytes = Vec::new();
+        cache(&[(1, A)]).to_writer(&mut bytes).unwrap();
+        let bytes = String::from_utf8(bytes).unwrap().replace(
+            &format!("\"format_version\": {TUNE_CACHE_FORMAT_VERSION}"),
+            "\"format_version\": 0",
+        );
+
+        let result = TuneCache::<Key>::from_reader(bytes.as_bytes());
+        assert!(matches!(
+            result,
+            Err(TuneCacheError::FormatVersion { found: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn select_imported_without_tuning() {
+        let mut bytes = Vec::new();
+        cache(&[(1, B)]).to_writer(&mut bytes).unwrap();
+        let mut cache = TuneCache::new("device");
+        cache
+            .merge(
+                TuneCache::from_reader(bytes.as_slice()).unwrap(),
+                MergePolicy::KeepExisting,
+            )
+            .unwrap();
+
+        let selected = cache.select(Key { m: 1, n: 1 }, &[A, B, C], |_| {
+            panic!("Imported entries shouldn't be tuned")
+        });
+        assert_eq!(selected, Some(&B));
+    }
+
+    #[test]
+    fn select_tunes_missing_strategy() {
+        let mut cache = cache(&[(1, Removed)]);
+
+        let selected = cache.select(Key { m: 1, n: 1 }, &[A, B], |_| Some(1));
+        assert_eq!(selected, Some(&B));
+        assert_eq!(cache.get(&Key { m: 1, n: 1 }), Some("B"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_rejects_duplicate_candidates() {
+        let mut cache = cache(&[]);
+        cache.select(Key { m: 1, n: 1 }, &[A, B, A], |_| Some(0));
+    }
+
+    #[test]
+    fn merge_keep_existing() {
+        let mut cache_a = cache(&[(1, A), (2, B)]);
+        cache_a
+            .merge(cache(&[(2, C), (3, D)]), MergePolicy::KeepExisting)
+            .unwrap();
+
+        assert_eq!(cache_a.get(&Key { m: 2, n: 1 }), Some("B"));
+        assert_eq!(cache_a.get(&Key { m: 3, n: 1 }), Some("D"));
+    }
+
+    #[test]
+    fn merge_overwrite() {
+        let mut cache_a = cache(&[(1, A), (2, B)]);
+        cache_a
+            .merge(cache(&[(2, C)]), MergePolicy::Overwrite)
+            .unwrap();
+
+        assert_eq!(cache_a.get(&Key { m: 1, n: 1 }), Some("A"));
+        assert_eq!(cache_a.get(&Key { m: 2, n: 1 }), Some("C"));
+    }
+
+    #[test]
+    fn merge_strict_conflict() {
+        let mut cache_a = cache(&[(1, A)]);
+        let result = cache_a.merge(cache(&[(1, B), (2, C)]), MergePolicy::Strict);
+
+        assert!(matches!(result, Err(TuneCacheError::Conflict { .. })));
+        assert_eq!(cache_a.len(), 1);
+    }
+
+    #[test]
+    fn merge_other_device() {
+        let mut cache_a = cache(&[(1, A)]);
+        let result = cache_a.merge(TuneCache::new("other"), MergePolicy::Overwrite);
+
+        assert!(matches!(result, Err(TuneCacheError::DeviceMismatch { .. })));
+    }
+}